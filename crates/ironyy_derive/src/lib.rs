@@ -0,0 +1,32 @@
+//! Derive macros for the `ironyy` crate.
+
+use proc_macro::TokenStream;
+use quote::quote;
+use syn::{DeriveInput, parse_macro_input};
+
+/// Implements `crate::ui::PageBoilerplate` for a page struct.
+///
+/// The generated `page_name` returns the struct's name, which `Page::title`
+/// falls back to, and `as_any` returns the page itself so tests can
+/// downcast a `Box<dyn Page>` to its concrete type.
+#[proc_macro_derive(PageBoilerplate)]
+pub fn derive_page_boilerplate(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    let name = &input.ident;
+    let name_string = name.to_string();
+    let (impl_generics, type_generics, where_clause) = input.generics.split_for_impl();
+
+    let expanded = quote! {
+        impl #impl_generics crate::ui::PageBoilerplate for #name #type_generics #where_clause {
+            fn page_name(&self) -> &'static str {
+                #name_string
+            }
+
+            fn as_any(&self) -> &dyn ::std::any::Any {
+                self
+            }
+        }
+    };
+
+    expanded.into()
+}
@@ -0,0 +1,41 @@
+use std::any::Any;
+
+use ironyy_derive::PageBoilerplate;
+
+// Stands in for `ironyy::ui`, which the generated impl refers to as
+// `crate::ui`.
+mod ui {
+    use std::any::Any;
+
+    pub trait PageBoilerplate {
+        fn page_name(&self) -> &'static str;
+        fn as_any(&self) -> &dyn Any;
+    }
+}
+
+use ui::PageBoilerplate;
+
+#[derive(PageBoilerplate)]
+struct HomePage;
+
+#[derive(PageBoilerplate)]
+struct EpicDetail {
+    epic_id: u32,
+}
+
+#[test]
+fn page_name_should_be_the_struct_name() {
+    assert_eq!(HomePage.page_name(), "HomePage");
+    assert_eq!(EpicDetail { epic_id: 1 }.page_name(), "EpicDetail");
+}
+
+#[test]
+fn as_any_should_downcast_to_the_page() {
+    let page: Box<dyn PageBoilerplate> = Box::new(EpicDetail { epic_id: 7 });
+
+    let any: &dyn Any = page.as_any();
+    let epic_detail = any.downcast_ref::<EpicDetail>().unwrap();
+
+    assert_eq!(epic_detail.epic_id, 7);
+    assert!(any.downcast_ref::<HomePage>().is_none());
+}
@@ -0,0 +1,198 @@
+//! Append-only record of every change made through `JiraDatabase`.
+//!
+//! Each change is written as one JSON object per line so the log can be
+//! tailed, grepped or loaded line by line without parsing the whole file.
+
+use std::{cell::RefCell, fs::OpenOptions, io::Write, path::PathBuf};
+
+use anyhow::Result;
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+
+use crate::constants::UNKNOWN_USER;
+
+#[derive(Serialize, Deserialize, PartialEq, Eq, Debug, Clone, Copy)]
+pub enum AuditOperation {
+    CreateEpic,
+    CreateStory,
+    DeleteEpic,
+    DeleteStory,
+    UpdateEpicStatus,
+    UpdateEpicName,
+    UpdateEpicDescription,
+    UpdateEpicDueDate,
+    UpdateEpicRelease,
+    UpdateStoryStatus,
+    UpdateStoryName,
+    UpdateStoryDescription,
+    UpdateStoryDueDate,
+    UpdateStoryEstimate,
+    UpdateStoryType,
+    UpdateStoryRelease,
+    LinkStoryDependency,
+    UnlinkStoryDependency,
+    ReopenStory,
+    CreateSprint,
+    UpdateSprint,
+    DeleteSprint,
+    AssignStoryToSprint,
+    RemoveStoryFromSprint,
+    MoveStory,
+    AddComment,
+    DeleteComment,
+    CreateProject,
+    SwitchProject,
+    DeleteProject,
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Eq, Debug, Clone)]
+pub struct AuditRecord {
+    pub timestamp: String,
+    pub operation: AuditOperation,
+    /// Set for operations on an epic, story, sprint or comment
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub item_id: Option<u32>,
+    /// Set for operations on a whole project
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub project: Option<String>,
+    pub username: String,
+}
+
+pub struct AuditLogger {
+    path: Option<PathBuf>,
+    username: RefCell<String>,
+}
+
+impl AuditLogger {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self {
+            path: Some(path.into()),
+            username: RefCell::new(UNKNOWN_USER.to_owned()),
+        }
+    }
+
+    /// A logger that drops every record, for tests and throwaway databases.
+    pub fn disable_audit() -> Self {
+        Self {
+            path: None,
+            username: RefCell::new(UNKNOWN_USER.to_owned()),
+        }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.path.is_some()
+    }
+
+    pub fn set_username(&self, username: &str) {
+        *self.username.borrow_mut() = username.to_owned();
+    }
+
+    pub fn username(&self) -> String {
+        self.username.borrow().clone()
+    }
+
+    pub fn log(&self, operation: AuditOperation, item_id: u32) -> Result<()> {
+        self.append(operation, Some(item_id), None)
+    }
+
+    pub fn log_project(&self, operation: AuditOperation, project: &str) -> Result<()> {
+        self.append(operation, None, Some(project.to_owned()))
+    }
+
+    fn append(
+        &self,
+        operation: AuditOperation,
+        item_id: Option<u32>,
+        project: Option<String>,
+    ) -> Result<()> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+
+        let record = AuditRecord {
+            timestamp: Utc::now().to_rfc3339(),
+            operation,
+            item_id,
+            project,
+            username: self.username.borrow().clone(),
+        };
+
+        // Append mode means several running instances add to the same log
+        // rather than truncating each other's history.
+        let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+        writeln!(file, "{}", serde_json::to_string(&record)?)?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn read_records(path: &std::path::Path) -> Vec<AuditRecord> {
+        std::fs::read_to_string(path)
+            .unwrap()
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn log_should_append_one_json_line_per_record() {
+        let tmpfile = tempfile::NamedTempFile::new().unwrap();
+        let logger = AuditLogger::new(tmpfile.path());
+
+        logger.log(AuditOperation::CreateEpic, 1).unwrap();
+        logger.set_username("alice");
+        logger.log(AuditOperation::DeleteStory, 2).unwrap();
+
+        let records = read_records(tmpfile.path());
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[0].operation, AuditOperation::CreateEpic);
+        assert_eq!(records[0].item_id, Some(1));
+        assert_eq!(records[0].username, UNKNOWN_USER);
+        assert_eq!(records[1].operation, AuditOperation::DeleteStory);
+        assert_eq!(records[1].item_id, Some(2));
+        assert_eq!(records[1].username, "alice");
+    }
+
+    #[test]
+    fn log_project_should_name_the_project_instead_of_an_item() {
+        let tmpfile = tempfile::NamedTempFile::new().unwrap();
+        let logger = AuditLogger::new(tmpfile.path());
+
+        logger
+            .log_project(AuditOperation::CreateProject, "side project")
+            .unwrap();
+
+        let line = std::fs::read_to_string(tmpfile.path()).unwrap();
+        assert!(!line.contains("item_id"));
+        let records = read_records(tmpfile.path());
+        assert_eq!(records[0].operation, AuditOperation::CreateProject);
+        assert_eq!(records[0].item_id, None);
+        assert_eq!(records[0].project.as_deref(), Some("side project"));
+    }
+
+    #[test]
+    fn log_should_not_overwrite_existing_records() {
+        let tmpfile = tempfile::NamedTempFile::new().unwrap();
+
+        AuditLogger::new(tmpfile.path())
+            .log(AuditOperation::CreateEpic, 1)
+            .unwrap();
+        AuditLogger::new(tmpfile.path())
+            .log(AuditOperation::CreateStory, 2)
+            .unwrap();
+
+        assert_eq!(read_records(tmpfile.path()).len(), 2);
+    }
+
+    #[test]
+    fn disabled_logger_should_not_write_anything() {
+        let logger = AuditLogger::disable_audit();
+
+        assert!(!logger.is_enabled());
+        assert!(logger.log(AuditOperation::CreateEpic, 1).is_ok());
+    }
+}
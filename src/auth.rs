@@ -1,176 +1,542 @@
-use std::any;
+//! User accounts and authentication.
+//!
+//! Passwords are hashed with Argon2id. Every hash gets its own random salt
+//! and is stored as a PHC string, so the salt and cost parameters travel
+//! with the hash and verification goes through Argon2's own verifier rather
+//! than re-hashing and comparing strings. The cost parameters for new hashes
+//! come from a `SecurityPolicy`, whose defaults are lowered under
+//! `cfg(test)` to keep the test suite fast.
+//!
+//! Two-factor authentication is optional and uses TOTP codes from an
+//! authenticator app. Enabling it also issues single-use backup codes for
+//! when the authenticator is lost; only their Argon2 hashes are kept.
+//!
+//! Users are persisted as `UserRecord`s, which keep the TOTP seed in Base32
+//! so it can be stored and rebuilt without the TOTP object itself.
+//!
+//! Five wrong passwords in a row lock the account for fifteen minutes. The
+//! lockout is part of the record, so restarting the app does not lift it.
+//!
+//! The hashes of the last ten passwords are kept so that a password change
+//! cannot go back to one of them.
 
-use argon2::{Algorithm, Argon2, Params, PasswordHasher, Version, password_hash};
-use base64::{Engine, prelude::BASE64_STANDARD_NO_PAD};
-use easy_totp::EasyTotp;
-use rand::{TryRngCore, rngs::OsRng};
+use std::{
+    collections::HashMap,
+    time::{Duration, SystemTime},
+};
+
+use argon2::{
+    Argon2, PasswordHash, PasswordHasher, PasswordVerifier,
+    password_hash::{
+        self, SaltString,
+        rand_core::{OsRng, RngCore},
+    },
+};
+use itertools::Itertools;
 use serde::{Deserialize, Serialize};
+use totp_rs::{Algorithm as TotpAlgorithm, Secret, TOTP};
 use uuid::Uuid;
 
 use crate::constants::APP_NAME;
 
+mod security_policy;
+mod user_store;
+pub use security_policy::SecurityPolicy;
+pub use user_store::UserStore;
+
 pub struct User {
     username: String,
     uuid: Uuid,
     password_hash: String,
     password_number: u32,
-    totp: Option<EasyTotp>,
+    password_history: Vec<String>,
+    totp: Option<TOTP>,
+    backup_codes: Vec<String>,
+    failed_attempts: u32,
+    locked_until: Option<SystemTime>,
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Eq, Debug, Clone)]
+pub struct UserRecord {
+    pub uuid: Uuid,
+    pub username: String,
+    pub password_hash: String,
+    pub password_number: u32,
+    #[serde(default)]
+    pub password_history: Vec<String>,
+    pub totp_enabled: bool,
+    pub totp_secret_base32: Option<String>,
+    #[serde(default)]
+    pub backup_codes: Vec<String>,
+    #[serde(default)]
+    pub failed_attempts: u32,
+    #[serde(default)]
+    pub locked_until: Option<SystemTime>,
 }
 
 impl User {
     // Hashing-related stuff
-    const HASH_ALGO: Algorithm = Algorithm::Argon2id;
-    const HASH_VERSION: Version = Version::V0x13;
-
-    #[cfg(test)]
-    const HASH_MEMORY_KIB: u32 = 1945;
-    #[cfg(test)]
-    const HASH_ITERATIONS: u32 = 1;
-
-    #[cfg(not(test))]
-    const HASH_MEMORY_KIB: u32 = 19_456;
-    #[cfg(not(test))]
-    const HASH_ITERATIONS: u32 = 8;
-
-    fn hash_params() -> Params {
-        Params::new(
-            Self::HASH_MEMORY_KIB, // Memory size, expressed in kibibytes.
-            Self::HASH_ITERATIONS, // Number of iterations/passes.
-            1u32,                  // Degree of parallelism.
-            Some(32usize),         // Length of the output (in bytes).
-        )
-        .expect("Failed to generate app-wide User hashing params properly")
-    }
-    fn hash(uuid: Uuid, pass_num: u32, password: String) -> Result<String, anyhow::Error> {
-        let salt_result = password_hash::SaltString::from_b64(
-            &BASE64_STANDARD_NO_PAD.encode((format!("{uuid}{pass_num}")).as_bytes()),
-        );
-        let salt;
-        match salt_result {
-            Ok(s) => salt = s,
-            Err(e) => {
-                return Err(anyhow::anyhow!(
-                    "Failed to generate salt for password hashing: {}",
-                    e
-                ))
-            }
-        }
 
-        let hash  = Argon2::new(Self::HASH_ALGO, Self::HASH_VERSION, Self::hash_params())
+    // Verification reads the cost parameters from the stored hash, so any
+    // valid hasher can check a hash made under any policy.
+    fn hasher() -> Argon2<'static> {
+        SecurityPolicy::default()
+            .hasher()
+            .expect("Failed to generate app-wide User hashing params properly")
+    }
+
+    fn hash(password: &str, policy: &SecurityPolicy) -> Result<String, anyhow::Error> {
+        let salt = SaltString::generate(&mut OsRng);
+
+        let hash = policy
+            .hasher()?
             .hash_password(password.as_bytes(), &salt)
             .map_err(|e| anyhow::anyhow!("Failed to hash password: {}", e))?;
 
         Ok(hash.to_string())
     }
 
-    pub fn new(username: String, password: String) -> Result<Self, anyhow::Error> {
+    pub fn new(
+        username: String,
+        password: String,
+        policy: &SecurityPolicy,
+    ) -> Result<Self, anyhow::Error> {
         let uuid = Uuid::new_v4();
-        if let Err(e) = is_password_compliant(&password) {
-            return Err(e);
-        }
+        is_username_valid(&username)?;
+        is_password_compliant(&password).map_err(password_errors_message)?;
 
         let password_number = 0;
-        let password_hash = Self::hash(uuid, password_number, password)?;
+        let password_hash = Self::hash(&password, policy)?;
 
         Ok(Self {
             username,
             uuid,
             password_hash,
             password_number,
+            password_history: vec![],
             totp: None,
+            backup_codes: vec![],
+            failed_attempts: 0,
+            locked_until: None,
         })
     }
 
-    pub fn enable_2fa(&mut self) -> Result<(), anyhow::Error> {
-        let issuer = Some(String::from(APP_NAME));
-        let account_name = self.username.clone();
+    // TOTP-related stuff
+    const TOTP_DIGITS: usize = 6;
+    const TOTP_SKEW: u8 = 1;
+    const TOTP_STEP_SECS: u64 = 30;
+
+    fn totp(&self, secret: Vec<u8>) -> Result<TOTP, anyhow::Error> {
+        TOTP::new(
+            TotpAlgorithm::SHA1,
+            Self::TOTP_DIGITS,
+            Self::TOTP_SKEW,
+            Self::TOTP_STEP_SECS,
+            secret,
+            Some(String::from(APP_NAME)),
+            self.username.clone(),
+        )
+        .map_err(|e| anyhow::anyhow!("Failed to set up TOTP: {}", e))
+    }
+
+    // Backup-code-related stuff
+    const BACKUP_CODE_COUNT: usize = 8;
+    const BACKUP_CODE_LEN: usize = 8;
+    const BACKUP_CODE_ALPHABET: &'static [u8] =
+        b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789";
+
+    fn generate_backup_code() -> String {
+        let alphabet_len = Self::BACKUP_CODE_ALPHABET.len();
+        // Bytes past the last whole multiple of the alphabet length are
+        // redrawn so every character is equally likely.
+        let unbiased_limit = 256 - 256 % alphabet_len;
+
+        let mut code = String::with_capacity(Self::BACKUP_CODE_LEN);
+        while code.len() < Self::BACKUP_CODE_LEN {
+            let mut byte = [0u8; 1];
+            OsRng.fill_bytes(&mut byte);
+            let byte = byte[0] as usize;
+            if byte < unbiased_limit {
+                code.push(Self::BACKUP_CODE_ALPHABET[byte % alphabet_len] as char);
+            }
+        }
+        code
+    }
+
+    /// Returns the plaintext backup codes. This is the only time they are
+    /// available, so the caller must show them to the user. They are stored
+    /// hashed with `policy`, like passwords.
+    pub fn enable_2fa(&mut self, policy: &SecurityPolicy) -> Result<Vec<String>, anyhow::Error> {
+        let secret = Secret::generate_secret()
+            .to_bytes()
+            .map_err(|e| anyhow::anyhow!("Failed to generate TOTP secret: {}", e))?;
+
+        let totp = self.totp(secret)?;
+        let my_qr_code = totp.get_qr_base64().map_err(|e| anyhow::anyhow!("{}", e))?;
 
-        let et = EasyTotp::new(issuer, account_name)?;
-        let my_qr_code = EasyTotp::create_qr_png(et.clone())
-            .map_err(|e| anyhow::anyhow!("{}", e))?;
-        self.totp = Some(et);
+        let backup_codes: Vec<String> = (0..Self::BACKUP_CODE_COUNT)
+            .map(|_| Self::generate_backup_code())
+            .collect();
+        self.backup_codes = backup_codes
+            .iter()
+            .map(|code| Self::hash(code, policy))
+            .collect::<Result<_, _>>()?;
+
+        self.totp = Some(totp);
         println!(
             "Scan this QR code with your authenticator app:\n{:#?}",
             my_qr_code
         );
-        Ok(())
+        Ok(backup_codes)
     }
 
-    pub fn verify_password(&self, password_attempt: String) -> Result<bool, anyhow::Error> {
-        let reference_hash = self.password_hash.clone();
-        let attempt_hash = Self::hash(self.uuid, self.password_number, password_attempt)?;
-        Ok(reference_hash == attempt_hash)
+    /// Checks `code` against the unused backup codes and, if it matches,
+    /// removes it so it cannot be used again.
+    pub fn consume_backup_code(&mut self, code: &str) -> Result<bool, anyhow::Error> {
+        let code = code.trim();
+
+        for (index, stored_hash) in self.backup_codes.iter().enumerate() {
+            let reference_hash = PasswordHash::new(stored_hash)
+                .map_err(|e| anyhow::anyhow!("Stored backup code hash is invalid: {}", e))?;
+
+            match Self::hasher().verify_password(code.as_bytes(), &reference_hash) {
+                Ok(()) => {
+                    self.backup_codes.remove(index);
+                    return Ok(true);
+                }
+                Err(password_hash::Error::Password) => continue,
+                Err(e) => return Err(anyhow::anyhow!("Failed to verify backup code: {}", e)),
+            }
+        }
+
+        Ok(false)
+    }
+
+    pub fn remaining_backup_codes(&self) -> usize {
+        self.backup_codes.len()
+    }
+
+    // Lockout-related stuff
+    const MAX_FAILED_ATTEMPTS: u32 = 5;
+    const LOCKOUT_DURATION: Duration = Duration::from_secs(15 * 60);
+
+    pub fn verify_password(&mut self, password_attempt: String) -> Result<bool, anyhow::Error> {
+        self.verify_password_at(password_attempt, SystemTime::now())
+    }
+
+    fn verify_password_at(
+        &mut self,
+        password_attempt: String,
+        now: SystemTime,
+    ) -> Result<bool, anyhow::Error> {
+        if let Some(locked_until) = self.locked_until {
+            match locked_until.duration_since(now) {
+                Ok(remaining) => {
+                    return Err(anyhow::anyhow!(
+                        "Account is locked. Try again in {} seconds.",
+                        remaining.as_secs().max(1)
+                    ));
+                }
+                // The lockout has run out, so the user starts over with a
+                // clean slate.
+                Err(_) => {
+                    self.locked_until = None;
+                    self.failed_attempts = 0;
+                }
+            }
+        }
+
+        if Self::password_matches(&password_attempt, &self.password_hash)? {
+            self.failed_attempts = 0;
+            Ok(true)
+        } else {
+            self.failed_attempts += 1;
+            if self.failed_attempts >= Self::MAX_FAILED_ATTEMPTS {
+                self.locked_until = Some(now + Self::LOCKOUT_DURATION);
+            }
+            Ok(false)
+        }
+    }
+
+    pub fn is_locked(&self) -> bool {
+        self.is_locked_at(SystemTime::now())
+    }
+
+    fn is_locked_at(&self, now: SystemTime) -> bool {
+        self.locked_until
+            .is_some_and(|locked_until| now < locked_until)
     }
 
     pub fn verify_totp(&self, code: &str) -> Result<bool, anyhow::Error> {
         match &self.totp {
-            Some(secret) => {
-                let reference_code = EasyTotp::generate_token(secret.clone())
-                    .map_err(|e| anyhow::anyhow!("{}", e))?;
-                Ok(reference_code == code)
-            }
+            Some(totp) => totp
+                .check_current(code)
+                .map_err(|e| anyhow::anyhow!("Failed to verify TOTP code: {}", e)),
             None => Err(anyhow::anyhow!("2FA is not enabled for this user.")),
         }
     }
 
-    pub fn change_password(&mut self, new_password: String) -> Result<(), anyhow::Error> {
-        if let Err(e) = is_password_compliant(&new_password) {
-            return Err(e);
+    /// How many of the previous passwords a new password must differ from.
+    const PASSWORD_HISTORY_LEN: usize = 10;
+
+    pub fn change_password(
+        &mut self,
+        new_password: String,
+        policy: &SecurityPolicy,
+    ) -> Result<(), anyhow::Error> {
+        is_password_compliant(&new_password).map_err(password_errors_message)?;
+        // Every hash has its own salt, so a reused password can only be
+        // spotted by verifying it against each old hash in turn.
+        for stored_hash in std::iter::once(&self.password_hash).chain(&self.password_history) {
+            if Self::password_matches(&new_password, stored_hash)? {
+                return Err(anyhow::anyhow!(
+                    "New password must differ from the last {} passwords.",
+                    Self::PASSWORD_HISTORY_LEN
+                ));
+            }
+        }
+
+        let new_hash = Self::hash(&new_password, policy)?;
+        let old_hash = std::mem::replace(&mut self.password_hash, new_hash);
+        self.password_history.push(old_hash);
+        if self.password_history.len() > Self::PASSWORD_HISTORY_LEN {
+            let excess = self.password_history.len() - Self::PASSWORD_HISTORY_LEN;
+            self.password_history.drain(..excess);
         }
         self.password_number += 1;
-        self.password_hash = Self::hash(self.uuid, self.password_number, new_password)?;
         Ok(())
     }
 
+    /// Whether `password` is the one `stored_hash` was made from. A wrong
+    /// password is `Ok(false)`; an unreadable hash is an error.
+    fn password_matches(password: &str, stored_hash: &str) -> Result<bool, anyhow::Error> {
+        let reference_hash = PasswordHash::new(stored_hash)
+            .map_err(|e| anyhow::anyhow!("Stored password hash is invalid: {}", e))?;
+
+        match Self::hasher().verify_password(password.as_bytes(), &reference_hash) {
+            Ok(()) => Ok(true),
+            Err(password_hash::Error::Password) => Ok(false),
+            Err(e) => Err(anyhow::anyhow!("Failed to verify password: {}", e)),
+        }
+    }
+
     pub fn disable_2fa(&mut self) {
         self.totp = None;
+        self.backup_codes.clear();
+    }
+
+    pub fn change_username(&mut self, new_username: String) -> Result<(), anyhow::Error> {
+        is_username_valid(&new_username)?;
+        self.username = new_username;
+        Ok(())
+    }
+
+    pub fn username(&self) -> &str {
+        &self.username
+    }
+
+    pub fn uuid(&self) -> Uuid {
+        self.uuid
+    }
+
+    pub fn password_number(&self) -> u32 {
+        self.password_number
+    }
+
+    pub fn to_record(&self) -> UserRecord {
+        UserRecord {
+            uuid: self.uuid,
+            username: self.username.clone(),
+            password_hash: self.password_hash.clone(),
+            password_number: self.password_number,
+            password_history: self.password_history.clone(),
+            totp_enabled: self.totp.is_some(),
+            totp_secret_base32: self.totp.as_ref().map(|totp| totp.get_secret_base32()),
+            backup_codes: self.backup_codes.clone(),
+            failed_attempts: self.failed_attempts,
+            locked_until: self.locked_until,
+        }
+    }
+
+    pub fn from_record(record: UserRecord) -> Result<Self, anyhow::Error> {
+        is_username_valid(&record.username)?;
+        PasswordHash::new(&record.password_hash)
+            .map_err(|e| anyhow::anyhow!("Stored password hash is invalid: {}", e))?;
+
+        let mut user = Self {
+            username: record.username,
+            uuid: record.uuid,
+            password_hash: record.password_hash,
+            password_number: record.password_number,
+            password_history: record.password_history,
+            totp: None,
+            backup_codes: record.backup_codes,
+            failed_attempts: record.failed_attempts,
+            locked_until: record.locked_until,
+        };
+
+        if record.totp_enabled {
+            let encoded_secret = record
+                .totp_secret_base32
+                .ok_or_else(|| anyhow::anyhow!("2FA is enabled but no TOTP secret is stored."))?;
+            let secret = Secret::Encoded(encoded_secret)
+                .to_bytes()
+                .map_err(|e| anyhow::anyhow!("Stored TOTP secret is invalid: {}", e))?;
+            user.totp = Some(user.totp(secret)?);
+        }
+
+        Ok(user)
+    }
+}
+
+fn is_username_valid(username: &str) -> Result<(), anyhow::Error> {
+    let is_acceptable_len = username.len() >= 3 && username.len() <= 32;
+    let has_allowed_chars = username
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-' || c == '.');
+
+    if !is_acceptable_len {
+        return Err(anyhow::anyhow!(
+            "Username must be between 3 and 32 characters long."
+        ));
+    }
+    if !has_allowed_chars {
+        return Err(anyhow::anyhow!(
+            "Username may only contain letters, digits, '_', '-' and '.'."
+        ));
     }
-    
+    Ok(())
 }
 
-fn is_password_compliant(password: &str) -> Result<(), anyhow::Error> {
-    let is_acceptable_len = password.len() >= 16 && password.len() <= 128;
-    let has_uppercase = password.chars().any(|c| c.is_uppercase());
-    let has_lowercase = password.chars().any(|c| c.is_lowercase());
-    let has_digit = password.chars().any(|c| c.is_digit(10));
-    let has_special = password.chars().any(|c| !c.is_alphanumeric());
-    let compliance_summary = (
-        is_acceptable_len,
-        has_uppercase,
-        has_lowercase,
-        has_digit,
-        has_special,
-    );
-    match compliance_summary {
-        (true, true, true, true, true) => Ok(()),
-        (false, _, _, _, _) => {
-            return Err(anyhow::anyhow!(
-                "Password must be between 16 and 128 characters long."
-            ))
-        }
-        (_, false, _, _, _) => {
-            return Err(anyhow::anyhow!(
-                "Password must contain at least one uppercase letter."
-            ))
-        }
-        (_, _, false, _, _) => {
-            return Err(anyhow::anyhow!(
-                "Password must contain at least one lowercase letter."
-            ))
-        }
-        (_, _, _, false, _) => {
-            return Err(anyhow::anyhow!(
-                "Password must contain at least one digit."
-            ))
-        }
-        (_, _, _, _, false) => {
-            return Err(anyhow::anyhow!(
-                "Password must contain at least one special character."
-            ))
+/// Bounds on a password's length, in characters.
+pub const MIN_PASSWORD_LEN: usize = 16;
+pub const MAX_PASSWORD_LEN: usize = 128;
+
+/// One way a password breaks the password rules. Each reads as the end of
+/// "Password must ...".
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum PasswordError {
+    TooShort,
+    TooLong,
+    MissingUppercase,
+    MissingLowercase,
+    MissingDigit,
+    MissingSpecial,
+}
+
+impl std::fmt::Display for PasswordError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::TooShort => write!(f, "be at least {} characters long", MIN_PASSWORD_LEN),
+            Self::TooLong => write!(f, "be at most {} characters long", MAX_PASSWORD_LEN),
+            Self::MissingUppercase => write!(f, "contain at least one uppercase letter"),
+            Self::MissingLowercase => write!(f, "contain at least one lowercase letter"),
+            Self::MissingDigit => write!(f, "contain at least one digit"),
+            Self::MissingSpecial => write!(f, "contain at least one special character"),
         }
     }
 }
 
+/// Returns every rule the password breaks, not just the first.
+pub fn is_password_compliant(password: &str) -> Result<(), Vec<PasswordError>> {
+    let mut problems = vec![];
+
+    let length = password.chars().count();
+    if length < MIN_PASSWORD_LEN {
+        problems.push(PasswordError::TooShort);
+    }
+    if length > MAX_PASSWORD_LEN {
+        problems.push(PasswordError::TooLong);
+    }
+    if !password.chars().any(|c| c.is_uppercase()) {
+        problems.push(PasswordError::MissingUppercase);
+    }
+    if !password.chars().any(|c| c.is_lowercase()) {
+        problems.push(PasswordError::MissingLowercase);
+    }
+    if !password.chars().any(|c| c.is_ascii_digit()) {
+        problems.push(PasswordError::MissingDigit);
+    }
+    if !password.chars().any(|c| !c.is_alphanumeric()) {
+        problems.push(PasswordError::MissingSpecial);
+    }
+
+    if problems.is_empty() {
+        Ok(())
+    } else {
+        Err(problems)
+    }
+}
+
+fn password_errors_message(problems: Vec<PasswordError>) -> anyhow::Error {
+    anyhow::anyhow!("Password must {}.", problems.iter().join(", "))
+}
+
+const COMMON_PASSWORD_WORDS: [&str; 16] = [
+    "password", "qwerty", "letmein", "welcome", "admin", "login", "dragon", "monkey", "sunshine",
+    "princess", "football", "baseball", "iloveyou", "master", "shadow", "secret",
+];
+
+/// Scores a password from 0 to 100.
+///
+/// This is guidance for the user, separate from `is_password_compliant`: up
+/// to 30 points for length, 20 for distinct characters, 20 for containing
+/// none of a small list of common words, and 30 for its Shannon entropy.
+pub fn password_strength(password: &str) -> u8 {
+    let chars: Vec<char> = password.chars().collect();
+    if chars.is_empty() {
+        return 0;
+    }
+
+    let mut char_counts: HashMap<char, usize> = HashMap::new();
+    for c in &chars {
+        *char_counts.entry(*c).or_default() += 1;
+    }
+
+    let length_points = chars.len().min(30) as u8;
+    let variety_points = char_counts.len().min(20) as u8;
+
+    let lowercase = password.to_lowercase();
+    let dictionary_points = if COMMON_PASSWORD_WORDS
+        .iter()
+        .any(|word| lowercase.contains(word))
+    {
+        0
+    } else {
+        20
+    };
+
+    let len = chars.len() as f64;
+    let bits_per_char: f64 = char_counts
+        .values()
+        .map(|&count| {
+            let p = count as f64 / len;
+            -p * p.log2()
+        })
+        .sum();
+    let entropy_points = (bits_per_char * len / 4.0).min(30.0) as u8;
+
+    length_points + variety_points + dictionary_points + entropy_points
+}
+
+pub fn password_strength_label(score: u8) -> &'static str {
+    match score {
+        0..=44 => "Weak",
+        45..=64 => "Fair",
+        65..=79 => "Strong",
+        _ => "Very Strong",
+    }
+}
+
+/// The line shown to a user who has just chosen `password`.
+pub fn password_strength_feedback(password: &str) -> String {
+    let score = password_strength(password);
+    format!(
+        "Password strength: {}/100 ({})",
+        score,
+        password_strength_label(score)
+    )
+}
 
 #[cfg(test)]
 mod tests {
@@ -181,7 +547,8 @@ mod tests {
         let username = String::from("testuser");
         let password = String::from("StrongPassword!123");
 
-        let user = User::new(username, password.clone()).expect("Failed to create user");
+        let mut user = User::new(username, password.clone(), &SecurityPolicy::default())
+            .expect("Failed to create user");
 
         assert!(user.verify_password(password).unwrap());
         assert!(!user.verify_password(String::from("WrongPassword")).unwrap());
@@ -192,17 +559,216 @@ mod tests {
         let username = String::from("testuser2");
         let password = String::from("AnotherStrongPass!456");
 
-        let mut user = User::new(username, password).expect("Failed to create user");
-        user.enable_2fa().expect("Failed to enable 2FA");
+        let mut user = User::new(username, password, &SecurityPolicy::default())
+            .expect("Failed to create user");
+        user.enable_2fa(&SecurityPolicy::default())
+            .expect("Failed to enable 2FA");
 
-        let secret = user.totp.clone().expect("TOTP secret should be set");
-        let code = EasyTotp::generate_token(secret)
+        let totp = user.totp.clone().expect("TOTP secret should be set");
+        let code = totp
+            .generate_current()
             .expect("Failed to generate TOTP code");
 
         assert!(user.verify_totp(&code).unwrap());
         assert!(!user.verify_totp("000000").unwrap());
     }
 
+    #[test]
+    fn test_user_record_round_trip() {
+        let password = String::from("RoundTripPassword!789");
+        let mut user = User::new(
+            String::from("testuser8"),
+            password.clone(),
+            &SecurityPolicy::default(),
+        )
+        .expect("Failed to create user");
+        user.enable_2fa(&SecurityPolicy::default())
+            .expect("Failed to enable 2FA");
+
+        let record = user.to_record();
+        assert!(record.totp_enabled);
+        assert!(record.totp_secret_base32.is_some());
+
+        let mut restored = User::from_record(record.clone()).expect("Failed to restore user");
+        assert_eq!(restored.username(), user.username());
+        assert_eq!(restored.uuid(), user.uuid());
+        assert_eq!(restored.to_record(), record);
+        assert!(restored.verify_password(password).unwrap());
+
+        let code = user.totp.as_ref().unwrap().generate_current().unwrap();
+        assert!(restored.verify_totp(&code).unwrap());
+    }
+
+    #[test]
+    fn test_user_record_survives_json() {
+        let user = User::new(
+            String::from("testuser9"),
+            String::from("JsonRoundTrip!12345"),
+            &SecurityPolicy::default(),
+        )
+        .expect("Failed to create user");
+
+        let json = serde_json::to_string(&user.to_record()).unwrap();
+        let record: UserRecord = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(record, user.to_record());
+        assert!(!record.totp_enabled);
+        assert!(User::from_record(record).is_ok());
+    }
+
+    #[test]
+    fn test_lockout_after_repeated_failures_and_recovery() {
+        let password = String::from("LockoutPassword!123");
+        let mut user = User::new(
+            String::from("testuser11"),
+            password.clone(),
+            &SecurityPolicy::default(),
+        )
+        .expect("Failed to create user");
+        let start = SystemTime::now();
+
+        for _ in 0..4 {
+            assert!(
+                !user
+                    .verify_password_at(String::from("WrongPassword"), start)
+                    .unwrap()
+            );
+        }
+        assert!(!user.is_locked_at(start));
+
+        assert!(
+            !user
+                .verify_password_at(String::from("WrongPassword"), start)
+                .unwrap()
+        );
+        assert!(user.is_locked_at(start));
+
+        // Even the right password is refused while locked
+        let ten_minutes_later = start + Duration::from_secs(10 * 60);
+        let error = user
+            .verify_password_at(password.clone(), ten_minutes_later)
+            .unwrap_err()
+            .to_string();
+        assert_eq!(error, "Account is locked. Try again in 300 seconds.");
+
+        let sixteen_minutes_later = start + Duration::from_secs(16 * 60);
+        assert!(!user.is_locked_at(sixteen_minutes_later));
+        assert!(
+            user.verify_password_at(password, sixteen_minutes_later)
+                .unwrap()
+        );
+        assert_eq!(user.failed_attempts, 0);
+        assert_eq!(user.locked_until, None);
+    }
+
+    #[test]
+    fn test_successful_login_resets_failed_attempts() {
+        let password = String::from("ResetAttempts!12345");
+        let mut user = User::new(
+            String::from("testuser12"),
+            password.clone(),
+            &SecurityPolicy::default(),
+        )
+        .expect("Failed to create user");
+
+        for _ in 0..4 {
+            assert!(!user.verify_password(String::from("WrongPassword")).unwrap());
+        }
+        assert!(user.verify_password(password).unwrap());
+        assert_eq!(user.failed_attempts, 0);
+
+        for _ in 0..4 {
+            assert!(!user.verify_password(String::from("WrongPassword")).unwrap());
+        }
+        assert!(!user.is_locked());
+    }
+
+    #[test]
+    fn test_lockout_survives_record_round_trip() {
+        let mut user = User::new(
+            String::from("testuser13"),
+            String::from("PersistLockout!12345"),
+            &SecurityPolicy::default(),
+        )
+        .expect("Failed to create user");
+        for _ in 0..5 {
+            assert!(!user.verify_password(String::from("WrongPassword")).unwrap());
+        }
+
+        let restored = User::from_record(user.to_record()).expect("Failed to restore user");
+
+        assert!(restored.is_locked());
+        assert_eq!(restored.to_record(), user.to_record());
+    }
+
+    #[test]
+    fn test_backup_codes_work_exactly_once() {
+        let mut user = User::new(
+            String::from("testuser14"),
+            String::from("BackupCodesPass!123"),
+            &SecurityPolicy::default(),
+        )
+        .expect("Failed to create user");
+        let codes = user
+            .enable_2fa(&SecurityPolicy::default())
+            .expect("Failed to enable 2FA");
+
+        assert_eq!(codes.len(), 8);
+        assert!(
+            codes
+                .iter()
+                .all(|code| code.len() == 8 && code.chars().all(|c| c.is_ascii_alphanumeric()))
+        );
+        assert!(!user.backup_codes.contains(&codes[0]));
+        assert_eq!(user.remaining_backup_codes(), 8);
+
+        assert!(!user.consume_backup_code("NOTACODE").unwrap());
+        assert_eq!(user.remaining_backup_codes(), 8);
+
+        for (used, code) in codes.iter().enumerate() {
+            assert!(user.consume_backup_code(code).unwrap());
+            assert!(!user.consume_backup_code(code).unwrap());
+            assert_eq!(user.remaining_backup_codes(), 8 - used - 1);
+        }
+
+        assert!(user.backup_codes.is_empty());
+    }
+
+    #[test]
+    fn test_backup_codes_survive_record_round_trip() {
+        let mut user = User::new(
+            String::from("testuser15"),
+            String::from("BackupRoundTrip!123"),
+            &SecurityPolicy::default(),
+        )
+        .expect("Failed to create user");
+        let codes = user
+            .enable_2fa(&SecurityPolicy::default())
+            .expect("Failed to enable 2FA");
+
+        let mut restored = User::from_record(user.to_record()).expect("Failed to restore user");
+
+        assert_eq!(restored.remaining_backup_codes(), 8);
+        assert!(restored.consume_backup_code(&codes[3]).unwrap());
+        assert_eq!(restored.remaining_backup_codes(), 7);
+    }
+
+    #[test]
+    fn test_user_record_rejects_missing_totp_secret() {
+        let user = User::new(
+            String::from("testuser10"),
+            String::from("MissingSecret!12345"),
+            &SecurityPolicy::default(),
+        )
+        .expect("Failed to create user");
+        let record = UserRecord {
+            totp_enabled: true,
+            ..user.to_record()
+        };
+
+        assert!(User::from_record(record).is_err());
+    }
+
     #[test]
     fn test_password_compliance() {
         let compliant_password = "ValidPass3045713y5t31ght!";
@@ -221,27 +787,301 @@ mod tests {
         assert!(is_password_compliant(&too_long_password).is_err());
     }
 
+    #[test]
+    fn test_password_length_counts_characters_not_bytes() {
+        // Two bytes per "é", so each is well over its length in bytes
+        let fifteen_chars = format!("Aa1!{}", "é".repeat(11));
+        let sixteen_chars = format!("Aa1!{}", "é".repeat(12));
+        let max_chars = format!("Aa1!{}", "é".repeat(MAX_PASSWORD_LEN - 4));
+        let too_many_chars = format!("Aa1!{}", "é".repeat(MAX_PASSWORD_LEN - 3));
+
+        assert_eq!(
+            is_password_compliant(&fifteen_chars),
+            Err(vec![PasswordError::TooShort])
+        );
+        assert!(is_password_compliant(&sixteen_chars).is_ok());
+        assert!(is_password_compliant(&max_chars).is_ok());
+        assert_eq!(
+            is_password_compliant(&too_many_chars),
+            Err(vec![PasswordError::TooLong])
+        );
+    }
+
+    #[test]
+    fn test_password_strength_scores() {
+        assert_eq!(password_strength(""), 0);
+        assert_eq!(password_strength("password"), 20);
+        assert_eq!(password_strength("aaaaaaaaaaaaaaaaaaaa"), 41);
+        assert_eq!(password_strength("Tr0ub4dor&3"), 50);
+        assert_eq!(password_strength("blue-Kettle-47-Sky"), 65);
+        assert_eq!(password_strength("T9#vQ2!mZ8$kL4&xR7@w"), 81);
+    }
+
+    #[test]
+    fn test_password_strength_label_boundaries() {
+        assert_eq!(password_strength_label(0), "Weak");
+        assert_eq!(password_strength_label(44), "Weak");
+        assert_eq!(password_strength_label(45), "Fair");
+        assert_eq!(password_strength_label(64), "Fair");
+        assert_eq!(password_strength_label(65), "Strong");
+        assert_eq!(password_strength_label(79), "Strong");
+        assert_eq!(password_strength_label(80), "Very Strong");
+        assert_eq!(password_strength_label(100), "Very Strong");
+    }
+
+    #[test]
+    fn test_password_strength_feedback_shows_score_and_label() {
+        assert_eq!(
+            password_strength_feedback("blue-Kettle-47-Sky"),
+            "Password strength: 65/100 (Strong)"
+        );
+    }
+
+    #[test]
+    fn test_password_compliance_reports_every_problem() {
+        assert_eq!(
+            is_password_compliant("short"),
+            Err(vec![
+                PasswordError::TooShort,
+                PasswordError::MissingUppercase,
+                PasswordError::MissingDigit,
+                PasswordError::MissingSpecial,
+            ])
+        );
+        assert_eq!(
+            is_password_compliant(""),
+            Err(vec![
+                PasswordError::TooShort,
+                PasswordError::MissingUppercase,
+                PasswordError::MissingLowercase,
+                PasswordError::MissingDigit,
+                PasswordError::MissingSpecial,
+            ])
+        );
+        assert_eq!(
+            is_password_compliant(&"a".repeat(129)),
+            Err(vec![
+                PasswordError::TooLong,
+                PasswordError::MissingUppercase,
+                PasswordError::MissingDigit,
+                PasswordError::MissingSpecial,
+            ])
+        );
+    }
+
+    #[test]
+    fn test_password_errors_are_all_listed_in_the_message() {
+        let Err(error) = User::new(
+            String::from("testuser"),
+            String::from("short"),
+            &SecurityPolicy::default(),
+        ) else {
+            panic!("a short password should be refused");
+        };
+
+        assert_eq!(
+            error.to_string(),
+            "Password must be at least 16 characters long, contain at least one uppercase \
+             letter, contain at least one digit, contain at least one special character."
+        );
+    }
+
+    #[test]
+    fn test_password_hashes_are_salted() {
+        let password = String::from("SamePasswordTwice!123");
+        let mut user_a = User::new(
+            String::from("testuser6"),
+            password.clone(),
+            &SecurityPolicy::default(),
+        )
+        .expect("Failed to create user");
+        let mut user_b = User::new(
+            String::from("testuser7"),
+            password.clone(),
+            &SecurityPolicy::default(),
+        )
+        .expect("Failed to create user");
+
+        assert_ne!(user_a.password_hash, user_b.password_hash);
+        assert!(user_a.verify_password(password.clone()).unwrap());
+        assert!(user_b.verify_password(password).unwrap());
+    }
+
+    #[test]
+    fn test_security_policy_controls_new_hashes() {
+        let password = String::from("PolicyPassword!123");
+        let stronger = SecurityPolicy {
+            memory_kib: 4096,
+            iterations: 2,
+            parallelism: 2,
+        };
+
+        let mut cheap = User::new(
+            String::from("testuser15"),
+            password.clone(),
+            &SecurityPolicy::default(),
+        )
+        .expect("Failed to create user");
+        let mut strong = User::new(String::from("testuser16"), password.clone(), &stronger)
+            .expect("Failed to create user");
+
+        // The cost parameters are the fourth field of the PHC string
+        let params = |user: &User| user.password_hash.split('$').nth(3).unwrap().to_owned();
+        assert_ne!(params(&cheap), params(&strong));
+        assert_eq!(params(&strong), "m=4096,t=2,p=2");
+
+        assert!(cheap.verify_password(password.clone()).unwrap());
+        assert!(strong.verify_password(password).unwrap());
+
+        let codes = strong.enable_2fa(&stronger).expect("Failed to enable 2FA");
+        assert!(
+            strong
+                .backup_codes
+                .iter()
+                .all(|hash| hash.split('$').nth(3) == Some("m=4096,t=2,p=2"))
+        );
+        assert!(strong.consume_backup_code(&codes[0]).unwrap());
+    }
+
     #[test]
     fn test_change_password() {
         let username = String::from("testuser3");
         let old_password = String::from("OldStrongPass!789");
         let new_password = String::from("NewStrongPass!012");
-        let mut user = User::new(username, old_password.clone()).expect("Failed to create user");
+        let mut user = User::new(username, old_password.clone(), &SecurityPolicy::default())
+            .expect("Failed to create user");
         assert!(user.verify_password(old_password).unwrap());
-        user.change_password(new_password.clone()).expect("Failed to change password");
+        user.change_password(new_password.clone(), &SecurityPolicy::default())
+            .expect("Failed to change password");
         assert!(user.verify_password(new_password).unwrap());
     }
 
+    #[test]
+    fn test_change_password_rejects_the_current_password() {
+        let password = String::from("SamePassword!123");
+        let mut user = User::new(
+            String::from("testuser12"),
+            password.clone(),
+            &SecurityPolicy::default(),
+        )
+        .expect("Failed to create user");
+
+        assert!(
+            user.change_password(password.clone(), &SecurityPolicy::default())
+                .is_err()
+        );
+        assert_eq!(user.password_number(), 0);
+        assert!(user.verify_password(password).unwrap());
+    }
+
+    #[test]
+    fn test_password_history_is_capped_at_ten() {
+        let first_password = String::from("FirstPassword!000");
+        let mut user = User::new(
+            String::from("testuser13"),
+            first_password.clone(),
+            &SecurityPolicy::default(),
+        )
+        .expect("Failed to create user");
+
+        for n in 1..=User::PASSWORD_HISTORY_LEN {
+            user.change_password(
+                format!("RotatedPassword!0{}", n),
+                &SecurityPolicy::default(),
+            )
+            .expect("Failed to change password");
+            assert!(user.password_history.len() <= User::PASSWORD_HISTORY_LEN);
+        }
+
+        // Ten changes ago is still remembered
+        assert!(
+            user.change_password(first_password.clone(), &SecurityPolicy::default())
+                .is_err()
+        );
+        assert!(
+            user.change_password(
+                String::from("RotatedPassword!01"),
+                &SecurityPolicy::default()
+            )
+            .is_err()
+        );
+
+        user.change_password(
+            String::from("RotatedPassword!011"),
+            &SecurityPolicy::default(),
+        )
+        .expect("Failed to change password");
+        assert_eq!(user.password_history.len(), User::PASSWORD_HISTORY_LEN);
+
+        // Eleven changes ago has dropped out of the history
+        user.change_password(first_password.clone(), &SecurityPolicy::default())
+            .expect("Old password should be allowed again");
+        assert!(user.verify_password(first_password).unwrap());
+    }
+
+    #[test]
+    fn test_password_history_survives_record_round_trip() {
+        let first_password = String::from("FirstPassword!000");
+        let mut user = User::new(
+            String::from("testuser14"),
+            first_password.clone(),
+            &SecurityPolicy::default(),
+        )
+        .expect("Failed to create user");
+        user.change_password(
+            String::from("SecondPassword!000"),
+            &SecurityPolicy::default(),
+        )
+        .expect("Failed to change password");
+
+        let mut restored = User::from_record(user.to_record()).expect("Failed to restore user");
+
+        assert!(
+            restored
+                .change_password(first_password, &SecurityPolicy::default())
+                .is_err()
+        );
+    }
+
     #[test]
     fn test_disable_2fa() {
         let username = String::from("testuser4");
         let password = String::from("Disable2FAPass!345");
-        let mut user = User::new(username, password).expect("Failed to create user");
-        user.enable_2fa().expect("Failed to enable 2FA");
+        let mut user = User::new(username, password, &SecurityPolicy::default())
+            .expect("Failed to create user");
+        user.enable_2fa(&SecurityPolicy::default())
+            .expect("Failed to enable 2FA");
         assert!(user.totp.is_some());
         user.disable_2fa();
         assert!(user.totp.is_none());
 
     }
 
-}
\ No newline at end of file
+    #[test]
+    fn test_username_validity() {
+        assert!(is_username_valid("testuser").is_ok());
+        assert!(is_username_valid("test.user_1-a").is_ok());
+        assert!(is_username_valid("ab").is_err());
+        assert!(is_username_valid(&"a".repeat(33)).is_err());
+        assert!(is_username_valid("test user").is_err());
+        assert!(is_username_valid("test:user").is_err());
+    }
+
+    #[test]
+    fn test_change_username() {
+        let username = String::from("testuser5");
+        let password = String::from("ChangeUsername!678");
+        let mut user = User::new(username.clone(), password, &SecurityPolicy::default())
+            .expect("Failed to create user");
+
+        user.change_username(username.clone()).expect("Failed to keep the same username");
+        assert_eq!(user.username(), username);
+
+        assert!(user.change_username(String::from("no spaces allowed")).is_err());
+        assert_eq!(user.username(), username);
+
+        user.change_username(String::from("renamed_user")).expect("Failed to change username");
+        assert_eq!(user.username(), "renamed_user");
+    }
+
+}
@@ -0,0 +1,115 @@
+//! Argon2 cost parameters for new password and backup code hashes.
+//!
+//! Changing the policy only affects hashes made from then on. Existing
+//! hashes keep the parameters they were made with, since those are part of
+//! the PHC string and the verifier reads them from there.
+
+use std::{fs, path::Path};
+
+use argon2::{Algorithm, Argon2, Params, Version};
+use serde::Deserialize;
+
+#[derive(Deserialize, PartialEq, Eq, Debug, Clone, Copy)]
+#[serde(default, deny_unknown_fields)]
+pub struct SecurityPolicy {
+    pub memory_kib: u32,
+    pub iterations: u32,
+    pub parallelism: u32,
+}
+
+impl SecurityPolicy {
+    const HASH_ALGO: Algorithm = Algorithm::Argon2id;
+    const HASH_VERSION: Version = Version::V0x13;
+    const HASH_OUTPUT_LEN: usize = 32;
+
+    #[cfg(test)]
+    const DEFAULT_MEMORY_KIB: u32 = 1945;
+    #[cfg(test)]
+    const DEFAULT_ITERATIONS: u32 = 1;
+
+    #[cfg(not(test))]
+    const DEFAULT_MEMORY_KIB: u32 = 19_456;
+    #[cfg(not(test))]
+    const DEFAULT_ITERATIONS: u32 = 8;
+
+    const DEFAULT_PARALLELISM: u32 = 1;
+
+    /// Reads a policy such as
+    ///
+    /// ```toml
+    /// memory_kib = 65536
+    /// iterations = 3
+    /// parallelism = 4
+    /// ```
+    ///
+    /// Missing keys keep their default values.
+    pub fn from_toml(path: &Path) -> Result<Self, anyhow::Error> {
+        let contents = fs::read_to_string(path)
+            .map_err(|e| anyhow::anyhow!("Failed to read {}: {}", path.display(), e))?;
+        let policy: Self = toml::from_str(&contents)
+            .map_err(|e| anyhow::anyhow!("Failed to parse {}: {}", path.display(), e))?;
+
+        // Catch parameters Argon2 won't accept now rather than at the next login
+        policy.hasher()?;
+
+        Ok(policy)
+    }
+
+    pub(super) fn hasher(&self) -> Result<Argon2<'static>, anyhow::Error> {
+        let params = Params::new(
+            self.memory_kib,
+            self.iterations,
+            self.parallelism,
+            Some(Self::HASH_OUTPUT_LEN),
+        )
+        .map_err(|e| anyhow::anyhow!("Invalid Argon2 parameters: {}", e))?;
+
+        Ok(Argon2::new(Self::HASH_ALGO, Self::HASH_VERSION, params))
+    }
+}
+
+impl Default for SecurityPolicy {
+    /// The production costs. Test builds use cheaper ones to keep the
+    /// suite fast.
+    fn default() -> Self {
+        Self {
+            memory_kib: Self::DEFAULT_MEMORY_KIB,
+            iterations: Self::DEFAULT_ITERATIONS,
+            parallelism: Self::DEFAULT_PARALLELISM,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Write;
+
+    use super::*;
+
+    #[test]
+    fn test_from_toml_fills_in_missing_keys() {
+        let mut file = tempfile::NamedTempFile::new().unwrap();
+        write!(file, "memory_kib = 4096\niterations = 2\n").unwrap();
+
+        let policy = SecurityPolicy::from_toml(file.path()).expect("Failed to load policy");
+
+        assert_eq!(
+            policy,
+            SecurityPolicy {
+                memory_kib: 4096,
+                iterations: 2,
+                ..SecurityPolicy::default()
+            }
+        );
+    }
+
+    #[test]
+    fn test_invalid_parameters_are_rejected() {
+        let policy = SecurityPolicy {
+            iterations: 0,
+            ..SecurityPolicy::default()
+        };
+
+        assert!(policy.hasher().is_err());
+    }
+}
@@ -0,0 +1,413 @@
+//! A JSON file of `UserRecord`s keyed by username.
+//!
+//! Every change is saved straight away, and undone again if the save fails.
+//! The file is written to a temporary sibling first and then renamed over
+//! the original, so a crash part way through a save leaves either the old
+//! file or the new one, never half of each. As it holds password hashes and
+//! TOTP secrets, only its owner can read it. The store holds no interior
+//! mutability, so sharing it between threads means wrapping it in a `Mutex`
+//! like any other owned value.
+
+use std::{collections::HashMap, fs, io::ErrorKind, path::PathBuf};
+
+use uuid::Uuid;
+
+use super::{SecurityPolicy, User, UserRecord};
+use crate::io_utils::atomic_write_private;
+
+pub struct UserStore {
+    users: HashMap<String, UserRecord>,
+    path: PathBuf,
+    policy: SecurityPolicy,
+}
+
+impl UserStore {
+    /// Reads the users saved at `path`, or starts empty if there is no
+    /// file there yet.
+    pub fn load_from_file(path: impl Into<PathBuf>) -> Result<Self, anyhow::Error> {
+        let path = path.into();
+
+        let users = match fs::read(&path) {
+            Ok(bytes) => serde_json::from_slice(&bytes)
+                .map_err(|e| anyhow::anyhow!("Failed to parse {}: {}", path.display(), e))?,
+            Err(e) if e.kind() == ErrorKind::NotFound => HashMap::new(),
+            Err(e) => return Err(anyhow::anyhow!("Failed to read {}: {}", path.display(), e)),
+        };
+
+        Ok(Self {
+            users,
+            path,
+            policy: SecurityPolicy::default(),
+        })
+    }
+
+    /// Hashes the passwords of users created from now on with `policy`.
+    pub fn with_security_policy(mut self, policy: SecurityPolicy) -> Self {
+        self.policy = policy;
+        self
+    }
+
+    pub fn save_to_file(&self) -> Result<(), anyhow::Error> {
+        atomic_write_private(&self.path, &serde_json::to_vec(&self.users)?)?;
+        Ok(())
+    }
+
+    // A change that could not be saved would be lost on restart anyway
+    fn save_or_undo(
+        &mut self,
+        undo: impl FnOnce(&mut HashMap<String, UserRecord>),
+    ) -> Result<(), anyhow::Error> {
+        self.save_to_file().inspect_err(|_| undo(&mut self.users))
+    }
+
+    pub fn create_user(
+        &mut self,
+        username: String,
+        password: String,
+    ) -> Result<User, anyhow::Error> {
+        if self.users.contains_key(&username) {
+            return Err(anyhow::anyhow!("Username {} is already taken.", username));
+        }
+
+        let user = User::new(username, password, &self.policy)?;
+        self.users
+            .insert(user.username().to_owned(), user.to_record());
+        self.save_or_undo(|users| {
+            users.remove(user.username());
+        })?;
+
+        Ok(user)
+    }
+
+    pub fn get_user(&self, username: &str) -> Result<User, anyhow::Error> {
+        let record = self
+            .users
+            .get(username)
+            .ok_or_else(|| anyhow::anyhow!("No user named {}.", username))?;
+
+        User::from_record(record.clone())
+    }
+
+    /// Saves `user` over the stored user with the same UUID, which also
+    /// picks up a changed username.
+    pub fn update_user(&mut self, user: &User) -> Result<(), anyhow::Error> {
+        let old_username = self
+            .users
+            .iter()
+            .find(|(_, record)| record.uuid == user.uuid())
+            .map(|(username, _)| username.clone())
+            .ok_or_else(|| anyhow::anyhow!("User {} is not in the store.", user.username()))?;
+
+        if old_username != user.username() && self.users.contains_key(user.username()) {
+            return Err(anyhow::anyhow!(
+                "Username {} is already taken.",
+                user.username()
+            ));
+        }
+
+        let old_record = self.users.remove(&old_username).unwrap();
+        self.users
+            .insert(user.username().to_owned(), user.to_record());
+        self.save_or_undo(|users| {
+            users.remove(user.username());
+            users.insert(old_username, old_record);
+        })
+    }
+
+    /// Renames the user with `user_id`, as long as no other user has
+    /// `new_username` already.
+    pub fn change_username(
+        &mut self,
+        user_id: Uuid,
+        new_username: String,
+    ) -> Result<(), anyhow::Error> {
+        let record = self
+            .get_user_by_uuid(user_id)
+            .ok_or_else(|| anyhow::anyhow!("No user with id {}.", user_id))?;
+
+        if self
+            .users
+            .get(&new_username)
+            .is_some_and(|other| other.uuid != user_id)
+        {
+            return Err(anyhow::anyhow!(
+                "Username {} is already taken.",
+                new_username
+            ));
+        }
+
+        let mut user = User::from_record(record.clone())?;
+        user.change_username(new_username)?;
+        self.update_user(&user)
+    }
+
+    pub fn delete_user(&mut self, username: &str) -> Result<(), anyhow::Error> {
+        let record = self
+            .users
+            .remove(username)
+            .ok_or_else(|| anyhow::anyhow!("No user named {}.", username))?;
+        self.save_or_undo(|users| {
+            users.insert(username.to_owned(), record);
+        })
+    }
+
+    /// Unknown usernames and wrong passwords both give `Ok(false)`, so a
+    /// caller can't tell which one it was. A locked account is an error.
+    pub fn authenticate(&mut self, username: &str, password: &str) -> Result<bool, anyhow::Error> {
+        let Some(record) = self.users.get(username) else {
+            return Ok(false);
+        };
+
+        let mut user = User::from_record(record.clone())?;
+        let verified = user.verify_password(password.to_owned());
+
+        // Failed attempts and lockouts have to outlive this call
+        if user.to_record() != *record {
+            self.users.insert(username.to_owned(), user.to_record());
+            self.save_to_file()?;
+        }
+
+        verified
+    }
+
+    /// Sorted alphabetically
+    pub fn list_usernames(&self) -> Vec<String> {
+        let mut usernames: Vec<String> = self.users.keys().cloned().collect();
+        usernames.sort();
+        usernames
+    }
+
+    pub fn count(&self) -> usize {
+        self.users.len()
+    }
+
+    pub fn user_exists(&self, username: &str) -> bool {
+        self.users.contains_key(username)
+    }
+
+    /// Finds a user by the UUID they keep across username changes.
+    pub fn get_user_by_uuid(&self, uuid: Uuid) -> Option<&UserRecord> {
+        self.users.values().find(|record| record.uuid == uuid)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::io_utils::tmp_path_for;
+
+    const PASSWORD: &str = "StoredPassword!123";
+
+    fn empty_store(dir: &tempfile::TempDir) -> UserStore {
+        UserStore::load_from_file(dir.path().join("users.json")).expect("Failed to load store")
+    }
+
+    fn renamed(user: &User, username: &str) -> User {
+        User::from_record(UserRecord {
+            username: username.to_owned(),
+            ..user.to_record()
+        })
+        .unwrap()
+    }
+
+    #[test]
+    fn test_create_and_get_user() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut store = empty_store(&dir);
+
+        let user = store
+            .create_user(String::from("storeuser1"), String::from(PASSWORD))
+            .expect("Failed to create user");
+
+        let fetched = store.get_user("storeuser1").expect("Failed to get user");
+        assert_eq!(fetched.to_record(), user.to_record());
+        assert!(store.get_user("nobody").is_err());
+        assert!(
+            store
+                .create_user(String::from("storeuser1"), String::from(PASSWORD))
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_update_user_follows_username_changes() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut store = empty_store(&dir);
+        let user = store
+            .create_user(String::from("storeuser2"), String::from(PASSWORD))
+            .unwrap();
+
+        let user = renamed(&user, "storeuser2b");
+        store.update_user(&user).expect("Failed to update user");
+
+        assert_eq!(store.list_usernames(), vec!["storeuser2b"]);
+        assert_eq!(store.get_user("storeuser2b").unwrap().uuid(), user.uuid());
+    }
+
+    #[test]
+    fn test_change_username() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut store = empty_store(&dir);
+        let user = store
+            .create_user(String::from("storeuser9"), String::from(PASSWORD))
+            .unwrap();
+        store
+            .create_user(String::from("storeuser9b"), String::from(PASSWORD))
+            .unwrap();
+
+        store
+            .change_username(user.uuid(), String::from("storeuser9"))
+            .expect("Failed to keep the same username");
+        assert!(
+            store
+                .change_username(user.uuid(), String::from("storeuser9b"))
+                .is_err()
+        );
+        assert!(
+            store
+                .change_username(user.uuid(), String::from("no spaces allowed"))
+                .is_err()
+        );
+        assert!(
+            store
+                .change_username(Uuid::new_v4(), String::from("storeuser9c"))
+                .is_err()
+        );
+        assert_eq!(store.list_usernames(), vec!["storeuser9", "storeuser9b"]);
+
+        store
+            .change_username(user.uuid(), String::from("storeuser9c"))
+            .expect("Failed to change username");
+        assert_eq!(store.get_user("storeuser9c").unwrap().uuid(), user.uuid());
+        assert!(store.get_user("storeuser9").is_err());
+
+        let reloaded = UserStore::load_from_file(dir.path().join("users.json")).unwrap();
+        assert_eq!(
+            reloaded.get_user("storeuser9c").unwrap().uuid(),
+            user.uuid()
+        );
+    }
+
+    #[test]
+    fn test_delete_user() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut store = empty_store(&dir);
+        store
+            .create_user(String::from("storeuser3"), String::from(PASSWORD))
+            .unwrap();
+
+        store
+            .delete_user("storeuser3")
+            .expect("Failed to delete user");
+
+        assert!(store.get_user("storeuser3").is_err());
+        assert!(store.delete_user("storeuser3").is_err());
+    }
+
+    #[test]
+    fn test_authentication_failure_is_not_an_error() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut store = empty_store(&dir);
+        store
+            .create_user(String::from("storeuser4"), String::from(PASSWORD))
+            .unwrap();
+
+        assert!(store.authenticate("storeuser4", PASSWORD).unwrap());
+        assert!(!store.authenticate("storeuser4", "WrongPassword").unwrap());
+        assert!(!store.authenticate("nobody", PASSWORD).unwrap());
+    }
+
+    #[test]
+    fn test_saves_are_atomic_and_reload() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("users.json");
+        let mut store = UserStore::load_from_file(&path).unwrap();
+        store
+            .create_user(String::from("storeuser5"), String::from(PASSWORD))
+            .unwrap();
+
+        assert!(path.exists());
+        assert!(!tmp_path_for(&path).exists());
+
+        let reloaded = UserStore::load_from_file(&path).unwrap();
+        assert_eq!(reloaded.list_usernames(), vec!["storeuser5"]);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_file_is_only_readable_by_its_owner() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("users.json");
+        let mut store = UserStore::load_from_file(&path).unwrap();
+        store
+            .create_user(String::from("storeuser7"), String::from(PASSWORD))
+            .unwrap();
+
+        let mode = fs::metadata(&path).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o600);
+    }
+
+    #[test]
+    fn test_failed_saves_leave_the_store_unchanged() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut store = empty_store(&dir);
+        let user = store
+            .create_user(String::from("storeuser8"), String::from(PASSWORD))
+            .unwrap();
+        // A directory cannot be written over like a file
+        store.path = dir.path().to_owned();
+
+        assert!(
+            store
+                .create_user(String::from("storeuser8b"), String::from(PASSWORD))
+                .is_err()
+        );
+        assert!(store.get_user("storeuser8b").is_err());
+
+        assert!(store.update_user(&renamed(&user, "storeuser8c")).is_err());
+        assert_eq!(store.list_usernames(), vec!["storeuser8"]);
+
+        assert!(store.delete_user("storeuser8").is_err());
+        assert!(store.get_user("storeuser8").is_ok());
+    }
+
+    #[test]
+    fn test_empty_store_has_no_users() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = empty_store(&dir);
+
+        assert!(store.list_usernames().is_empty());
+        assert_eq!(store.count(), 0);
+        assert!(!store.user_exists("nobody"));
+        assert!(store.get_user_by_uuid(Uuid::new_v4()).is_none());
+    }
+
+    #[test]
+    fn test_lists_and_finds_multiple_users() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut store = empty_store(&dir);
+        let mut users = vec![];
+        for username in ["storeuser6c", "storeuser6a", "storeuser6b"] {
+            users.push(
+                store
+                    .create_user(String::from(username), String::from(PASSWORD))
+                    .unwrap(),
+            );
+        }
+
+        assert_eq!(
+            store.list_usernames(),
+            vec!["storeuser6a", "storeuser6b", "storeuser6c"]
+        );
+        assert_eq!(store.count(), 3);
+        assert!(store.user_exists("storeuser6b"));
+        assert!(!store.user_exists("storeuser6"));
+        for user in &users {
+            let record = store.get_user_by_uuid(user.uuid()).unwrap();
+            assert_eq!(record.username, user.username());
+        }
+        assert!(store.get_user_by_uuid(Uuid::new_v4()).is_none());
+    }
+}
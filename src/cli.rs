@@ -0,0 +1,608 @@
+use std::{
+    fs::File,
+    io::{self, BufReader, BufWriter, IsTerminal, Write},
+    path::Path,
+};
+
+use anyhow::{Context, Result, anyhow};
+use clap::{Parser, Subcommand, ValueEnum};
+use itertools::Itertools;
+use serde::Serialize;
+
+use crate::{
+    db::{ImportReport, JiraDatabase},
+    export::{self, MarkdownFormat},
+    io_utils,
+    models::{Epic, Status, Story},
+};
+
+/// Command line arguments. Without a subcommand the interactive UI starts.
+#[derive(Parser, Debug)]
+#[command(name = "ironyy", about = "A terminal issue tracker")]
+pub struct Cli {
+    /// Path to the database file
+    #[arg(long, default_value = "./data/db.json")]
+    pub db: String,
+
+    /// How results are printed
+    #[arg(long, value_enum, global = true, default_value = "json")]
+    pub format: OutputFormat,
+
+    /// Who to credit for new items and record in the audit log
+    #[arg(long, global = true)]
+    pub user: Option<String>,
+
+    #[command(subcommand)]
+    pub command: Option<Command>,
+}
+
+#[derive(Subcommand, Debug, PartialEq, Eq)]
+pub enum Command {
+    /// Print every epic in the active project
+    ListEpics,
+    /// Print the stories of an epic
+    ListStories {
+        #[arg(long)]
+        epic: u32,
+    },
+    CreateEpic {
+        #[arg(long)]
+        name: String,
+        #[arg(long)]
+        desc: String,
+    },
+    CreateStory {
+        #[arg(long)]
+        epic: u32,
+        #[arg(long)]
+        name: String,
+        #[arg(long)]
+        desc: String,
+    },
+    UpdateStatus {
+        #[arg(long = "type", value_enum)]
+        item_type: ItemType,
+        #[arg(long)]
+        id: u32,
+        #[arg(long)]
+        status: String,
+    },
+    Delete {
+        #[arg(long = "type", value_enum)]
+        item_type: ItemType,
+        #[arg(long)]
+        id: u32,
+    },
+    /// Add the epics and stories of a CSV file written by export-csv
+    ImportCsv {
+        #[arg(long)]
+        path: String,
+        /// Skip stories whose epic cannot be found instead of importing nothing
+        #[arg(long)]
+        lenient: bool,
+    },
+    /// Write every epic and story of the active project to a CSV file
+    ExportCsv {
+        #[arg(long)]
+        path: String,
+    },
+    /// Write the active project to a Markdown file
+    ExportMarkdown {
+        #[arg(long)]
+        path: String,
+        #[arg(long, value_enum, default_value = "report")]
+        markdown_format: MarkdownFormat,
+    },
+    /// Drop story references that point at deleted stories
+    Repair {
+        /// Also delete sprints that ended more than this many days ago
+        #[arg(long)]
+        purge_sprints_older_than: Option<u32>,
+    },
+    Sprint {
+        #[command(subcommand)]
+        command: SprintCommand,
+    },
+}
+
+#[derive(Subcommand, Debug, PartialEq, Eq)]
+pub enum SprintCommand {
+    /// Write a plain-text retrospective of a sprint to a file
+    Report {
+        #[arg(long)]
+        sprint_id: u32,
+        #[arg(long)]
+        output: String,
+    },
+}
+
+#[derive(ValueEnum, Debug, PartialEq, Eq, Clone, Copy)]
+pub enum ItemType {
+    Epic,
+    Story,
+}
+
+#[derive(ValueEnum, Debug, PartialEq, Eq, Clone, Copy, Default)]
+pub enum OutputFormat {
+    /// A single JSON value
+    #[default]
+    Json,
+    /// One tab separated line per item, without a header row
+    Tsv,
+}
+
+#[derive(Serialize)]
+struct Listed<'a, T> {
+    id: u32,
+    #[serde(flatten)]
+    item: &'a T,
+}
+
+#[derive(Serialize)]
+struct Changed {
+    id: u32,
+}
+
+#[derive(Serialize)]
+struct Imported {
+    created_epics: usize,
+    created_stories: usize,
+    errors: Vec<String>,
+}
+
+#[derive(Serialize)]
+struct Exported<'a> {
+    path: &'a str,
+}
+
+#[derive(Serialize)]
+struct Repaired {
+    /// (epic id, story id) pairs
+    removed_story_references: Vec<(u32, u32)>,
+    purged_sprints: usize,
+}
+
+/// Runs a single command against `db` and writes its result in `format`.
+pub fn run(
+    command: Command,
+    format: OutputFormat,
+    db: &JiraDatabase,
+    mut out: impl Write,
+) -> Result<()> {
+    match command {
+        Command::ListEpics => {
+            let parsed = db.read_db()?;
+            let epics: Vec<Listed<Epic>> = parsed
+                .epics
+                .keys()
+                .sorted()
+                .map(|id| Listed {
+                    id: *id,
+                    item: &parsed.epics[id],
+                })
+                .collect();
+            match format {
+                OutputFormat::Json => write_json(&mut out, &epics)?,
+                OutputFormat::Tsv => {
+                    for Listed { id, item: epic } in epics {
+                        write_tsv_row(
+                            &mut out,
+                            &[
+                                id.to_string(),
+                                epic.name.clone(),
+                                epic.description.clone(),
+                                epic.status.to_string(),
+                                epic.priority.to_string(),
+                                epic.created_at.to_rfc3339(),
+                            ],
+                        )?;
+                    }
+                }
+            }
+        }
+        Command::ListStories { epic } => {
+            let view = db.get_epic_with_stories(epic)?;
+            let stories: Vec<Listed<Story>> = view
+                .stories
+                .iter()
+                .map(|(id, item)| Listed { id: *id, item })
+                .collect();
+            match format {
+                OutputFormat::Json => write_json(&mut out, &stories)?,
+                OutputFormat::Tsv => {
+                    for Listed { id, item: story } in stories {
+                        write_tsv_row(
+                            &mut out,
+                            &[
+                                id.to_string(),
+                                story.name.clone(),
+                                story.description.clone(),
+                                story.status.to_string(),
+                                story.priority.to_string(),
+                                story.created_at.to_rfc3339(),
+                            ],
+                        )?;
+                    }
+                }
+            }
+        }
+        Command::CreateEpic { name, desc } => {
+            let id = db.create_epic(Epic::new(name, desc))?;
+            write_changed(&mut out, format, id)?;
+        }
+        Command::CreateStory { epic, name, desc } => {
+            let id = db.create_story(Story::new(name, desc), epic)?;
+            write_changed(&mut out, format, id)?;
+        }
+        Command::UpdateStatus {
+            item_type,
+            id,
+            status,
+        } => {
+            let status: Status = status.parse()?;
+            match item_type {
+                ItemType::Epic => db.update_epic_status(id, status)?,
+                ItemType::Story => db.update_story_status(id, status)?,
+            }
+            write_changed(&mut out, format, id)?;
+        }
+        Command::Delete { item_type, id } => {
+            match item_type {
+                ItemType::Epic => db.delete_epic(id)?,
+                ItemType::Story => {
+                    let epic_id = db.get_story_with_epic(id)?.epic_id;
+                    db.delete_story(epic_id, id)?;
+                }
+            }
+            write_changed(&mut out, format, id)?;
+        }
+        Command::ImportCsv { path, lenient } => {
+            let file = File::open(&path).with_context(|| anyhow!("failed to open {}!", path))?;
+            let reader = BufReader::new(file);
+            let report = with_spinner("Importing", || {
+                if lenient {
+                    db.import_csv_lenient(reader)
+                } else {
+                    db.import_csv(reader)
+                }
+            })?;
+            write_imported(&mut out, format, report)?;
+        }
+        Command::ExportCsv { path } => {
+            let file =
+                File::create(&path).with_context(|| anyhow!("failed to create {}!", path))?;
+            with_spinner("Exporting", || db.export_csv(BufWriter::new(file)))?;
+            match format {
+                OutputFormat::Json => write_json(&mut out, &Exported { path: &path })?,
+                OutputFormat::Tsv => write_tsv_row(&mut out, &[path])?,
+            }
+        }
+        Command::ExportMarkdown {
+            path,
+            markdown_format,
+        } => {
+            let file =
+                File::create(&path).with_context(|| anyhow!("failed to create {}!", path))?;
+            export::export_markdown(db, markdown_format, BufWriter::new(file))?;
+            match format {
+                OutputFormat::Json => write_json(&mut out, &Exported { path: &path })?,
+                OutputFormat::Tsv => write_tsv_row(&mut out, &[path])?,
+            }
+        }
+        Command::Repair {
+            purge_sprints_older_than,
+        } => {
+            let report = db.repair()?;
+            let purged_sprints = match purge_sprints_older_than {
+                Some(days) => db.purge_old_sprints(days)?,
+                None => 0,
+            };
+            match format {
+                OutputFormat::Json => write_json(
+                    &mut out,
+                    &Repaired {
+                        removed_story_references: report.removed_story_references,
+                        purged_sprints,
+                    },
+                )?,
+                OutputFormat::Tsv => write_tsv_row(
+                    &mut out,
+                    &[
+                        report.removed_story_references.len().to_string(),
+                        purged_sprints.to_string(),
+                    ],
+                )?,
+            }
+        }
+        Command::Sprint {
+            command: SprintCommand::Report { sprint_id, output },
+        } => {
+            db.export_sprint_report(sprint_id, Path::new(&output))?;
+            match format {
+                OutputFormat::Json => write_json(&mut out, &Exported { path: &output })?,
+                OutputFormat::Tsv => write_tsv_row(&mut out, &[output])?,
+            }
+        }
+    }
+
+    Ok(())
+}
+
+// Only someone watching the terminal needs a spinner; a script capturing
+// stderr would just get noise
+fn with_spinner<T>(message: &str, f: impl FnOnce() -> Result<T>) -> Result<T> {
+    let spinner = io::stderr()
+        .is_terminal()
+        .then(|| io_utils::start_spinner(message));
+    let result = f();
+    if let Some(spinner) = spinner {
+        spinner.stop();
+    }
+    result
+}
+
+fn write_json(out: &mut impl Write, value: &impl Serialize) -> Result<()> {
+    serde_json::to_writer(&mut *out, value)?;
+    writeln!(out)?;
+    Ok(())
+}
+
+fn write_changed(out: &mut impl Write, format: OutputFormat, id: u32) -> Result<()> {
+    match format {
+        OutputFormat::Json => write_json(out, &Changed { id }),
+        OutputFormat::Tsv => write_tsv_row(out, &[id.to_string()]),
+    }
+}
+
+fn write_imported(out: &mut impl Write, format: OutputFormat, report: ImportReport) -> Result<()> {
+    match format {
+        OutputFormat::Json => write_json(
+            out,
+            &Imported {
+                created_epics: report.created_epics,
+                created_stories: report.created_stories,
+                errors: report.errors,
+            },
+        ),
+        OutputFormat::Tsv => write_tsv_row(
+            out,
+            &[
+                report.created_epics.to_string(),
+                report.created_stories.to_string(),
+                report.errors.join("; "),
+            ],
+        ),
+    }
+}
+
+// Tabs and line breaks inside a field would split it, so they become spaces
+fn write_tsv_row(out: &mut impl Write, fields: &[String]) -> Result<()> {
+    let line = fields
+        .iter()
+        .map(|field| field.replace(['\t', '\n', '\r'], " "))
+        .join("\t");
+    writeln!(out, "{}", line)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{db::test_utils::MockDB, models::Sprint};
+
+    #[test]
+    fn run_should_apply_changes_to_the_database() {
+        let db = JiraDatabase::with_database(Box::new(MockDB::new()));
+        let epic_id = db
+            .create_epic(Epic::new("epic".to_owned(), "".to_owned()))
+            .unwrap();
+        let story_id = db
+            .create_story(Story::new("story".to_owned(), "".to_owned()), epic_id)
+            .unwrap();
+
+        run(
+            Command::UpdateStatus {
+                item_type: ItemType::Story,
+                id: story_id,
+                status: "in progress".to_owned(),
+            },
+            OutputFormat::Json,
+            &db,
+            Vec::new(),
+        )
+        .unwrap();
+        assert_eq!(
+            db.read_db().unwrap().stories[&story_id].status,
+            Status::InProgress
+        );
+
+        run(
+            Command::Delete {
+                item_type: ItemType::Story,
+                id: story_id,
+            },
+            OutputFormat::Json,
+            &db,
+            Vec::new(),
+        )
+        .unwrap();
+        assert!(db.read_db().unwrap().stories.is_empty());
+    }
+
+    #[test]
+    fn run_should_reject_unknown_statuses_and_ids() {
+        let db = JiraDatabase::with_database(Box::new(MockDB::new()));
+        let epic_id = db
+            .create_epic(Epic::new("epic".to_owned(), "".to_owned()))
+            .unwrap();
+
+        let bad_status = Command::UpdateStatus {
+            item_type: ItemType::Epic,
+            id: epic_id,
+            status: "shipped".to_owned(),
+        };
+        assert!(run(bad_status, OutputFormat::Json, &db, Vec::new()).is_err());
+
+        let bad_id = Command::Delete {
+            item_type: ItemType::Story,
+            id: 999,
+        };
+        assert!(run(bad_id, OutputFormat::Json, &db, Vec::new()).is_err());
+    }
+
+    #[test]
+    fn csv_export_should_import_into_another_database() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("backlog.csv").to_str().unwrap().to_owned();
+        let db = JiraDatabase::with_database(Box::new(MockDB::new()));
+        let epic_id = db
+            .create_epic(Epic::new("epic".to_owned(), "".to_owned()))
+            .unwrap();
+        db.create_story(Story::new("story".to_owned(), "".to_owned()), epic_id)
+            .unwrap();
+
+        let mut output = Vec::new();
+        let export = Command::ExportCsv { path: path.clone() };
+        run(export, OutputFormat::Tsv, &db, &mut output).unwrap();
+        assert_eq!(String::from_utf8(output).unwrap(), format!("{}\n", path));
+
+        let other_db = JiraDatabase::with_database(Box::new(MockDB::new()));
+        let mut output = Vec::new();
+        let import = Command::ImportCsv {
+            path,
+            lenient: false,
+        };
+        run(import, OutputFormat::Tsv, &other_db, &mut output).unwrap();
+        assert_eq!(String::from_utf8(output).unwrap(), "1\t1\t\n");
+
+        let imported = other_db.read_db().unwrap();
+        assert_eq!(imported.epics[&1].name, "epic");
+        assert_eq!(imported.stories[&2].name, "story");
+        assert_eq!(imported.epics[&1].stories, vec![2]);
+    }
+
+    #[test]
+    fn repair_should_purge_old_sprints_only_when_asked() {
+        let db = JiraDatabase::with_database(Box::new(MockDB::new()));
+        let ended = chrono::Local::now().date_naive() - chrono::Days::new(60);
+        db.create_sprint(Sprint::new(
+            "sprint".to_owned(),
+            ended - chrono::Days::new(14),
+            ended,
+        ))
+        .unwrap();
+
+        let mut output = Vec::new();
+        let repair = Command::Repair {
+            purge_sprints_older_than: None,
+        };
+        run(repair, OutputFormat::Tsv, &db, &mut output).unwrap();
+        assert_eq!(String::from_utf8(output).unwrap(), "0\t0\n");
+
+        let mut output = Vec::new();
+        let repair = Command::Repair {
+            purge_sprints_older_than: Some(30),
+        };
+        run(repair, OutputFormat::Json, &db, &mut output).unwrap();
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            "{\"removed_story_references\":[],\"purged_sprints\":1}\n"
+        );
+        assert!(db.read_db().unwrap().sprints.is_empty());
+    }
+
+    #[test]
+    fn sprint_report_should_write_the_report_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("report.txt").to_str().unwrap().to_owned();
+        let db = JiraDatabase::with_database(Box::new(MockDB::new()));
+        let sprint_id = db
+            .create_sprint(Sprint::new(
+                "sprint 1".to_owned(),
+                chrono::NaiveDate::from_ymd_opt(2025, 6, 2).unwrap(),
+                chrono::NaiveDate::from_ymd_opt(2025, 6, 13).unwrap(),
+            ))
+            .unwrap();
+
+        let cli = Cli::try_parse_from([
+            "ironyy",
+            "sprint",
+            "report",
+            "--sprint-id",
+            &sprint_id.to_string(),
+            "--output",
+            &path,
+        ])
+        .unwrap();
+        let mut output = Vec::new();
+        run(cli.command.unwrap(), OutputFormat::Tsv, &db, &mut output).unwrap();
+
+        assert_eq!(String::from_utf8(output).unwrap(), format!("{}\n", path));
+        assert!(
+            std::fs::read_to_string(&path)
+                .unwrap()
+                .starts_with("Sprint report: sprint 1\n")
+        );
+    }
+
+    #[test]
+    fn export_markdown_should_write_wiki_tables_when_asked() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("wiki.md").to_str().unwrap().to_owned();
+        let db = JiraDatabase::with_database(Box::new(MockDB::new()));
+        db.create_epic(Epic::new("epic".to_owned(), "".to_owned()))
+            .unwrap();
+
+        let cli = Cli::try_parse_from([
+            "ironyy",
+            "export-markdown",
+            "--path",
+            &path,
+            "--markdown-format",
+            "wiki-table",
+        ])
+        .unwrap();
+        run(cli.command.unwrap(), OutputFormat::Tsv, &db, Vec::new()).unwrap();
+
+        assert!(
+            std::fs::read_to_string(&path)
+                .unwrap()
+                .contains("| ID | Name | Status | Stories | Points |\n")
+        );
+    }
+
+    #[test]
+    fn import_csv_should_fail_for_a_missing_file() {
+        let db = JiraDatabase::with_database(Box::new(MockDB::new()));
+        let import = Command::ImportCsv {
+            path: "no/such/file.csv".to_owned(),
+            lenient: true,
+        };
+
+        assert!(run(import, OutputFormat::Json, &db, Vec::new()).is_err());
+    }
+
+    #[test]
+    fn tsv_should_print_one_line_per_epic_without_a_header() {
+        let db = JiraDatabase::with_database(Box::new(MockDB::new()));
+        db.create_epic(Epic::new("Billing".to_owned(), "Take\tpayments".to_owned()))
+            .unwrap();
+        let search_id = db
+            .create_epic(Epic::new("Search".to_owned(), "".to_owned()))
+            .unwrap();
+        db.update_epic_status(search_id, Status::Resolved).unwrap();
+
+        let mut output = Vec::new();
+        run(Command::ListEpics, OutputFormat::Tsv, &db, &mut output).unwrap();
+
+        let epics = db.read_db().unwrap().epics;
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            format!(
+                "1\tBilling\tTake payments\tOPEN\tMEDIUM\t{}\n2\tSearch\t\tRESOLVED\tMEDIUM\t{}\n",
+                epics[&1].created_at.to_rfc3339(),
+                epics[&2].created_at.to_rfc3339()
+            )
+        );
+    }
+}
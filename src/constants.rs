@@ -1 +1,22 @@
-pub const APP_NAME: &str = "Ironyy";
\ No newline at end of file
+pub const APP_NAME: &str = "Ironyy";
+
+/// Recorded as the user behind a change when nobody has logged in, and as
+/// the reporter of items saved before reporters were tracked.
+pub const UNKNOWN_USER: &str = "unknown";
+
+pub const MAX_EPIC_NAME_LEN: usize = 64;
+pub const MAX_EPIC_DESCRIPTION_LEN: usize = 1000;
+pub const MAX_STORY_NAME_LEN: usize = 64;
+pub const MAX_STORY_DESCRIPTION_LEN: usize = 1000;
+pub const MAX_SPRINT_NAME_LEN: usize = 64;
+pub const MAX_COMMENT_LEN: usize = 1000;
+
+/// Typed alone on a line to finish a multi-line description.
+pub const DESCRIPTION_TERMINATOR: &str = ".";
+
+pub const HOME_PAGE_SIZE: usize = 10;
+pub const EPIC_DETAIL_PAGE_SIZE: usize = 20;
+pub const DASHBOARD_ACTIVITY_LIMIT: usize = 5;
+
+/// Story point estimates follow the Fibonacci sequence.
+pub const STORY_ESTIMATE_VALUES: [u8; 7] = [1, 2, 3, 5, 8, 13, 21];
@@ -1,26 +1,520 @@
-use std::fs;
+use std::{
+    cell::{Cell, RefCell},
+    collections::{HashMap, HashSet},
+    fs,
+    io::{self, Read, Write},
+    path::{Path, PathBuf},
+    thread,
+    time::{Duration, Instant},
+};
 
 use anyhow::{Result, anyhow};
+use chrono::{DateTime, Days, Local, NaiveDate, Utc};
+use itertools::Itertools;
+
+use crate::{
+    audit::{AuditLogger, AuditOperation},
+    constants::{
+        MAX_COMMENT_LEN, MAX_EPIC_DESCRIPTION_LEN, MAX_EPIC_NAME_LEN, MAX_SPRINT_NAME_LEN,
+        MAX_STORY_DESCRIPTION_LEN, MAX_STORY_NAME_LEN, STORY_ESTIMATE_VALUES, UNKNOWN_USER,
+    },
+    io_utils::atomic_write,
+    log,
+    logging::LogLevel,
+    models::{Comment, DBState, Epic, Priority, ProjectState, Sprint, Status, Story, StoryType},
+};
+
+const CSV_EPIC_HEADER: [&str; 5] = ["id", "name", "description", "status", "priority"];
+const CSV_STORY_HEADER: [&str; 6] = ["id", "epic_id", "name", "description", "status", "estimate"];
+const CLONED_STORY_PREFIX: &str = "[COPY] ";
+
+const LOCK_TIMEOUT: Duration = Duration::from_millis(500);
+const LOCK_RETRY_INTERVAL: Duration = Duration::from_millis(10);
+
+#[derive(Debug, PartialEq, Eq, Default)]
+pub struct ImportReport {
+    pub created_epics: usize,
+    pub created_stories: usize,
+    pub errors: Vec<String>,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum MergeItem {
+    Epic,
+    Story,
+}
+
+/// An incoming epic or story that was left out of the merge because its id
+/// is used in the base for something else, or because its epic was. The
+/// base version is the one that was kept.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct ConflictRecord {
+    pub item: MergeItem,
+    pub id: u32,
+}
+
+#[derive(Debug, PartialEq, Eq, Default)]
+pub struct MergeReport {
+    pub inserted_epics: usize,
+    pub inserted_stories: usize,
+    pub conflicts: Vec<ConflictRecord>,
+}
+
+/// Something in a loaded database that the app would never have written.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum IntegrityIssue {
+    /// The epic lists a story that is not in the database
+    OrphanedStoryReference { epic_id: u32, story_id: u32 },
+    /// The story is listed by no epic, or by more than one
+    MismatchedEpicId { story_id: u32 },
+    /// The id is used by more than one epic, story or sprint, counting
+    /// every project
+    DuplicateId { id: u32 },
+}
+
+impl std::fmt::Display for IntegrityIssue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::OrphanedStoryReference { epic_id, story_id } => write!(
+                f,
+                "epic {} lists story {}, which does not exist",
+                epic_id, story_id
+            ),
+            Self::MismatchedEpicId { story_id } => {
+                write!(f, "story {} does not belong to exactly one epic", story_id)
+            }
+            Self::DuplicateId { id } => write!(f, "id {} is used more than once", id),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Default)]
+pub struct DeleteReport {
+    pub deleted: Vec<u32>,
+    pub not_found: Vec<u32>,
+}
+
+#[derive(Debug, PartialEq, Eq, Default)]
+pub struct RepairReport {
+    /// (epic id, story id) pairs that were dropped from the epics
+    pub removed_story_references: Vec<(u32, u32)>,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct ReleaseSummary {
+    pub name: String,
+    pub epic_count: usize,
+    pub story_count: usize,
+}
+
+/// An epic together with its stories, in the epic's own order.
+#[derive(Debug, PartialEq)]
+pub struct EpicView {
+    pub epic_id: u32,
+    pub epic: Epic,
+    pub stories: Vec<(u32, Story)>,
+    /// Stories the epic lists that are missing from the database
+    pub missing_story_ids: Vec<u32>,
+}
+
+/// A story together with the epic it belongs to.
+#[derive(Debug, PartialEq)]
+pub struct StoryView {
+    pub story_id: u32,
+    pub story: Story,
+    pub epic_id: u32,
+    pub epic: Epic,
+}
+
+/// Filters for `search_epics` and `search_stories`. Every filter that is
+/// set must match; one left unset matches everything. Text is matched
+/// ignoring case.
+#[derive(Debug, PartialEq, Eq, Clone, Default)]
+pub struct SearchQuery {
+    text: Option<String>,
+    name_contains: Option<String>,
+    status: Option<Vec<Status>>,
+    tags: Option<Vec<String>>,
+    reporter: Option<String>,
+    priority: Option<Priority>,
+}
+
+impl SearchQuery {
+    pub fn builder() -> SearchQueryBuilder {
+        SearchQueryBuilder::default()
+    }
+
+    fn matches(
+        &self,
+        name: &str,
+        description: &str,
+        status: &Status,
+        has_tag: impl Fn(&str) -> bool,
+        reporter: &str,
+        priority: Priority,
+    ) -> bool {
+        self.text
+            .as_ref()
+            .is_none_or(|text| matches_query(name, description, text))
+            && self
+                .name_contains
+                .as_ref()
+                .is_none_or(|part| name.to_lowercase().contains(&part.trim().to_lowercase()))
+            && self
+                .status
+                .as_ref()
+                .is_none_or(|statuses| statuses.contains(status))
+            && self
+                .tags
+                .as_ref()
+                .is_none_or(|tags| tags.iter().all(|tag| has_tag(tag)))
+            && self
+                .reporter
+                .as_ref()
+                .is_none_or(|wanted| reporter == wanted)
+            && self.priority.is_none_or(|wanted| wanted == priority)
+    }
+
+    fn matches_epic(&self, epic: &Epic) -> bool {
+        self.matches(
+            &epic.name,
+            &epic.description,
+            &epic.status,
+            |tag| epic.has_tag(tag),
+            &epic.reporter,
+            epic.priority,
+        )
+    }
 
-use crate::models::{DBState, Epic, Status, Story};
+    fn matches_story(&self, story: &Story) -> bool {
+        self.matches(
+            &story.name,
+            &story.description,
+            &story.status,
+            |tag| story.has_tag(tag),
+            &story.reporter,
+            story.priority,
+        )
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct SearchQueryBuilder {
+    query: SearchQuery,
+}
+
+impl SearchQueryBuilder {
+    /// Matches the name or the description, like the search page.
+    pub fn with_text(mut self, text: impl Into<String>) -> Self {
+        self.query.text = Some(text.into());
+        self
+    }
+
+    pub fn with_name_contains(mut self, part: impl Into<String>) -> Self {
+        self.query.name_contains = Some(part.into());
+        self
+    }
+
+    /// Can be called more than once to match any of several statuses.
+    pub fn with_status(mut self, status: Status) -> Self {
+        self.query.status.get_or_insert_default().push(status);
+        self
+    }
+
+    /// Can be called more than once; items must carry every tag.
+    pub fn with_tag(mut self, tag: impl Into<String>) -> Self {
+        self.query.tags.get_or_insert_default().push(tag.into());
+        self
+    }
+
+    pub fn with_reporter(mut self, reporter: impl Into<String>) -> Self {
+        self.query.reporter = Some(reporter.into());
+        self
+    }
+
+    pub fn with_priority(mut self, priority: Priority) -> Self {
+        self.query.priority = Some(priority);
+        self
+    }
+
+    pub fn build(self) -> SearchQuery {
+        self.query
+    }
+}
+
+enum CsvSection {
+    Epics,
+    Stories,
+}
 
 pub struct JiraDatabase {
     pub database: Box<dyn Database>,
+    state_cache: RefCell<Option<DBState>>,
+    audit_logger: AuditLogger,
+    write_lock: RefCell<Option<FileLock>>,
+    lock_depth: Cell<usize>,
+    in_transaction: Cell<bool>,
+    pending_audits: RefCell<Vec<(AuditOperation, u32)>>,
+}
+
+/// Held for the length of a read-modify-write. Nested writes share the
+/// outer lock, and the file lock is released when the outermost guard drops.
+struct WriteGuard<'a> {
+    db: &'a JiraDatabase,
+}
+
+impl Drop for WriteGuard<'_> {
+    fn drop(&mut self) {
+        let depth = self.db.lock_depth.get() - 1;
+        self.db.lock_depth.set(depth);
+        if depth == 0 {
+            self.db.write_lock.borrow_mut().take();
+        }
+    }
 }
 
 impl JiraDatabase {
+    /// Changes are audited to `audit.log` next to the database file.
     pub fn new(file_path: String) -> Self {
+        let audit_path = Path::new(&file_path).with_file_name("audit.log");
+        Self::with_database(Box::new(JSONFileDatabase { file_path }))
+            .with_audit_logger(AuditLogger::new(audit_path))
+    }
+
+    /// Auditing is off until a logger is attached with `with_audit_logger`.
+    pub fn with_database(database: Box<dyn Database>) -> Self {
         Self {
-            database: Box::new(JSONFileDatabase { file_path }),
+            database,
+            state_cache: RefCell::new(None),
+            audit_logger: AuditLogger::disable_audit(),
+            write_lock: RefCell::new(None),
+            lock_depth: Cell::new(0),
+            in_transaction: Cell::new(false),
+            pending_audits: RefCell::new(Vec::new()),
+        }
+    }
+
+    pub fn with_audit_logger(mut self, audit_logger: AuditLogger) -> Self {
+        self.audit_logger = audit_logger;
+        self
+    }
+
+    pub fn set_audit_user(&self, username: &str) {
+        self.audit_logger.set_username(username);
+    }
+
+    pub fn audit_user(&self) -> String {
+        self.audit_logger.username()
+    }
+
+    // New items that don't say who reported them are credited to the audit
+    // user
+    fn fill_in_reporter(&self, reporter: &mut String) {
+        if reporter == UNKNOWN_USER {
+            *reporter = self.audit_user();
+        }
+    }
+
+    // The change has already been written by the time it is audited, so a
+    // logging failure is reported rather than failing the operation.
+    fn audit(&self, operation: AuditOperation, item_id: u32) {
+        // A transaction audits its changes once they are on disk
+        if self.in_transaction.get() {
+            self.pending_audits.borrow_mut().push((operation, item_id));
+            return;
+        }
+
+        log!(
+            LogLevel::Info,
+            "database changed",
+            operation = operation,
+            item_id = item_id,
+            user = self.audit_user(),
+        );
+
+        if let Err(error) = self.audit_logger.log(operation, item_id) {
+            log!(
+                LogLevel::Warn,
+                "failed to write audit record",
+                error = error.to_string(),
+            );
+        }
+    }
+
+    // Projects are switched and deleted outside of transactions, so unlike
+    // `audit` there is nothing to hold back
+    fn audit_project(&self, operation: AuditOperation, project: &str) {
+        if let Err(error) = self.audit_logger.log_project(operation, project) {
+            log!(
+                LogLevel::Warn,
+                "failed to write audit record",
+                error = error.to_string(),
+            );
         }
     }
 
     pub fn read_db(&self) -> Result<DBState> {
-        self.database.read_db()
+        if let Some(state) = self.state_cache.borrow().as_ref() {
+            return Ok(state.clone());
+        }
+
+        let state = self.database.read_db()?;
+        *self.state_cache.borrow_mut() = Some(state.clone());
+        Ok(state)
+    }
+
+    // Every method that writes takes this before reading, so another
+    // process cannot write between our read and our write.
+    fn lock_for_write(&self) -> Result<WriteGuard<'_>> {
+        if self.lock_depth.get() == 0
+            && let Some(lock) = self.database.lock()?
+        {
+            // Another process may have written since the cache was filled
+            self.invalidate_cache();
+            *self.write_lock.borrow_mut() = Some(lock);
+        }
+        self.lock_depth.set(self.lock_depth.get() + 1);
+        Ok(WriteGuard { db: self })
+    }
+
+    // The cache is only updated once the write has succeeded, so a failed
+    // write leaves both as they were. Inside a transaction the write waits
+    // for the commit.
+    fn write_db(&self, db_state: &DBState) -> Result<()> {
+        if !self.in_transaction.get() {
+            self.database.write_db(db_state)?;
+        }
+        *self.state_cache.borrow_mut() = Some(db_state.clone());
+        Ok(())
+    }
+
+    /// Runs `f` so that every change it makes is written at once, or not at
+    /// all. If `f` returns an error or the final write fails, the database
+    /// is restored to how it was before `f` ran. A transaction started
+    /// inside another one joins the outer transaction.
+    pub fn with_transaction<F, T>(&self, f: F) -> Result<T>
+    where
+        F: FnOnce(&Self) -> Result<T>,
+    {
+        if self.in_transaction.get() {
+            return f(self);
+        }
+
+        let _lock = self.lock_for_write()?;
+        let snapshot = self.read_db()?;
+
+        self.in_transaction.set(true);
+        let result = f(self);
+        self.in_transaction.set(false);
+        let pending_audits = self.pending_audits.take();
+
+        let result = result.and_then(|value| {
+            let state = self.read_db()?;
+            if state != snapshot {
+                self.write_db(&state)?;
+            }
+            Ok(value)
+        });
+
+        match result {
+            Ok(value) => {
+                for (operation, item_id) in pending_audits {
+                    self.audit(operation, item_id);
+                }
+                Ok(value)
+            }
+            Err(error) => {
+                *self.state_cache.borrow_mut() = Some(snapshot);
+                Err(error)
+            }
+        }
+    }
+
+    pub fn invalidate_cache(&self) {
+        *self.state_cache.borrow_mut() = None;
+    }
+
+    pub fn active_project(&self) -> Result<String> {
+        Ok(self.read_db()?.active_project)
+    }
+
+    pub fn list_projects(&self) -> Result<Vec<String>> {
+        let parsed = self.read_db()?;
+
+        let mut projects: Vec<String> = parsed.projects.into_keys().collect();
+        projects.push(parsed.active_project);
+        projects.sort();
+
+        Ok(projects)
+    }
+
+    pub fn use_project(&self, name: &str) -> Result<()> {
+        let _lock = self.lock_for_write()?;
+        let name = name.trim();
+        if name.is_empty() {
+            return Err(anyhow!("project name cannot be empty!"));
+        }
+
+        let mut parsed = self.read_db()?;
+        if parsed.active_project == name {
+            return Ok(());
+        }
+
+        let next_project = parsed.projects.remove(name);
+        let operation = if next_project.is_some() {
+            AuditOperation::SwitchProject
+        } else {
+            AuditOperation::CreateProject
+        };
+        let next_project = next_project.unwrap_or_default();
+        let previous_project = ProjectState {
+            epics: std::mem::replace(&mut parsed.epics, next_project.epics),
+            stories: std::mem::replace(&mut parsed.stories, next_project.stories),
+            sprints: std::mem::replace(&mut parsed.sprints, next_project.sprints),
+        };
+        let previous_name = std::mem::replace(&mut parsed.active_project, name.to_owned());
+        parsed.projects.insert(previous_name, previous_project);
+
+        self.write_db(&parsed)?;
+        log!(LogLevel::Info, "switched project", project = name);
+        self.audit_project(operation, name);
+        Ok(())
+    }
+
+    pub fn delete_project(&self, name: &str) -> Result<()> {
+        let _lock = self.lock_for_write()?;
+        let name = name.trim();
+        let mut parsed = self.read_db()?;
+
+        if parsed.active_project == name {
+            return Err(anyhow!("cannot delete the active project!"));
+        }
+        parsed
+            .projects
+            .remove(name)
+            .ok_or_else(|| anyhow!("could not find project in database!"))?;
+
+        self.write_db(&parsed)?;
+        log!(LogLevel::Info, "deleted project", project = name);
+        self.audit_project(AuditOperation::DeleteProject, name);
+        Ok(())
     }
 
-    pub fn create_epic(&self, epic: Epic) -> Result<u32> {
-        let mut parsed = self.database.read_db()?;
+    /// Saves a new epic. An epic whose reporter is unknown is credited to
+    /// the audit user.
+    pub fn create_epic(&self, mut epic: Epic) -> Result<u32> {
+        let _lock = self.lock_for_write()?;
+        validate_item(
+            "epic",
+            &epic.name,
+            MAX_EPIC_NAME_LEN,
+            &epic.description,
+            MAX_EPIC_DESCRIPTION_LEN,
+        )?;
+
+        self.fill_in_reporter(&mut epic.reporter);
+
+        let mut parsed = self.read_db()?;
 
         let last_id = parsed.last_item_id;
         let new_id = last_id + 1;
@@ -28,12 +522,41 @@ impl JiraDatabase {
         parsed.last_item_id = new_id;
         parsed.epics.insert(new_id, epic);
 
-        self.database.write_db(&parsed)?;
+        self.write_db(&parsed)?;
+        self.audit(AuditOperation::CreateEpic, new_id);
         Ok(new_id)
     }
 
-    pub fn create_story(&self, story: Story, epic_id: u32) -> Result<u32> {
-        let mut parsed = self.database.read_db()?;
+    pub fn create_epic_unique(&self, epic: Epic) -> Result<u32> {
+        let _lock = self.lock_for_write()?;
+        let parsed = self.read_db()?;
+
+        if parsed
+            .epics
+            .values()
+            .any(|existing| existing.name.trim() == epic.name.trim())
+        {
+            return Err(anyhow!("an epic named \"{}\" already exists!", epic.name));
+        }
+
+        self.create_epic(epic)
+    }
+
+    /// Saves a new story under `epic_id`. Like `create_epic`, an unknown
+    /// reporter is replaced by the audit user.
+    pub fn create_story(&self, mut story: Story, epic_id: u32) -> Result<u32> {
+        let _lock = self.lock_for_write()?;
+        validate_item(
+            "story",
+            &story.name,
+            MAX_STORY_NAME_LEN,
+            &story.description,
+            MAX_STORY_DESCRIPTION_LEN,
+        )?;
+
+        self.fill_in_reporter(&mut story.reporter);
+
+        let mut parsed = self.read_db()?;
 
         let last_id = parsed.last_item_id;
         let new_id = last_id + 1;
@@ -47,12 +570,88 @@ impl JiraDatabase {
             .stories
             .push(new_id);
 
-        self.database.write_db(&parsed)?;
+        self.write_db(&parsed)?;
+        self.audit(AuditOperation::CreateStory, new_id);
         Ok(new_id)
     }
 
+    /// Copies a story into the same epic as a fresh, unestimated OPEN
+    /// story named "[COPY] <name>". Dependency links, comments and sprint
+    /// membership are not copied.
+    pub fn clone_story(&self, story_id: u32) -> Result<u32> {
+        let _lock = self.lock_for_write()?;
+        let parsed = self.read_db()?;
+
+        let source = parsed
+            .stories
+            .get(&story_id)
+            .ok_or_else(|| anyhow!("could not find story in database!"))?;
+        let epic_id = parsed
+            .epics
+            .iter()
+            .find(|(_, epic)| epic.stories.contains(&story_id))
+            .map(|(epic_id, _)| *epic_id)
+            .ok_or_else(|| anyhow!("could not find the epic of story {}!", story_id))?;
+
+        // Long names are shortened so the prefix still fits
+        let name = format!("{}{}", CLONED_STORY_PREFIX, source.name)
+            .chars()
+            .take(MAX_STORY_NAME_LEN)
+            .collect();
+        let now = Utc::now();
+        let clone = Story {
+            name,
+            status: Status::Open,
+            estimate: None,
+            blocks: vec![],
+            blocked_by: vec![],
+            comments: vec![],
+            last_comment_id: 0,
+            created_at: now,
+            updated_at: now,
+            ..source.clone()
+        };
+
+        self.create_story(clone, epic_id)
+    }
+
+    /// Moves a story out of whichever epic holds it and onto the end of
+    /// `target_epic_id`'s list. Both epics change in a single write.
+    pub fn move_story(&self, story_id: u32, target_epic_id: u32) -> Result<()> {
+        let _lock = self.lock_for_write()?;
+        let mut parsed = self.read_db()?;
+
+        if !parsed.stories.contains_key(&story_id) {
+            return Err(anyhow!("could not find story {} in database!", story_id));
+        }
+        if !parsed.epics.contains_key(&target_epic_id) {
+            return Err(anyhow!(
+                "could not find epic {} in database!",
+                target_epic_id
+            ));
+        }
+        if parsed.epics[&target_epic_id].stories.contains(&story_id) {
+            return Ok(());
+        }
+
+        for epic in parsed.epics.values_mut() {
+            epic.stories.retain(|id| *id != story_id);
+        }
+        if let Some(target) = parsed.epics.get_mut(&target_epic_id) {
+            target.stories.push(story_id);
+        }
+        if let Some(story) = parsed.stories.get_mut(&story_id) {
+            story.touch();
+        }
+
+        self.write_db(&parsed)?;
+        self.audit(AuditOperation::MoveStory, story_id);
+        Ok(())
+    }
+
     pub fn delete_epic(&self, epic_id: u32) -> Result<()> {
-        let mut parsed = self.database.read_db()?;
+        let _lock = self.lock_for_write()?;
+        let mut parsed = self.read_db()?;
 
         for story_id in &parsed
             .epics
@@ -64,13 +663,16 @@ impl JiraDatabase {
         }
 
         parsed.epics.remove(&epic_id);
+        drop_dangling_story_links(&mut parsed);
 
-        self.database.write_db(&parsed)?;
+        self.write_db(&parsed)?;
+        self.audit(AuditOperation::DeleteEpic, epic_id);
         Ok(())
     }
 
     pub fn delete_story(&self, epic_id: u32, story_id: u32) -> Result<()> {
-        let mut parsed = self.database.read_db()?;
+        let _lock = self.lock_for_write()?;
+        let mut parsed = self.read_db()?;
 
         let epic = parsed
             .epics
@@ -85,362 +687,4040 @@ impl JiraDatabase {
         epic.stories.remove(story_index);
 
         parsed.stories.remove(&story_id);
+        drop_dangling_story_links(&mut parsed);
 
-        self.database.write_db(&parsed)?;
+        self.write_db(&parsed)?;
+        self.audit(AuditOperation::DeleteStory, story_id);
         Ok(())
     }
 
+    /// Deletes every story in `ids` that exists, from whichever epic it is
+    /// in, with a single write. Ids that don't exist are reported rather
+    /// than failing the whole batch.
+    pub fn delete_stories(&self, ids: &[u32]) -> Result<DeleteReport> {
+        let _lock = self.lock_for_write()?;
+        let mut parsed = self.read_db()?;
+
+        let mut report = DeleteReport::default();
+        for id in ids.iter().copied().unique() {
+            if parsed.stories.remove(&id).is_some() {
+                report.deleted.push(id);
+            } else {
+                report.not_found.push(id);
+            }
+        }
+        if report.deleted.is_empty() {
+            return Ok(report);
+        }
+
+        for epic in parsed.epics.values_mut() {
+            epic.stories.retain(|id| !report.deleted.contains(id));
+        }
+        drop_dangling_story_links(&mut parsed);
+
+        self.write_db(&parsed)?;
+        for id in &report.deleted {
+            self.audit(AuditOperation::DeleteStory, *id);
+        }
+        Ok(report)
+    }
+
     pub fn update_epic_status(&self, epic_id: u32, status: Status) -> Result<()> {
-        let mut parsed = self.database.read_db()?;
+        let _lock = self.lock_for_write()?;
+        let mut parsed = self.read_db()?;
 
-        parsed
+        let epic = parsed
             .epics
             .get_mut(&epic_id)
-            .ok_or_else(|| anyhow!("could not find epic in database!"))?
-            .status = status;
+            .ok_or_else(|| anyhow!("could not find epic in database!"))?;
+        epic.status = status;
+        epic.touch();
 
-        self.database.write_db(&parsed)?;
+        self.write_db(&parsed)?;
+        self.audit(AuditOperation::UpdateEpicStatus, epic_id);
         Ok(())
     }
 
     pub fn update_story_status(&self, story_id: u32, status: Status) -> Result<()> {
-        let mut parsed = self.database.read_db()?;
+        let _lock = self.lock_for_write()?;
+        let mut parsed = self.read_db()?;
 
-        parsed
+        let story = parsed
             .stories
             .get_mut(&story_id)
-            .ok_or_else(|| anyhow!("could not find story in database!"))?
-            .status = status;
+            .ok_or_else(|| anyhow!("could not find story in database!"))?;
+        story.status = status;
+        story.touch();
 
-        self.database.write_db(&parsed)?;
+        self.write_db(&parsed)?;
+        self.audit(AuditOperation::UpdateStoryStatus, story_id);
         Ok(())
     }
-}
-
-pub trait Database {
-    fn read_db(&self) -> Result<DBState>;
-    fn write_db(&self, db_state: &DBState) -> Result<()>;
-}
 
-struct JSONFileDatabase {
-    pub file_path: String,
-}
+    pub fn update_epic_due_date(&self, epic_id: u32, due_date: Option<NaiveDate>) -> Result<()> {
+        let _lock = self.lock_for_write()?;
+        let mut parsed = self.read_db()?;
 
-impl Database for JSONFileDatabase {
-    fn read_db(&self) -> Result<DBState> {
-        let db_content = fs::read_to_string(&self.file_path)?;
-        let parsed: DBState = serde_json::from_str(&db_content)?;
-        Ok(parsed)
-    }
+        let epic = parsed
+            .epics
+            .get_mut(&epic_id)
+            .ok_or_else(|| anyhow!("could not find epic in database!"))?;
+        epic.due_date = due_date;
+        epic.touch();
 
-    fn write_db(&self, db_state: &DBState) -> Result<()> {
-        fs::write(&self.file_path, &serde_json::to_vec(db_state)?)?;
+        self.write_db(&parsed)?;
+        self.audit(AuditOperation::UpdateEpicDueDate, epic_id);
         Ok(())
     }
-}
 
-pub mod test_utils {
-    use std::{cell::RefCell, collections::HashMap};
+    pub fn update_story_due_date(&self, story_id: u32, due_date: Option<NaiveDate>) -> Result<()> {
+        let _lock = self.lock_for_write()?;
+        let mut parsed = self.read_db()?;
 
-    use super::*;
+        let story = parsed
+            .stories
+            .get_mut(&story_id)
+            .ok_or_else(|| anyhow!("could not find story in database!"))?;
+        story.due_date = due_date;
+        story.touch();
 
-    pub struct MockDB {
-        last_written_state: RefCell<DBState>,
+        self.write_db(&parsed)?;
+        self.audit(AuditOperation::UpdateStoryDueDate, story_id);
+        Ok(())
     }
 
-    impl MockDB {
-        pub fn new() -> Self {
-            Self {
-                last_written_state: RefCell::new(DBState {
-                    last_item_id: 0,
-                    epics: HashMap::new(),
-                    stories: HashMap::new(),
-                }),
-            }
+    pub fn update_story_estimate(&self, story_id: u32, estimate: u8) -> Result<()> {
+        let _lock = self.lock_for_write()?;
+        if !STORY_ESTIMATE_VALUES.contains(&estimate) {
+            return Err(anyhow!(
+                "{} is not a valid estimate, expected one of {:?}!",
+                estimate,
+                STORY_ESTIMATE_VALUES
+            ));
         }
-    }
 
-    impl Database for MockDB {
-        fn read_db(&self) -> Result<DBState> {
-            let state = self.last_written_state.borrow().clone();
-            Ok(state)
-        }
+        let mut parsed = self.read_db()?;
 
-        fn write_db(&self, db_state: &DBState) -> Result<()> {
-            let latest_state = &self.last_written_state;
-            *latest_state.borrow_mut() = db_state.clone();
-            Ok(())
-        }
-    }
-}
+        let story = parsed
+            .stories
+            .get_mut(&story_id)
+            .ok_or_else(|| anyhow!("could not find story in database!"))?;
+        story.estimate = Some(estimate);
+        story.touch();
 
-#[cfg(test)]
-mod tests {
-    use super::test_utils::MockDB;
-    use super::*;
+        self.write_db(&parsed)?;
+        self.audit(AuditOperation::UpdateStoryEstimate, story_id);
+        Ok(())
+    }
 
-    #[test]
-    fn create_epic_should_work() {
-        let db = JiraDatabase {
-            database: Box::new(MockDB::new()),
-        };
-        let epic = Epic::new("".to_owned(), "".to_owned());
+    pub fn update_story_type(&self, story_id: u32, story_type: StoryType) -> Result<()> {
+        let _lock = self.lock_for_write()?;
+        let mut parsed = self.read_db()?;
 
-        let result = db.create_epic(epic.clone());
+        let story = parsed
+            .stories
+            .get_mut(&story_id)
+            .ok_or_else(|| anyhow!("could not find story in database!"))?;
+        story.story_type = story_type;
+        story.touch();
 
-        assert_eq!(result.is_ok(), true);
+        self.write_db(&parsed)?;
+        self.audit(AuditOperation::UpdateStoryType, story_id);
+        Ok(())
+    }
 
-        let id = result.unwrap();
-        let db_state = db.read_db().unwrap();
+    pub fn update_epic_release(&self, epic_id: u32, release: Option<String>) -> Result<()> {
+        let _lock = self.lock_for_write()?;
+        let mut parsed = self.read_db()?;
 
-        let expected_id = 1;
+        let epic = parsed
+            .epics
+            .get_mut(&epic_id)
+            .ok_or_else(|| anyhow!("could not find epic in database!"))?;
+        epic.release = normalize_release(release);
+        epic.touch();
 
-        assert_eq!(id, expected_id);
-        assert_eq!(db_state.last_item_id, expected_id);
-        assert_eq!(db_state.epics.get(&id), Some(&epic));
+        self.write_db(&parsed)?;
+        self.audit(AuditOperation::UpdateEpicRelease, epic_id);
+        Ok(())
     }
 
-    #[test]
-    fn create_story_should_error_if_invalid_epic_id() {
-        let db = JiraDatabase {
-            database: Box::new(MockDB::new()),
-        };
-        let story = Story::new("".to_owned(), "".to_owned());
+    pub fn update_story_release(&self, story_id: u32, release: Option<String>) -> Result<()> {
+        let _lock = self.lock_for_write()?;
+        let mut parsed = self.read_db()?;
 
-        let non_existent_epic_id = 999;
+        let story = parsed
+            .stories
+            .get_mut(&story_id)
+            .ok_or_else(|| anyhow!("could not find story in database!"))?;
+        story.release = normalize_release(release);
+        story.touch();
 
-        let result = db.create_story(story, non_existent_epic_id);
+        self.write_db(&parsed)?;
+        self.audit(AuditOperation::UpdateStoryRelease, story_id);
+        Ok(())
+    }
+
+    pub fn add_comment(&self, story_id: u32, author: &str, text: &str) -> Result<u32> {
+        let _lock = self.lock_for_write()?;
+        let text = text.trim();
+        if text.is_empty() {
+            return Err(anyhow!("comment cannot be empty!"));
+        }
+        if text.chars().count() > MAX_COMMENT_LEN {
+            return Err(anyhow!(
+                "comment cannot be longer than {} characters!",
+                MAX_COMMENT_LEN
+            ));
+        }
+
+        let mut parsed = self.read_db()?;
+
+        let story = parsed
+            .stories
+            .get_mut(&story_id)
+            .ok_or_else(|| anyhow!("could not find story in database!"))?;
+        // Stories saved before the counter existed carry on from their
+        // highest comment id
+        let highest_id = story.comments.iter().map(|c| c.id).max().unwrap_or(0);
+        story.last_comment_id = story.last_comment_id.max(highest_id) + 1;
+        let comment_id = story.last_comment_id;
+        story.comments.push(Comment {
+            id: comment_id,
+            author: author.to_owned(),
+            text: text.to_owned(),
+            created_at: Utc::now().naive_utc(),
+        });
+        story.touch();
+
+        self.write_db(&parsed)?;
+        self.audit(AuditOperation::AddComment, story_id);
+        Ok(comment_id)
+    }
+
+    pub fn delete_comment(&self, story_id: u32, comment_id: u32) -> Result<()> {
+        let _lock = self.lock_for_write()?;
+        let mut parsed = self.read_db()?;
+
+        let story = parsed
+            .stories
+            .get_mut(&story_id)
+            .ok_or_else(|| anyhow!("could not find story in database!"))?;
+        let index = story
+            .comments
+            .iter()
+            .position(|c| c.id == comment_id)
+            .ok_or_else(|| {
+                anyhow!(
+                    "could not find comment {} on story {}!",
+                    comment_id,
+                    story_id
+                )
+            })?;
+        story.comments.remove(index);
+        story.touch();
+
+        self.write_db(&parsed)?;
+        self.audit(AuditOperation::DeleteComment, story_id);
+        Ok(())
+    }
+
+    /// Records that `blocker_id` must be done before `blocked_id`. Fails if
+    /// `blocked_id` already (directly or indirectly) blocks `blocker_id`,
+    /// since the new link would then close a cycle.
+    pub fn add_story_dependency(&self, blocker_id: u32, blocked_id: u32) -> Result<()> {
+        let _lock = self.lock_for_write()?;
+        let mut parsed = self.read_db()?;
+
+        for id in [blocker_id, blocked_id] {
+            if !parsed.stories.contains_key(&id) {
+                return Err(anyhow!("could not find story {} in database!", id));
+            }
+        }
+        if blocker_id == blocked_id {
+            return Err(anyhow!("a story cannot block itself!"));
+        }
+        if parsed.stories[&blocker_id].blocks.contains(&blocked_id) {
+            return Ok(());
+        }
+        if story_blocks_transitively(&parsed.stories, blocked_id, blocker_id) {
+            return Err(anyhow!(
+                "story {} already blocks story {}, linking them would create a cycle!",
+                blocked_id,
+                blocker_id
+            ));
+        }
+
+        if let Some(blocker) = parsed.stories.get_mut(&blocker_id) {
+            blocker.blocks.push(blocked_id);
+            blocker.touch();
+        }
+        if let Some(blocked) = parsed.stories.get_mut(&blocked_id) {
+            blocked.blocked_by.push(blocker_id);
+            blocked.touch();
+        }
+
+        self.write_db(&parsed)?;
+        self.audit(AuditOperation::LinkStoryDependency, blocker_id);
+        Ok(())
+    }
+
+    pub fn remove_story_dependency(&self, blocker_id: u32, blocked_id: u32) -> Result<()> {
+        let _lock = self.lock_for_write()?;
+        let mut parsed = self.read_db()?;
+
+        let blocker = parsed
+            .stories
+            .get_mut(&blocker_id)
+            .ok_or_else(|| anyhow!("could not find story {} in database!", blocker_id))?;
+        if !blocker.blocks.contains(&blocked_id) {
+            return Err(anyhow!(
+                "story {} does not block story {}!",
+                blocker_id,
+                blocked_id
+            ));
+        }
+        blocker.blocks.retain(|id| *id != blocked_id);
+        blocker.touch();
+
+        if let Some(blocked) = parsed.stories.get_mut(&blocked_id) {
+            blocked.blocked_by.retain(|id| *id != blocker_id);
+            blocked.touch();
+        }
+
+        self.write_db(&parsed)?;
+        self.audit(AuditOperation::UnlinkStoryDependency, blocker_id);
+        Ok(())
+    }
+
+    pub fn reopen_story(&self, story_id: u32) -> Result<()> {
+        let _lock = self.lock_for_write()?;
+        let mut parsed = self.read_db()?;
+
+        let epic = parsed
+            .epics
+            .values()
+            .find(|epic| epic.stories.contains(&story_id))
+            .ok_or_else(|| anyhow!("could not find the epic for this story in database!"))?;
+
+        let story = parsed
+            .stories
+            .get_mut(&story_id)
+            .ok_or_else(|| anyhow!("could not find story in database!"))?;
+        story.reopen(epic)?;
+        story.touch();
+
+        self.write_db(&parsed)?;
+        self.audit(AuditOperation::ReopenStory, story_id);
+        Ok(())
+    }
+
+    pub fn update_epic_name(&self, epic_id: u32, name: String) -> Result<()> {
+        let _lock = self.lock_for_write()?;
+        validate_name("epic", &name, MAX_EPIC_NAME_LEN)?;
+        let mut parsed = self.read_db()?;
+
+        let epic = parsed
+            .epics
+            .get_mut(&epic_id)
+            .ok_or_else(|| anyhow!("could not find epic in database!"))?;
+        epic.name = name;
+        epic.touch();
+
+        self.write_db(&parsed)?;
+        self.audit(AuditOperation::UpdateEpicName, epic_id);
+        Ok(())
+    }
+
+    pub fn update_epic_description(&self, epic_id: u32, description: String) -> Result<()> {
+        let _lock = self.lock_for_write()?;
+        validate_description("epic", &description, MAX_EPIC_DESCRIPTION_LEN)?;
+        let mut parsed = self.read_db()?;
+
+        let epic = parsed
+            .epics
+            .get_mut(&epic_id)
+            .ok_or_else(|| anyhow!("could not find epic in database!"))?;
+        epic.description = description;
+        epic.touch();
+
+        self.write_db(&parsed)?;
+        self.audit(AuditOperation::UpdateEpicDescription, epic_id);
+        Ok(())
+    }
+
+    pub fn update_story_name(&self, story_id: u32, name: String) -> Result<()> {
+        let _lock = self.lock_for_write()?;
+        validate_name("story", &name, MAX_STORY_NAME_LEN)?;
+        let mut parsed = self.read_db()?;
+
+        let story = parsed
+            .stories
+            .get_mut(&story_id)
+            .ok_or_else(|| anyhow!("could not find story in database!"))?;
+        story.name = name;
+        story.touch();
+
+        self.write_db(&parsed)?;
+        self.audit(AuditOperation::UpdateStoryName, story_id);
+        Ok(())
+    }
+
+    pub fn update_story_description(&self, story_id: u32, description: String) -> Result<()> {
+        let _lock = self.lock_for_write()?;
+        validate_description("story", &description, MAX_STORY_DESCRIPTION_LEN)?;
+        let mut parsed = self.read_db()?;
+
+        let story = parsed
+            .stories
+            .get_mut(&story_id)
+            .ok_or_else(|| anyhow!("could not find story in database!"))?;
+        story.description = description;
+        story.touch();
+
+        self.write_db(&parsed)?;
+        self.audit(AuditOperation::UpdateStoryDescription, story_id);
+        Ok(())
+    }
+
+    pub fn overdue_stories(&self, today: NaiveDate) -> Result<Vec<(u32, Story)>> {
+        let parsed = self.read_db()?;
+
+        let mut overdue: Vec<(u32, Story)> = parsed
+            .stories
+            .into_iter()
+            .filter(|(_, story)| story.is_overdue(today))
+            .collect();
+        overdue.sort_by_key(|(id, _)| *id);
+
+        Ok(overdue)
+    }
+
+    pub fn create_sprint(&self, mut sprint: Sprint) -> Result<u32> {
+        let _lock = self.lock_for_write()?;
+        let mut parsed = self.read_db()?;
+
+        let new_id = parsed.last_item_id + 1;
+        sprint.id = new_id;
+        validate_sprint(&parsed, &sprint)?;
+
+        parsed.last_item_id = new_id;
+        parsed.sprints.insert(new_id, sprint);
+
+        self.write_db(&parsed)?;
+        self.audit(AuditOperation::CreateSprint, new_id);
+        Ok(new_id)
+    }
+
+    pub fn get_sprint(&self, sprint_id: u32) -> Result<Sprint> {
+        self.read_db()?
+            .sprints
+            .remove(&sprint_id)
+            .ok_or_else(|| anyhow!("could not find sprint in database!"))
+    }
+
+    /// Replaces the sprint with the same id as `sprint`.
+    pub fn update_sprint(&self, sprint: Sprint) -> Result<()> {
+        let _lock = self.lock_for_write()?;
+        let mut parsed = self.read_db()?;
+
+        if !parsed.sprints.contains_key(&sprint.id) {
+            return Err(anyhow!("could not find sprint in database!"));
+        }
+        validate_sprint(&parsed, &sprint)?;
+
+        let sprint_id = sprint.id;
+        parsed.sprints.insert(sprint_id, sprint);
+
+        self.write_db(&parsed)?;
+        self.audit(AuditOperation::UpdateSprint, sprint_id);
+        Ok(())
+    }
+
+    /// Deletes the sprint only; its stories stay in their epics.
+    pub fn delete_sprint(&self, sprint_id: u32) -> Result<()> {
+        let _lock = self.lock_for_write()?;
+        let mut parsed = self.read_db()?;
+
+        parsed
+            .sprints
+            .remove(&sprint_id)
+            .ok_or_else(|| anyhow!("could not find sprint in database!"))?;
+
+        self.write_db(&parsed)?;
+        self.audit(AuditOperation::DeleteSprint, sprint_id);
+        Ok(())
+    }
+
+    /// Deletes the active project's sprints that ended more than
+    /// `older_than_days` days ago and returns how many were removed.
+    /// Membership is stored on the sprint, so its stories simply stop
+    /// belonging to any sprint.
+    pub fn purge_old_sprints(&self, older_than_days: u32) -> Result<usize> {
+        let _lock = self.lock_for_write()?;
+        let mut parsed = self.read_db()?;
+
+        // No sprint can end before the earliest date there is
+        let Some(cutoff) = Local::now()
+            .date_naive()
+            .checked_sub_days(Days::new(older_than_days.into()))
+        else {
+            return Ok(0);
+        };
+        // Ending before the cutoff also means the sprint is no longer active
+        let purged: Vec<u32> = parsed
+            .sprints
+            .iter()
+            .filter(|(_, sprint)| sprint.end_date < cutoff)
+            .map(|(id, _)| *id)
+            .sorted()
+            .collect();
+        if purged.is_empty() {
+            return Ok(0);
+        }
+        for sprint_id in &purged {
+            parsed.sprints.remove(sprint_id);
+        }
+
+        self.write_db(&parsed)?;
+        for sprint_id in &purged {
+            self.audit(AuditOperation::DeleteSprint, *sprint_id);
+        }
+        Ok(purged.len())
+    }
+
+    /// A story belongs to at most one sprint, so assigning it moves it out
+    /// of whichever sprint it was in before.
+    pub fn assign_story_to_sprint(&self, sprint_id: u32, story_id: u32) -> Result<()> {
+        let _lock = self.lock_for_write()?;
+        let mut parsed = self.read_db()?;
+
+        if !parsed.sprints.contains_key(&sprint_id) {
+            return Err(anyhow!("could not find sprint in database!"));
+        }
+        if !parsed.stories.contains_key(&story_id) {
+            return Err(anyhow!("could not find story {} in database!", story_id));
+        }
+
+        for sprint in parsed.sprints.values_mut() {
+            if sprint.id != sprint_id {
+                sprint.story_ids.retain(|id| *id != story_id);
+            }
+        }
+        if let Some(sprint) = parsed
+            .sprints
+            .get_mut(&sprint_id)
+            .filter(|sprint| !sprint.story_ids.contains(&story_id))
+        {
+            sprint.story_ids.push(story_id);
+        }
+
+        self.write_db(&parsed)?;
+        self.audit(AuditOperation::AssignStoryToSprint, sprint_id);
+        Ok(())
+    }
+
+    pub fn remove_story_from_sprint(&self, sprint_id: u32, story_id: u32) -> Result<()> {
+        let _lock = self.lock_for_write()?;
+        let mut parsed = self.read_db()?;
+
+        let sprint = parsed
+            .sprints
+            .get_mut(&sprint_id)
+            .ok_or_else(|| anyhow!("could not find sprint in database!"))?;
+        let story_index = sprint
+            .story_ids
+            .iter()
+            .position(|id| *id == story_id)
+            .ok_or_else(|| anyhow!("story {} is not in this sprint!", story_id))?;
+        sprint.story_ids.remove(story_index);
+
+        self.write_db(&parsed)?;
+        self.audit(AuditOperation::RemoveStoryFromSprint, sprint_id);
+        Ok(())
+    }
+
+    /// Stories that are not in any sprint, as `(epic_id, story_id, story)`
+    /// sorted by epic and then by priority.
+    pub fn backlog_stories(&self) -> Result<Vec<(u32, u32, Story)>> {
+        let mut parsed = self.read_db()?;
+
+        let planned: HashSet<u32> = parsed
+            .sprints
+            .values()
+            .flat_map(|sprint| sprint.story_ids.iter().copied())
+            .collect();
+
+        let mut backlog = vec![];
+        for (epic_id, epic) in &parsed.epics {
+            for story_id in &epic.stories {
+                if planned.contains(story_id) {
+                    continue;
+                }
+                if let Some(story) = parsed.stories.remove(story_id) {
+                    backlog.push((*epic_id, *story_id, story));
+                }
+            }
+        }
+        backlog.sort_by_key(|(epic_id, story_id, story)| (*epic_id, story.priority, *story_id));
+
+        Ok(backlog)
+    }
+
+    /// Writes a plain-text retrospective of the sprint to `dest`. RESOLVED
+    /// and CLOSED stories count as completed; the rest are carried over.
+    /// Assignments are not timestamped, so a story counts as added
+    /// mid-sprint when it was created after the sprint started.
+    pub fn export_sprint_report(&self, sprint_id: u32, dest: &Path) -> Result<()> {
+        let mut parsed = self.read_db()?;
+        let sprint = parsed
+            .sprints
+            .remove(&sprint_id)
+            .ok_or_else(|| anyhow!("could not find sprint in database!"))?;
+        let stories: Vec<(u32, Story)> = sprint
+            .story_ids
+            .iter()
+            .filter_map(|id| parsed.stories.remove(id).map(|story| (*id, story)))
+            .collect();
+
+        let is_completed =
+            |story: &Story| matches!(story.status, Status::Resolved | Status::Closed);
+        let points = |story: &Story| u32::from(story.estimate.unwrap_or(0));
+        let committed: u32 = stories.iter().map(|(_, story)| points(story)).sum();
+        let completed: u32 = stories
+            .iter()
+            .filter(|(_, story)| is_completed(story))
+            .map(|(_, story)| points(story))
+            .sum();
+        let percentage = match committed {
+            0 => 0,
+            _ => completed * 100 / committed,
+        };
+
+        let mut report = String::new();
+        report.push_str(&format!("Sprint report: {}\n", sprint.name));
+        report.push_str(&format!(
+            "Dates: {} to {}\n",
+            sprint.start_date, sprint.end_date
+        ));
+        report.push_str(&format!("Committed points: {}\n", committed));
+        report.push_str(&format!(
+            "Completed points: {} ({}%)\n\n",
+            completed, percentage
+        ));
+
+        report.push_str(&format!(
+            "{:<6} {:<12} {:>6}  {}\n",
+            "ID", "STATUS", "POINTS", "NAME"
+        ));
+        for (id, story) in &stories {
+            let estimate = story
+                .estimate
+                .map(|estimate| estimate.to_string())
+                .unwrap_or_else(|| "-".to_owned());
+            report.push_str(&format!(
+                "{:<6} {:<12} {:>6}  {}\n",
+                id,
+                story.status.to_string(),
+                estimate,
+                story.name
+            ));
+        }
+
+        let sections = [
+            (
+                "Added mid-sprint",
+                stories
+                    .iter()
+                    .filter(|(_, story)| story.created_at.date_naive() > sprint.start_date)
+                    .collect::<Vec<_>>(),
+            ),
+            (
+                "Carried over",
+                stories
+                    .iter()
+                    .filter(|(_, story)| !is_completed(story))
+                    .collect(),
+            ),
+        ];
+        for (title, section) in sections {
+            report.push_str(&format!("\n{}:\n", title));
+            if section.is_empty() {
+                report.push_str("- none\n");
+            }
+            for (id, story) in section {
+                report.push_str(&format!("- #{} {}\n", id, story.name));
+            }
+        }
+
+        fs::write(dest, report)?;
+        Ok(())
+    }
+
+    pub fn search_epics(&self, query: &SearchQuery) -> Result<Vec<(u32, Epic)>> {
+        let parsed = self.read_db()?;
+
+        let mut epics: Vec<(u32, Epic)> = parsed
+            .epics
+            .into_iter()
+            .filter(|(_, epic)| query.matches_epic(epic))
+            .collect();
+        epics.sort_by_key(|(id, _)| *id);
+
+        Ok(epics)
+    }
+
+    /// Epics carrying `tag`, in id order. Tags are matched ignoring case.
+    pub fn get_epics_by_tag(&self, tag: &str) -> Result<Vec<Epic>> {
+        let parsed = self.read_db()?;
+
+        Ok(parsed
+            .epics
+            .into_iter()
+            .filter(|(_, epic)| epic.has_tag(tag))
+            .sorted_by_key(|(id, _)| *id)
+            .map(|(_, epic)| epic)
+            .collect())
+    }
+
+    pub fn search_stories(&self, query: &SearchQuery) -> Result<Vec<(u32, Story)>> {
+        let parsed = self.read_db()?;
+
+        let mut stories: Vec<(u32, Story)> = parsed
+            .stories
+            .into_iter()
+            .filter(|(_, story)| query.matches_story(story))
+            .collect();
+        stories.sort_by_key(|(id, _)| *id);
+
+        Ok(stories)
+    }
+
+    pub fn get_epics_by_reporter(&self, reporter: &str) -> Result<Vec<(u32, Epic)>> {
+        let parsed = self.read_db()?;
+
+        let mut epics: Vec<(u32, Epic)> = parsed
+            .epics
+            .into_iter()
+            .filter(|(_, epic)| epic.reporter == reporter)
+            .collect();
+        epics.sort_by_key(|(id, _)| *id);
+
+        Ok(epics)
+    }
+
+    pub fn get_stories_by_reporter(&self, reporter: &str) -> Result<Vec<(u32, Story)>> {
+        let parsed = self.read_db()?;
+
+        let mut stories: Vec<(u32, Story)> = parsed
+            .stories
+            .into_iter()
+            .filter(|(_, story)| story.reporter == reporter)
+            .collect();
+        stories.sort_by_key(|(id, _)| *id);
+
+        Ok(stories)
+    }
+
+    pub fn get_epics_for_release(&self, release: &str) -> Result<Vec<(u32, Epic)>> {
+        let parsed = self.read_db()?;
+
+        let mut epics: Vec<(u32, Epic)> = parsed
+            .epics
+            .into_iter()
+            .filter(|(_, epic)| epic.release.as_deref() == Some(release))
+            .collect();
+        epics.sort_by_key(|(id, _)| *id);
+
+        Ok(epics)
+    }
+
+    pub fn get_stories_for_release(&self, release: &str) -> Result<Vec<(u32, Story)>> {
+        let parsed = self.read_db()?;
+
+        let mut stories: Vec<(u32, Story)> = parsed
+            .stories
+            .into_iter()
+            .filter(|(_, story)| story.release.as_deref() == Some(release))
+            .collect();
+        stories.sort_by_key(|(id, _)| *id);
+
+        Ok(stories)
+    }
+
+    pub fn get_epic_with_stories(&self, epic_id: u32) -> Result<EpicView> {
+        let mut parsed = self.read_db()?;
+
+        let epic = parsed
+            .epics
+            .remove(&epic_id)
+            .ok_or_else(|| anyhow!("could not find epic in database!"))?;
+
+        let mut stories = vec![];
+        let mut missing_story_ids = vec![];
+        for story_id in &epic.stories {
+            match parsed.stories.remove(story_id) {
+                Some(story) => stories.push((*story_id, story)),
+                None => missing_story_ids.push(*story_id),
+            }
+        }
+
+        Ok(EpicView {
+            epic_id,
+            epic,
+            stories,
+            missing_story_ids,
+        })
+    }
+
+    pub fn get_story_with_epic(&self, story_id: u32) -> Result<StoryView> {
+        let mut parsed = self.read_db()?;
+
+        let story = parsed
+            .stories
+            .remove(&story_id)
+            .ok_or_else(|| anyhow!("could not find story in database!"))?;
+        let (epic_id, epic) = parsed
+            .epics
+            .into_iter()
+            .find(|(_, epic)| epic.stories.contains(&story_id))
+            .ok_or_else(|| anyhow!("could not find the epic for story {}!", story_id))?;
+
+        Ok(StoryView {
+            story_id,
+            story,
+            epic_id,
+            epic,
+        })
+    }
+
+    /// Every release used by an epic or story, sorted by name.
+    pub fn releases(&self) -> Result<Vec<ReleaseSummary>> {
+        let parsed = self.read_db()?;
+
+        let mut counts: HashMap<&str, (usize, usize)> = HashMap::new();
+        for release in parsed
+            .epics
+            .values()
+            .filter_map(|epic| epic.release.as_deref())
+        {
+            counts.entry(release).or_default().0 += 1;
+        }
+        for release in parsed
+            .stories
+            .values()
+            .filter_map(|story| story.release.as_deref())
+        {
+            counts.entry(release).or_default().1 += 1;
+        }
+
+        Ok(counts
+            .into_iter()
+            .sorted()
+            .map(|(name, (epic_count, story_count))| ReleaseSummary {
+                name: name.to_owned(),
+                epic_count,
+                story_count,
+            })
+            .collect())
+    }
+
+    pub fn stories_above_word_count(&self, limit: usize) -> Result<Vec<(u32, Story)>> {
+        let parsed = self.read_db()?;
+
+        let mut stories: Vec<(u32, Story)> = parsed
+            .stories
+            .into_iter()
+            .filter(|(_, story)| story.word_count_description() > limit)
+            .collect();
+        stories.sort_by_key(|(id, _)| *id);
+
+        Ok(stories)
+    }
+
+    pub fn health_check(&self) -> Result<()> {
+        let _lock = self.lock_for_write()?;
+        let mut parsed = self.read_db()?;
+
+        let max_id = parsed.ids().max().copied().unwrap_or(0);
+
+        if max_id > parsed.last_item_id {
+            log!(
+                LogLevel::Warn,
+                "last_item_id is behind the highest id in the database, bumping it",
+                last_item_id = parsed.last_item_id,
+                max_id = max_id,
+            );
+            parsed.last_item_id = max_id + 1;
+            self.write_db(&parsed)?;
+        }
+
+        for issue in parsed.validate() {
+            log!(LogLevel::Warn, issue.to_string());
+        }
+
+        Ok(())
+    }
+
+    /// Drops the story ids that epics list but that are not in the
+    /// database. Nothing is written when there is nothing to drop.
+    pub fn repair(&self) -> Result<RepairReport> {
+        let _lock = self.lock_for_write()?;
+        let mut parsed = self.read_db()?;
+
+        let mut report = RepairReport::default();
+        for (epic_id, epic) in parsed.epics.iter_mut().sorted_by_key(|(id, _)| **id) {
+            for story_id in &epic.stories {
+                if !parsed.stories.contains_key(story_id) {
+                    report.removed_story_references.push((*epic_id, *story_id));
+                }
+            }
+            epic.stories
+                .retain(|story_id| parsed.stories.contains_key(story_id));
+        }
+
+        if !report.removed_story_references.is_empty() {
+            self.write_db(&parsed)?;
+        }
+        Ok(report)
+    }
+
+    /// Writes the active project as two CSV sections separated by a blank
+    /// line: epics first, then stories. Stories that no epic lists are left
+    /// out with a warning, since an import would have nowhere to put them.
+    pub fn export_csv(&self, mut writer: impl Write) -> Result<()> {
+        let parsed = self.read_db()?;
+
+        // The sections have different columns, so each gets its own csv writer.
+        let mut epics_writer = csv::Writer::from_writer(&mut writer);
+        epics_writer.write_record(CSV_EPIC_HEADER)?;
+        for id in parsed.epics.keys().sorted() {
+            let epic = &parsed.epics[id];
+            epics_writer.write_record([
+                id.to_string(),
+                epic.name.clone(),
+                epic.description.clone(),
+                epic.status.to_string(),
+                epic.priority.to_string(),
+            ])?;
+        }
+        epics_writer.flush()?;
+        drop(epics_writer);
+
+        writer.write_all(b"\n")?;
+
+        let epic_ids: HashMap<u32, u32> = parsed
+            .epics
+            .iter()
+            .flat_map(|(epic_id, epic)| epic.stories.iter().map(|story_id| (*story_id, *epic_id)))
+            .collect();
+
+        let mut stories_writer = csv::Writer::from_writer(&mut writer);
+        stories_writer.write_record(CSV_STORY_HEADER)?;
+        for id in parsed.stories.keys().sorted() {
+            let story = &parsed.stories[id];
+            let Some(epic_id) = epic_ids.get(id) else {
+                log!(
+                    LogLevel::Warn,
+                    "story does not belong to any epic, leaving it out of the export",
+                    story_id = id,
+                );
+                continue;
+            };
+            stories_writer.write_record([
+                id.to_string(),
+                epic_id.to_string(),
+                story.name.clone(),
+                story.description.clone(),
+                story.status.to_string(),
+                story
+                    .estimate
+                    .map(|estimate| estimate.to_string())
+                    .unwrap_or_default(),
+            ])?;
+        }
+        stories_writer.flush()?;
+
+        Ok(())
+    }
+
+    /// Reads the format written by `export_csv`. Imported items get fresh
+    /// ids, and a story's epic_id may point either at an epic from the same
+    /// file or at an epic already in the database. Any bad row fails the
+    /// whole import and nothing is written.
+    pub fn import_csv(&self, reader: impl Read) -> Result<ImportReport> {
+        self.import_csv_rows(reader, false)
+    }
+
+    /// Like `import_csv`, except stories whose epic cannot be found are
+    /// skipped and listed in `ImportReport::errors` instead.
+    pub fn import_csv_lenient(&self, reader: impl Read) -> Result<ImportReport> {
+        self.import_csv_rows(reader, true)
+    }
+
+    fn import_csv_rows(&self, reader: impl Read, lenient: bool) -> Result<ImportReport> {
+        let _lock = self.lock_for_write()?;
+        // Everything is applied to this copy and written once at the end, so
+        // bailing out part way through leaves the database untouched.
+        let mut parsed = self.read_db()?;
+        let mut report = ImportReport::default();
+        let mut imported_epic_ids: HashMap<u32, u32> = HashMap::new();
+        let mut created = Vec::new();
+        let mut section = None;
+
+        let mut csv_reader = csv::ReaderBuilder::new()
+            .has_headers(false)
+            .flexible(true)
+            .from_reader(reader);
+
+        for record in csv_reader.records() {
+            let record = record?;
+            let line = record.position().map_or(0, |position| position.line());
+            let fields: Vec<&str> = record.iter().collect();
+
+            if fields == CSV_EPIC_HEADER {
+                section = Some(CsvSection::Epics);
+                continue;
+            }
+            if fields == CSV_STORY_HEADER {
+                section = Some(CsvSection::Stories);
+                continue;
+            }
+
+            match section {
+                None => return Err(anyhow!("line {}: expected an epic or story header!", line)),
+                Some(CsvSection::Epics) => {
+                    let (file_id, mut epic) =
+                        epic_from_csv(&fields).map_err(|e| anyhow!("line {}: {}", line, e))?;
+                    self.fill_in_reporter(&mut epic.reporter);
+
+                    parsed.last_item_id += 1;
+                    parsed.epics.insert(parsed.last_item_id, epic);
+                    imported_epic_ids.insert(file_id, parsed.last_item_id);
+                    created.push((AuditOperation::CreateEpic, parsed.last_item_id));
+                    report.created_epics += 1;
+                }
+                Some(CsvSection::Stories) => {
+                    let (file_epic_id, mut story) =
+                        story_from_csv(&fields).map_err(|e| anyhow!("line {}: {}", line, e))?;
+                    self.fill_in_reporter(&mut story.reporter);
+
+                    let epic_id = imported_epic_ids.get(&file_epic_id).copied().or_else(|| {
+                        parsed
+                            .epics
+                            .contains_key(&file_epic_id)
+                            .then_some(file_epic_id)
+                    });
+                    let Some(epic_id) = epic_id else {
+                        let error = format!(
+                            "line {}: story \"{}\" references epic {} which does not exist",
+                            line, story.name, file_epic_id
+                        );
+                        if lenient {
+                            report.errors.push(error);
+                            continue;
+                        }
+                        return Err(anyhow!(error));
+                    };
+
+                    parsed.last_item_id += 1;
+                    parsed.stories.insert(parsed.last_item_id, story);
+                    parsed
+                        .epics
+                        .get_mut(&epic_id)
+                        .ok_or_else(|| anyhow!("could not find epic in database!"))?
+                        .stories
+                        .push(parsed.last_item_id);
+                    created.push((AuditOperation::CreateStory, parsed.last_item_id));
+                    report.created_stories += 1;
+                }
+            }
+        }
+
+        self.write_db(&parsed)?;
+        for (operation, item_id) in created {
+            self.audit(operation, item_id);
+        }
+        Ok(report)
+    }
+}
+
+impl DBState {
+    /// Copies the epics and stories of `incoming` whose ids are free in
+    /// `base`, where an id counts as used if any epic, story or sprint of any
+    /// project has it. Ids used by both with identical content, timestamps
+    /// aside, are left alone. Ids used by both with different content are
+    /// reported as conflicts while `base` keeps its own version, and so are
+    /// the stories of a conflicting epic. Sprints and other projects are
+    /// taken from `base` only.
+    pub fn merge(base: DBState, incoming: DBState) -> (DBState, MergeReport) {
+        let mut merged = base;
+        let mut report = MergeReport::default();
+        let mut inserted_story_ids = HashSet::new();
+
+        // Epics, stories and sprints of every project share one id space
+        let used_ids: HashSet<u32> = merged.ids().copied().collect();
+        let conflicting_epic_ids: HashSet<u32> = incoming
+            .epics
+            .iter()
+            .filter(|(id, epic)| match merged.epics.get(id) {
+                // Extra stories on either side are not a conflict on their own
+                Some(existing) => !same_epic(existing, epic),
+                None => used_ids.contains(id),
+            })
+            .map(|(id, _)| *id)
+            .collect();
+
+        for (id, story) in incoming.stories.iter().sorted_by_key(|(id, _)| **id) {
+            if let Some(existing) = merged.stories.get(id) {
+                if !same_story(existing, story) {
+                    report.conflicts.push(ConflictRecord {
+                        item: MergeItem::Story,
+                        id: *id,
+                    });
+                }
+                continue;
+            }
+
+            let in_conflicting_epic = incoming.epics.iter().any(|(epic_id, epic)| {
+                conflicting_epic_ids.contains(epic_id) && epic.stories.contains(id)
+            });
+            if used_ids.contains(id) || in_conflicting_epic {
+                report.conflicts.push(ConflictRecord {
+                    item: MergeItem::Story,
+                    id: *id,
+                });
+                continue;
+            }
+
+            merged.stories.insert(*id, story.clone());
+            inserted_story_ids.insert(*id);
+            report.inserted_stories += 1;
+        }
+
+        for (id, epic) in incoming.epics.iter().sorted_by_key(|(id, _)| **id) {
+            if conflicting_epic_ids.contains(id) {
+                report.conflicts.push(ConflictRecord {
+                    item: MergeItem::Epic,
+                    id: *id,
+                });
+                continue;
+            }
+
+            match merged.epics.get_mut(id) {
+                None => {
+                    // Stories that `base` already had stay in their base epic
+                    let mut epic = epic.clone();
+                    epic.stories
+                        .retain(|story_id| inserted_story_ids.contains(story_id));
+                    merged.epics.insert(*id, epic);
+                    report.inserted_epics += 1;
+                }
+                Some(existing) => {
+                    // Newly inserted stories still need an epic to live in
+                    for story_id in &epic.stories {
+                        if inserted_story_ids.contains(story_id)
+                            && !existing.stories.contains(story_id)
+                        {
+                            existing.stories.push(*story_id);
+                        }
+                    }
+                }
+            }
+        }
+
+        merged.last_item_id = merged.last_item_id.max(incoming.last_item_id);
+        drop_dangling_story_links(&mut merged);
+
+        (merged, report)
+    }
+
+    /// Checks the references between epics and stories in the active
+    /// project, and that no id is used twice anywhere. Issues are sorted by
+    /// the ids involved.
+    pub fn validate(&self) -> Vec<IntegrityIssue> {
+        let mut issues = vec![];
+
+        let mut story_owners: HashMap<u32, usize> = HashMap::new();
+        for (epic_id, epic) in self.epics.iter().sorted_by_key(|(id, _)| **id) {
+            for story_id in &epic.stories {
+                if self.stories.contains_key(story_id) {
+                    *story_owners.entry(*story_id).or_default() += 1;
+                } else {
+                    issues.push(IntegrityIssue::OrphanedStoryReference {
+                        epic_id: *epic_id,
+                        story_id: *story_id,
+                    });
+                }
+            }
+        }
+
+        issues.extend(
+            self.stories
+                .keys()
+                .filter(|story_id| story_owners.get(story_id) != Some(&1))
+                .sorted()
+                .map(|story_id| IntegrityIssue::MismatchedEpicId {
+                    story_id: *story_id,
+                }),
+        );
+
+        issues.extend(
+            self.ids()
+                .counts()
+                .into_iter()
+                .filter(|(_, count)| *count > 1)
+                .map(|(id, _)| *id)
+                .sorted()
+                .map(|id| IntegrityIssue::DuplicateId { id }),
+        );
+
+        issues
+    }
+
+    // Every epic, story and sprint id, in this project and the others
+    fn ids(&self) -> impl Iterator<Item = &u32> {
+        self.epics
+            .keys()
+            .chain(self.stories.keys())
+            .chain(self.sprints.keys())
+            .chain(self.projects.values().flat_map(|project| {
+                project
+                    .epics
+                    .keys()
+                    .chain(project.stories.keys())
+                    .chain(project.sprints.keys())
+            }))
+    }
+}
+
+// Surrounding whitespace is dropped and a blank release means none
+fn normalize_release(release: Option<String>) -> Option<String> {
+    release
+        .map(|release| release.trim().to_owned())
+        .filter(|release| !release.is_empty())
+}
+
+fn validate_item(
+    kind: &str,
+    name: &str,
+    max_name_len: usize,
+    description: &str,
+    max_description_len: usize,
+) -> Result<()> {
+    validate_name(kind, name, max_name_len)?;
+    validate_description(kind, description, max_description_len)
+}
+
+fn validate_name(kind: &str, name: &str, max_len: usize) -> Result<()> {
+    if name.trim().is_empty() {
+        return Err(anyhow!("{} name cannot be empty!", kind));
+    }
+    if name.chars().count() > max_len {
+        return Err(anyhow!(
+            "{} name cannot be longer than {} characters!",
+            kind,
+            max_len
+        ));
+    }
+
+    Ok(())
+}
+
+fn validate_description(kind: &str, description: &str, max_len: usize) -> Result<()> {
+    if description.chars().count() > max_len {
+        return Err(anyhow!(
+            "{} description cannot be longer than {} characters!",
+            kind,
+            max_len
+        ));
+    }
+
+    Ok(())
+}
+
+fn validate_sprint(state: &DBState, sprint: &Sprint) -> Result<()> {
+    if sprint.name.trim().is_empty() {
+        return Err(anyhow!("sprint name cannot be empty!"));
+    }
+    if sprint.name.chars().count() > MAX_SPRINT_NAME_LEN {
+        return Err(anyhow!(
+            "sprint name cannot be longer than {} characters!",
+            MAX_SPRINT_NAME_LEN
+        ));
+    }
+    if sprint.end_date < sprint.start_date {
+        return Err(anyhow!("sprint cannot end before it starts!"));
+    }
+
+    for story_id in &sprint.story_ids {
+        if !state.stories.contains_key(story_id) {
+            return Err(anyhow!("could not find story {} in database!", story_id));
+        }
+        let other_sprint = state
+            .sprints
+            .values()
+            .find(|other| other.id != sprint.id && other.story_ids.contains(story_id));
+        if let Some(other_sprint) = other_sprint {
+            return Err(anyhow!(
+                "story {} is already in sprint \"{}\"!",
+                story_id,
+                other_sprint.name
+            ));
+        }
+    }
+
+    Ok(())
+}
+
+// Depth-first walk along `blocks` links, looking for a path from `from` to `to`.
+fn story_blocks_transitively(stories: &HashMap<u32, Story>, from: u32, to: u32) -> bool {
+    let mut visited = HashSet::new();
+    let mut stack = vec![from];
+
+    while let Some(id) = stack.pop() {
+        if id == to {
+            return true;
+        }
+        if !visited.insert(id) {
+            continue;
+        }
+        if let Some(story) = stories.get(&id) {
+            stack.extend(story.blocks.iter().copied());
+        }
+    }
+
+    false
+}
+
+// Whether two epics have the same content, leaving out their stories and
+// timestamps. Items saved before timestamps were tracked get the time they
+// were loaded, so two loads of one file never agree on them.
+fn same_epic(a: &Epic, b: &Epic) -> bool {
+    let content = |epic: &Epic| Epic {
+        stories: vec![],
+        created_at: DateTime::UNIX_EPOCH,
+        updated_at: DateTime::UNIX_EPOCH,
+        ..epic.clone()
+    };
+    content(a) == content(b)
+}
+
+// Whether two stories have the same content, leaving out their timestamps.
+fn same_story(a: &Story, b: &Story) -> bool {
+    let content = |story: &Story| Story {
+        created_at: DateTime::UNIX_EPOCH,
+        updated_at: DateTime::UNIX_EPOCH,
+        ..story.clone()
+    };
+    content(a) == content(b)
+}
+
+// Removes references to deleted stories from dependency links and sprints.
+fn drop_dangling_story_links(state: &mut DBState) {
+    let existing: HashSet<u32> = state.stories.keys().copied().collect();
+
+    for story in state.stories.values_mut() {
+        story.blocks.retain(|id| existing.contains(id));
+        story.blocked_by.retain(|id| existing.contains(id));
+    }
+    for sprint in state.sprints.values_mut() {
+        sprint.story_ids.retain(|id| existing.contains(id));
+    }
+}
+
+fn matches_query(name: &str, description: &str, query: &str) -> bool {
+    let query = query.trim().to_lowercase();
+    name.to_lowercase().contains(&query) || description.to_lowercase().contains(&query)
+}
+
+fn epic_from_csv(fields: &[&str]) -> Result<(u32, Epic)> {
+    let [id, name, description, status, priority] = fields else {
+        return Err(anyhow!(
+            "expected {} epic columns, found {}!",
+            CSV_EPIC_HEADER.len(),
+            fields.len()
+        ));
+    };
+
+    validate_item(
+        "epic",
+        name,
+        MAX_EPIC_NAME_LEN,
+        description,
+        MAX_EPIC_DESCRIPTION_LEN,
+    )?;
+
+    let id = id
+        .parse::<u32>()
+        .map_err(|_| anyhow!("invalid epic id '{}'!", id))?;
+    let epic = Epic {
+        status: status.parse::<Status>()?,
+        priority: priority.parse::<Priority>()?,
+        ..Epic::new(name.to_string(), description.to_string())
+    };
+
+    Ok((id, epic))
+}
+
+fn story_from_csv(fields: &[&str]) -> Result<(u32, Story)> {
+    let [_, epic_id, name, description, status, estimate] = fields else {
+        return Err(anyhow!(
+            "expected {} story columns, found {}!",
+            CSV_STORY_HEADER.len(),
+            fields.len()
+        ));
+    };
+
+    validate_item(
+        "story",
+        name,
+        MAX_STORY_NAME_LEN,
+        description,
+        MAX_STORY_DESCRIPTION_LEN,
+    )?;
+
+    let epic_id = epic_id
+        .parse::<u32>()
+        .map_err(|_| anyhow!("invalid epic id '{}'!", epic_id))?;
+    let estimate = match estimate.trim() {
+        "" => None,
+        estimate => Some(
+            estimate
+                .parse::<u8>()
+                .ok()
+                .filter(|estimate| STORY_ESTIMATE_VALUES.contains(estimate))
+                .ok_or_else(|| anyhow!("invalid estimate '{}'!", estimate))?,
+        ),
+    };
+    let story = Story {
+        status: status.parse::<Status>()?,
+        estimate,
+        ..Story::new(name.to_string(), description.to_string())
+    };
+
+    Ok((epic_id, story))
+}
+
+pub trait Database {
+    fn read_db(&self) -> Result<DBState>;
+    fn write_db(&self, db_state: &DBState) -> Result<()>;
+
+    /// Where the database lives on disk, if it lives on disk at all.
+    fn file_path(&self) -> Option<&str> {
+        None
+    }
+
+    /// Locks the database against other processes until the lock is
+    /// dropped. Databases that cannot be shared need no lock.
+    fn lock(&self) -> Result<Option<FileLock>> {
+        Ok(None)
+    }
+}
+
+/// An advisory lock held as a sentinel file. The file is removed on drop.
+pub struct FileLock {
+    path: PathBuf,
+}
+
+impl FileLock {
+    pub fn acquire(path: PathBuf, timeout: Duration) -> Result<Self> {
+        let started = Instant::now();
+
+        loop {
+            match fs::OpenOptions::new()
+                .write(true)
+                .create_new(true)
+                .open(&path)
+            {
+                Ok(_) => return Ok(Self { path }),
+                Err(error) if error.kind() == io::ErrorKind::AlreadyExists => {
+                    if started.elapsed() >= timeout {
+                        return Err(anyhow!(
+                            "the database is being written by another instance of ironyy! If none is running, delete {} and try again.",
+                            path.display()
+                        ));
+                    }
+                    thread::sleep(LOCK_RETRY_INTERVAL);
+                }
+                Err(error) => return Err(error.into()),
+            }
+        }
+    }
+}
+
+impl Drop for FileLock {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.path);
+    }
+}
+
+struct JSONFileDatabase {
+    pub file_path: String,
+}
+
+impl Database for JSONFileDatabase {
+    fn read_db(&self) -> Result<DBState> {
+        let db_content = fs::read_to_string(&self.file_path)?;
+        let parsed: DBState = serde_json::from_str(&db_content)?;
+        Ok(parsed)
+    }
+
+    fn write_db(&self, db_state: &DBState) -> Result<()> {
+        atomic_write(Path::new(&self.file_path), &serde_json::to_vec(db_state)?)?;
+        Ok(())
+    }
+
+    fn file_path(&self) -> Option<&str> {
+        Some(&self.file_path)
+    }
+
+    fn lock(&self) -> Result<Option<FileLock>> {
+        let path = PathBuf::from(format!("{}.lock", self.file_path));
+        FileLock::acquire(path, LOCK_TIMEOUT).map(Some)
+    }
+}
+
+pub mod test_utils {
+    use std::{
+        cell::{Cell, RefCell},
+        collections::HashMap,
+        rc::Rc,
+    };
+
+    use super::*;
+    use crate::models::DEFAULT_PROJECT_NAME;
+
+    pub struct MockDB {
+        last_written_state: RefCell<DBState>,
+        read_count: Rc<Cell<usize>>,
+        write_count: Rc<Cell<usize>>,
+        fail_writes: Rc<Cell<bool>>,
+    }
+
+    impl MockDB {
+        pub fn new() -> Self {
+            Self {
+                last_written_state: RefCell::new(DBState {
+                    last_item_id: 0,
+                    epics: HashMap::new(),
+                    stories: HashMap::new(),
+                    sprints: HashMap::new(),
+                    active_project: DEFAULT_PROJECT_NAME.to_owned(),
+                    projects: HashMap::new(),
+                }),
+                read_count: Rc::new(Cell::new(0)),
+                write_count: Rc::new(Cell::new(0)),
+                fail_writes: Rc::new(Cell::new(false)),
+            }
+        }
+
+        // Shared handles so tests can still inspect the counts after the mock is boxed
+        pub fn read_count(&self) -> Rc<Cell<usize>> {
+            Rc::clone(&self.read_count)
+        }
+
+        pub fn write_count(&self) -> Rc<Cell<usize>> {
+            Rc::clone(&self.write_count)
+        }
+
+        /// While set, every write fails as if the disk were full.
+        pub fn fail_writes(&self) -> Rc<Cell<bool>> {
+            Rc::clone(&self.fail_writes)
+        }
+    }
+
+    impl Database for MockDB {
+        fn read_db(&self) -> Result<DBState> {
+            let state = self.last_written_state.borrow().clone();
+            self.read_count.set(self.read_count.get() + 1);
+            Ok(state)
+        }
+
+        fn write_db(&self, db_state: &DBState) -> Result<()> {
+            if self.fail_writes.get() {
+                return Err(anyhow!("injected write failure"));
+            }
+
+            let latest_state = &self.last_written_state;
+            *latest_state.borrow_mut() = db_state.clone();
+            self.write_count.set(self.write_count.get() + 1);
+            Ok(())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::test_utils::MockDB;
+    use super::*;
+
+    #[test]
+    fn changes_should_be_written_to_the_audit_log() {
+        use crate::audit::AuditRecord;
+
+        let tmpfile = tempfile::NamedTempFile::new().unwrap();
+        let db = JiraDatabase::with_database(Box::new(MockDB::new()))
+            .with_audit_logger(AuditLogger::new(tmpfile.path()));
+        db.set_audit_user("alice");
+
+        let epic_id = db
+            .create_epic(Epic::new("epic".to_owned(), "".to_owned()))
+            .unwrap();
+        let story_id = db
+            .create_story(Story::new("story".to_owned(), "".to_owned()), epic_id)
+            .unwrap();
+        db.update_story_status(story_id, Status::Closed).unwrap();
+        db.delete_story(epic_id, story_id).unwrap();
+        // Failed operations change nothing, so they are not audited
+        assert!(db.delete_epic(999).is_err());
+
+        let records: Vec<(AuditOperation, u32, String)> = std::fs::read_to_string(tmpfile.path())
+            .unwrap()
+            .lines()
+            .map(|line| serde_json::from_str::<AuditRecord>(line).unwrap())
+            .map(|record| (record.operation, record.item_id.unwrap(), record.username))
+            .collect();
+        assert_eq!(
+            records,
+            vec![
+                (AuditOperation::CreateEpic, epic_id, "alice".to_owned()),
+                (AuditOperation::CreateStory, story_id, "alice".to_owned()),
+                (
+                    AuditOperation::UpdateStoryStatus,
+                    story_id,
+                    "alice".to_owned()
+                ),
+                (AuditOperation::DeleteStory, story_id, "alice".to_owned()),
+            ]
+        );
+    }
+
+    #[test]
+    fn project_changes_should_be_written_to_the_audit_log() {
+        use crate::audit::AuditRecord;
+
+        let tmpfile = tempfile::NamedTempFile::new().unwrap();
+        let db = JiraDatabase::with_database(Box::new(MockDB::new()))
+            .with_audit_logger(AuditLogger::new(tmpfile.path()));
+        db.set_audit_user("alice");
+
+        db.use_project("side project").unwrap();
+        db.use_project("default").unwrap();
+        // Switching to the active project changes nothing
+        db.use_project("default").unwrap();
+        db.delete_project("side project").unwrap();
+
+        let records: Vec<(AuditOperation, String, String)> =
+            std::fs::read_to_string(tmpfile.path())
+                .unwrap()
+                .lines()
+                .map(|line| serde_json::from_str::<AuditRecord>(line).unwrap())
+                .map(|record| (record.operation, record.project.unwrap(), record.username))
+                .collect();
+        assert_eq!(
+            records,
+            vec![
+                (
+                    AuditOperation::CreateProject,
+                    "side project".to_owned(),
+                    "alice".to_owned()
+                ),
+                (
+                    AuditOperation::SwitchProject,
+                    "default".to_owned(),
+                    "alice".to_owned()
+                ),
+                (
+                    AuditOperation::DeleteProject,
+                    "side project".to_owned(),
+                    "alice".to_owned()
+                ),
+            ]
+        );
+    }
+
+    // States built separately only compare equal once their timestamps agree
+    fn pin_timestamps(state: &mut DBState) {
+        let timestamp = "2025-06-15T12:00:00Z".parse().unwrap();
+        for epic in state.epics.values_mut() {
+            epic.created_at = timestamp;
+            epic.updated_at = timestamp;
+        }
+        for story in state.stories.values_mut() {
+            story.created_at = timestamp;
+            story.updated_at = timestamp;
+        }
+    }
+
+    fn state_with_epic(epic_name: &str, story_names: &[&str]) -> DBState {
+        let db = JiraDatabase::with_database(Box::new(MockDB::new()));
+        let epic_id = db
+            .create_epic(Epic::new(epic_name.to_owned(), "".to_owned()))
+            .unwrap();
+        for name in story_names {
+            db.create_story(Story::new((*name).to_owned(), "".to_owned()), epic_id)
+                .unwrap();
+        }
+        let mut state = db.read_db().unwrap();
+        pin_timestamps(&mut state);
+        state
+    }
+
+    #[test]
+    fn merge_should_insert_everything_when_ids_are_free() {
+        let base = state_with_epic("base", &["base story"]);
+        let mut incoming = state_with_epic("incoming", &["incoming story"]);
+        // Move the incoming items onto ids that base doesn't use
+        let epic = incoming.epics.remove(&1).unwrap();
+        let story = incoming.stories.remove(&2).unwrap();
+        incoming.epics.insert(
+            10,
+            Epic {
+                stories: vec![11],
+                ..epic
+            },
+        );
+        incoming.stories.insert(11, story);
+        incoming.last_item_id = 11;
+
+        let (merged, report) = DBState::merge(base, incoming);
+
+        assert_eq!(
+            report,
+            MergeReport {
+                inserted_epics: 1,
+                inserted_stories: 1,
+                conflicts: vec![],
+            }
+        );
+        assert_eq!(merged.epics.len(), 2);
+        assert_eq!(merged.epics[&10].stories, vec![11]);
+        assert_eq!(merged.stories[&11].name, "incoming story");
+        assert_eq!(merged.last_item_id, 11);
+    }
+
+    #[test]
+    fn merge_should_keep_the_base_version_of_conflicts() {
+        let base = state_with_epic("base", &["base story"]);
+        let incoming = state_with_epic("incoming", &["incoming story"]);
+
+        let (merged, report) = DBState::merge(base.clone(), incoming);
+
+        assert_eq!(
+            report,
+            MergeReport {
+                inserted_epics: 0,
+                inserted_stories: 0,
+                conflicts: vec![
+                    ConflictRecord {
+                        item: MergeItem::Story,
+                        id: 2,
+                    },
+                    ConflictRecord {
+                        item: MergeItem::Epic,
+                        id: 1,
+                    },
+                ],
+            }
+        );
+        assert_eq!(merged, base);
+    }
+
+    #[test]
+    fn merge_should_insert_free_ids_alongside_conflicts() {
+        let base = state_with_epic("epic", &["story"]);
+        // Story 2 collides, story 3 is new and joins the matching epic 1
+        let incoming = state_with_epic("epic", &["other story", "new story"]);
+
+        let (merged, report) = DBState::merge(base, incoming);
+
+        assert_eq!(
+            report,
+            MergeReport {
+                inserted_epics: 0,
+                inserted_stories: 1,
+                conflicts: vec![ConflictRecord {
+                    item: MergeItem::Story,
+                    id: 2,
+                }],
+            }
+        );
+        assert_eq!(merged.stories[&2].name, "story");
+        assert_eq!(merged.stories[&3].name, "new story");
+        assert_eq!(merged.epics[&1].stories, vec![2, 3]);
+        assert_eq!(merged.last_item_id, 3);
+    }
+
+    #[test]
+    fn merge_should_not_reuse_an_id_taken_by_another_kind_of_item() {
+        let base = state_with_epic("base", &[]);
+        // Story 1 collides with the base epic 1, epic 10 is free
+        let mut incoming = state_with_epic("incoming", &["incoming story"]);
+        let epic = incoming.epics.remove(&1).unwrap();
+        let story = incoming.stories.remove(&2).unwrap();
+        incoming.epics.insert(
+            10,
+            Epic {
+                stories: vec![1],
+                ..epic
+            },
+        );
+        incoming.stories.insert(1, story);
+        incoming.last_item_id = 10;
+
+        let (merged, report) = DBState::merge(base, incoming);
+
+        assert_eq!(
+            report,
+            MergeReport {
+                inserted_epics: 1,
+                inserted_stories: 0,
+                conflicts: vec![ConflictRecord {
+                    item: MergeItem::Story,
+                    id: 1,
+                }],
+            }
+        );
+        assert_eq!(merged.epics[&1].name, "base");
+        assert!(merged.epics[&10].stories.is_empty());
+        assert!(merged.stories.is_empty());
+        assert_eq!(merged.validate(), vec![]);
+    }
+
+    #[test]
+    fn merge_should_not_add_stories_of_a_conflicting_epic() {
+        let base = state_with_epic("base", &[]);
+        // Story 2 is free, but its epic 1 differs from the base epic 1
+        let incoming = state_with_epic("incoming", &["incoming story"]);
+
+        let (merged, report) = DBState::merge(base.clone(), incoming);
+
+        assert_eq!(
+            report,
+            MergeReport {
+                inserted_epics: 0,
+                inserted_stories: 0,
+                conflicts: vec![
+                    ConflictRecord {
+                        item: MergeItem::Story,
+                        id: 2,
+                    },
+                    ConflictRecord {
+                        item: MergeItem::Epic,
+                        id: 1,
+                    },
+                ],
+            }
+        );
+        assert_eq!(merged.epics, base.epics);
+        assert!(merged.stories.is_empty());
+        assert_eq!(merged.validate(), vec![]);
+    }
+
+    #[test]
+    fn merge_should_not_report_conflicts_between_two_loads_of_a_file_without_timestamps() {
+        let mut tmpfile = tempfile::NamedTempFile::new().unwrap();
+        let file_contents = r#"{ "last_item_id": 2, "epics": { "1": { "name": "epic 1", "description": "epic 1", "status": "Open", "stories": [2] } }, "stories": { "2": { "name": "story 1", "description": "story 1", "status": "Open" } } }"#;
+        write!(tmpfile, "{}", file_contents).unwrap();
+        let db = JSONFileDatabase {
+            file_path: tmpfile.path().to_str().unwrap().to_owned(),
+        };
+        let base = db.read_db().unwrap();
+        let incoming = db.read_db().unwrap();
+
+        let (merged, report) = DBState::merge(base.clone(), incoming);
+
+        assert_eq!(report, MergeReport::default());
+        assert_eq!(merged, base);
+    }
+
+    #[test]
+    fn get_epics_by_tag_should_only_return_tagged_epics() {
+        let db = JiraDatabase::with_database(Box::new(MockDB::new()));
+        let tagged = |name: &str, tags: &[&str]| Epic {
+            tags: tags.iter().map(|tag| (*tag).to_owned()).collect(),
+            ..Epic::new(name.to_owned(), "".to_owned())
+        };
+        db.create_epic(tagged("first", &["backend"])).unwrap();
+        db.create_epic(tagged("second", &["frontend"])).unwrap();
+        db.create_epic(tagged("third", &["Backend", "ops"]))
+            .unwrap();
+
+        let names: Vec<String> = db
+            .get_epics_by_tag("backend")
+            .unwrap()
+            .into_iter()
+            .map(|epic| epic.name)
+            .collect();
+
+        assert_eq!(names, vec!["first", "third"]);
+        assert!(db.get_epics_by_tag("missing").unwrap().is_empty());
+    }
+
+    #[test]
+    fn create_epic_should_work() {
+        let db = JiraDatabase::with_database(Box::new(MockDB::new()));
+        let epic = Epic::new("epic".to_owned(), "".to_owned());
+
+        let result = db.create_epic(epic.clone());
+
+        assert_eq!(result.is_ok(), true);
+
+        let id = result.unwrap();
+        let db_state = db.read_db().unwrap();
+
+        let expected_id = 1;
+
+        assert_eq!(id, expected_id);
+        assert_eq!(db_state.last_item_id, expected_id);
+        assert_eq!(db_state.epics.get(&id), Some(&epic));
+    }
+
+    #[test]
+    fn create_epic_should_validate_name_and_description() {
+        let db = JiraDatabase::with_database(Box::new(MockDB::new()));
+
+        let empty_name = Epic::new("  ".to_owned(), "".to_owned());
+        let max_name = Epic::new("a".repeat(MAX_EPIC_NAME_LEN), "".to_owned());
+        let long_name = Epic::new("a".repeat(MAX_EPIC_NAME_LEN + 1), "".to_owned());
+        let long_description =
+            Epic::new("epic".to_owned(), "a".repeat(MAX_EPIC_DESCRIPTION_LEN + 1));
+
+        assert!(db.create_epic(empty_name).is_err());
+        assert!(db.create_epic(max_name).is_ok());
+        assert!(db.create_epic(long_name).is_err());
+        assert!(db.create_epic(long_description).is_err());
+        assert_eq!(db.read_db().unwrap().epics.len(), 1);
+    }
+
+    #[test]
+    fn update_epic_should_validate_name_and_description() {
+        let db = JiraDatabase::with_database(Box::new(MockDB::new()));
+        let epic_id = db
+            .create_epic(Epic::new("epic".to_owned(), "".to_owned()))
+            .unwrap();
+
+        assert!(db.update_epic_name(epic_id, " ".to_owned()).is_err());
+        assert!(
+            db.update_epic_name(epic_id, "a".repeat(MAX_EPIC_NAME_LEN + 1))
+                .is_err()
+        );
+        assert!(
+            db.update_epic_description(epic_id, "a".repeat(MAX_EPIC_DESCRIPTION_LEN + 1))
+                .is_err()
+        );
+        assert_eq!(db.read_db().unwrap().epics[&epic_id].name, "epic");
+
+        assert!(
+            db.update_epic_name(epic_id, "a".repeat(MAX_EPIC_NAME_LEN))
+                .is_ok()
+        );
+        assert!(
+            db.update_epic_description(epic_id, "a".repeat(MAX_EPIC_DESCRIPTION_LEN))
+                .is_ok()
+        );
+    }
+
+    #[test]
+    fn create_epic_unique_should_reject_duplicate_names() {
+        let db = JiraDatabase::with_database(Box::new(MockDB::new()));
+
+        assert!(
+            db.create_epic_unique(Epic::new("epic".to_owned(), "".to_owned()))
+                .is_ok()
+        );
+        assert!(
+            db.create_epic_unique(Epic::new("epic".to_owned(), "again".to_owned()))
+                .is_err()
+        );
+        assert!(
+            db.create_epic_unique(Epic::new("other epic".to_owned(), "".to_owned()))
+                .is_ok()
+        );
+    }
+
+    #[test]
+    fn create_story_should_validate_name_and_description() {
+        let db = JiraDatabase::with_database(Box::new(MockDB::new()));
+        let epic_id = db
+            .create_epic(Epic::new("epic".to_owned(), "".to_owned()))
+            .unwrap();
+
+        let empty_name = Story::new("".to_owned(), "".to_owned());
+        let long_name = Story::new("a".repeat(MAX_STORY_NAME_LEN + 1), "".to_owned());
+        let max_description = Story::new("story".to_owned(), "a".repeat(MAX_STORY_DESCRIPTION_LEN));
+        let long_description = Story::new(
+            "story".to_owned(),
+            "a".repeat(MAX_STORY_DESCRIPTION_LEN + 1),
+        );
+
+        assert!(db.create_story(empty_name, epic_id).is_err());
+        assert!(db.create_story(long_name, epic_id).is_err());
+        assert!(db.create_story(max_description, epic_id).is_ok());
+        assert!(db.create_story(long_description, epic_id).is_err());
+        assert_eq!(db.read_db().unwrap().stories.len(), 1);
+    }
+
+    #[test]
+    fn update_story_should_validate_name_and_description() {
+        let db = JiraDatabase::with_database(Box::new(MockDB::new()));
+        let epic_id = db
+            .create_epic(Epic::new("epic".to_owned(), "".to_owned()))
+            .unwrap();
+        let story_id = db
+            .create_story(Story::new("story".to_owned(), "".to_owned()), epic_id)
+            .unwrap();
+
+        assert!(db.update_story_name(story_id, "".to_owned()).is_err());
+        assert!(
+            db.update_story_name(story_id, "a".repeat(MAX_STORY_NAME_LEN + 1))
+                .is_err()
+        );
+        assert!(
+            db.update_story_description(story_id, "a".repeat(MAX_STORY_DESCRIPTION_LEN + 1))
+                .is_err()
+        );
+        assert_eq!(db.read_db().unwrap().stories[&story_id].name, "story");
+
+        assert!(
+            db.update_story_name(story_id, "a".repeat(MAX_STORY_NAME_LEN))
+                .is_ok()
+        );
+        assert!(
+            db.update_story_description(story_id, "a".repeat(MAX_STORY_DESCRIPTION_LEN))
+                .is_ok()
+        );
+    }
+
+    #[test]
+    fn create_story_should_error_if_invalid_epic_id() {
+        let db = JiraDatabase::with_database(Box::new(MockDB::new()));
+        let story = Story::new("story".to_owned(), "".to_owned());
+
+        let non_existent_epic_id = 999;
+
+        let result = db.create_story(story, non_existent_epic_id);
+        assert_eq!(result.is_err(), true);
+    }
+
+    #[test]
+    fn create_story_should_work() {
+        let db = JiraDatabase::with_database(Box::new(MockDB::new()));
+        let epic = Epic::new("epic".to_owned(), "".to_owned());
+        let story = Story::new("story".to_owned(), "".to_owned());
+
+        let result = db.create_epic(epic);
+        assert_eq!(result.is_ok(), true);
+
+        let epic_id = result.unwrap();
+
+        let result = db.create_story(story.clone(), epic_id);
+        assert_eq!(result.is_ok(), true);
+
+        let id = result.unwrap();
+        let db_state = db.read_db().unwrap();
+
+        let expected_id = 2;
+
+        assert_eq!(id, expected_id);
+        assert_eq!(db_state.last_item_id, expected_id);
+        assert_eq!(
+            db_state.epics.get(&epic_id).unwrap().stories.contains(&id),
+            true
+        );
+        assert_eq!(db_state.stories.get(&id), Some(&story));
+    }
+
+    #[test]
+    fn clone_story_should_copy_into_the_same_epic() {
+        let db = JiraDatabase::with_database(Box::new(MockDB::new()));
+        let epic_id = db
+            .create_epic(Epic::new("epic".to_owned(), "".to_owned()))
+            .unwrap();
+        let original = Story {
+            status: Status::InProgress,
+            priority: Priority::High,
+            estimate: Some(5),
+            ..Story::new("story".to_owned(), "description".to_owned())
+        };
+        let story_id = db.create_story(original.clone(), epic_id).unwrap();
+
+        let clone_id = db.clone_story(story_id).unwrap();
+
+        let db_state = db.read_db().unwrap();
+        let clone = &db_state.stories[&clone_id];
+        assert_ne!(clone_id, story_id);
+        assert_eq!(clone.name, "[COPY] story");
+        assert_eq!(clone.description, "description");
+        assert_eq!(clone.priority, Priority::High);
+        assert_eq!(clone.status, Status::Open);
+        assert_eq!(clone.estimate, None);
+        assert_eq!(db_state.epics[&epic_id].stories, vec![story_id, clone_id]);
+
+        db.update_story_status(clone_id, Status::Closed).unwrap();
+        assert_eq!(db.read_db().unwrap().stories[&story_id], original);
+    }
+
+    #[test]
+    fn clone_story_should_keep_long_names_within_the_limit() {
+        let db = JiraDatabase::with_database(Box::new(MockDB::new()));
+        let epic_id = db
+            .create_epic(Epic::new("epic".to_owned(), "".to_owned()))
+            .unwrap();
+        let story_id = db
+            .create_story(
+                Story::new("a".repeat(MAX_STORY_NAME_LEN), "".to_owned()),
+                epic_id,
+            )
+            .unwrap();
+
+        let clone_id = db.clone_story(story_id).unwrap();
+
+        let name = &db.read_db().unwrap().stories[&clone_id].name;
+        assert!(name.starts_with("[COPY] a"));
+        assert_eq!(name.chars().count(), MAX_STORY_NAME_LEN);
+        assert!(db.clone_story(999).is_err());
+    }
+
+    #[test]
+    fn move_story_should_move_the_story_between_epics() {
+        let db = JiraDatabase::with_database(Box::new(MockDB::new()));
+        let source_id = db
+            .create_epic(Epic::new("source".to_owned(), "".to_owned()))
+            .unwrap();
+        let story_ids = [
+            db.create_story(Story::new("first".to_owned(), "".to_owned()), source_id)
+                .unwrap(),
+            db.create_story(Story::new("second".to_owned(), "".to_owned()), source_id)
+                .unwrap(),
+        ];
+        let target_id = db
+            .create_epic(Epic::new("target".to_owned(), "".to_owned()))
+            .unwrap();
+
+        db.move_story(story_ids[0], target_id).unwrap();
+
+        let db_state = db.read_db().unwrap();
+        assert_eq!(db_state.epics[&source_id].stories, vec![story_ids[1]]);
+        assert_eq!(db_state.epics[&target_id].stories, vec![story_ids[0]]);
+
+        // Moving it to the epic it is already in changes nothing
+        db.move_story(story_ids[0], target_id).unwrap();
+        assert_eq!(db.read_db().unwrap(), db_state);
+    }
+
+    #[test]
+    fn move_story_should_error_if_invalid_ids() {
+        let db = JiraDatabase::with_database(Box::new(MockDB::new()));
+        let epic_id = db
+            .create_epic(Epic::new("epic".to_owned(), "".to_owned()))
+            .unwrap();
+        let story_id = db
+            .create_story(Story::new("story".to_owned(), "".to_owned()), epic_id)
+            .unwrap();
+
+        assert!(db.move_story(999, epic_id).is_err());
+        assert!(db.move_story(story_id, 999).is_err());
+        assert_eq!(
+            db.read_db().unwrap().epics[&epic_id].stories,
+            vec![story_id]
+        );
+    }
+
+    #[test]
+    fn delete_epic_should_error_if_invalid_epic_id() {
+        let db = JiraDatabase::with_database(Box::new(MockDB::new()));
+
+        let non_existent_epic_id = 999;
+
+        let result = db.delete_epic(non_existent_epic_id);
+        assert_eq!(result.is_err(), true);
+    }
+
+    #[test]
+    fn delete_epic_should_work() {
+        let db = JiraDatabase::with_database(Box::new(MockDB::new()));
+        let epic = Epic::new("epic".to_owned(), "".to_owned());
+        let story = Story::new("story".to_owned(), "".to_owned());
+
+        let result = db.create_epic(epic);
+        assert_eq!(result.is_ok(), true);
+
+        let epic_id = result.unwrap();
+
+        let result = db.create_story(story, epic_id);
+        assert_eq!(result.is_ok(), true);
+
+        let story_id = result.unwrap();
+
+        let result = db.delete_epic(epic_id);
+        assert_eq!(result.is_ok(), true);
+
+        let db_state = db.read_db().unwrap();
+
+        let expected_last_id = 2;
+
+        assert_eq!(db_state.last_item_id, expected_last_id);
+        assert_eq!(db_state.epics.get(&epic_id), None);
+        assert_eq!(db_state.stories.get(&story_id), None);
+    }
+
+    #[test]
+    fn delete_story_should_error_if_invalid_epic_id() {
+        let db = JiraDatabase::with_database(Box::new(MockDB::new()));
+        let epic = Epic::new("epic".to_owned(), "".to_owned());
+        let story = Story::new("story".to_owned(), "".to_owned());
+
+        let result = db.create_epic(epic);
+        assert_eq!(result.is_ok(), true);
+
+        let epic_id = result.unwrap();
+
+        let result = db.create_story(story, epic_id);
+        assert_eq!(result.is_ok(), true);
+
+        let story_id = result.unwrap();
+
+        let non_existent_epic_id = 999;
+
+        let result = db.delete_story(non_existent_epic_id, story_id);
+        assert_eq!(result.is_err(), true);
+    }
+
+    #[test]
+    fn delete_story_should_error_if_story_not_found_in_epic() {
+        let db = JiraDatabase::with_database(Box::new(MockDB::new()));
+        let epic = Epic::new("epic".to_owned(), "".to_owned());
+        let story = Story::new("story".to_owned(), "".to_owned());
+
+        let result = db.create_epic(epic);
+        assert_eq!(result.is_ok(), true);
+
+        let epic_id = result.unwrap();
+
+        let result = db.create_story(story, epic_id);
+        assert_eq!(result.is_ok(), true);
+
+        let non_existent_story_id = 999;
+
+        let result = db.delete_story(epic_id, non_existent_story_id);
+        assert_eq!(result.is_err(), true);
+    }
+
+    #[test]
+    fn delete_story_should_work() {
+        let db = JiraDatabase::with_database(Box::new(MockDB::new()));
+        let epic = Epic::new("epic".to_owned(), "".to_owned());
+        let story = Story::new("story".to_owned(), "".to_owned());
+
+        let result = db.create_epic(epic);
+        assert_eq!(result.is_ok(), true);
+
+        let epic_id = result.unwrap();
+
+        let result = db.create_story(story, epic_id);
+        assert_eq!(result.is_ok(), true);
+
+        let story_id = result.unwrap();
+
+        let result = db.delete_story(epic_id, story_id);
+        assert_eq!(result.is_ok(), true);
+
+        let db_state = db.read_db().unwrap();
+
+        let expected_last_id = 2;
+
+        assert_eq!(db_state.last_item_id, expected_last_id);
+        assert_eq!(
+            db_state
+                .epics
+                .get(&epic_id)
+                .unwrap()
+                .stories
+                .contains(&story_id),
+            false
+        );
+        assert_eq!(db_state.stories.get(&story_id), None);
+    }
+
+    #[test]
+    fn delete_stories_should_delete_across_epics_in_one_write() {
+        let mock = MockDB::new();
+        let write_count = mock.write_count();
+        let db = JiraDatabase::with_database(Box::new(mock));
+        let first_epic_id = db
+            .create_epic(Epic::new("first".to_owned(), "".to_owned()))
+            .unwrap();
+        let second_epic_id = db
+            .create_epic(Epic::new("second".to_owned(), "".to_owned()))
+            .unwrap();
+        let story = || Story::new("story".to_owned(), "".to_owned());
+        let deleted_id = db.create_story(story(), first_epic_id).unwrap();
+        let kept_id = db.create_story(story(), first_epic_id).unwrap();
+        let other_deleted_id = db.create_story(story(), second_epic_id).unwrap();
+        db.add_story_dependency(kept_id, deleted_id).unwrap();
+        let writes_before = write_count.get();
+
+        let report = db
+            .delete_stories(&[deleted_id, 999, other_deleted_id, deleted_id])
+            .unwrap();
+
+        assert_eq!(report.deleted, vec![deleted_id, other_deleted_id]);
+        assert_eq!(report.not_found, vec![999]);
+        assert_eq!(write_count.get(), writes_before + 1);
+
+        let db_state = db.read_db().unwrap();
+        assert_eq!(db_state.stories.keys().collect::<Vec<_>>(), vec![&kept_id]);
+        assert_eq!(db_state.epics[&first_epic_id].stories, vec![kept_id]);
+        assert!(db_state.epics[&second_epic_id].stories.is_empty());
+        assert!(db_state.stories[&kept_id].blocks.is_empty());
+    }
+
+    #[test]
+    fn delete_stories_should_not_write_when_nothing_was_found() {
+        let mock = MockDB::new();
+        let write_count = mock.write_count();
+        let db = JiraDatabase::with_database(Box::new(mock));
+
+        let report = db.delete_stories(&[1, 2]).unwrap();
+
+        assert!(report.deleted.is_empty());
+        assert_eq!(report.not_found, vec![1, 2]);
+        assert_eq!(write_count.get(), 0);
+    }
+
+    #[test]
+    fn update_epic_status_should_error_if_invalid_epic_id() {
+        let db = JiraDatabase::with_database(Box::new(MockDB::new()));
+
+        let non_existent_epic_id = 999;
+
+        let result = db.update_epic_status(non_existent_epic_id, Status::Closed);
+        assert_eq!(result.is_err(), true);
+    }
+
+    #[test]
+    fn update_epic_status_should_work() {
+        let db = JiraDatabase::with_database(Box::new(MockDB::new()));
+        let epic = Epic::new("epic".to_owned(), "".to_owned());
+
+        let result = db.create_epic(epic);
+
+        assert_eq!(result.is_ok(), true);
+
+        let epic_id = result.unwrap();
+
+        let result = db.update_epic_status(epic_id, Status::Closed);
+
+        assert_eq!(result.is_ok(), true);
+
+        let db_state = db.read_db().unwrap();
+
+        assert_eq!(db_state.epics.get(&epic_id).unwrap().status, Status::Closed);
+    }
+
+    #[test]
+    fn update_story_status_should_error_if_invalid_story_id() {
+        let db = JiraDatabase::with_database(Box::new(MockDB::new()));
+
+        let non_existent_story_id = 999;
+
+        let result = db.update_story_status(non_existent_story_id, Status::Closed);
         assert_eq!(result.is_err(), true);
     }
 
     #[test]
-    fn create_story_should_work() {
-        let db = JiraDatabase {
-            database: Box::new(MockDB::new()),
+    fn update_story_status_should_work() {
+        let db = JiraDatabase::with_database(Box::new(MockDB::new()));
+        let epic = Epic::new("epic".to_owned(), "".to_owned());
+        let story = Story::new("story".to_owned(), "".to_owned());
+
+        let result = db.create_epic(epic);
+
+        let epic_id = result.unwrap();
+
+        let result = db.create_story(story, epic_id);
+
+        let story_id = result.unwrap();
+
+        let result = db.update_story_status(story_id, Status::Closed);
+
+        assert_eq!(result.is_ok(), true);
+
+        let db_state = db.read_db().unwrap();
+
+        assert_eq!(
+            db_state.stories.get(&story_id).unwrap().status,
+            Status::Closed
+        );
+    }
+
+    #[test]
+    fn update_due_dates_should_work() {
+        let db = JiraDatabase::with_database(Box::new(MockDB::new()));
+        let epic_id = db
+            .create_epic(Epic::new("epic".to_owned(), "".to_owned()))
+            .unwrap();
+        let story_id = db
+            .create_story(Story::new("story".to_owned(), "".to_owned()), epic_id)
+            .unwrap();
+        let due_date = NaiveDate::from_ymd_opt(2025, 6, 15);
+
+        db.update_epic_due_date(epic_id, due_date).unwrap();
+        db.update_story_due_date(story_id, due_date).unwrap();
+
+        let db_state = db.read_db().unwrap();
+        assert_eq!(db_state.epics[&epic_id].due_date, due_date);
+        assert_eq!(db_state.stories[&story_id].due_date, due_date);
+
+        assert!(db.update_epic_due_date(999, due_date).is_err());
+        assert!(db.update_story_due_date(999, due_date).is_err());
+    }
+
+    #[test]
+    fn search_should_match_names_and_descriptions_case_insensitively() {
+        let db = JiraDatabase::with_database(Box::new(MockDB::new()));
+        let login_epic_id = db
+            .create_epic(Epic::new("Login".to_owned(), "".to_owned()))
+            .unwrap();
+        let billing_epic_id = db
+            .create_epic(Epic::new(
+                "Billing".to_owned(),
+                "Invoices for logged-in users".to_owned(),
+            ))
+            .unwrap();
+        let story_id = db
+            .create_story(
+                Story::new("Remember me checkbox".to_owned(), "".to_owned()),
+                login_epic_id,
+            )
+            .unwrap();
+        db.create_story(
+            Story::new("Refunds".to_owned(), "".to_owned()),
+            billing_epic_id,
+        )
+        .unwrap();
+
+        let epic_ids: Vec<u32> = db
+            .search_epics(&SearchQuery::builder().with_text("LOG").build())
+            .unwrap()
+            .into_iter()
+            .map(|(id, _)| id)
+            .collect();
+        assert_eq!(epic_ids, vec![login_epic_id, billing_epic_id]);
+
+        let story_ids: Vec<u32> = db
+            .search_stories(&SearchQuery::builder().with_text("me check").build())
+            .unwrap()
+            .into_iter()
+            .map(|(id, _)| id)
+            .collect();
+        assert_eq!(story_ids, vec![story_id]);
+
+        let nothing = SearchQuery::builder()
+            .with_text("nothing like this")
+            .build();
+        assert!(db.search_stories(&nothing).unwrap().is_empty());
+    }
+
+    fn search_fixture() -> JiraDatabase {
+        let db = JiraDatabase::with_database(Box::new(MockDB::new()));
+        let backend = db
+            .create_epic(Epic {
+                tags: vec!["backend".to_owned(), "q3".to_owned()],
+                priority: Priority::High,
+                ..Epic::new("Payments API".to_owned(), "".to_owned())
+            })
+            .unwrap();
+        let frontend = db
+            .create_epic(Epic {
+                tags: vec!["frontend".to_owned(), "Q3".to_owned()],
+                ..Epic::new("Checkout page".to_owned(), "Pay by api".to_owned())
+            })
+            .unwrap();
+        db.update_epic_status(frontend, Status::InProgress).unwrap();
+
+        for (name, reporter, priority, status) in [
+            ("Refund api", "alice", Priority::High, Status::Open),
+            ("Card form", "bob", Priority::Medium, Status::Resolved),
+            ("Receipt api", "bob", Priority::High, Status::Blocked),
+        ] {
+            let story_id = db
+                .create_story(
+                    Story {
+                        reporter: reporter.to_owned(),
+                        priority,
+                        tags: vec!["backend".to_owned()],
+                        ..Story::new(name.to_owned(), "".to_owned())
+                    },
+                    backend,
+                )
+                .unwrap();
+            db.update_story_status(story_id, status).unwrap();
+        }
+
+        db
+    }
+
+    fn epic_names(db: &JiraDatabase, query: SearchQuery) -> Vec<String> {
+        db.search_epics(&query)
+            .unwrap()
+            .into_iter()
+            .map(|(_, epic)| epic.name)
+            .collect()
+    }
+
+    fn story_names(db: &JiraDatabase, query: SearchQuery) -> Vec<String> {
+        db.search_stories(&query)
+            .unwrap()
+            .into_iter()
+            .map(|(_, story)| story.name)
+            .collect()
+    }
+
+    #[test]
+    fn search_query_should_match_everything_when_empty() {
+        let db = search_fixture();
+
+        assert_eq!(epic_names(&db, SearchQuery::builder().build()).len(), 2);
+        assert_eq!(story_names(&db, SearchQuery::default()).len(), 3);
+    }
+
+    #[test]
+    fn search_query_should_filter_by_each_field() {
+        let db = search_fixture();
+
+        // Only the name counts, unlike the text filter
+        let api = || SearchQuery::builder().with_name_contains(" API ");
+        assert_eq!(epic_names(&db, api().build()), vec!["Payments API"]);
+        assert_eq!(
+            epic_names(&db, SearchQuery::builder().with_text("api").build()),
+            vec!["Payments API", "Checkout page"]
+        );
+        assert_eq!(
+            story_names(&db, api().build()),
+            vec!["Refund api", "Receipt api"]
+        );
+
+        let open_or_blocked = SearchQuery::builder()
+            .with_status(Status::Open)
+            .with_status(Status::Blocked)
+            .build();
+        assert_eq!(
+            story_names(&db, open_or_blocked.clone()),
+            vec!["Refund api", "Receipt api"]
+        );
+        assert_eq!(epic_names(&db, open_or_blocked), vec!["Payments API"]);
+
+        let q3 = SearchQuery::builder().with_tag("q3").build();
+        assert_eq!(epic_names(&db, q3.clone()).len(), 2);
+        assert!(story_names(&db, q3).is_empty());
+
+        let bob = SearchQuery::builder().with_reporter("bob").build();
+        assert_eq!(
+            story_names(&db, bob.clone()),
+            vec!["Card form", "Receipt api"]
+        );
+        assert!(epic_names(&db, bob).is_empty());
+
+        let high = SearchQuery::builder().with_priority(Priority::High).build();
+        assert_eq!(epic_names(&db, high.clone()), vec!["Payments API"]);
+        assert_eq!(story_names(&db, high), vec!["Refund api", "Receipt api"]);
+    }
+
+    #[test]
+    fn search_query_should_require_every_filter_to_match() {
+        let db = search_fixture();
+
+        let query = SearchQuery::builder()
+            .with_name_contains("api")
+            .with_reporter("bob")
+            .with_priority(Priority::High)
+            .with_status(Status::Blocked)
+            .with_tag("backend")
+            .build();
+        assert_eq!(story_names(&db, query), vec!["Receipt api"]);
+
+        let query = SearchQuery::builder()
+            .with_tag("backend")
+            .with_tag("frontend")
+            .build();
+        assert!(epic_names(&db, query).is_empty());
+
+        let query = SearchQuery::builder()
+            .with_reporter("alice")
+            .with_status(Status::Resolved)
+            .build();
+        assert!(story_names(&db, query).is_empty());
+    }
+
+    #[test]
+    fn update_story_estimate_should_error_if_invalid_story_id() {
+        let db = JiraDatabase::with_database(Box::new(MockDB::new()));
+
+        let non_existent_story_id = 999;
+
+        let result = db.update_story_estimate(non_existent_story_id, 3);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn update_story_estimate_should_reject_non_fibonacci_values() {
+        let db = JiraDatabase::with_database(Box::new(MockDB::new()));
+        let epic_id = db
+            .create_epic(Epic::new("epic".to_owned(), "".to_owned()))
+            .unwrap();
+        let story_id = db
+            .create_story(Story::new("story".to_owned(), "".to_owned()), epic_id)
+            .unwrap();
+
+        assert!(db.update_story_estimate(story_id, 4).is_err());
+        assert!(db.update_story_estimate(story_id, 0).is_err());
+
+        let db_state = db.read_db().unwrap();
+        assert_eq!(db_state.stories.get(&story_id).unwrap().estimate, None);
+    }
+
+    #[test]
+    fn update_story_estimate_should_work() {
+        let db = JiraDatabase::with_database(Box::new(MockDB::new()));
+        let epic_id = db
+            .create_epic(Epic::new("epic".to_owned(), "".to_owned()))
+            .unwrap();
+        let story_id = db
+            .create_story(Story::new("story".to_owned(), "".to_owned()), epic_id)
+            .unwrap();
+
+        let result = db.update_story_estimate(story_id, 13);
+
+        assert!(result.is_ok());
+
+        let db_state = db.read_db().unwrap();
+        assert_eq!(db_state.stories.get(&story_id).unwrap().estimate, Some(13));
+    }
+
+    #[test]
+    fn updates_should_refresh_updated_at_but_not_created_at() {
+        let db = JiraDatabase::with_database(Box::new(MockDB::new()));
+        let long_ago = "2020-01-01T00:00:00Z".parse().unwrap();
+        let epic_id = db
+            .create_epic(Epic {
+                created_at: long_ago,
+                updated_at: long_ago,
+                ..Epic::new("epic".to_owned(), "".to_owned())
+            })
+            .unwrap();
+        let story_id = db
+            .create_story(
+                Story {
+                    created_at: long_ago,
+                    updated_at: long_ago,
+                    ..Story::new("story".to_owned(), "".to_owned())
+                },
+                epic_id,
+            )
+            .unwrap();
+
+        db.update_epic_status(epic_id, Status::InProgress).unwrap();
+        db.update_story_status(story_id, Status::InProgress)
+            .unwrap();
+
+        let db_state = db.read_db().unwrap();
+        assert_eq!(db_state.epics[&epic_id].created_at, long_ago);
+        assert!(db_state.epics[&epic_id].updated_at > long_ago);
+        assert_eq!(db_state.stories[&story_id].created_at, long_ago);
+        assert!(db_state.stories[&story_id].updated_at > long_ago);
+    }
+
+    #[test]
+    fn update_story_type_should_work() {
+        let db = JiraDatabase::with_database(Box::new(MockDB::new()));
+        let epic_id = db
+            .create_epic(Epic::new("epic".to_owned(), "".to_owned()))
+            .unwrap();
+        let story_id = db
+            .create_story(Story::new("story".to_owned(), "".to_owned()), epic_id)
+            .unwrap();
+
+        db.update_story_type(story_id, StoryType::Bug).unwrap();
+
+        let db_state = db.read_db().unwrap();
+        assert_eq!(db_state.stories[&story_id].story_type, StoryType::Bug);
+        assert!(db.update_story_type(999, StoryType::Bug).is_err());
+    }
+
+    #[test]
+    fn add_comment_should_append_to_the_story() {
+        let db = JiraDatabase::with_database(Box::new(MockDB::new()));
+        let epic_id = db
+            .create_epic(Epic::new("epic".to_owned(), "".to_owned()))
+            .unwrap();
+        let story_id = db
+            .create_story(Story::new("story".to_owned(), "".to_owned()), epic_id)
+            .unwrap();
+
+        let first_id = db.add_comment(story_id, "alice", " looks good ").unwrap();
+        let second_id = db.add_comment(story_id, "bob", "needs tests").unwrap();
+
+        let comments = db.read_db().unwrap().stories[&story_id].comments.clone();
+        assert_eq!(first_id, 1);
+        assert_eq!(second_id, 2);
+        assert_eq!(comments.len(), 2);
+        assert_eq!(comments[0].author, "alice");
+        assert_eq!(comments[0].text, "looks good");
+        assert_eq!(comments[1].author, "bob");
+    }
+
+    #[test]
+    fn add_comment_should_reject_invalid_comments() {
+        let db = JiraDatabase::with_database(Box::new(MockDB::new()));
+        let epic_id = db
+            .create_epic(Epic::new("epic".to_owned(), "".to_owned()))
+            .unwrap();
+        let story_id = db
+            .create_story(Story::new("story".to_owned(), "".to_owned()), epic_id)
+            .unwrap();
+
+        assert!(db.add_comment(story_id, "alice", "   ").is_err());
+        assert!(
+            db.add_comment(story_id, "alice", &"a".repeat(MAX_COMMENT_LEN + 1))
+                .is_err()
+        );
+        assert!(db.add_comment(999, "alice", "hello").is_err());
+        assert!(db.read_db().unwrap().stories[&story_id].comments.is_empty());
+    }
+
+    #[test]
+    fn delete_comment_should_only_remove_that_comment() {
+        let db = JiraDatabase::with_database(Box::new(MockDB::new()));
+        let epic_id = db
+            .create_epic(Epic::new("epic".to_owned(), "".to_owned()))
+            .unwrap();
+        let story_id = db
+            .create_story(Story::new("story".to_owned(), "".to_owned()), epic_id)
+            .unwrap();
+        let first_id = db.add_comment(story_id, "alice", "first").unwrap();
+        let second_id = db.add_comment(story_id, "alice", "second").unwrap();
+
+        db.delete_comment(story_id, first_id).unwrap();
+
+        let comments = db.read_db().unwrap().stories[&story_id].comments.clone();
+        assert_eq!(comments.len(), 1);
+        assert_eq!(comments[0].id, second_id);
+        assert!(db.delete_comment(story_id, first_id).is_err());
+        assert!(db.delete_comment(999, second_id).is_err());
+    }
+
+    #[test]
+    fn add_comment_should_not_reuse_the_id_of_a_deleted_comment() {
+        let db = JiraDatabase::with_database(Box::new(MockDB::new()));
+        let epic_id = db
+            .create_epic(Epic::new("epic".to_owned(), "".to_owned()))
+            .unwrap();
+        let story_id = db
+            .create_story(Story::new("story".to_owned(), "".to_owned()), epic_id)
+            .unwrap();
+        db.add_comment(story_id, "alice", "first").unwrap();
+        let second_id = db.add_comment(story_id, "alice", "second").unwrap();
+
+        db.delete_comment(story_id, second_id).unwrap();
+
+        assert_eq!(db.add_comment(story_id, "alice", "third").unwrap(), 3);
+    }
+
+    #[test]
+    fn add_comment_should_continue_from_comments_saved_without_a_counter() {
+        let db = JiraDatabase::with_database(Box::new(MockDB::new()));
+        let epic_id = db
+            .create_epic(Epic::new("epic".to_owned(), "".to_owned()))
+            .unwrap();
+        let story_id = db
+            .create_story(Story::new("story".to_owned(), "".to_owned()), epic_id)
+            .unwrap();
+        db.add_comment(story_id, "alice", "first").unwrap();
+        db.add_comment(story_id, "alice", "second").unwrap();
+        let mut db_state = db.read_db().unwrap();
+        db_state.stories.get_mut(&story_id).unwrap().last_comment_id = 0;
+        db.database.write_db(&db_state).unwrap();
+        db.invalidate_cache();
+
+        assert_eq!(db.add_comment(story_id, "alice", "third").unwrap(), 3);
+    }
+
+    fn create_stories(db: &JiraDatabase, count: usize) -> Vec<u32> {
+        let epic_id = db
+            .create_epic(Epic::new("epic".to_owned(), "".to_owned()))
+            .unwrap();
+        (0..count)
+            .map(|i| {
+                db.create_story(Story::new(format!("story {}", i), "".to_owned()), epic_id)
+                    .unwrap()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn add_story_dependency_should_link_both_stories() {
+        let db = JiraDatabase::with_database(Box::new(MockDB::new()));
+        let ids = create_stories(&db, 2);
+
+        db.add_story_dependency(ids[0], ids[1]).unwrap();
+        // Linking twice is a no-op rather than a duplicate entry
+        db.add_story_dependency(ids[0], ids[1]).unwrap();
+
+        let db_state = db.read_db().unwrap();
+        assert_eq!(db_state.stories[&ids[0]].blocks, vec![ids[1]]);
+        assert_eq!(db_state.stories[&ids[1]].blocked_by, vec![ids[0]]);
+    }
+
+    #[test]
+    fn add_story_dependency_should_reject_unknown_and_self_links() {
+        let db = JiraDatabase::with_database(Box::new(MockDB::new()));
+        let ids = create_stories(&db, 1);
+
+        assert!(db.add_story_dependency(ids[0], 999).is_err());
+        assert!(db.add_story_dependency(999, ids[0]).is_err());
+        assert!(db.add_story_dependency(ids[0], ids[0]).is_err());
+    }
+
+    #[test]
+    fn add_story_dependency_should_reject_cycles() {
+        let db = JiraDatabase::with_database(Box::new(MockDB::new()));
+        let ids = create_stories(&db, 3);
+
+        db.add_story_dependency(ids[0], ids[1]).unwrap();
+        db.add_story_dependency(ids[1], ids[2]).unwrap();
+
+        assert!(db.add_story_dependency(ids[1], ids[0]).is_err());
+        assert!(db.add_story_dependency(ids[2], ids[0]).is_err());
+
+        let db_state = db.read_db().unwrap();
+        assert!(db_state.stories[&ids[0]].blocked_by.is_empty());
+        assert!(db_state.stories[&ids[2]].blocks.is_empty());
+    }
+
+    #[test]
+    fn remove_story_dependency_should_unlink_both_stories() {
+        let db = JiraDatabase::with_database(Box::new(MockDB::new()));
+        let ids = create_stories(&db, 2);
+
+        assert!(db.remove_story_dependency(ids[0], ids[1]).is_err());
+
+        db.add_story_dependency(ids[0], ids[1]).unwrap();
+        db.remove_story_dependency(ids[0], ids[1]).unwrap();
+
+        let db_state = db.read_db().unwrap();
+        assert!(db_state.stories[&ids[0]].blocks.is_empty());
+        assert!(db_state.stories[&ids[1]].blocked_by.is_empty());
+    }
+
+    #[test]
+    fn delete_story_should_drop_its_dependency_links() {
+        let db = JiraDatabase::with_database(Box::new(MockDB::new()));
+        let ids = create_stories(&db, 3);
+        let epic_id = *db.read_db().unwrap().epics.keys().next().unwrap();
+
+        db.add_story_dependency(ids[0], ids[1]).unwrap();
+        db.add_story_dependency(ids[1], ids[2]).unwrap();
+        db.delete_story(epic_id, ids[1]).unwrap();
+
+        let db_state = db.read_db().unwrap();
+        assert!(db_state.stories[&ids[0]].blocks.is_empty());
+        assert!(db_state.stories[&ids[2]].blocked_by.is_empty());
+    }
+
+    #[test]
+    fn export_csv_should_write_epic_and_story_sections() {
+        let db = JiraDatabase::with_database(Box::new(MockDB::new()));
+        let epic_id = db
+            .create_epic(Epic::new(
+                "epic".to_owned(),
+                "plans, with a comma".to_owned(),
+            ))
+            .unwrap();
+        let story_id = db
+            .create_story(Story::new("story".to_owned(), "".to_owned()), epic_id)
+            .unwrap();
+        db.update_story_estimate(story_id, 3).unwrap();
+        db.create_story(Story::new("unestimated".to_owned(), "".to_owned()), epic_id)
+            .unwrap();
+
+        let mut output = Vec::new();
+        db.export_csv(&mut output).unwrap();
+
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            "id,name,description,status,priority\n\
+             1,epic,\"plans, with a comma\",OPEN,MEDIUM\n\
+             \n\
+             id,epic_id,name,description,status,estimate\n\
+             2,1,story,,OPEN,3\n\
+             3,1,unestimated,,OPEN,\n"
+        );
+    }
+
+    #[test]
+    fn import_csv_should_round_trip_an_export() {
+        let source = JiraDatabase::with_database(Box::new(MockDB::new()));
+        let epic_id = source
+            .create_epic(Epic {
+                priority: Priority::High,
+                ..Epic::new("epic".to_owned(), "plans, with a comma".to_owned())
+            })
+            .unwrap();
+        let story_id = source
+            .create_story(
+                Story::new("story".to_owned(), "line one\nline two".to_owned()),
+                epic_id,
+            )
+            .unwrap();
+        source
+            .update_story_status(story_id, Status::Blocked)
+            .unwrap();
+        source.update_story_estimate(story_id, 8).unwrap();
+
+        let mut exported = Vec::new();
+        source.export_csv(&mut exported).unwrap();
+
+        let target = JiraDatabase::with_database(Box::new(MockDB::new()));
+        let report = target.import_csv(exported.as_slice()).unwrap();
+
+        assert_eq!(
+            report,
+            ImportReport {
+                created_epics: 1,
+                created_stories: 1,
+                errors: vec![],
+            }
+        );
+        // CSV has no timestamp columns, so imported items are new as of the import
+        let mut source_state = source.read_db().unwrap();
+        let mut target_state = target.read_db().unwrap();
+        pin_timestamps(&mut source_state);
+        pin_timestamps(&mut target_state);
+        assert_eq!(target_state.epics, source_state.epics);
+        assert_eq!(target_state.stories, source_state.stories);
+    }
+
+    #[test]
+    fn export_csv_should_leave_out_stories_without_an_epic() {
+        let source = JiraDatabase::with_database(Box::new(MockDB::new()));
+        let epic_id = source
+            .create_epic(Epic::new("epic".to_owned(), "".to_owned()))
+            .unwrap();
+        source
+            .create_story(Story::new("story".to_owned(), "".to_owned()), epic_id)
+            .unwrap();
+        let mut db_state = source.read_db().unwrap();
+        db_state
+            .stories
+            .insert(50, Story::new("unowned".to_owned(), "".to_owned()));
+        source.database.write_db(&db_state).unwrap();
+
+        let mut exported = Vec::new();
+        source.export_csv(&mut exported).unwrap();
+        assert!(
+            !String::from_utf8(exported.clone())
+                .unwrap()
+                .contains("unowned")
+        );
+
+        let target = JiraDatabase::with_database(Box::new(MockDB::new()));
+        let report = target.import_csv(exported.as_slice()).unwrap();
+        assert_eq!(report.created_epics, 1);
+        assert_eq!(report.created_stories, 1);
+        let target_state = target.read_db().unwrap();
+        assert_eq!(target_state.stories[&2].name, "story");
+        assert!(target_state.validate().is_empty());
+    }
+
+    #[test]
+    fn import_csv_should_attach_stories_to_existing_epics() {
+        let db = JiraDatabase::with_database(Box::new(MockDB::new()));
+        let epic_id = db
+            .create_epic(Epic::new("epic".to_owned(), "".to_owned()))
+            .unwrap();
+
+        let csv = format!(
+            "id,epic_id,name,description,status,estimate\n9,{},story,,OPEN,\n",
+            epic_id
+        );
+        db.import_csv(csv.as_bytes()).unwrap();
+
+        let db_state = db.read_db().unwrap();
+        assert_eq!(db_state.epics[&epic_id].stories, vec![2]);
+        assert_eq!(db_state.stories[&2].name, "story");
+    }
+
+    #[test]
+    fn import_csv_should_credit_imported_items_to_the_audit_user() {
+        let db = JiraDatabase::with_database(Box::new(MockDB::new()));
+        db.set_audit_user("alice");
+
+        let csv = "id,name,description,status,priority\n\
+                   7,epic,,OPEN,MEDIUM\n\
+                   id,epic_id,name,description,status,estimate\n\
+                   8,7,story,,OPEN,\n";
+        db.import_csv(csv.as_bytes()).unwrap();
+
+        let db_state = db.read_db().unwrap();
+        assert_eq!(db_state.epics[&1].reporter, "alice");
+        assert_eq!(db_state.stories[&2].reporter, "alice");
+    }
+
+    #[test]
+    fn import_csv_should_roll_back_when_a_row_is_invalid() {
+        let db = JiraDatabase::with_database(Box::new(MockDB::new()));
+
+        let csv = "id,name,description,status,priority\n\
+                   1,epic,,OPEN,HIGH\n\
+                   2,another epic,,DONE,HIGH\n";
+        let result = db.import_csv(csv.as_bytes());
+
+        assert!(result.is_err());
+        let db_state = db.read_db().unwrap();
+        assert!(db_state.epics.is_empty());
+        assert_eq!(db_state.last_item_id, 0);
+    }
+
+    #[test]
+    fn import_csv_should_reject_missing_epics_unless_lenient() {
+        let csv = "id,name,description,status,priority\n\
+                   1,epic,,OPEN,MEDIUM\n\
+                   \n\
+                   id,epic_id,name,description,status,estimate\n\
+                   2,1,kept,,OPEN,\n\
+                   3,42,orphan,,OPEN,\n";
+
+        let strict = JiraDatabase::with_database(Box::new(MockDB::new()));
+        assert!(strict.import_csv(csv.as_bytes()).is_err());
+        assert!(strict.read_db().unwrap().epics.is_empty());
+
+        let lenient = JiraDatabase::with_database(Box::new(MockDB::new()));
+        let report = lenient.import_csv_lenient(csv.as_bytes()).unwrap();
+
+        assert_eq!(
+            report,
+            ImportReport {
+                created_epics: 1,
+                created_stories: 1,
+                errors: vec![
+                    "line 6: story \"orphan\" references epic 42 which does not exist".to_owned()
+                ],
+            }
+        );
+        assert_eq!(lenient.read_db().unwrap().stories.len(), 1);
+    }
+
+    #[test]
+    fn reopen_story_should_work() {
+        let db = JiraDatabase::with_database(Box::new(MockDB::new()));
+        let epic_id = db
+            .create_epic(Epic::new("epic".to_owned(), "".to_owned()))
+            .unwrap();
+        let story = Story::new("story".to_owned(), "".to_owned());
+        let story_id = db.create_story(story.clone(), epic_id).unwrap();
+        db.update_story_status(story_id, Status::Closed).unwrap();
+
+        let result = db.reopen_story(story_id);
+
+        assert!(result.is_ok());
+        let reopened = db.read_db().unwrap().stories.remove(&story_id).unwrap();
+        assert_eq!(
+            reopened,
+            Story {
+                updated_at: reopened.updated_at,
+                ..story
+            }
+        );
+    }
+
+    #[test]
+    fn reopen_story_should_error_if_epic_is_closed() {
+        let db = JiraDatabase::with_database(Box::new(MockDB::new()));
+        let epic_id = db
+            .create_epic(Epic::new("epic".to_owned(), "".to_owned()))
+            .unwrap();
+        let story_id = db
+            .create_story(Story::new("story".to_owned(), "".to_owned()), epic_id)
+            .unwrap();
+        db.update_story_status(story_id, Status::Resolved).unwrap();
+        db.update_epic_status(epic_id, Status::Closed).unwrap();
+
+        let result = db.reopen_story(story_id);
+
+        assert!(result.is_err());
+        assert_eq!(
+            db.read_db().unwrap().stories.get(&story_id).unwrap().status,
+            Status::Resolved
+        );
+    }
+
+    #[test]
+    fn reopen_story_should_error_if_story_is_not_resolved_or_closed() {
+        let db = JiraDatabase::with_database(Box::new(MockDB::new()));
+        let epic_id = db
+            .create_epic(Epic::new("epic".to_owned(), "".to_owned()))
+            .unwrap();
+        let story_id = db
+            .create_story(Story::new("story".to_owned(), "".to_owned()), epic_id)
+            .unwrap();
+
+        let result = db.reopen_story(story_id);
+
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn use_project_should_keep_projects_separate() {
+        let db = JiraDatabase::with_database(Box::new(MockDB::new()));
+        let default_epic = Epic::new("default epic".to_owned(), "".to_owned());
+        let default_epic_id = db.create_epic(default_epic.clone()).unwrap();
+
+        db.use_project("side project").unwrap();
+
+        assert_eq!(db.active_project().unwrap(), "side project");
+        assert!(db.read_db().unwrap().epics.is_empty());
+
+        let side_epic = Epic::new("side epic".to_owned(), "".to_owned());
+        let side_epic_id = db.create_epic(side_epic.clone()).unwrap();
+        assert_ne!(side_epic_id, default_epic_id);
+
+        db.use_project("default").unwrap();
+
+        let db_state = db.read_db().unwrap();
+        assert_eq!(db_state.active_project, "default");
+        assert_eq!(db_state.epics.get(&default_epic_id), Some(&default_epic));
+        assert_eq!(db_state.epics.get(&side_epic_id), None);
+        assert_eq!(
+            db_state.projects["side project"].epics.get(&side_epic_id),
+            Some(&side_epic)
+        );
+    }
+
+    fn sprint_1() -> Sprint {
+        Sprint::new(
+            "sprint 1".to_owned(),
+            NaiveDate::from_ymd_opt(2025, 6, 2).unwrap(),
+            NaiveDate::from_ymd_opt(2025, 6, 13).unwrap(),
+        )
+    }
+
+    #[test]
+    fn create_sprint_should_assign_an_id() {
+        let db = JiraDatabase::with_database(Box::new(MockDB::new()));
+
+        let sprint_id = db.create_sprint(sprint_1()).unwrap();
+
+        let expected = Sprint {
+            id: sprint_id,
+            ..sprint_1()
         };
-        let epic = Epic::new("".to_owned(), "".to_owned());
-        let story = Story::new("".to_owned(), "".to_owned());
+        assert_eq!(db.get_sprint(sprint_id).unwrap(), expected);
+        assert!(db.get_sprint(999).is_err());
+    }
 
-        let result = db.create_epic(epic);
-        assert_eq!(result.is_ok(), true);
+    #[test]
+    fn create_sprint_should_validate_name_and_dates() {
+        let db = JiraDatabase::with_database(Box::new(MockDB::new()));
 
-        let epic_id = result.unwrap();
+        let unnamed = Sprint {
+            name: " ".to_owned(),
+            ..sprint_1()
+        };
+        let backwards = Sprint {
+            end_date: NaiveDate::from_ymd_opt(2025, 6, 1).unwrap(),
+            ..sprint_1()
+        };
+        let unknown_story = Sprint {
+            story_ids: vec![999],
+            ..sprint_1()
+        };
 
-        let result = db.create_story(story.clone(), epic_id);
-        assert_eq!(result.is_ok(), true);
+        assert!(db.create_sprint(unnamed).is_err());
+        assert!(db.create_sprint(backwards).is_err());
+        assert!(db.create_sprint(unknown_story).is_err());
+        assert!(db.read_db().unwrap().sprints.is_empty());
+    }
+
+    #[test]
+    fn update_sprint_should_replace_the_sprint() {
+        let db = JiraDatabase::with_database(Box::new(MockDB::new()));
+        let sprint_id = db.create_sprint(sprint_1()).unwrap();
+
+        let renamed = Sprint {
+            id: sprint_id,
+            name: "sprint 1 (extended)".to_owned(),
+            end_date: NaiveDate::from_ymd_opt(2025, 6, 20).unwrap(),
+            ..sprint_1()
+        };
+        db.update_sprint(renamed.clone()).unwrap();
+
+        assert_eq!(db.get_sprint(sprint_id).unwrap(), renamed);
+        assert!(
+            db.update_sprint(Sprint {
+                id: 999,
+                ..sprint_1()
+            })
+            .is_err()
+        );
+    }
+
+    #[test]
+    fn delete_sprint_should_keep_its_stories() {
+        let db = JiraDatabase::with_database(Box::new(MockDB::new()));
+        let story_ids = create_stories(&db, 1);
+        let sprint_id = db.create_sprint(sprint_1()).unwrap();
+        db.assign_story_to_sprint(sprint_id, story_ids[0]).unwrap();
+
+        db.delete_sprint(sprint_id).unwrap();
 
-        let id = result.unwrap();
         let db_state = db.read_db().unwrap();
+        assert!(db_state.sprints.is_empty());
+        assert!(db_state.stories.contains_key(&story_ids[0]));
+        assert!(db.delete_sprint(sprint_id).is_err());
+    }
 
-        let expected_id = 2;
+    #[test]
+    fn purge_old_sprints_should_only_remove_sprints_that_ended_long_ago() {
+        let db = JiraDatabase::with_database(Box::new(MockDB::new()));
+        let story_ids = create_stories(&db, 1);
+        let today = Local::now().date_naive();
+        let ending = |days_ago: u64, days_ahead: u64| {
+            Sprint::new(
+                "sprint".to_owned(),
+                today - Days::new(days_ago + 14),
+                today - Days::new(days_ago) + Days::new(days_ahead),
+            )
+        };
+        let old_id = db.create_sprint(ending(100, 0)).unwrap();
+        let recent_id = db.create_sprint(ending(20, 0)).unwrap();
+        let active_id = db.create_sprint(ending(0, 5)).unwrap();
+        db.assign_story_to_sprint(old_id, story_ids[0]).unwrap();
 
-        assert_eq!(id, expected_id);
-        assert_eq!(db_state.last_item_id, expected_id);
+        assert_eq!(db.purge_old_sprints(30).unwrap(), 1);
+
+        let db_state = db.read_db().unwrap();
         assert_eq!(
-            db_state.epics.get(&epic_id).unwrap().stories.contains(&id),
-            true
+            db_state.sprints.keys().sorted().collect::<Vec<_>>(),
+            vec![&recent_id, &active_id]
         );
-        assert_eq!(db_state.stories.get(&id), Some(&story));
+        assert!(db_state.stories.contains_key(&story_ids[0]));
+        assert_eq!(db.purge_old_sprints(30).unwrap(), 0);
+        assert_eq!(db.purge_old_sprints(u32::MAX).unwrap(), 0);
+        assert_eq!(db.purge_old_sprints(0).unwrap(), 1);
+    }
+
+    #[test]
+    fn export_sprint_report_should_summarize_the_sprint() {
+        let db = JiraDatabase::with_database(Box::new(MockDB::new()));
+        let story_ids = create_stories(&db, 3);
+        let sprint_id = db.create_sprint(sprint_1()).unwrap();
+        for (story_id, estimate) in story_ids.iter().zip([5, 3, 2]) {
+            db.update_story_estimate(*story_id, estimate).unwrap();
+            db.assign_story_to_sprint(sprint_id, *story_id).unwrap();
+        }
+        db.update_story_status(story_ids[0], Status::Resolved)
+            .unwrap();
+        db.update_story_status(story_ids[1], Status::Closed)
+            .unwrap();
+        // Only the last story was created after the sprint started
+        let mut db_state = db.read_db().unwrap();
+        for story_id in &story_ids[..2] {
+            db_state.stories.get_mut(story_id).unwrap().created_at =
+                "2025-06-01T09:00:00Z".parse().unwrap();
+        }
+        db.database.write_db(&db_state).unwrap();
+        db.invalidate_cache();
+
+        let dir = tempfile::tempdir().unwrap();
+        let dest = dir.path().join("report.txt");
+        db.export_sprint_report(sprint_id, &dest).unwrap();
+
+        let report = fs::read_to_string(&dest).unwrap();
+        let lines: Vec<&str> = report.lines().collect();
+        assert_eq!(lines[0], "Sprint report: sprint 1");
+        assert_eq!(lines[1], "Dates: 2025-06-02 to 2025-06-13");
+        assert_eq!(lines[2], "Committed points: 10");
+        assert_eq!(lines[3], "Completed points: 8 (80%)");
+        assert!(report.contains(&format!(
+            "{:<6} {:<12} {:>6}  story 1\n",
+            story_ids[1], "CLOSED", 3
+        )));
+        assert!(report.contains(&format!(
+            "\nAdded mid-sprint:\n- #{} story 2\n",
+            story_ids[2]
+        )));
+        assert!(report.ends_with(&format!("\nCarried over:\n- #{} story 2\n", story_ids[2])));
+        assert!(db.export_sprint_report(999, &dest).is_err());
+    }
+
+    #[test]
+    fn assign_story_to_sprint_should_move_the_story_between_sprints() {
+        let db = JiraDatabase::with_database(Box::new(MockDB::new()));
+        let story_ids = create_stories(&db, 1);
+        let first_id = db.create_sprint(sprint_1()).unwrap();
+        let second_id = db.create_sprint(sprint_1()).unwrap();
+
+        db.assign_story_to_sprint(first_id, story_ids[0]).unwrap();
+        db.assign_story_to_sprint(second_id, story_ids[0]).unwrap();
+        // Assigning again is a no-op rather than a duplicate entry
+        db.assign_story_to_sprint(second_id, story_ids[0]).unwrap();
+
+        assert!(db.get_sprint(first_id).unwrap().story_ids.is_empty());
+        assert_eq!(db.get_sprint(second_id).unwrap().story_ids, story_ids);
+        assert!(db.assign_story_to_sprint(first_id, 999).is_err());
+        assert!(db.assign_story_to_sprint(999, story_ids[0]).is_err());
+    }
+
+    #[test]
+    fn remove_story_from_sprint_should_work() {
+        let db = JiraDatabase::with_database(Box::new(MockDB::new()));
+        let story_ids = create_stories(&db, 1);
+        let sprint_id = db.create_sprint(sprint_1()).unwrap();
+
+        assert!(
+            db.remove_story_from_sprint(sprint_id, story_ids[0])
+                .is_err()
+        );
+
+        db.assign_story_to_sprint(sprint_id, story_ids[0]).unwrap();
+        db.remove_story_from_sprint(sprint_id, story_ids[0])
+            .unwrap();
+
+        assert!(db.get_sprint(sprint_id).unwrap().story_ids.is_empty());
+    }
+
+    #[test]
+    fn backlog_stories_should_skip_planned_stories_and_sort_by_priority() {
+        let db = JiraDatabase::with_database(Box::new(MockDB::new()));
+        let story_ids = create_stories(&db, 2);
+        let epic_id = *db.read_db().unwrap().epics.keys().next().unwrap();
+        let urgent_id = db
+            .create_story(
+                Story {
+                    priority: Priority::Critical,
+                    ..Story::new("urgent".to_owned(), "".to_owned())
+                },
+                epic_id,
+            )
+            .unwrap();
+        let sprint_id = db.create_sprint(sprint_1()).unwrap();
+        db.assign_story_to_sprint(sprint_id, story_ids[0]).unwrap();
+
+        let backlog: Vec<(u32, u32)> = db
+            .backlog_stories()
+            .unwrap()
+            .into_iter()
+            .map(|(epic_id, story_id, _)| (epic_id, story_id))
+            .collect();
+
+        assert_eq!(backlog, vec![(epic_id, urgent_id), (epic_id, story_ids[1])]);
+    }
+
+    #[test]
+    fn delete_story_should_remove_it_from_its_sprint() {
+        let db = JiraDatabase::with_database(Box::new(MockDB::new()));
+        let story_ids = create_stories(&db, 1);
+        let epic_id = *db.read_db().unwrap().epics.keys().next().unwrap();
+        let sprint_id = db.create_sprint(sprint_1()).unwrap();
+        db.assign_story_to_sprint(sprint_id, story_ids[0]).unwrap();
+
+        db.delete_story(epic_id, story_ids[0]).unwrap();
+
+        assert!(db.get_sprint(sprint_id).unwrap().story_ids.is_empty());
+    }
+
+    #[test]
+    fn use_project_should_keep_sprints_with_their_project() {
+        let db = JiraDatabase::with_database(Box::new(MockDB::new()));
+        let sprint_id = db.create_sprint(sprint_1()).unwrap();
+
+        db.use_project("side project").unwrap();
+        assert!(db.get_sprint(sprint_id).is_err());
+
+        db.use_project("default").unwrap();
+        assert!(db.get_sprint(sprint_id).is_ok());
+    }
+
+    #[test]
+    fn use_project_should_error_on_empty_name() {
+        let db = JiraDatabase::with_database(Box::new(MockDB::new()));
+
+        assert!(db.use_project("  ").is_err());
+        assert_eq!(db.active_project().unwrap(), "default");
+    }
+
+    #[test]
+    fn list_projects_should_include_the_active_project() {
+        let db = JiraDatabase::with_database(Box::new(MockDB::new()));
+        assert_eq!(db.list_projects().unwrap(), vec!["default".to_owned()]);
+
+        db.use_project("beta").unwrap();
+        db.use_project("alpha").unwrap();
+
+        assert_eq!(
+            db.list_projects().unwrap(),
+            vec!["alpha".to_owned(), "beta".to_owned(), "default".to_owned()]
+        );
+    }
+
+    #[test]
+    fn delete_project_should_work() {
+        let db = JiraDatabase::with_database(Box::new(MockDB::new()));
+        db.use_project("scratch").unwrap();
+        db.use_project("default").unwrap();
+
+        assert!(db.delete_project("default").is_err());
+        assert!(db.delete_project(" default ").is_err());
+        assert!(db.delete_project("missing").is_err());
+        assert!(db.delete_project(" scratch ").is_ok());
+        assert_eq!(db.list_projects().unwrap(), vec!["default".to_owned()]);
+    }
+
+    #[test]
+    fn stories_above_word_count_should_only_return_longer_descriptions() {
+        let db = JiraDatabase::with_database(Box::new(MockDB::new()));
+        let epic_id = db
+            .create_epic(Epic::new("epic".to_owned(), "".to_owned()))
+            .unwrap();
+        let short_story = Story::new("story".to_owned(), "just three words".to_owned());
+        let long_story = Story::new("story".to_owned(), "this one has five words".to_owned());
+        db.create_story(short_story, epic_id).unwrap();
+        let long_story_id = db.create_story(long_story.clone(), epic_id).unwrap();
+
+        assert_eq!(
+            db.stories_above_word_count(3).unwrap(),
+            vec![(long_story_id, long_story)]
+        );
+        assert_eq!(db.stories_above_word_count(2).unwrap().len(), 2);
+        assert!(db.stories_above_word_count(5).unwrap().is_empty());
+    }
+
+    #[test]
+    fn release_filters_should_only_return_items_in_that_release() {
+        let db = JiraDatabase::with_database(Box::new(MockDB::new()));
+        let epic_id = db
+            .create_epic(Epic::new("epic".to_owned(), "".to_owned()))
+            .unwrap();
+        db.create_epic(Epic::new("other epic".to_owned(), "".to_owned()))
+            .unwrap();
+        let story_id = db
+            .create_story(Story::new("story".to_owned(), "".to_owned()), epic_id)
+            .unwrap();
+        let other_story_id = db
+            .create_story(Story::new("other story".to_owned(), "".to_owned()), epic_id)
+            .unwrap();
+
+        db.update_epic_release(epic_id, Some(" 1.0 ".to_owned()))
+            .unwrap();
+        db.update_story_release(story_id, Some("1.0".to_owned()))
+            .unwrap();
+        db.update_story_release(other_story_id, Some("2.0".to_owned()))
+            .unwrap();
+
+        let epic_ids: Vec<u32> = db
+            .get_epics_for_release("1.0")
+            .unwrap()
+            .into_iter()
+            .map(|(id, _)| id)
+            .collect();
+        let story_ids: Vec<u32> = db
+            .get_stories_for_release("1.0")
+            .unwrap()
+            .into_iter()
+            .map(|(id, _)| id)
+            .collect();
+        assert_eq!(epic_ids, vec![epic_id]);
+        assert_eq!(story_ids, vec![story_id]);
+        assert!(db.get_epics_for_release("2.0").unwrap().is_empty());
+        assert!(db.get_stories_for_release("3.0").unwrap().is_empty());
+    }
+
+    #[test]
+    fn get_epic_with_stories_should_return_the_stories_in_epic_order() {
+        let db = JiraDatabase::with_database(Box::new(MockDB::new()));
+        let epic_id = db
+            .create_epic(Epic::new("epic".to_owned(), "".to_owned()))
+            .unwrap();
+        let first = db
+            .create_story(Story::new("first".to_owned(), "".to_owned()), epic_id)
+            .unwrap();
+        let second = db
+            .create_story(Story::new("second".to_owned(), "".to_owned()), epic_id)
+            .unwrap();
+
+        let view = db.get_epic_with_stories(epic_id).unwrap();
+        assert_eq!(view.epic.name, "epic");
+        let names: Vec<(u32, &str)> = view
+            .stories
+            .iter()
+            .map(|(id, story)| (*id, story.name.as_str()))
+            .collect();
+        assert_eq!(names, vec![(first, "first"), (second, "second")]);
+        assert!(view.missing_story_ids.is_empty());
+
+        let view = db.get_story_with_epic(second).unwrap();
+        assert_eq!(view.story.name, "second");
+        assert_eq!(view.epic_id, epic_id);
+        assert_eq!(view.epic.stories, vec![first, second]);
+    }
+
+    #[test]
+    fn get_epic_with_stories_should_fail_for_unknown_ids() {
+        let db = JiraDatabase::with_database(Box::new(MockDB::new()));
+
+        assert!(db.get_epic_with_stories(999).is_err());
+        assert!(db.get_story_with_epic(999).is_err());
+    }
+
+    #[test]
+    fn get_epic_with_stories_should_report_missing_stories() {
+        let mock = MockDB::new();
+        let mut state = mock.read_db().unwrap();
+        state.last_item_id = 2;
+        state.epics.insert(
+            1,
+            Epic {
+                stories: vec![42, 2],
+                ..Epic::new("epic".to_owned(), "".to_owned())
+            },
+        );
+        state
+            .stories
+            .insert(2, Story::new("story".to_owned(), "".to_owned()));
+        // A story that no epic lists
+        state
+            .stories
+            .insert(3, Story::new("stray".to_owned(), "".to_owned()));
+        mock.write_db(&state).unwrap();
+        let db = JiraDatabase::with_database(Box::new(mock));
+
+        let view = db.get_epic_with_stories(1).unwrap();
+        assert_eq!(view.stories.len(), 1);
+        assert_eq!(view.stories[0].0, 2);
+        assert_eq!(view.missing_story_ids, vec![42]);
+
+        assert!(db.get_story_with_epic(3).is_err());
+    }
+
+    #[test]
+    fn releases_should_count_epics_and_stories_per_release() {
+        let db = JiraDatabase::with_database(Box::new(MockDB::new()));
+        let epic_id = db
+            .create_epic(Epic::new("epic".to_owned(), "".to_owned()))
+            .unwrap();
+        let story_ids = [
+            db.create_story(Story::new("a".to_owned(), "".to_owned()), epic_id)
+                .unwrap(),
+            db.create_story(Story::new("b".to_owned(), "".to_owned()), epic_id)
+                .unwrap(),
+        ];
+
+        db.update_epic_release(epic_id, Some("2.0".to_owned()))
+            .unwrap();
+        for story_id in story_ids {
+            db.update_story_release(story_id, Some("1.0".to_owned()))
+                .unwrap();
+        }
+        // A blank release clears it
+        db.update_story_release(story_ids[1], Some("  ".to_owned()))
+            .unwrap();
+
+        assert_eq!(
+            db.releases().unwrap(),
+            vec![
+                ReleaseSummary {
+                    name: "1.0".to_owned(),
+                    epic_count: 0,
+                    story_count: 1,
+                },
+                ReleaseSummary {
+                    name: "2.0".to_owned(),
+                    epic_count: 1,
+                    story_count: 0,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn get_epics_by_reporter_should_only_return_their_epics() {
+        let db = JiraDatabase::with_database(Box::new(MockDB::new()));
+        let alice_epic = Epic {
+            reporter: "alice".to_owned(),
+            ..Epic::new("alice's".to_owned(), "".to_owned())
+        };
+        let bob_epic = Epic {
+            reporter: "bob".to_owned(),
+            ..Epic::new("bob's".to_owned(), "".to_owned())
+        };
+        let alice_epic_id = db.create_epic(alice_epic.clone()).unwrap();
+        let bob_epic_id = db.create_epic(bob_epic.clone()).unwrap();
+
+        assert_eq!(
+            db.get_epics_by_reporter("alice").unwrap(),
+            vec![(alice_epic_id, alice_epic)]
+        );
+        assert_eq!(
+            db.get_epics_by_reporter("bob").unwrap(),
+            vec![(bob_epic_id, bob_epic)]
+        );
+        assert!(db.get_epics_by_reporter("carol").unwrap().is_empty());
+    }
+
+    #[test]
+    fn create_should_credit_items_without_a_reporter_to_the_audit_user() {
+        let db = JiraDatabase::with_database(Box::new(MockDB::new()));
+        db.set_audit_user("alice");
+
+        let epic_id = db
+            .create_epic(Epic::new("epic".to_owned(), "".to_owned()))
+            .unwrap();
+        let story_id = db
+            .create_story(Story::new("story".to_owned(), "".to_owned()), epic_id)
+            .unwrap();
+        let bobs_story_id = db
+            .create_story(
+                Story {
+                    reporter: "bob".to_owned(),
+                    ..Story::new("bob's".to_owned(), "".to_owned())
+                },
+                epic_id,
+            )
+            .unwrap();
+
+        let db_state = db.read_db().unwrap();
+        assert_eq!(db_state.epics[&epic_id].reporter, "alice");
+        assert_eq!(db_state.stories[&story_id].reporter, "alice");
+        assert_eq!(db_state.stories[&bobs_story_id].reporter, "bob");
     }
 
     #[test]
-    fn delete_epic_should_error_if_invalid_epic_id() {
-        let db = JiraDatabase {
-            database: Box::new(MockDB::new()),
-        };
+    fn get_stories_by_reporter_should_only_return_their_stories() {
+        let db = JiraDatabase::with_database(Box::new(MockDB::new()));
+        let epic_id = db
+            .create_epic(Epic::new("epic".to_owned(), "".to_owned()))
+            .unwrap();
+        let mut alice_story = Story::new("story".to_owned(), "".to_owned());
+        alice_story.reporter = "alice".to_owned();
+        let alice_story_id = db.create_story(alice_story.clone(), epic_id).unwrap();
+        db.create_story(Story::new("story".to_owned(), "".to_owned()), epic_id)
+            .unwrap();
 
-        let non_existent_epic_id = 999;
-
-        let result = db.delete_epic(non_existent_epic_id);
-        assert_eq!(result.is_err(), true);
+        assert_eq!(
+            db.get_stories_by_reporter("alice").unwrap(),
+            vec![(alice_story_id, alice_story)]
+        );
+        assert_eq!(db.get_stories_by_reporter("unknown").unwrap().len(), 1);
+        assert!(db.get_stories_by_reporter("bob").unwrap().is_empty());
     }
 
     #[test]
-    fn delete_epic_should_work() {
-        let db = JiraDatabase {
-            database: Box::new(MockDB::new()),
-        };
-        let epic = Epic::new("".to_owned(), "".to_owned());
-        let story = Story::new("".to_owned(), "".to_owned());
-
-        let result = db.create_epic(epic);
-        assert_eq!(result.is_ok(), true);
+    fn read_db_should_only_hit_the_database_once() {
+        let mock = MockDB::new();
+        let read_count = mock.read_count();
+        let db = JiraDatabase::with_database(Box::new(mock));
+
+        let epic_id = db
+            .create_epic(Epic::new("epic".to_owned(), "".to_owned()))
+            .unwrap();
+
+        for _ in 0..100 {
+            let db_state = db.read_db().unwrap();
+            assert!(db_state.epics.contains_key(&epic_id));
+        }
 
-        let epic_id = result.unwrap();
+        assert_eq!(read_count.get(), 1);
+    }
 
-        let result = db.create_story(story, epic_id);
-        assert_eq!(result.is_ok(), true);
+    #[test]
+    fn invalidate_cache_should_force_a_fresh_read() {
+        let mock = MockDB::new();
+        let read_count = mock.read_count();
+        let db = JiraDatabase::with_database(Box::new(mock));
 
-        let story_id = result.unwrap();
+        db.read_db().unwrap();
+        db.invalidate_cache();
+        db.read_db().unwrap();
 
-        let result = db.delete_epic(epic_id);
-        assert_eq!(result.is_ok(), true);
+        assert_eq!(read_count.get(), 2);
+    }
 
-        let db_state = db.read_db().unwrap();
+    #[test]
+    fn update_epic_name_should_error_if_invalid_epic_id() {
+        let db = JiraDatabase::with_database(Box::new(MockDB::new()));
 
-        let expected_last_id = 2;
+        let non_existent_epic_id = 999;
 
-        assert_eq!(db_state.last_item_id, expected_last_id);
-        assert_eq!(db_state.epics.get(&epic_id), None);
-        assert_eq!(db_state.stories.get(&story_id), None);
+        let result = db.update_epic_name(non_existent_epic_id, "new".to_owned());
+        assert!(result.is_err());
     }
 
     #[test]
-    fn delete_story_should_error_if_invalid_epic_id() {
-        let db = JiraDatabase {
-            database: Box::new(MockDB::new()),
-        };
-        let epic = Epic::new("".to_owned(), "".to_owned());
-        let story = Story::new("".to_owned(), "".to_owned());
-
-        let result = db.create_epic(epic);
-        assert_eq!(result.is_ok(), true);
-
-        let epic_id = result.unwrap();
+    fn update_epic_name_and_description_should_work() {
+        let original = Epic::new("name".to_owned(), "description".to_owned());
+        let db = JiraDatabase::with_database(Box::new(MockDB::new()));
+        let epic_id = db.create_epic(original.clone()).unwrap();
 
-        let result = db.create_story(story, epic_id);
-        assert_eq!(result.is_ok(), true);
+        assert!(db.update_epic_name(epic_id, "new name".to_owned()).is_ok());
 
-        let story_id = result.unwrap();
+        let epic = db.read_db().unwrap().epics.remove(&epic_id).unwrap();
+        assert_eq!(
+            epic,
+            Epic {
+                name: "new name".to_owned(),
+                updated_at: epic.updated_at,
+                ..original.clone()
+            }
+        );
 
-        let non_existent_epic_id = 999;
+        assert!(
+            db.update_epic_description(epic_id, "new description".to_owned())
+                .is_ok()
+        );
 
-        let result = db.delete_story(non_existent_epic_id, story_id);
-        assert_eq!(result.is_err(), true);
+        let epic = db.read_db().unwrap().epics.remove(&epic_id).unwrap();
+        assert_eq!(
+            epic,
+            Epic {
+                name: "new name".to_owned(),
+                description: "new description".to_owned(),
+                updated_at: epic.updated_at,
+                ..original.clone()
+            }
+        );
     }
 
     #[test]
-    fn delete_story_should_error_if_story_not_found_in_epic() {
-        let db = JiraDatabase {
-            database: Box::new(MockDB::new()),
-        };
-        let epic = Epic::new("".to_owned(), "".to_owned());
-        let story = Story::new("".to_owned(), "".to_owned());
+    fn update_story_name_should_error_if_invalid_story_id() {
+        let db = JiraDatabase::with_database(Box::new(MockDB::new()));
 
-        let result = db.create_epic(epic);
-        assert_eq!(result.is_ok(), true);
+        let non_existent_story_id = 999;
 
-        let epic_id = result.unwrap();
+        let result = db.update_story_name(non_existent_story_id, "new".to_owned());
+        assert!(result.is_err());
+    }
 
-        let result = db.create_story(story, epic_id);
-        assert_eq!(result.is_ok(), true);
+    #[test]
+    fn update_story_name_and_description_should_work() {
+        let original = Story::new("name".to_owned(), "description".to_owned());
+        let db = JiraDatabase::with_database(Box::new(MockDB::new()));
+        let epic_id = db
+            .create_epic(Epic::new("epic".to_owned(), "".to_owned()))
+            .unwrap();
+        let story_id = db.create_story(original.clone(), epic_id).unwrap();
+
+        assert!(
+            db.update_story_name(story_id, "new name".to_owned())
+                .is_ok()
+        );
 
-        let non_existent_story_id = 999;
+        let story = db.read_db().unwrap().stories.remove(&story_id).unwrap();
+        assert_eq!(
+            story,
+            Story {
+                name: "new name".to_owned(),
+                updated_at: story.updated_at,
+                ..original.clone()
+            }
+        );
 
-        let result = db.delete_story(epic_id, non_existent_story_id);
-        assert_eq!(result.is_err(), true);
+        assert!(
+            db.update_story_description(story_id, "new description".to_owned())
+                .is_ok()
+        );
+
+        let story = db.read_db().unwrap().stories.remove(&story_id).unwrap();
+        assert_eq!(
+            story,
+            Story {
+                name: "new name".to_owned(),
+                description: "new description".to_owned(),
+                updated_at: story.updated_at,
+                ..original.clone()
+            }
+        );
     }
 
     #[test]
-    fn delete_story_should_work() {
-        let db = JiraDatabase {
-            database: Box::new(MockDB::new()),
-        };
-        let epic = Epic::new("".to_owned(), "".to_owned());
-        let story = Story::new("".to_owned(), "".to_owned());
+    fn overdue_stories_should_only_return_past_due_open_stories() {
+        let db = JiraDatabase::with_database(Box::new(MockDB::new()));
+        let today = NaiveDate::from_ymd_opt(2025, 6, 15).unwrap();
 
-        let result = db.create_epic(epic);
-        assert_eq!(result.is_ok(), true);
+        let epic_id = db
+            .create_epic(Epic::new("epic".to_owned(), "".to_owned()))
+            .unwrap();
 
-        let epic_id = result.unwrap();
+        let mut past_due = Story::new("past".to_owned(), "".to_owned());
+        past_due.due_date = NaiveDate::from_ymd_opt(2025, 6, 14);
+        let past_due_id = db.create_story(past_due.clone(), epic_id).unwrap();
 
-        let result = db.create_story(story, epic_id);
-        assert_eq!(result.is_ok(), true);
+        let mut due_today = Story::new("today".to_owned(), "".to_owned());
+        due_today.due_date = Some(today);
+        db.create_story(due_today, epic_id).unwrap();
 
-        let story_id = result.unwrap();
+        let mut future = Story::new("future".to_owned(), "".to_owned());
+        future.due_date = NaiveDate::from_ymd_opt(2025, 7, 1);
+        db.create_story(future, epic_id).unwrap();
 
-        let result = db.delete_story(epic_id, story_id);
-        assert_eq!(result.is_ok(), true);
+        let mut past_due_closed = Story::new("closed".to_owned(), "".to_owned());
+        past_due_closed.due_date = NaiveDate::from_ymd_opt(2025, 1, 1);
+        past_due_closed.status = Status::Closed;
+        db.create_story(past_due_closed, epic_id).unwrap();
 
-        let db_state = db.read_db().unwrap();
+        db.create_story(Story::new("no due date".to_owned(), "".to_owned()), epic_id)
+            .unwrap();
 
-        let expected_last_id = 2;
+        let overdue = db.overdue_stories(today).unwrap();
 
-        assert_eq!(db_state.last_item_id, expected_last_id);
-        assert_eq!(
-            db_state
-                .epics
-                .get(&epic_id)
-                .unwrap()
-                .stories
-                .contains(&story_id),
-            false
-        );
-        assert_eq!(db_state.stories.get(&story_id), None);
+        assert_eq!(overdue, vec![(past_due_id, past_due)]);
     }
 
     #[test]
-    fn update_epic_status_should_error_if_invalid_epic_id() {
-        let db = JiraDatabase {
-            database: Box::new(MockDB::new()),
-        };
+    fn health_check_should_bump_last_item_id_past_highest_id() {
+        let db = JiraDatabase::with_database(Box::new(MockDB::new()));
+        let epic_id = db
+            .create_epic(Epic::new("epic".to_owned(), "".to_owned()))
+            .unwrap();
+
+        let mut db_state = db.read_db().unwrap();
+        db_state
+            .stories
+            .insert(9999, Story::new("story".to_owned(), "".to_owned()));
+        db_state.epics.get_mut(&epic_id).unwrap().stories.push(9999);
+        db.database.write_db(&db_state).unwrap();
+        db.invalidate_cache();
+
+        assert!(db.health_check().is_ok());
+        assert_eq!(db.read_db().unwrap().last_item_id, 10000);
+
+        let story_id = db
+            .create_story(Story::new("story".to_owned(), "".to_owned()), epic_id)
+            .unwrap();
+        assert_eq!(story_id, 10001);
+    }
 
-        let non_existent_epic_id = 999;
+    #[test]
+    fn health_check_should_bump_last_item_id_past_the_highest_sprint_id() {
+        let db = JiraDatabase::with_database(Box::new(MockDB::new()));
 
-        let result = db.update_epic_status(non_existent_epic_id, Status::Closed);
-        assert_eq!(result.is_err(), true);
+        let mut db_state = db.read_db().unwrap();
+        db_state.sprints.insert(500, sprint_1());
+        db.database.write_db(&db_state).unwrap();
+        db.invalidate_cache();
+
+        assert!(db.health_check().is_ok());
+        assert_eq!(db.read_db().unwrap().last_item_id, 501);
+
+        let epic_id = db
+            .create_epic(Epic::new("epic".to_owned(), "".to_owned()))
+            .unwrap();
+        assert_eq!(epic_id, 502);
     }
 
     #[test]
-    fn update_epic_status_should_work() {
-        let db = JiraDatabase {
-            database: Box::new(MockDB::new()),
-        };
-        let epic = Epic::new("".to_owned(), "".to_owned());
+    fn health_check_should_not_write_when_ids_are_consistent() {
+        let mock = MockDB::new();
+        let write_count = mock.write_count();
+        let db = JiraDatabase::with_database(Box::new(mock));
+        db.create_epic(Epic::new("epic".to_owned(), "".to_owned()))
+            .unwrap();
+
+        assert!(db.health_check().is_ok());
+        assert_eq!(write_count.get(), 1);
+        assert_eq!(db.read_db().unwrap().last_item_id, 1);
+    }
 
-        let result = db.create_epic(epic);
+    fn integrity_fixture() -> (JiraDatabase, u32, u32) {
+        let db = JiraDatabase::with_database(Box::new(MockDB::new()));
+        let epic_id = db
+            .create_epic(Epic::new("epic".to_owned(), "".to_owned()))
+            .unwrap();
+        let story_id = db
+            .create_story(Story::new("story".to_owned(), "".to_owned()), epic_id)
+            .unwrap();
+        (db, epic_id, story_id)
+    }
 
-        assert_eq!(result.is_ok(), true);
+    #[test]
+    fn validate_should_find_nothing_wrong_with_a_consistent_database() {
+        let (db, _, _) = integrity_fixture();
 
-        let epic_id = result.unwrap();
+        assert!(db.read_db().unwrap().validate().is_empty());
+    }
 
-        let result = db.update_epic_status(epic_id, Status::Closed);
+    #[test]
+    fn validate_should_report_orphaned_story_references() {
+        let (db, epic_id, _) = integrity_fixture();
+        let mut db_state = db.read_db().unwrap();
+        db_state.epics.get_mut(&epic_id).unwrap().stories.push(42);
 
-        assert_eq!(result.is_ok(), true);
+        assert_eq!(
+            db_state.validate(),
+            vec![IntegrityIssue::OrphanedStoryReference {
+                epic_id,
+                story_id: 42
+            }]
+        );
+    }
 
-        let db_state = db.read_db().unwrap();
+    #[test]
+    fn validate_should_report_stories_without_exactly_one_epic() {
+        let (db, _, story_id) = integrity_fixture();
+        let other_epic_id = db
+            .create_epic(Epic::new("other".to_owned(), "".to_owned()))
+            .unwrap();
+        let mut db_state = db.read_db().unwrap();
+        db_state
+            .stories
+            .insert(50, Story::new("unowned".to_owned(), "".to_owned()));
+        db_state
+            .epics
+            .get_mut(&other_epic_id)
+            .unwrap()
+            .stories
+            .push(story_id);
 
-        assert_eq!(db_state.epics.get(&epic_id).unwrap().status, Status::Closed);
+        assert_eq!(
+            db_state.validate(),
+            vec![
+                IntegrityIssue::MismatchedEpicId { story_id },
+                IntegrityIssue::MismatchedEpicId { story_id: 50 },
+            ]
+        );
     }
 
     #[test]
-    fn update_story_status_should_error_if_invalid_story_id() {
-        let db = JiraDatabase {
-            database: Box::new(MockDB::new()),
-        };
-
-        let non_existent_story_id = 999;
+    fn validate_should_report_ids_used_twice() {
+        let (db, epic_id, story_id) = integrity_fixture();
+        let mut db_state = db.read_db().unwrap();
+        db_state.projects.insert(
+            "other".to_owned(),
+            ProjectState {
+                epics: HashMap::from([(story_id, Epic::new("parked".to_owned(), "".to_owned()))]),
+                ..Default::default()
+            },
+        );
+        db_state.sprints.insert(epic_id, sprint_1());
 
-        let result = db.update_story_status(non_existent_story_id, Status::Closed);
-        assert_eq!(result.is_err(), true);
+        assert_eq!(
+            db_state.validate(),
+            vec![
+                IntegrityIssue::DuplicateId { id: epic_id },
+                IntegrityIssue::DuplicateId { id: story_id },
+            ]
+        );
     }
 
     #[test]
-    fn update_story_status_should_work() {
-        let db = JiraDatabase {
-            database: Box::new(MockDB::new()),
-        };
-        let epic = Epic::new("".to_owned(), "".to_owned());
-        let story = Story::new("".to_owned(), "".to_owned());
+    fn repair_should_drop_orphaned_story_references() {
+        let (db, epic_id, story_id) = integrity_fixture();
+        let mut db_state = db.read_db().unwrap();
+        let stories = &mut db_state.epics.get_mut(&epic_id).unwrap().stories;
+        stories.insert(0, 42);
+        stories.push(43);
+        db.database.write_db(&db_state).unwrap();
+        db.invalidate_cache();
 
-        let result = db.create_epic(epic);
+        let report = db.repair().unwrap();
 
-        let epic_id = result.unwrap();
+        assert_eq!(
+            report.removed_story_references,
+            vec![(epic_id, 42), (epic_id, 43)]
+        );
+        assert_eq!(
+            db.read_db().unwrap().epics[&epic_id].stories,
+            vec![story_id]
+        );
+        assert!(db.read_db().unwrap().validate().is_empty());
+    }
 
-        let result = db.create_story(story, epic_id);
+    #[test]
+    fn repair_should_not_write_when_nothing_is_orphaned() {
+        let mock = MockDB::new();
+        let write_count = mock.write_count();
+        let db = JiraDatabase::with_database(Box::new(mock));
+        db.create_epic(Epic::new("epic".to_owned(), "".to_owned()))
+            .unwrap();
+
+        assert_eq!(db.repair().unwrap(), RepairReport::default());
+        assert_eq!(write_count.get(), 1);
+    }
 
-        let story_id = result.unwrap();
+    #[test]
+    fn with_transaction_should_write_all_changes_at_once() {
+        let mock = MockDB::new();
+        let write_count = mock.write_count();
+        let db = JiraDatabase::with_database(Box::new(mock));
+
+        let story_id = db
+            .with_transaction(|db| {
+                let epic_id = db.create_epic(Epic::new("epic".to_owned(), "".to_owned()))?;
+                db.create_story(Story::new("story".to_owned(), "".to_owned()), epic_id)
+            })
+            .unwrap();
+
+        assert_eq!(write_count.get(), 1);
+        db.invalidate_cache();
+        let state = db.read_db().unwrap();
+        assert_eq!(state.epics.len(), 1);
+        assert!(state.stories.contains_key(&story_id));
+    }
 
-        let result = db.update_story_status(story_id, Status::Closed);
+    #[test]
+    fn with_transaction_should_roll_back_when_the_write_fails() {
+        let mock = MockDB::new();
+        let fail_writes = mock.fail_writes();
+        let db = JiraDatabase::with_database(Box::new(mock));
+        db.create_epic(Epic::new("existing".to_owned(), "".to_owned()))
+            .unwrap();
+        let before = db.read_db().unwrap();
+
+        fail_writes.set(true);
+        let result = db.with_transaction(|db| {
+            let epic_id = db.create_epic(Epic::new("epic".to_owned(), "".to_owned()))?;
+            db.create_story(Story::new("story".to_owned(), "".to_owned()), epic_id)?;
+            db.update_epic_status(1, Status::Closed)
+        });
+        fail_writes.set(false);
+
+        assert!(result.is_err());
+        assert_eq!(db.read_db().unwrap(), before);
+        db.invalidate_cache();
+        assert_eq!(db.read_db().unwrap(), before);
+    }
 
-        assert_eq!(result.is_ok(), true);
+    #[test]
+    fn with_transaction_should_roll_back_when_the_closure_fails() {
+        let mock = MockDB::new();
+        let write_count = mock.write_count();
+        let db = JiraDatabase::with_database(Box::new(mock));
+        let before = db.read_db().unwrap();
+
+        let result: Result<()> = db.with_transaction(|db| {
+            db.create_epic(Epic::new("epic".to_owned(), "".to_owned()))?;
+            Err(anyhow!("changed my mind"))
+        });
+
+        assert!(result.is_err());
+        assert_eq!(write_count.get(), 0);
+        assert_eq!(db.read_db().unwrap(), before);
+    }
 
-        let db_state = db.read_db().unwrap();
+    #[test]
+    fn single_writes_should_leave_the_cache_alone_when_they_fail() {
+        let mock = MockDB::new();
+        let fail_writes = mock.fail_writes();
+        let db = JiraDatabase::with_database(Box::new(mock));
+        let before = db.read_db().unwrap();
 
-        assert_eq!(
-            db_state.stories.get(&story_id).unwrap().status,
-            Status::Closed
-        );
+        fail_writes.set(true);
+        let result = db.create_epic(Epic::new("epic".to_owned(), "".to_owned()));
+
+        assert!(result.is_err());
+        assert_eq!(db.read_db().unwrap(), before);
     }
 
     mod database {
@@ -448,6 +4728,7 @@ mod tests {
         use std::io::Write;
 
         use super::*;
+        use crate::{io_utils::tmp_path_for, models::Priority};
 
         #[test]
         fn read_db_should_fail_with_invalid_path() {
@@ -495,6 +4776,88 @@ mod tests {
             let result = db.read_db();
 
             assert_eq!(result.is_ok(), true);
+            assert_eq!(result.unwrap().active_project, "default");
+        }
+
+        #[test]
+        fn read_db_should_default_missing_priorities_to_medium() {
+            let mut tmpfile = tempfile::NamedTempFile::new().unwrap();
+
+            let file_contents = r#"{ "last_item_id": 2, "epics": { "1": { "name": "epic 1", "description": "epic 1", "status": "Open", "stories": [2] } }, "stories": { "2": { "name": "story 1", "description": "story 1", "status": "Open" } } }"#;
+            write!(tmpfile, "{}", file_contents).unwrap();
+
+            let db = JSONFileDatabase {
+                file_path: tmpfile
+                    .path()
+                    .to_str()
+                    .expect("failed to convert tmpfile path to str")
+                    .to_string(),
+            };
+
+            let db_state = db.read_db().unwrap();
+
+            assert_eq!(db_state.epics[&1].priority, Priority::Medium);
+            assert_eq!(db_state.stories[&2].priority, Priority::Medium);
+        }
+
+        #[test]
+        fn read_db_should_default_missing_story_types_to_feature() {
+            let mut tmpfile = tempfile::NamedTempFile::new().unwrap();
+
+            let file_contents = r#"{ "last_item_id": 2, "epics": { "1": { "name": "epic 1", "description": "epic 1", "status": "Open", "stories": [2] } }, "stories": { "2": { "name": "story 1", "description": "story 1", "status": "Open" } } }"#;
+            write!(tmpfile, "{}", file_contents).unwrap();
+
+            let db = JSONFileDatabase {
+                file_path: tmpfile
+                    .path()
+                    .to_str()
+                    .expect("failed to convert tmpfile path to str")
+                    .to_string(),
+            };
+
+            let db_state = db.read_db().unwrap();
+
+            assert_eq!(db_state.stories[&2].story_type, StoryType::Feature);
+        }
+
+        #[test]
+        fn read_db_should_default_missing_reporters_to_unknown() {
+            let mut tmpfile = tempfile::NamedTempFile::new().unwrap();
+
+            let file_contents = r#"{ "last_item_id": 2, "epics": { "1": { "name": "epic 1", "description": "epic 1", "status": "Open", "stories": [2] } }, "stories": { "2": { "name": "story 1", "description": "story 1", "status": "Open" } } }"#;
+            write!(tmpfile, "{}", file_contents).unwrap();
+
+            let db = JSONFileDatabase {
+                file_path: tmpfile
+                    .path()
+                    .to_str()
+                    .expect("failed to convert tmpfile path to str")
+                    .to_string(),
+            };
+
+            let db_state = db.read_db().unwrap();
+
+            assert_eq!(db_state.epics[&1].reporter, "unknown");
+            assert_eq!(db_state.stories[&2].reporter, "unknown");
+        }
+
+        #[test]
+        fn health_check_should_repair_last_item_id_loaded_from_file() {
+            let mut tmpfile = tempfile::NamedTempFile::new().unwrap();
+
+            let file_contents = r#"{ "last_item_id": 5, "epics": { "1": { "name": "epic 1", "description": "epic 1", "status": "Open", "stories": [9999] } }, "stories": { "9999": { "name": "story 1", "description": "story 1", "status": "Open" } } }"#;
+            write!(tmpfile, "{}", file_contents).unwrap();
+
+            let db = JiraDatabase::new(
+                tmpfile
+                    .path()
+                    .to_str()
+                    .expect("failed to convert tmpfile path to str")
+                    .to_string(),
+            );
+
+            assert!(db.health_check().is_ok());
+            assert!(db.read_db().unwrap().last_item_id >= 10000);
         }
 
         #[test]
@@ -512,16 +4875,37 @@ mod tests {
                     .to_string(),
             };
 
+            let timestamp = "2025-06-15T12:00:00Z".parse().unwrap();
             let story = Story {
                 name: "epic 1".to_owned(),
                 description: "epic 1".to_owned(),
                 status: Status::Open,
+                story_type: StoryType::Task,
+                priority: Priority::High,
+                due_date: None,
+                estimate: Some(5),
+                blocks: vec![],
+                blocked_by: vec![],
+                tags: vec![],
+                release: None,
+                reporter: "alice".to_owned(),
+                comments: vec![],
+                last_comment_id: 0,
+                created_at: timestamp,
+                updated_at: timestamp,
             };
             let epic = Epic {
                 name: "epic 1".to_owned(),
                 description: "epic 1".to_owned(),
                 status: Status::Open,
+                priority: Priority::Low,
+                due_date: None,
+                tags: vec![],
+                release: None,
+                reporter: "alice".to_owned(),
                 stories: vec![2],
+                created_at: timestamp,
+                updated_at: timestamp,
             };
 
             let mut stories = HashMap::new();
@@ -534,6 +4918,9 @@ mod tests {
                 last_item_id: 2,
                 epics,
                 stories,
+                sprints: HashMap::new(),
+                active_project: "default".to_owned(),
+                projects: HashMap::new(),
             };
 
             let write_result = db.write_db(&state);
@@ -542,5 +4929,120 @@ mod tests {
             assert_eq!(write_result.is_ok(), true);
             assert_eq!(read_result, state);
         }
+
+        #[test]
+        fn write_db_should_not_leave_a_tmp_file_behind() {
+            let dir = tempfile::tempdir().unwrap();
+            let path = dir.path().join("db.json");
+            fs::write(
+                &path,
+                r#"{ "last_item_id": 0, "epics": {}, "stories": {} }"#,
+            )
+            .unwrap();
+            let db = JSONFileDatabase {
+                file_path: path.to_str().unwrap().to_owned(),
+            };
+
+            let mut state = db.read_db().unwrap();
+            state.last_item_id = 1;
+            db.write_db(&state).unwrap();
+
+            assert_eq!(db.read_db().unwrap(), state);
+            assert!(!tmp_path_for(&path).exists());
+        }
+
+        #[test]
+        fn read_db_should_ignore_a_partial_write_left_by_a_crash() {
+            let dir = tempfile::tempdir().unwrap();
+            let path = dir.path().join("db.json");
+            fs::write(
+                &path,
+                r#"{ "last_item_id": 3, "epics": {}, "stories": {} }"#,
+            )
+            .unwrap();
+            // What a write killed before the rename would leave behind
+            fs::write(tmp_path_for(&path), r#"{ "last_item_id": 4, "epi"#).unwrap();
+            let db = JSONFileDatabase {
+                file_path: path.to_str().unwrap().to_owned(),
+            };
+
+            assert_eq!(db.read_db().unwrap().last_item_id, 3);
+
+            let mut state = db.read_db().unwrap();
+            state.last_item_id = 5;
+            db.write_db(&state).unwrap();
+            assert_eq!(db.read_db().unwrap().last_item_id, 5);
+            assert!(!tmp_path_for(&path).exists());
+        }
+
+        fn empty_file_db(dir: &tempfile::TempDir) -> String {
+            let path = dir.path().join("db.json");
+            fs::write(
+                &path,
+                r#"{ "last_item_id": 0, "epics": {}, "stories": {} }"#,
+            )
+            .unwrap();
+            path.to_str().unwrap().to_owned()
+        }
+
+        #[test]
+        fn writes_should_fail_while_another_process_holds_the_lock() {
+            let dir = tempfile::tempdir().unwrap();
+            let file_path = empty_file_db(&dir);
+            let lock_path = PathBuf::from(format!("{}.lock", file_path));
+
+            let lock = FileLock::acquire(lock_path.clone(), LOCK_TIMEOUT).unwrap();
+            let db = JiraDatabase::with_database(Box::new(JSONFileDatabase { file_path }));
+
+            let started = Instant::now();
+            let error = db
+                .create_epic(Epic::new("epic".to_owned(), "".to_owned()))
+                .unwrap_err();
+
+            assert!(started.elapsed() >= LOCK_TIMEOUT);
+            assert!(error.to_string().contains("another instance"));
+
+            drop(lock);
+            assert!(!lock_path.exists());
+        }
+
+        #[test]
+        fn concurrent_writers_should_not_lose_each_others_epics() {
+            let dir = tempfile::tempdir().unwrap();
+            let file_path = empty_file_db(&dir);
+
+            let writers: Vec<_> = ["first", "second"]
+                .into_iter()
+                .map(|name| {
+                    let file_path = file_path.clone();
+                    thread::spawn(move || {
+                        let db =
+                            JiraDatabase::with_database(Box::new(JSONFileDatabase { file_path }));
+                        for i in 0..10 {
+                            db.create_epic(Epic::new(format!("{} {}", name, i), "".to_owned()))
+                                .unwrap();
+                        }
+                    })
+                })
+                .collect();
+            for writer in writers {
+                writer.join().unwrap();
+            }
+
+            let db = JSONFileDatabase { file_path };
+            let state = db.read_db().unwrap();
+            assert_eq!(state.epics.len(), 20);
+            assert_eq!(state.last_item_id, 20);
+            for name in ["first", "second"] {
+                assert_eq!(
+                    state
+                        .epics
+                        .values()
+                        .filter(|epic| epic.name.starts_with(name))
+                        .count(),
+                    10
+                );
+            }
+        }
     }
 }
@@ -0,0 +1,236 @@
+use std::io::Write;
+
+use anyhow::Result;
+use clap::ValueEnum;
+use itertools::Itertools;
+
+use crate::{
+    db::JiraDatabase,
+    models::{DBState, Story},
+};
+
+#[derive(ValueEnum, Debug, PartialEq, Eq, Clone, Copy, Default)]
+pub enum MarkdownFormat {
+    /// One section per epic with its stories as a bullet list
+    #[default]
+    Report,
+    /// Pipe tables for embedding in a wiki page
+    WikiTable,
+}
+
+/// Writes the active project as a Markdown report in the given format.
+/// Stories are listed in their epic's own order.
+pub fn export_markdown(
+    db: &JiraDatabase,
+    format: MarkdownFormat,
+    mut writer: impl Write,
+) -> Result<()> {
+    let parsed = db.read_db()?;
+
+    match format {
+        MarkdownFormat::Report => write_report(&parsed, &mut writer)?,
+        MarkdownFormat::WikiTable => write_wiki_table(&parsed, &mut writer)?,
+    }
+
+    writer.flush()?;
+
+    Ok(())
+}
+
+fn write_report(parsed: &DBState, writer: &mut impl Write) -> Result<()> {
+    writeln!(writer, "# Project Report")?;
+    writeln!(writer)?;
+    writeln!(writer, "## Epics")?;
+
+    for id in parsed.epics.keys().sorted() {
+        let epic = &parsed.epics[id];
+
+        writeln!(writer)?;
+        writeln!(writer, "### {}", epic.name)?;
+        writeln!(writer)?;
+        writeln!(writer, "- Status: {}", epic.status)?;
+        writeln!(writer, "- Description: {}", epic.description)?;
+        writeln!(writer, "- Stories:")?;
+
+        let stories: Vec<_> = epic
+            .stories
+            .iter()
+            .filter_map(|story_id| parsed.stories.get(story_id))
+            .collect();
+        if stories.is_empty() {
+            writeln!(writer, "  - (none)")?;
+        }
+        for story in stories {
+            let estimate = story
+                .estimate
+                .map(|estimate| format!("estimate {}", estimate))
+                .unwrap_or_else(|| "no estimate".to_owned());
+            writeln!(writer, "  - {}: {}, {}", story.name, story.status, estimate)?;
+        }
+    }
+
+    Ok(())
+}
+
+// One table of epics, then a table of stories under a heading per epic
+fn write_wiki_table(parsed: &DBState, writer: &mut impl Write) -> Result<()> {
+    let epic_stories = |story_ids: &[u32]| -> Vec<(u32, &Story)> {
+        story_ids
+            .iter()
+            .filter_map(|id| parsed.stories.get(id).map(|story| (*id, story)))
+            .collect()
+    };
+    let points = |story: &Story| u32::from(story.estimate.unwrap_or(0));
+
+    writeln!(writer, "# Project Report")?;
+    writeln!(writer)?;
+    writeln!(writer, "## Epics")?;
+    writeln!(writer)?;
+    writeln!(writer, "| ID | Name | Status | Stories | Points |")?;
+    writeln!(writer, "|---|---|---|---|---|")?;
+    for id in parsed.epics.keys().sorted() {
+        let epic = &parsed.epics[id];
+        let stories = epic_stories(&epic.stories);
+        let total: u32 = stories.iter().map(|(_, story)| points(story)).sum();
+        writeln!(
+            writer,
+            "| {} | {} | {} | {} | {} |",
+            id,
+            table_cell(&epic.name),
+            epic.status,
+            stories.len(),
+            total
+        )?;
+    }
+
+    for id in parsed.epics.keys().sorted() {
+        let epic = &parsed.epics[id];
+
+        writeln!(writer)?;
+        writeln!(writer, "### {}: {}", id, epic.name)?;
+        writeln!(writer)?;
+
+        let stories = epic_stories(&epic.stories);
+        if stories.is_empty() {
+            writeln!(writer, "No stories.")?;
+            continue;
+        }
+        writeln!(writer, "| ID | Name | Status | Points |")?;
+        writeln!(writer, "|---|---|---|---|")?;
+        for (story_id, story) in stories {
+            let estimate = story
+                .estimate
+                .map(|estimate| estimate.to_string())
+                .unwrap_or_else(|| "-".to_owned());
+            writeln!(
+                writer,
+                "| {} | {} | {} | {} |",
+                story_id,
+                table_cell(&story.name),
+                story.status,
+                estimate
+            )?;
+        }
+    }
+
+    Ok(())
+}
+
+// A pipe would end the cell and a line break would end the row
+fn table_cell(text: &str) -> String {
+    text.replace('|', "\\|").replace(['\n', '\r'], " ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::test_utils::MockDB;
+    use crate::models::{Epic, Status, Story};
+
+    #[test]
+    fn export_markdown_should_write_each_epic_with_its_stories() {
+        let db = JiraDatabase::with_database(Box::new(MockDB::new()));
+
+        let billing = db
+            .create_epic(Epic::new("Billing".to_owned(), "Take payments".to_owned()))
+            .unwrap();
+        let mut pay = Story::new("Pay".to_owned(), "".to_owned());
+        pay.estimate = Some(3);
+        db.create_story(pay, billing).unwrap();
+        let receipts = db
+            .create_story(Story::new("Receipts".to_owned(), "".to_owned()), billing)
+            .unwrap();
+        db.update_story_status(receipts, Status::InProgress)
+            .unwrap();
+
+        let search = db
+            .create_epic(Epic::new("Search".to_owned(), "Find things".to_owned()))
+            .unwrap();
+        db.update_epic_status(search, Status::Resolved).unwrap();
+        let mut index = Story::new("Index".to_owned(), "".to_owned());
+        index.estimate = Some(8);
+        db.create_story(index, search).unwrap();
+
+        let mut output = Vec::new();
+        export_markdown(&db, MarkdownFormat::Report, &mut output).unwrap();
+
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            "# Project Report\n\
+             \n\
+             ## Epics\n\
+             \n\
+             ### Billing\n\
+             \n\
+             - Status: OPEN\n\
+             - Description: Take payments\n\
+             - Stories:\n  \
+             - Pay: OPEN, estimate 3\n  \
+             - Receipts: IN PROGRESS, no estimate\n\
+             \n\
+             ### Search\n\
+             \n\
+             - Status: RESOLVED\n\
+             - Description: Find things\n\
+             - Stories:\n  \
+             - Index: OPEN, estimate 8\n"
+        );
+    }
+
+    #[test]
+    fn wiki_table_should_write_an_epic_table_and_a_story_table_per_epic() {
+        let db = JiraDatabase::with_database(Box::new(MockDB::new()));
+
+        let billing = db
+            .create_epic(Epic::new("Billing | Payments".to_owned(), "".to_owned()))
+            .unwrap();
+        let mut pay = Story::new("Pay".to_owned(), "".to_owned());
+        pay.estimate = Some(3);
+        db.create_story(pay, billing).unwrap();
+        let mut refund = Story::new("Refund".to_owned(), "".to_owned());
+        refund.estimate = Some(5);
+        db.create_story(refund, billing).unwrap();
+        db.create_story(Story::new("Receipts".to_owned(), "".to_owned()), billing)
+            .unwrap();
+        db.create_epic(Epic::new("Search".to_owned(), "".to_owned()))
+            .unwrap();
+
+        let mut output = Vec::new();
+        export_markdown(&db, MarkdownFormat::WikiTable, &mut output).unwrap();
+        let output = String::from_utf8(output).unwrap();
+
+        let first_row = output.lines().find(|line| line.starts_with('|')).unwrap();
+        assert!(first_row.starts_with("| ID |"));
+        // The epic table and Billing's story table; Search has no stories
+        assert_eq!(
+            output
+                .lines()
+                .filter(|line| line.starts_with("|---"))
+                .count(),
+            2
+        );
+        assert!(output.contains("| 1 | Billing \\| Payments | OPEN | 3 | 8 |\n"));
+        assert!(output.contains("| 4 | Receipts | OPEN | - |\n"));
+        assert!(output.contains("### 5: Search\n\nNo stories.\n"));
+    }
+}
@@ -1,4 +1,24 @@
-use std::io;
+use std::{
+    fs,
+    io::{self, BufRead, Write},
+    path::{Path, PathBuf},
+    sync::{
+        Arc,
+        atomic::{AtomicBool, Ordering},
+    },
+    thread::{self, JoinHandle},
+    time::Duration,
+};
+
+use anyhow::anyhow;
+use crossterm::{
+    cursor::MoveToColumn,
+    event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers},
+    queue,
+    terminal::{self, Clear, ClearType},
+};
+
+use crate::auth::password_strength_feedback;
 
 pub fn get_user_input() -> String {
     let mut user_input = String::new();
@@ -8,6 +28,689 @@ pub fn get_user_input() -> String {
     user_input
 }
 
+/// Prints `(current: <default>) > ` and reads a line. Pressing Enter
+/// without typing anything keeps `default`.
+pub fn get_user_input_with_default(default: &str) -> Result<String, io::Error> {
+    read_input_with_default(&mut io::stdin().lock(), &mut io::stdout(), default)
+}
+
+fn read_input_with_default(
+    reader: &mut impl BufRead,
+    out: &mut impl Write,
+    default: &str,
+) -> Result<String, io::Error> {
+    write!(out, "(current: {}) > ", default)?;
+    out.flush()?;
+
+    let mut user_input = String::new();
+    if reader.read_line(&mut user_input)? == 0 {
+        return Err(io::Error::new(
+            io::ErrorKind::UnexpectedEof,
+            "no input left to read from",
+        ));
+    }
+
+    let user_input = user_input.trim_end_matches(['\r', '\n']);
+    if user_input.is_empty() {
+        Ok(default.to_owned())
+    } else {
+        Ok(user_input.to_owned())
+    }
+}
+
+/// Asks for a new password twice and returns it once both entries match,
+/// followed by how strong it is.
+pub fn read_password_confirmed() -> Result<String, io::Error> {
+    read_password_confirmed_from(&mut io::stdin().lock(), &mut io::stdout())
+}
+
+fn read_password_confirmed_from(
+    reader: &mut impl BufRead,
+    out: &mut impl Write,
+) -> Result<String, io::Error> {
+    let mut read_entry = |prompt: &str| -> Result<String, io::Error> {
+        write!(out, "{}", prompt)?;
+        out.flush()?;
+
+        let mut entry = String::new();
+        if reader.read_line(&mut entry)? == 0 {
+            return Err(io::Error::new(
+                io::ErrorKind::UnexpectedEof,
+                "no input left to read from",
+            ));
+        }
+        Ok(entry.trim_end_matches(['\r', '\n']).to_owned())
+    };
+
+    let password = read_entry("Password: ")?;
+    let confirmation = read_entry("Confirm password: ")?;
+    if password != confirmation {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "the passwords do not match",
+        ));
+    }
+
+    writeln!(out, "{}", password_strength_feedback(&password))?;
+    Ok(password)
+}
+
+/// Reads lines until `terminator` is entered alone on a line and returns
+/// them joined with `\n`. Running out of input also ends the text, as long
+/// as something was read first.
+pub fn read_multiline_input(terminator: &str) -> Result<String, io::Error> {
+    read_multiline(&mut io::stdin().lock(), terminator)
+}
+
+fn read_multiline(reader: &mut impl BufRead, terminator: &str) -> Result<String, io::Error> {
+    let mut lines = vec![];
+    loop {
+        let mut line = String::new();
+        if reader.read_line(&mut line)? == 0 {
+            if lines.is_empty() {
+                return Err(io::Error::new(
+                    io::ErrorKind::UnexpectedEof,
+                    "no input left to read from",
+                ));
+            }
+            break;
+        }
+
+        let line = line.trim_end_matches(['\r', '\n']);
+        if line.trim() == terminator {
+            break;
+        }
+        lines.push(line.to_owned());
+    }
+
+    Ok(lines.join("\n"))
+}
+
 pub fn wait_for_key_press() {
     io::stdin().read_line(&mut String::new()).unwrap();
 }
+
+pub fn write_to_file(dest: &Path, contents: &str) -> Result<(), anyhow::Error> {
+    fs::write(dest, contents).map_err(|e| anyhow!("Failed to write to {}: {}", dest.display(), e))
+}
+
+/// Writes to a sibling `.tmp` file, syncs it and renames it over `path`, so
+/// a crash part way through leaves either the old file or the new one.
+pub fn atomic_write(path: &Path, contents: &[u8]) -> Result<(), io::Error> {
+    write_and_rename(path, contents, false)
+}
+
+/// Like [`atomic_write`], but only the owner can read the file, for files
+/// that hold secrets.
+pub fn atomic_write_private(path: &Path, contents: &[u8]) -> Result<(), io::Error> {
+    write_and_rename(path, contents, true)
+}
+
+fn write_and_rename(path: &Path, contents: &[u8], private: bool) -> Result<(), io::Error> {
+    let tmp_path = tmp_path_for(path);
+
+    let mut file = fs::File::create(&tmp_path)?;
+    // Set on the open file, as a leftover `.tmp` keeps its old mode
+    if private {
+        restrict_to_owner(&file)?;
+    }
+    file.write_all(contents)?;
+    file.sync_all()?;
+
+    fs::rename(&tmp_path, path)
+}
+
+#[cfg(unix)]
+fn restrict_to_owner(file: &fs::File) -> Result<(), io::Error> {
+    use std::os::unix::fs::PermissionsExt;
+
+    file.set_permissions(fs::Permissions::from_mode(0o600))
+}
+
+#[cfg(not(unix))]
+fn restrict_to_owner(_file: &fs::File) -> Result<(), io::Error> {
+    Ok(())
+}
+
+pub fn tmp_path_for(path: &Path) -> PathBuf {
+    let mut tmp_path = path.as_os_str().to_owned();
+    tmp_path.push(".tmp");
+    PathBuf::from(tmp_path)
+}
+
+pub struct Spinner {
+    handle: JoinHandle<()>,
+    stop: Arc<AtomicBool>,
+}
+
+impl Spinner {
+    pub fn stop(self) {
+        self.stop.store(true, Ordering::Relaxed);
+        let _ = self.handle.join();
+        eprintln!();
+    }
+}
+
+/// Prints `message` with a rotating `|/-\` after it on a background thread
+/// until the returned spinner is stopped. It goes to stderr, so it does not
+/// mix with results printed to stdout.
+pub fn start_spinner(message: &str) -> Spinner {
+    const FRAMES: [char; 4] = ['|', '/', '-', '\\'];
+    const FRAME_INTERVAL: Duration = Duration::from_millis(100);
+
+    let stop = Arc::new(AtomicBool::new(false));
+    let message = message.to_owned();
+
+    let thread_stop = Arc::clone(&stop);
+    let handle = thread::spawn(move || {
+        for frame in FRAMES.iter().cycle() {
+            if thread_stop.load(Ordering::Relaxed) {
+                break;
+            }
+            eprint!("\r{} {}", message, frame);
+            let _ = io::stderr().flush();
+            thread::sleep(FRAME_INTERVAL);
+        }
+    });
+
+    Spinner { handle, stop }
+}
+
+pub fn terminal_height() -> usize {
+    const DEFAULT_TERMINAL_HEIGHT: usize = 24;
+
+    crossterm::terminal::size()
+        .map(|(_, rows)| rows as usize)
+        .unwrap_or(DEFAULT_TERMINAL_HEIGHT)
+}
+
+pub fn truncate_lines(mut lines: Vec<String>, max_height: usize) -> Vec<String> {
+    if lines.len() <= max_height {
+        return lines;
+    }
+    if max_height == 0 {
+        return vec![];
+    }
+
+    let hidden_line_count = lines.len() - (max_height - 1);
+    lines.truncate(max_height - 1);
+    lines.push(format!("... ({} more lines)", hidden_line_count));
+
+    lines
+}
+
+/// Like `truncate_lines`, except the last `footer_rows` lines are kept, so a
+/// page's command bar stays on screen and the cut comes out of the body.
+pub fn truncate_lines_keeping_footer(
+    mut lines: Vec<String>,
+    max_height: usize,
+    footer_rows: usize,
+) -> Vec<String> {
+    if lines.len() <= max_height {
+        return lines;
+    }
+
+    let footer_rows = footer_rows.min(max_height);
+    let footer = lines.split_off(lines.len() - footer_rows);
+    let mut lines = truncate_lines(lines, max_height - footer_rows);
+    lines.extend(footer);
+
+    lines
+}
+
+/// Reads a line of input in raw mode, showing the candidates whose id starts
+/// with what has been typed so far. Tab completes to the first match and Esc
+/// clears the line. Candidates are `(id, display_name)` pairs.
+///
+/// Raw mode stops the terminal turning Ctrl+C into a signal, so Ctrl+C and
+/// Ctrl+D return an [`io::ErrorKind::Interrupted`] error instead.
+pub fn input_with_suggestions(
+    prompt: &str,
+    candidates: &[(String, String)],
+) -> Result<String, anyhow::Error> {
+    println!("{}", prompt);
+
+    terminal::enable_raw_mode()?;
+    let keys = std::iter::from_fn(|| {
+        loop {
+            match event::read() {
+                Ok(Event::Key(
+                    key @ KeyEvent {
+                        kind: KeyEventKind::Press,
+                        ..
+                    },
+                )) => return Some(Ok(key)),
+                Ok(_) => continue,
+                Err(error) => return Some(Err(error)),
+            }
+        }
+    });
+    let result = read_with_suggestions(keys, &mut io::stdout(), candidates);
+    terminal::disable_raw_mode()?;
+
+    result
+}
+
+/// Whether `error` is the user pressing Ctrl+C or Ctrl+D in
+/// [`input_with_suggestions`].
+pub fn is_interrupted(error: &anyhow::Error) -> bool {
+    error
+        .downcast_ref::<io::Error>()
+        .is_some_and(|error| error.kind() == io::ErrorKind::Interrupted)
+}
+
+fn read_with_suggestions(
+    keys: impl Iterator<Item = io::Result<KeyEvent>>,
+    out: &mut impl Write,
+    candidates: &[(String, String)],
+) -> Result<String, anyhow::Error> {
+    const MAX_SUGGESTIONS: usize = 3;
+
+    let mut input = String::new();
+
+    for key in keys {
+        let key = key?;
+        let is_chord = key
+            .modifiers
+            .intersects(KeyModifiers::CONTROL | KeyModifiers::ALT);
+
+        match key.code {
+            KeyCode::Char('c' | 'd') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                write!(out, "\r\n")?;
+                out.flush()?;
+                return Err(io::Error::new(io::ErrorKind::Interrupted, "input interrupted").into());
+            }
+            KeyCode::Esc => input.clear(),
+            KeyCode::Enter => {
+                write!(out, "\r\n")?;
+                out.flush()?;
+                return Ok(input);
+            }
+            KeyCode::Tab => {
+                if let Some((id, _)) = matching_candidates(&input, candidates).first() {
+                    input = id.clone();
+                }
+            }
+            KeyCode::Backspace => {
+                input.pop();
+            }
+            KeyCode::Char(c) if !is_chord => input.push(c),
+            _ => {}
+        }
+
+        let suggestions: Vec<String> = matching_candidates(&input, candidates)
+            .iter()
+            .take(MAX_SUGGESTIONS)
+            .map(|(id, name)| format!("{}: {}", id, name))
+            .collect();
+
+        queue!(out, Clear(ClearType::CurrentLine), MoveToColumn(0))?;
+        if suggestions.is_empty() {
+            write!(out, "{}", input)?;
+        } else {
+            write!(out, "{}    ({})", input, suggestions.join(", "))?;
+        }
+        out.flush()?;
+    }
+
+    Err(anyhow!("no input left to read from"))
+}
+
+fn matching_candidates<'a>(
+    input: &str,
+    candidates: &'a [(String, String)],
+) -> Vec<&'a (String, String)> {
+    if input.is_empty() {
+        return vec![];
+    }
+
+    candidates
+        .iter()
+        .filter(|(id, _)| id.starts_with(input))
+        .collect()
+}
+
+pub fn get_user_input_multiselect(
+    prompt: &str,
+    valid_ids: &[u32],
+) -> Result<Vec<u32>, anyhow::Error> {
+    read_multiselect(&mut io::stdin().lock(), prompt, valid_ids)
+}
+
+fn read_multiselect(
+    reader: &mut impl BufRead,
+    prompt: &str,
+    valid_ids: &[u32],
+) -> Result<Vec<u32>, anyhow::Error> {
+    loop {
+        println!("{}", prompt);
+
+        let mut user_input = String::new();
+        if reader.read_line(&mut user_input)? == 0 {
+            return Err(anyhow!("no input left to read a selection from"));
+        }
+
+        match parse_multiselect(&user_input, valid_ids) {
+            Ok(ids) => return Ok(ids),
+            Err(invalid_token) => {
+                println!(
+                    "'{}' is not a valid id, please try again (e.g. 1, 2 3)",
+                    invalid_token
+                );
+            }
+        }
+    }
+}
+
+fn parse_multiselect(input: &str, valid_ids: &[u32]) -> Result<Vec<u32>, String> {
+    let mut ids = vec![];
+
+    for token in input
+        .split(|c: char| c == ',' || c.is_whitespace())
+        .filter(|token| !token.is_empty())
+    {
+        match token.parse::<u32>() {
+            Ok(id) if valid_ids.contains(&id) => ids.push(id),
+            _ => return Err(token.to_owned()),
+        }
+    }
+
+    ids.sort();
+    ids.dedup();
+
+    Ok(ids)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use super::*;
+
+    #[test]
+    fn truncate_lines_should_cut_long_output_and_count_the_rest() {
+        let lines: Vec<String> = (1..=100).map(|n| format!("line {}", n)).collect();
+
+        let truncated = truncate_lines(lines, 10);
+
+        assert_eq!(truncated.len(), 10);
+        assert_eq!(truncated[8], "line 9");
+        assert!(truncated[9].contains("91 more lines"));
+    }
+
+    #[test]
+    fn truncate_lines_should_leave_short_output_alone() {
+        let lines: Vec<String> = (1..=10).map(|n| format!("line {}", n)).collect();
+
+        assert_eq!(truncate_lines(lines.clone(), 10), lines);
+        assert_eq!(truncate_lines(vec![], 0), Vec::<String>::new());
+    }
+
+    #[test]
+    fn truncate_lines_keeping_footer_should_cut_the_body_instead() {
+        let lines: Vec<String> = (1..=100).map(|n| format!("line {}", n)).collect();
+
+        let truncated = truncate_lines_keeping_footer(lines.clone(), 10, 2);
+
+        assert_eq!(truncated.len(), 10);
+        assert_eq!(truncated[6], "line 7");
+        assert!(truncated[7].contains("91 more lines"));
+        assert_eq!(truncated[8..], ["line 99", "line 100"]);
+
+        assert_eq!(truncate_lines_keeping_footer(lines.clone(), 100, 2), lines);
+        assert_eq!(
+            truncate_lines_keeping_footer(lines, 2, 5),
+            ["line 99", "line 100"]
+        );
+    }
+
+    #[test]
+    fn spinner_should_start_without_blocking_and_stop_promptly() {
+        let spinner = start_spinner("Working");
+        // Still spinning, so start_spinner did not wait for it
+        assert!(!spinner.handle.is_finished());
+
+        thread::sleep(Duration::from_millis(150));
+        assert!(!spinner.handle.is_finished());
+
+        let stop = Arc::clone(&spinner.stop);
+        let stopping = std::time::Instant::now();
+        spinner.stop();
+        assert!(stop.load(Ordering::Relaxed));
+        assert!(stopping.elapsed() < Duration::from_millis(500));
+    }
+
+    fn epic_candidates() -> Vec<(String, String)> {
+        vec![
+            ("3".to_owned(), "Backlog grooming".to_owned()),
+            ("12".to_owned(), "Login flow".to_owned()),
+            ("15".to_owned(), "Exports".to_owned()),
+        ]
+    }
+
+    fn keys(events: &[KeyEvent]) -> impl Iterator<Item = io::Result<KeyEvent>> + use<> {
+        events.to_vec().into_iter().map(Ok)
+    }
+
+    fn key(code: KeyCode) -> KeyEvent {
+        KeyEvent::new(code, KeyModifiers::NONE)
+    }
+
+    fn ctrl(c: char) -> KeyEvent {
+        KeyEvent::new(KeyCode::Char(c), KeyModifiers::CONTROL)
+    }
+
+    #[test]
+    fn input_with_suggestions_should_complete_on_tab() {
+        let keys = keys(&[
+            key(KeyCode::Char('1')),
+            key(KeyCode::Tab),
+            key(KeyCode::Enter),
+        ]);
+        let mut out = vec![];
+
+        let input = read_with_suggestions(keys, &mut out, &epic_candidates()).unwrap();
+
+        assert_eq!(input, "12");
+        assert!(String::from_utf8(out).unwrap().contains("12: Login flow"));
+    }
+
+    #[test]
+    fn input_with_suggestions_should_keep_input_without_a_match() {
+        let keys = keys(&[
+            key(KeyCode::Char('9')),
+            key(KeyCode::Tab),
+            key(KeyCode::Backspace),
+            key(KeyCode::Char('q')),
+            key(KeyCode::Enter),
+        ]);
+
+        let input = read_with_suggestions(keys, &mut vec![], &epic_candidates()).unwrap();
+
+        assert_eq!(input, "q");
+    }
+
+    #[test]
+    fn input_with_suggestions_should_error_when_input_runs_out() {
+        let keys = keys(&[key(KeyCode::Char('1'))]);
+
+        assert!(read_with_suggestions(keys, &mut vec![], &epic_candidates()).is_err());
+    }
+
+    #[test]
+    fn input_with_suggestions_should_be_interrupted_by_ctrl_c_and_ctrl_d() {
+        for interrupt in ['c', 'd'] {
+            let keys = keys(&[ctrl(interrupt), key(KeyCode::Enter)]);
+
+            let error = read_with_suggestions(keys, &mut vec![], &epic_candidates()).unwrap_err();
+
+            assert!(is_interrupted(&error));
+        }
+    }
+
+    #[test]
+    fn input_with_suggestions_should_ignore_other_chords() {
+        let keys = keys(&[
+            ctrl('x'),
+            KeyEvent::new(KeyCode::Char('c'), KeyModifiers::ALT),
+            KeyEvent::new(KeyCode::Char('Q'), KeyModifiers::SHIFT),
+            key(KeyCode::Enter),
+        ]);
+
+        let input = read_with_suggestions(keys, &mut vec![], &epic_candidates()).unwrap();
+
+        assert_eq!(input, "Q");
+    }
+
+    #[test]
+    fn input_with_suggestions_should_clear_the_line_on_esc() {
+        let keys = keys(&[
+            key(KeyCode::Char('c')),
+            key(KeyCode::Esc),
+            key(KeyCode::Char('1')),
+            key(KeyCode::Enter),
+        ]);
+
+        let input = read_with_suggestions(keys, &mut vec![], &epic_candidates()).unwrap();
+
+        assert_eq!(input, "1");
+    }
+
+    #[test]
+    fn input_with_default_should_keep_the_default_on_enter() {
+        let mut out = vec![];
+
+        let input = read_input_with_default(&mut Cursor::new("\n"), &mut out, "old name").unwrap();
+
+        assert_eq!(input, "old name");
+        assert_eq!(String::from_utf8(out).unwrap(), "(current: old name) > ");
+    }
+
+    #[test]
+    fn input_with_default_should_return_typed_text() {
+        let input =
+            read_input_with_default(&mut Cursor::new("new name\r\n"), &mut vec![], "old name")
+                .unwrap();
+
+        assert_eq!(input, "new name");
+    }
+
+    #[test]
+    fn input_with_default_should_error_when_input_runs_out() {
+        let result = read_input_with_default(&mut Cursor::new(""), &mut vec![], "old name");
+
+        assert_eq!(result.unwrap_err().kind(), io::ErrorKind::UnexpectedEof);
+    }
+
+    #[test]
+    fn password_confirmed_should_show_the_strength_of_the_new_password() {
+        let mut out = vec![];
+        let password = "correct-Horse-battery-9";
+
+        let input = read_password_confirmed_from(
+            &mut Cursor::new(format!("{}\n{}\n", password, password)),
+            &mut out,
+        )
+        .unwrap();
+
+        assert_eq!(input, password);
+        let out = String::from_utf8(out).unwrap();
+        assert!(out.starts_with("Password: Confirm password: "));
+        assert!(out.ends_with(&format!("{}\n", password_strength_feedback(password))));
+    }
+
+    #[test]
+    fn password_confirmed_should_error_when_the_entries_differ() {
+        let mut out = vec![];
+
+        let result = read_password_confirmed_from(
+            &mut Cursor::new("first-Pass-1\nsecond-Pass-2\n"),
+            &mut out,
+        );
+
+        assert_eq!(result.unwrap_err().kind(), io::ErrorKind::InvalidInput);
+        assert!(
+            !String::from_utf8(out)
+                .unwrap()
+                .contains("Password strength")
+        );
+    }
+
+    #[test]
+    fn multiline_input_should_read_until_the_terminator() {
+        let mut input =
+            Cursor::new("first line\nsecond line\r\nthird line\n.\nnot read\n".as_bytes());
+
+        let text = read_multiline(&mut input, ".").unwrap();
+
+        assert_eq!(text, "first line\nsecond line\nthird line");
+    }
+
+    #[test]
+    fn multiline_input_should_end_at_the_end_of_input() {
+        assert_eq!(
+            read_multiline(&mut Cursor::new("only line\n END \n"), "END").unwrap(),
+            "only line"
+        );
+        assert_eq!(
+            read_multiline(&mut Cursor::new("no terminator"), ".").unwrap(),
+            "no terminator"
+        );
+        assert_eq!(
+            read_multiline(&mut Cursor::new(""), ".")
+                .unwrap_err()
+                .kind(),
+            io::ErrorKind::UnexpectedEof
+        );
+    }
+
+    #[test]
+    fn multiselect_should_parse_valid_ids() {
+        let mut input = Cursor::new("3, 1 2\n");
+        let ids = read_multiselect(&mut input, "", &[1, 2, 3, 4]).unwrap();
+        assert_eq!(ids, vec![1, 2, 3]);
+    }
+
+    #[test]
+    fn multiselect_should_reprompt_on_partially_invalid_input() {
+        let mut input = Cursor::new("1, 999\n1, 4\n");
+        let ids = read_multiselect(&mut input, "", &[1, 2, 3, 4]).unwrap();
+        assert_eq!(ids, vec![1, 4]);
+    }
+
+    #[test]
+    fn multiselect_should_reprompt_until_input_is_valid() {
+        let mut input = Cursor::new("abc\n999 j983f2j\n2\n");
+        let ids = read_multiselect(&mut input, "", &[1, 2, 3, 4]).unwrap();
+        assert_eq!(ids, vec![2]);
+    }
+
+    #[test]
+    fn multiselect_should_error_when_input_runs_out() {
+        let mut input = Cursor::new("abc\n");
+        assert!(read_multiselect(&mut input, "", &[1, 2, 3, 4]).is_err());
+    }
+
+    #[test]
+    fn multiselect_should_return_empty_vec_for_empty_input() {
+        let mut input = Cursor::new("\n");
+        let ids = read_multiselect(&mut input, "", &[1, 2, 3, 4]).unwrap();
+        assert!(ids.is_empty());
+    }
+
+    #[test]
+    fn multiselect_should_deduplicate_ids() {
+        let mut input = Cursor::new("2,2, 1 2\n");
+        let ids = read_multiselect(&mut input, "", &[1, 2, 3, 4]).unwrap();
+        assert_eq!(ids, vec![1, 2]);
+    }
+
+    #[test]
+    fn parse_multiselect_should_name_the_invalid_token() {
+        assert_eq!(
+            parse_multiselect("1, x2, 3", &[1, 2, 3]),
+            Err("x2".to_owned())
+        );
+        assert_eq!(parse_multiselect("1, 7", &[1, 2, 3]), Err("7".to_owned()));
+    }
+}
@@ -1,13 +1,23 @@
+pub mod audit;
+
 pub mod auth;
 
+pub mod cli;
+
 pub mod constants;
 
 mod models;
 
 pub mod db;
 
+pub mod export;
+
 pub mod ui;
 
+pub mod watch_list;
+
 pub mod io_utils;
 
+pub mod logging;
+
 pub mod navigator;
@@ -0,0 +1,253 @@
+//! Structured diagnostics for the running application.
+//!
+//! Each record is written as one JSON object per line, to stderr unless
+//! another output is installed with `set_logger`. Records below the
+//! configured level are dropped before their fields are built. The level
+//! defaults to `warn` and can be changed with the `IRONYY_LOG` environment
+//! variable.
+
+use std::{
+    io::{self, Write},
+    str::FromStr,
+    sync::{LazyLock, Mutex, MutexGuard},
+};
+
+use anyhow::Result;
+use chrono::Utc;
+use serde::Serialize;
+use serde_json::{Map, Value};
+
+const LEVEL_ENV_VAR: &str = "IRONYY_LOG";
+
+#[derive(Serialize, PartialEq, Eq, PartialOrd, Ord, Debug, Clone, Copy, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum LogLevel {
+    Debug,
+    Info,
+    #[default]
+    Warn,
+    Error,
+}
+
+impl FromStr for LogLevel {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.trim().to_lowercase().as_str() {
+            "debug" => Ok(Self::Debug),
+            "info" => Ok(Self::Info),
+            "warn" | "warning" => Ok(Self::Warn),
+            "error" => Ok(Self::Error),
+            _ => Err(anyhow::anyhow!(
+                "unknown log level '{}' (expected debug, info, warn or error)",
+                s.trim()
+            )),
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct LogRecord<'a> {
+    ts: String,
+    level: LogLevel,
+    module: &'a str,
+    message: &'a str,
+    #[serde(skip_serializing_if = "Map::is_empty")]
+    fields: Map<String, Value>,
+}
+
+pub struct Logger {
+    level: LogLevel,
+    output: Box<dyn Write + Send>,
+}
+
+impl Logger {
+    pub fn new(level: LogLevel, output: Box<dyn Write + Send>) -> Self {
+        Self { level, output }
+    }
+
+    /// Logs to stderr at the level named by `IRONYY_LOG`, or `warn` when it
+    /// is unset or unrecognised.
+    pub fn from_env() -> Self {
+        let level = std::env::var(LEVEL_ENV_VAR)
+            .ok()
+            .and_then(|level| level.parse().ok())
+            .unwrap_or_default();
+        Self::new(level, Box::new(io::stderr()))
+    }
+
+    pub fn enabled(&self, level: LogLevel) -> bool {
+        level >= self.level
+    }
+
+    pub fn log(
+        &mut self,
+        level: LogLevel,
+        module: &str,
+        message: &str,
+        fields: Vec<(&str, Value)>,
+    ) -> Result<()> {
+        if !self.enabled(level) {
+            return Ok(());
+        }
+
+        let record = LogRecord {
+            ts: Utc::now().to_rfc3339(),
+            level,
+            module,
+            message,
+            fields: fields
+                .into_iter()
+                .map(|(key, value)| (key.to_owned(), value))
+                .collect(),
+        };
+
+        writeln!(self.output, "{}", serde_json::to_string(&record)?)?;
+        self.output.flush()?;
+
+        Ok(())
+    }
+}
+
+static LOGGER: LazyLock<Mutex<Logger>> = LazyLock::new(|| Mutex::new(Logger::from_env()));
+
+// A panic while logging must not silence every later record
+fn logger() -> MutexGuard<'static, Logger> {
+    LOGGER
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+/// Replaces the application-wide logger.
+pub fn set_logger(logger_to_use: Logger) {
+    *logger() = logger_to_use;
+}
+
+pub fn enabled(level: LogLevel) -> bool {
+    logger().enabled(level)
+}
+
+/// Used by `log!`. There is nowhere left to report a failed write, so it
+/// is dropped.
+#[doc(hidden)]
+pub fn log(level: LogLevel, module: &str, message: &str, fields: Vec<(&str, Value)>) {
+    let _ = logger().log(level, module, message, fields);
+}
+
+#[doc(hidden)]
+pub fn field(value: &impl Serialize) -> Value {
+    serde_json::to_value(value).unwrap_or(Value::Null)
+}
+
+/// Logs `message` with optional `key = value` fields, tagged with the
+/// calling module:
+///
+/// `log!(LogLevel::Info, "created epic", id = epic_id);`
+#[macro_export]
+macro_rules! log {
+    ($level:expr, $message:expr $(, $key:ident = $value:expr)* $(,)?) => {
+        if $crate::logging::enabled($level) {
+            $crate::logging::log(
+                $level,
+                module_path!(),
+                &$message,
+                vec![$((stringify!($key), $crate::logging::field(&$value))),*],
+            );
+        }
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::*;
+
+    #[derive(Clone, Default)]
+    struct SharedBuffer(Arc<Mutex<Vec<u8>>>);
+
+    impl Write for SharedBuffer {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.0.lock().unwrap().write(buf)
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    impl SharedBuffer {
+        fn lines(&self) -> Vec<Value> {
+            String::from_utf8(self.0.lock().unwrap().clone())
+                .unwrap()
+                .lines()
+                .map(|line| serde_json::from_str(line).unwrap())
+                .collect()
+        }
+    }
+
+    #[test]
+    fn log_should_write_one_json_object_per_record() {
+        let buffer = SharedBuffer::default();
+        let mut logger = Logger::new(LogLevel::Debug, Box::new(buffer.clone()));
+
+        logger
+            .log(
+                LogLevel::Info,
+                "ironyy::db",
+                "database changed",
+                vec![("item_id", field(&3)), ("user", field(&"alice"))],
+            )
+            .unwrap();
+        logger
+            .log(LogLevel::Error, "ironyy::navigator", "no fields", vec![])
+            .unwrap();
+
+        let records = buffer.lines();
+        assert_eq!(records.len(), 2);
+
+        let record = records[0].as_object().unwrap();
+        let mut keys: Vec<&str> = record.keys().map(String::as_str).collect();
+        keys.sort();
+        assert_eq!(keys, ["fields", "level", "message", "module", "ts"]);
+        assert!(
+            record["ts"]
+                .as_str()
+                .unwrap()
+                .parse::<chrono::DateTime<Utc>>()
+                .is_ok()
+        );
+        assert_eq!(record["level"], "info");
+        assert_eq!(record["module"], "ironyy::db");
+        assert_eq!(record["message"], "database changed");
+        assert_eq!(record["fields"]["item_id"], 3);
+        assert_eq!(record["fields"]["user"], "alice");
+
+        assert_eq!(records[1]["level"], "error");
+        assert!(records[1].get("fields").is_none());
+    }
+
+    #[test]
+    fn log_should_drop_records_below_the_level() {
+        let buffer = SharedBuffer::default();
+        let mut logger = Logger::new(LogLevel::Warn, Box::new(buffer.clone()));
+
+        logger
+            .log(LogLevel::Debug, "ironyy::db", "ignored", vec![])
+            .unwrap();
+        logger
+            .log(LogLevel::Info, "ironyy::db", "ignored", vec![])
+            .unwrap();
+
+        assert!(buffer.0.lock().unwrap().is_empty());
+        assert!(logger.enabled(LogLevel::Error));
+    }
+
+    #[test]
+    fn log_level_should_parse_names_case_insensitively() {
+        assert_eq!("DEBUG".parse::<LogLevel>().unwrap(), LogLevel::Debug);
+        assert_eq!(" warning ".parse::<LogLevel>().unwrap(), LogLevel::Warn);
+        assert!("verbose".parse::<LogLevel>().is_err());
+        assert!(LogLevel::Debug < LogLevel::Error);
+    }
+}
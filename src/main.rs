@@ -1,26 +1,113 @@
-use std::rc::Rc;
+use std::{io, process, rc::Rc};
 
-use ironyy::{auth, constants, db, io_utils, navigator};
+use clap::Parser;
+use ironyy::{auth, cli, constants, db, io_utils, navigator, ui, watch_list::WatchList};
 
 fn main() {
-    let db = Rc::new(db::JiraDatabase::new("./data/db.json".to_owned()));
-    let mut navigator = navigator::Navigator::new(Rc::clone(&db));
+    let args = cli::Cli::parse();
+    let db = Rc::new(db::JiraDatabase::new(args.db));
+
+    if let Some(user) = &args.user {
+        db.set_audit_user(user);
+    }
+
+    if let Some(command) = args.command {
+        if let Err(error) = cli::run(command, args.format, &db, io::stdout().lock()) {
+            eprintln!("Error: {:#}", error);
+            process::exit(1);
+        }
+        return;
+    }
+
+    if let Err(error) = db.health_check() {
+        println!(
+            "Error checking database health: {}\nPress any key to continue...",
+            error
+        );
+        io_utils::wait_for_key_press();
+    }
+
+    let watch_list = match WatchList::default_path() {
+        Some(path) => WatchList::load_from_file(path).unwrap_or_else(|error| {
+            println!(
+                "Error loading watch list: {}\nPress any key to continue...",
+                error
+            );
+            io_utils::wait_for_key_press();
+            WatchList::in_memory()
+        }),
+        None => WatchList::in_memory(),
+    };
+
+    let mut navigator = navigator::Navigator::new(Rc::clone(&db)).with_watch_list(watch_list);
 
     loop {
         clearscreen::clear().unwrap();
 
+        if let Some(message) = navigator.last_message() {
+            println!("{}", message);
+            println!();
+        }
+
         if let Some(page) = navigator.get_current_page() {
-            if let Err(error) = page.draw_page() {
-                println!(
-                    "Error rendering page: {}\nPress any key to continue...",
-                    error
-                );
-                io_utils::wait_for_key_press();
+            // Rebuilt every frame so a resized terminal is picked up
+            let draw_context = ui::DrawContext {
+                can_go_back: navigator.can_go_back(),
+                ..ui::DrawContext::for_terminal()
+            };
+            match page.draw_page(&draw_context) {
+                Ok(lines) => {
+                    println!("{}", navigator.breadcrumb());
+
+                    // Leave room for the breadcrumb, the global shortcuts line,
+                    // the input line and the status message.
+                    let message_height = if navigator.last_message().is_some() {
+                        2
+                    } else {
+                        0
+                    };
+                    let max_height = io_utils::terminal_height().saturating_sub(3 + message_height);
+                    let command_bar_rows = draw_context.command_bar_rows.get();
+                    for line in
+                        io_utils::truncate_lines_keeping_footer(lines, max_height, command_bar_rows)
+                    {
+                        println!("{}", line);
+                    }
+                }
+                Err(error) => {
+                    println!(
+                        "Error rendering page: {}\nPress any key to continue...",
+                        error
+                    );
+                    io_utils::wait_for_key_press();
+                }
+            }
+
+            let global_shortcuts: Vec<String> = page
+                .global_shortcuts()
+                .iter()
+                .map(|(key, description)| format!("[{}] {}", key, description))
+                .collect();
+            println!("{}", global_shortcuts.join(" | "));
+
+            let user_input = match page.input_suggestions() {
+                Ok(candidates) if !candidates.is_empty() => {
+                    match io_utils::input_with_suggestions("", &candidates) {
+                        Ok(user_input) => user_input,
+                        Err(error) if io_utils::is_interrupted(&error) => break,
+                        Err(_) => io_utils::get_user_input(),
+                    }
+                }
+                _ => io_utils::get_user_input(),
             };
+            let user_input = user_input.trim();
 
-            let user_input = io_utils::get_user_input();
+            let action = match ui::handle_global_input(user_input) {
+                Some(action) => Ok(Some(action)),
+                None => page.handle_input(user_input),
+            };
 
-            match page.handle_input(user_input.trim()) {
+            match action {
                 Err(error) => {
                     println!(
                         "Error getting user input: {}\nPress any key to continue...",
@@ -44,4 +131,12 @@ fn main() {
             break;
         }
     }
+
+    let session_seconds = navigator.session_duration().as_secs();
+    println!(
+        "Session lasted {}m {}s. Goodbye.",
+        session_seconds / 60,
+        session_seconds % 60
+    );
+    println!("{} actions performed", navigator.actions_this_session());
 }
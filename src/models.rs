@@ -1,28 +1,266 @@
+use anyhow::{Result, anyhow};
+use chrono::{DateTime, NaiveDate, NaiveDateTime, Utc};
 use serde::{Deserialize, Serialize};
-use std::{collections::HashMap, fmt::Display};
+use std::{collections::HashMap, fmt::Display, path::PathBuf, str::FromStr};
 
-#[derive(Debug, PartialEq, Eq)]
+use crate::constants::UNKNOWN_USER;
+
+mod sprint;
+pub use sprint::Sprint;
+
+#[derive(Debug, PartialEq, Eq, Clone, Hash)]
 pub enum Action {
     Register,
     Login { username: String },
     NavigateToEpicDetail { epic_id: u32 },
     NavigateToStoryDetail { epic_id: u32, story_id: u32 },
     NavigateToPreviousPage,
+    NavigateToHomePage,
+    NavigateToProjectSelect,
+    NavigateToSprintList,
+    NavigateToBacklog,
+    NavigateToSprintDetail { sprint_id: u32 },
+    // Prompts for a query when `query` is empty
+    Search { query: String },
+    // Prompts for the sort key when `key` is None
+    SortHomePage { key: Option<SortKey> },
+    ReverseHomePageSort,
+    // Prompts for the sort key when `key` is None
+    SortEpicStories { key: Option<StorySortKey> },
+    ReverseEpicStoriesSort,
+    // Prompts for a tag when `tag` is None
+    FilterHomePageByTag { tag: Option<String> },
+    ClearHomePageTagFilter,
+    // Prompts for a reporter when `reporter` is None
+    NavigateToReporterStories { reporter: Option<String> },
+    NavigateToReleases,
+    NavigateToReleaseDetail { release: String },
+    NavigateToWatchList,
+    NavigateToDashboard,
+    NavigateToImport,
+    NextPage,
+    PreviousListPage,
+    ExportCsv,
+    ExportMarkdown,
+    ImportCsv { path: String },
+    CreateProject,
+    SwitchProject { name: String },
+    CreateSprint,
+    DeleteSprint { sprint_id: u32 },
+    // Prompts for the story when `story_id` is None
+    AssignStoryToSprint { sprint_id: u32, story_id: Option<u32> },
+    RemoveStoryFromSprint { sprint_id: u32 },
     CreateEpic,
     UpdateEpicStatus { epic_id: u32 },
+    // Prompts for a date when `date` is None
+    UpdateEpicDueDate { epic_id: u32, date: Option<NaiveDate> },
+    // Prompts for the new text when `name` or `description` is None
+    UpdateEpicName { epic_id: u32, name: Option<String> },
+    UpdateEpicDescription { epic_id: u32, description: Option<String> },
+    // Prompts for the release when `release` is None
+    UpdateEpicRelease { epic_id: u32, release: Option<String> },
     DeleteEpic { epic_id: u32 },
     CreateStory { epic_id: u32 },
     UpdateStoryStatus { story_id: u32 },
+    UpdateStoryEstimate { story_id: u32 },
+    // Prompts for a type when `story_type` is None
+    UpdateStoryType { story_id: u32, story_type: Option<StoryType> },
+    // Prompts for the release when `release` is None
+    UpdateStoryRelease { story_id: u32, release: Option<String> },
+    CloneStory { story_id: u32 },
+    ToggleWatch { story_id: u32 },
+    // Prompts for the epic when `target_epic_id` is None
+    MoveStory { story_id: u32, target_epic_id: Option<u32> },
+    NavigateToComments { story_id: u32 },
+    AddComment { story_id: u32 },
+    DeleteComment { story_id: u32 },
+    LinkStoryDependency { story_id: u32 },
+    UnlinkStoryDependency { story_id: u32 },
+    // Prompts for a date when `date` is None
+    UpdateStoryDueDate { story_id: u32, date: Option<NaiveDate> },
+    // Prompts for the new text when `name` or `description` is None
+    UpdateStoryName { story_id: u32, name: Option<String> },
+    UpdateStoryDescription { story_id: u32, description: Option<String> },
     DeleteStory { epic_id: u32, story_id: u32 },
+    // Asks for confirmation once for all of the stories
+    BatchDeleteStories { story_ids: Vec<u32> },
+    // Prompts for a destination when `dest` is None
+    ExportCurrentPage { dest: Option<PathBuf> },
+    ShowHelp { context: PageType },
+    // Rereads the database file so changes made outside this session show up
+    Refresh,
     Exit,
 }
 
+/// What the home page orders epics by.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default, Hash)]
+pub enum SortKey {
+    #[default]
+    Id,
+    Name,
+    Status,
+    Priority,
+    DueDate,
+    CompletionPct,
+}
+
+impl Display for SortKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Id => write!(f, "id"),
+            Self::Name => write!(f, "name"),
+            Self::Status => write!(f, "status"),
+            Self::Priority => write!(f, "priority"),
+            Self::DueDate => write!(f, "due date"),
+            Self::CompletionPct => write!(f, "progress"),
+        }
+    }
+}
+
+/// What the epic detail page orders its stories by.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default, Hash)]
+pub enum StorySortKey {
+    Id,
+    Name,
+    // Unfinished work first, as the page always listed it
+    #[default]
+    Status,
+    Estimate,
+    StoryType,
+}
+
+impl Display for StorySortKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Id => write!(f, "id"),
+            Self::Name => write!(f, "name"),
+            Self::Status => write!(f, "status"),
+            Self::Estimate => write!(f, "estimate"),
+            Self::StoryType => write!(f, "type"),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default, Hash)]
+pub enum SortDir {
+    #[default]
+    Ascending,
+    Descending,
+}
+
+impl SortDir {
+    pub fn reversed(self) -> Self {
+        match self {
+            Self::Ascending => Self::Descending,
+            Self::Descending => Self::Ascending,
+        }
+    }
+
+    /// Flips `ordering` when descending. Equal items stay equal, so a stable
+    /// sort keeps them in their original order either way.
+    pub fn apply(self, ordering: std::cmp::Ordering) -> std::cmp::Ordering {
+        match self {
+            Self::Ascending => ordering,
+            Self::Descending => ordering.reverse(),
+        }
+    }
+}
+
+impl Display for SortDir {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Ascending => write!(f, "ascending"),
+            Self::Descending => write!(f, "descending"),
+        }
+    }
+}
+
+// Which page a help screen describes
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub enum PageType {
+    Home,
+    ProjectSelect,
+    EpicDetail,
+    StoryDetail,
+    Comments,
+    SprintList,
+    SprintDetail,
+    Backlog,
+    Search,
+    Reporter,
+    Releases,
+    ReleaseDetail,
+    WatchList,
+    Dashboard,
+}
+
+impl Display for PageType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Home => write!(f, "home"),
+            Self::ProjectSelect => write!(f, "projects"),
+            Self::EpicDetail => write!(f, "epic"),
+            Self::StoryDetail => write!(f, "story"),
+            Self::Comments => write!(f, "comments"),
+            Self::SprintList => write!(f, "sprints"),
+            Self::SprintDetail => write!(f, "sprint"),
+            Self::Backlog => write!(f, "backlog"),
+            Self::Search => write!(f, "search"),
+            Self::Reporter => write!(f, "reporter"),
+            Self::Releases => write!(f, "releases"),
+            Self::ReleaseDetail => write!(f, "release"),
+            Self::WatchList => write!(f, "watch list"),
+            Self::Dashboard => write!(f, "dashboard"),
+        }
+    }
+}
+
 #[derive(Serialize, Deserialize, PartialEq, Eq, Debug, Clone)]
 pub enum Status {
     Open,
     InProgress,
     Resolved,
     Closed,
+    Blocked,
+}
+
+impl Status {
+    // Blocked work has been started but can't move on, so it sorts between
+    // InProgress and Resolved
+    fn workflow_position(&self) -> u8 {
+        match self {
+            Self::Open => 0,
+            Self::InProgress => 1,
+            Self::Blocked => 2,
+            Self::Resolved => 3,
+            Self::Closed => 4,
+        }
+    }
+
+    /// The next step in the Open -> InProgress -> Resolved -> Closed cycle.
+    /// Blocked is never reached this way; a blocked item moves back to
+    /// InProgress.
+    pub fn next_status(&self) -> Status {
+        match self {
+            Self::Open => Self::InProgress,
+            Self::InProgress => Self::Resolved,
+            Self::Resolved => Self::Closed,
+            Self::Closed => Self::Open,
+            Self::Blocked => Self::InProgress,
+        }
+    }
+}
+
+impl PartialOrd for Status {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Status {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.workflow_position().cmp(&other.workflow_position())
+    }
 }
 
 impl Display for Status {
@@ -40,6 +278,168 @@ impl Display for Status {
             Self::Closed => {
                 write!(f, "CLOSED")
             }
+            Self::Blocked => {
+                write!(f, "BLOCKED")
+            }
+        }
+    }
+}
+
+// Declared most urgent first, so sorting by priority puts Critical on top
+#[derive(Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, Debug, Clone, Copy, Default)]
+pub enum Priority {
+    Critical,
+    High,
+    #[default]
+    Medium,
+    Low,
+    Backlog,
+}
+
+impl Display for Priority {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Critical => write!(f, "CRITICAL"),
+            Self::High => write!(f, "HIGH"),
+            Self::Medium => write!(f, "MEDIUM"),
+            Self::Low => write!(f, "LOW"),
+            Self::Backlog => write!(f, "BACKLOG"),
+        }
+    }
+}
+
+// Declared in the order the epic detail page sorts by type
+#[derive(
+    Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, Debug, Clone, Copy, Default, Hash,
+)]
+pub enum StoryType {
+    Bug,
+    #[default]
+    Feature,
+    Task,
+    Chore,
+}
+
+impl Display for StoryType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Bug => write!(f, "BUG"),
+            Self::Feature => write!(f, "FEATURE"),
+            Self::Task => write!(f, "TASK"),
+            Self::Chore => write!(f, "CHORE"),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum StoryTypeParseError {
+    Empty,
+    Unknown(String),
+}
+
+impl Display for StoryTypeParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Empty => write!(f, "story type cannot be empty"),
+            Self::Unknown(input) => write!(
+                f,
+                "unknown story type '{}' (expected bug, feature, task or chore)",
+                input
+            ),
+        }
+    }
+}
+
+impl std::error::Error for StoryTypeParseError {}
+
+impl FromStr for StoryType {
+    type Err = StoryTypeParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "" => Err(StoryTypeParseError::Empty),
+            "bug" => Ok(Self::Bug),
+            "feature" => Ok(Self::Feature),
+            "task" => Ok(Self::Task),
+            "chore" => Ok(Self::Chore),
+            _ => Err(StoryTypeParseError::Unknown(s.trim().to_owned())),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum PriorityParseError {
+    Empty,
+    Unknown(String),
+}
+
+impl Display for PriorityParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Empty => write!(f, "priority cannot be empty"),
+            Self::Unknown(input) => write!(
+                f,
+                "unknown priority '{}' (expected critical, high, medium, low or backlog)",
+                input
+            ),
+        }
+    }
+}
+
+impl std::error::Error for PriorityParseError {}
+
+impl FromStr for Priority {
+    type Err = PriorityParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "" => Err(PriorityParseError::Empty),
+            "critical" => Ok(Self::Critical),
+            "high" => Ok(Self::High),
+            "medium" => Ok(Self::Medium),
+            "low" => Ok(Self::Low),
+            "backlog" => Ok(Self::Backlog),
+            _ => Err(PriorityParseError::Unknown(s.trim().to_owned())),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum StatusParseError {
+    Empty,
+    Unknown(String),
+}
+
+impl Display for StatusParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Empty => write!(f, "status cannot be empty"),
+            Self::Unknown(input) => write!(
+                f,
+                "unknown status '{}' (expected open, in-progress, resolved, closed or blocked)",
+                input
+            ),
+        }
+    }
+}
+
+impl std::error::Error for StatusParseError {}
+
+impl FromStr for Status {
+    type Err = StatusParseError;
+
+    // Accepts the Display form too, so "IN PROGRESS" parses back
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let normalized = s.trim().to_lowercase().replace([' ', '_'], "-");
+
+        match normalized.as_str() {
+            "" => Err(StatusParseError::Empty),
+            "open" => Ok(Self::Open),
+            "in-progress" | "inprogress" => Ok(Self::InProgress),
+            "resolved" => Ok(Self::Resolved),
+            "closed" => Ok(Self::Closed),
+            "blocked" => Ok(Self::Blocked),
+            _ => Err(StatusParseError::Unknown(s.trim().to_owned())),
         }
     }
 }
@@ -49,18 +449,76 @@ pub struct Epic {
     pub name: String,
     pub description: String,
     pub status: Status,
+    #[serde(default)]
+    pub priority: Priority,
+    #[serde(default)]
+    pub due_date: Option<NaiveDate>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    #[serde(default)]
+    pub release: Option<String>,
+    #[serde(default = "default_reporter")]
+    pub reporter: String,
     pub stories: Vec<u32>,
+    // Items saved before timestamps were tracked get the time they were loaded
+    #[serde(default = "Utc::now")]
+    pub created_at: DateTime<Utc>,
+    #[serde(default = "Utc::now")]
+    pub updated_at: DateTime<Utc>,
 }
 
 impl Epic {
     pub fn new(name: String, description: String) -> Self {
+        let now = Utc::now();
         Self {
             name,
             description,
             status: Status::Open,
+            priority: Priority::default(),
+            due_date: None,
+            tags: vec![],
+            release: None,
+            reporter: default_reporter(),
             stories: vec![],
+            created_at: now,
+            updated_at: now,
         }
     }
+
+    /// Marks the epic as modified just now.
+    pub fn touch(&mut self) {
+        self.updated_at = Utc::now();
+    }
+
+    pub fn has_tag(&self, tag: &str) -> bool {
+        has_tag(&self.tags, tag)
+    }
+
+    pub fn is_overdue(&self, today: NaiveDate) -> bool {
+        match self.due_date {
+            Some(due_date) => {
+                due_date < today && !matches!(self.status, Status::Resolved | Status::Closed)
+            }
+            None => false,
+        }
+    }
+
+    /// Percentage of this epic's stories that are resolved or closed.
+    /// Stories missing from `stories` still count towards the total.
+    pub fn completion_percentage(&self, stories: &HashMap<u32, Story>) -> f64 {
+        if self.stories.is_empty() {
+            return 0.0;
+        }
+
+        let done = self
+            .stories
+            .iter()
+            .filter_map(|id| stories.get(id))
+            .filter(|story| matches!(story.status, Status::Resolved | Status::Closed))
+            .count();
+
+        done as f64 * 100.0 / self.stories.len() as f64
+    }
 }
 
 #[derive(Serialize, Deserialize, PartialEq, Eq, Debug, Clone)]
@@ -68,21 +526,366 @@ pub struct Story {
     pub name: String,
     pub description: String,
     pub status: Status,
+    #[serde(default)]
+    pub story_type: StoryType,
+    #[serde(default)]
+    pub priority: Priority,
+    #[serde(default)]
+    pub due_date: Option<NaiveDate>,
+    #[serde(default)]
+    pub estimate: Option<u8>,
+    #[serde(default)]
+    pub blocks: Vec<u32>,
+    #[serde(default)]
+    pub blocked_by: Vec<u32>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    #[serde(default)]
+    pub release: Option<String>,
+    #[serde(default = "default_reporter")]
+    pub reporter: String,
+    #[serde(default)]
+    pub comments: Vec<Comment>,
+    // The last comment id handed out, so a deleted comment's id is not reused
+    #[serde(default)]
+    pub last_comment_id: u32,
+    #[serde(default = "Utc::now")]
+    pub created_at: DateTime<Utc>,
+    #[serde(default = "Utc::now")]
+    pub updated_at: DateTime<Utc>,
 }
 
 impl Story {
     pub fn new(name: String, description: String) -> Self {
+        let now = Utc::now();
         Self {
             name,
             description,
             status: Status::Open,
+            story_type: StoryType::default(),
+            priority: Priority::default(),
+            due_date: None,
+            estimate: None,
+            blocks: vec![],
+            blocked_by: vec![],
+            tags: vec![],
+            release: None,
+            reporter: default_reporter(),
+            comments: vec![],
+            last_comment_id: 0,
+            created_at: now,
+            updated_at: now,
         }
     }
+
+    /// Marks the story as modified just now.
+    pub fn touch(&mut self) {
+        self.updated_at = Utc::now();
+    }
+
+    pub fn has_tag(&self, tag: &str) -> bool {
+        has_tag(&self.tags, tag)
+    }
+
+    pub fn is_overdue(&self, today: NaiveDate) -> bool {
+        match self.due_date {
+            Some(due_date) => {
+                due_date < today && !matches!(self.status, Status::Resolved | Status::Closed)
+            }
+            None => false,
+        }
+    }
+
+    pub fn word_count_description(&self) -> usize {
+        word_count(&self.description)
+    }
+
+    pub fn reopen(&mut self, epic: &Epic) -> Result<()> {
+        if !matches!(self.status, Status::Resolved | Status::Closed) {
+            return Err(anyhow!(
+                "only resolved or closed stories can be reopened (story is {})",
+                self.status
+            ));
+        }
+        if epic.status == Status::Closed {
+            return Err(anyhow!("cannot reopen a story in a closed epic"));
+        }
+
+        self.status = Status::Open;
+        Ok(())
+    }
 }
 
+// Comment ids are only unique within their story
+#[derive(Serialize, Deserialize, PartialEq, Eq, Debug, Clone)]
+pub struct Comment {
+    pub id: u32,
+    pub author: String,
+    pub text: String,
+    pub created_at: NaiveDateTime,
+}
+
+pub fn word_count(s: &str) -> usize {
+    s.split_whitespace().count()
+}
+
+// Tags are compared ignoring case and surrounding whitespace
+fn has_tag(tags: &[String], tag: &str) -> bool {
+    let tag = tag.trim().to_lowercase();
+    tags.iter().any(|t| t.trim().to_lowercase() == tag)
+}
+
+pub const DEFAULT_PROJECT_NAME: &str = "default";
+
+fn default_project_name() -> String {
+    DEFAULT_PROJECT_NAME.to_owned()
+}
+
+fn default_reporter() -> String {
+    UNKNOWN_USER.to_owned()
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Eq, Debug, Clone, Default)]
+pub struct ProjectState {
+    pub epics: HashMap<u32, Epic>,
+    pub stories: HashMap<u32, Story>,
+    #[serde(default)]
+    pub sprints: HashMap<u32, Sprint>,
+}
+
+// `epics`, `stories` and `sprints` always hold the active project. Switching projects
+// parks them in `projects` and loads the other project in their place, so
+// ids stay unique across every project and databases written before
+// projects existed load as the default project.
 #[derive(Serialize, Deserialize, PartialEq, Eq, Debug, Clone)]
 pub struct DBState {
     pub last_item_id: u32,
     pub epics: HashMap<u32, Epic>,
     pub stories: HashMap<u32, Story>,
+    #[serde(default)]
+    pub sprints: HashMap<u32, Sprint>,
+    #[serde(default = "default_project_name")]
+    pub active_project: String,
+    #[serde(default)]
+    pub projects: HashMap<String, ProjectState>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn status_should_order_by_workflow_position() {
+        assert!(Status::Open < Status::InProgress);
+        assert!(Status::InProgress < Status::Resolved);
+        assert!(Status::Resolved < Status::Closed);
+        assert_eq!(
+            Status::Closed.cmp(&Status::Closed),
+            std::cmp::Ordering::Equal
+        );
+    }
+
+    #[test]
+    fn blocked_should_display_and_sort_between_in_progress_and_resolved() {
+        assert_eq!(Status::Blocked.to_string(), "BLOCKED");
+        assert!(Status::InProgress < Status::Blocked);
+        assert!(Status::Blocked < Status::Resolved);
+    }
+
+    #[test]
+    fn next_status_should_cycle_through_the_workflow_and_skip_blocked() {
+        assert_eq!(Status::Open.next_status(), Status::InProgress);
+        assert_eq!(Status::InProgress.next_status(), Status::Resolved);
+        assert_eq!(Status::Resolved.next_status(), Status::Closed);
+        assert_eq!(Status::Closed.next_status(), Status::Open);
+        assert_eq!(Status::Blocked.next_status(), Status::InProgress);
+    }
+
+    #[test]
+    fn status_should_parse_case_insensitively() {
+        assert_eq!("open".parse(), Ok(Status::Open));
+        assert_eq!("In-Progress".parse(), Ok(Status::InProgress));
+        assert_eq!("INPROGRESS".parse(), Ok(Status::InProgress));
+        assert_eq!(" resolved ".parse(), Ok(Status::Resolved));
+        assert_eq!("Closed".parse(), Ok(Status::Closed));
+        assert_eq!("blocked".parse(), Ok(Status::Blocked));
+        assert_eq!("RESOLVED".parse(), Ok(Status::Resolved));
+    }
+
+    #[test]
+    fn status_should_reject_unknown_input() {
+        assert_eq!("".parse::<Status>(), Err(StatusParseError::Empty));
+        assert_eq!(
+            "done".parse::<Status>(),
+            Err(StatusParseError::Unknown("done".to_owned()))
+        );
+    }
+
+    #[test]
+    fn status_should_round_trip_through_display() {
+        for status in [
+            Status::Open,
+            Status::InProgress,
+            Status::Resolved,
+            Status::Closed,
+            Status::Blocked,
+        ] {
+            assert_eq!(status.to_string().parse::<Status>(), Ok(status));
+        }
+    }
+
+    #[test]
+    fn priority_should_round_trip_through_display() {
+        for priority in [
+            Priority::Critical,
+            Priority::High,
+            Priority::Medium,
+            Priority::Low,
+            Priority::Backlog,
+        ] {
+            assert_eq!(priority.to_string().parse::<Priority>(), Ok(priority));
+        }
+        assert_eq!(" low ".parse(), Ok(Priority::Low));
+        assert_eq!("".parse::<Priority>(), Err(PriorityParseError::Empty));
+        assert_eq!(
+            "urgent".parse::<Priority>(),
+            Err(PriorityParseError::Unknown("urgent".to_owned()))
+        );
+    }
+
+    #[test]
+    fn story_type_should_round_trip_through_display() {
+        for story_type in [
+            StoryType::Bug,
+            StoryType::Feature,
+            StoryType::Task,
+            StoryType::Chore,
+        ] {
+            assert_eq!(story_type.to_string().parse::<StoryType>(), Ok(story_type));
+        }
+        assert_eq!(" Bug ".parse(), Ok(StoryType::Bug));
+        assert_eq!("".parse::<StoryType>(), Err(StoryTypeParseError::Empty));
+        assert_eq!(
+            "epic".parse::<StoryType>(),
+            Err(StoryTypeParseError::Unknown("epic".to_owned()))
+        );
+        assert_eq!(
+            Story::new("story".to_owned(), "".to_owned()).story_type,
+            StoryType::Feature
+        );
+    }
+
+    #[test]
+    fn sorting_stories_by_status_should_follow_the_workflow() {
+        let mut stories: Vec<Story> = [
+            Status::Closed,
+            Status::Open,
+            Status::Resolved,
+            Status::InProgress,
+            Status::Open,
+        ]
+        .into_iter()
+        .map(|status| Story {
+            status,
+            ..Story::new("story".to_owned(), "".to_owned())
+        })
+        .collect();
+
+        stories.sort_by(|a, b| a.status.cmp(&b.status));
+
+        let statuses: Vec<Status> = stories.into_iter().map(|story| story.status).collect();
+        assert_eq!(
+            statuses,
+            vec![
+                Status::Open,
+                Status::Open,
+                Status::InProgress,
+                Status::Resolved,
+                Status::Closed
+            ]
+        );
+    }
+
+    #[test]
+    fn priority_should_default_to_medium_and_sort_most_urgent_first() {
+        assert_eq!(Priority::default(), Priority::Medium);
+        assert_eq!(
+            Epic::new("".to_owned(), "".to_owned()).priority,
+            Priority::Medium
+        );
+        assert_eq!(
+            Story::new("".to_owned(), "".to_owned()).priority,
+            Priority::Medium
+        );
+
+        let mut priorities = vec![
+            Priority::Low,
+            Priority::Critical,
+            Priority::Backlog,
+            Priority::High,
+        ];
+        priorities.sort();
+        assert_eq!(
+            priorities,
+            vec![
+                Priority::Critical,
+                Priority::High,
+                Priority::Low,
+                Priority::Backlog
+            ]
+        );
+    }
+
+    #[test]
+    fn word_count_should_count_whitespace_separated_words() {
+        assert_eq!(word_count(""), 0);
+        assert_eq!(word_count("   "), 0);
+        assert_eq!(word_count("word"), 1);
+        assert_eq!(word_count("several   spaces\tand\nlines"), 4);
+        assert_eq!(word_count("  leading and trailing  "), 3);
+    }
+
+    #[test]
+    fn word_count_description_should_only_count_the_description() {
+        let story = Story::new("a long name".to_owned(), "two words".to_owned());
+
+        assert_eq!(story.word_count_description(), 2);
+    }
+
+    #[test]
+    fn has_tag_should_ignore_case_and_whitespace() {
+        let story = Story {
+            tags: vec!["Backend".to_owned(), "urgent".to_owned()],
+            ..Story::new("story".to_owned(), "".to_owned())
+        };
+
+        assert!(story.has_tag("backend"));
+        assert!(story.has_tag(" URGENT "));
+        assert!(!story.has_tag("frontend"));
+        assert!(!Epic::new("epic".to_owned(), "".to_owned()).has_tag("backend"));
+    }
+
+    #[test]
+    fn completion_percentage_should_count_resolved_and_closed_stories() {
+        let mut epic = Epic::new("epic".to_owned(), "".to_owned());
+        assert_eq!(epic.completion_percentage(&HashMap::new()), 0.0);
+
+        let statuses = [
+            Status::Closed,
+            Status::Resolved,
+            Status::InProgress,
+            Status::Open,
+        ];
+        let mut stories = HashMap::new();
+        for (id, status) in (1..).zip(statuses) {
+            let mut story = Story::new(format!("story {}", id), "".to_owned());
+            story.status = status;
+            stories.insert(id, story);
+            epic.stories.push(id);
+        }
+        // Stories of other epics are ignored
+        stories.insert(99, Story::new("other".to_owned(), "".to_owned()));
+
+        assert_eq!(epic.completion_percentage(&stories), 50.0);
+    }
 }
@@ -0,0 +1,47 @@
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, PartialEq, Eq, Debug, Clone)]
+pub struct Sprint {
+    pub id: u32,
+    pub name: String,
+    pub start_date: NaiveDate,
+    pub end_date: NaiveDate,
+    pub story_ids: Vec<u32>,
+}
+
+impl Sprint {
+    /// The id is assigned by `JiraDatabase::create_sprint`.
+    pub fn new(name: String, start_date: NaiveDate, end_date: NaiveDate) -> Self {
+        Self {
+            id: 0,
+            name,
+            start_date,
+            end_date,
+            story_ids: vec![],
+        }
+    }
+
+    pub fn is_active(&self, today: NaiveDate) -> bool {
+        self.start_date <= today && today <= self.end_date
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn is_active_should_include_both_ends_of_the_sprint() {
+        let sprint = Sprint::new(
+            "sprint 1".to_owned(),
+            NaiveDate::from_ymd_opt(2025, 6, 2).unwrap(),
+            NaiveDate::from_ymd_opt(2025, 6, 13).unwrap(),
+        );
+
+        assert!(!sprint.is_active(NaiveDate::from_ymd_opt(2025, 6, 1).unwrap()));
+        assert!(sprint.is_active(NaiveDate::from_ymd_opt(2025, 6, 2).unwrap()));
+        assert!(sprint.is_active(NaiveDate::from_ymd_opt(2025, 6, 13).unwrap()));
+        assert!(!sprint.is_active(NaiveDate::from_ymd_opt(2025, 6, 14).unwrap()));
+    }
+}
@@ -1,66 +1,507 @@
 use anyhow::{Context, Ok, Result, anyhow};
-use std::rc::Rc;
+use std::{
+    cell::RefCell,
+    collections::VecDeque,
+    fs::File,
+    io::{BufReader, BufWriter},
+    rc::Rc,
+    time::{Duration, Instant},
+};
 
 use crate::{
     db::JiraDatabase,
-    models::Action,
-    ui::{EpicDetail, HomePage, Page, Prompts, StoryDetail},
+    export::{self, MarkdownFormat},
+    io_utils, log,
+    logging::LogLevel,
+    models::{Action, Epic, Story},
+    ui::{
+        BacklogPage, CommentsPage, DashboardPage, EpicDetail, HelpPage, HomePage, ImportPage, Page,
+        ProjectSelectPage, Prompts, ReleaseDetail, ReleasePage, ReporterPage, SearchPage,
+        SprintDetail, SprintPage, StoryDetail, WatchListPage,
+    },
+    watch_list::WatchList,
 };
 
 pub struct Navigator {
     pages: Vec<Box<dyn Page>>,
     prompts: Prompts,
     db: Rc<JiraDatabase>,
+    action_history: VecDeque<Action>,
+    last_message: Option<String>,
+    session_started: Instant,
+    actions_this_session: u32,
+    watched_stories: Rc<RefCell<WatchList>>,
 }
 
 impl Navigator {
+    const ACTION_HISTORY_LIMIT: usize = 20;
+
     pub fn new(db: Rc<JiraDatabase>) -> Self {
         Self {
-            pages: vec![Box::new(HomePage { db: Rc::clone(&db) })],
+            pages: vec![Box::new(HomePage::new(Rc::clone(&db)))],
             prompts: Prompts::new(),
             db,
+            action_history: VecDeque::new(),
+            last_message: None,
+            session_started: Instant::now(),
+            actions_this_session: 0,
+            watched_stories: Rc::new(RefCell::new(WatchList::in_memory())),
+        }
+    }
+
+    /// Answers prompts with `prompts` instead of asking on stdin.
+    pub fn new_with_prompts(db: Rc<JiraDatabase>, prompts: Prompts) -> Self {
+        Self {
+            prompts,
+            ..Self::new(db)
         }
     }
 
+    /// Starts from a saved watch list. Without one, watched stories are
+    /// forgotten when the session ends.
+    pub fn with_watch_list(mut self, watch_list: WatchList) -> Self {
+        self.watched_stories = Rc::new(RefCell::new(watch_list));
+        self
+    }
+
     pub fn get_current_page(&self) -> Option<&Box<dyn Page>> {
         self.pages.last()
     }
 
-    pub fn handle_action(&mut self, action: Action) -> Result<()> {
-        match action {
+    pub fn push_page(&mut self, page: Box<dyn Page>) {
+        self.pages.push(page);
+    }
+
+    /// Swaps the current page for `page` without growing the stack, so the
+    /// replaced page is not left behind in the history. An empty stack just
+    /// gets `page` pushed.
+    pub fn replace_top_page(&mut self, page: Box<dyn Page>) {
+        self.pages.pop();
+        self.pages.push(page);
+    }
+
+    /// How many pages are on the stack, including the current one.
+    pub fn history_len(&self) -> usize {
+        self.pages.len()
+    }
+
+    /// The titles of the pages on the stack, oldest first, e.g.
+    /// "Epics > Epic #1 > Story #3".
+    pub fn breadcrumb(&self) -> String {
+        let titles: Vec<String> = self.pages.iter().map(|page| page.page_title()).collect();
+        titles.join(" > ")
+    }
+
+    /// Whether `[p] previous` has a page to return to.
+    pub fn can_go_back(&self) -> bool {
+        self.history_len() > 1
+    }
+
+    /// Popping the last page leaves the stack empty, which ends the main loop.
+    pub fn pop_page(&mut self) -> Option<Box<dyn Page>> {
+        self.pages.pop()
+    }
+
+    pub fn last_action(&self) -> Option<&Action> {
+        self.action_history.back()
+    }
+
+    pub fn action_history(&self) -> &VecDeque<Action> {
+        &self.action_history
+    }
+
+    pub fn session_duration(&self) -> Duration {
+        self.session_started.elapsed()
+    }
+
+    pub fn actions_this_session(&self) -> u32 {
+        self.actions_this_session
+    }
+
+    pub fn last_message(&self) -> Option<&str> {
+        self.last_message.as_deref()
+    }
+
+    pub fn handle_action(&mut self, action: Action) -> Result<Option<String>> {
+        let history_entry = action.clone();
+        self.last_message = None;
+        log!(
+            LogLevel::Debug,
+            "handling action",
+            action = format!("{:?}", history_entry),
+        );
+
+        let message = match action {
             Action::Register => todo!(),
             Action::Login { username } => todo!(),
             Action::NavigateToEpicDetail { epic_id } => {
-                self.pages.push(Box::new(EpicDetail {
-                    epic_id,
-                    db: Rc::clone(&self.db),
-                }));
+                self.push_page(Box::new(EpicDetail::new(epic_id, Rc::clone(&self.db))));
+                None
             }
             Action::NavigateToStoryDetail { epic_id, story_id } => {
-                self.pages.push(Box::new(StoryDetail {
+                // Coming back should show the page the story is listed on
+                if self.current_page_is::<EpicDetail>() {
+                    let db_state = self.db.read_db()?;
+                    if let Some(epic) = db_state.epics.get(&epic_id) {
+                        self.update_epic_detail(|epic_detail| {
+                            if let Some(page) =
+                                epic_detail.page_of(story_id, epic, &db_state.stories)
+                            {
+                                epic_detail.page = page;
+                            }
+                        })?;
+                    }
+                }
+                self.push_page(Box::new(StoryDetail {
                     epic_id,
                     story_id,
                     db: Rc::clone(&self.db),
                 }));
+                None
             }
             Action::NavigateToPreviousPage => {
-                if !self.pages.is_empty() {
-                    self.pages.pop();
+                self.pop_page();
+                None
+            }
+            Action::NavigateToHomePage => {
+                self.pages.truncate(1);
+                None
+            }
+            Action::NavigateToProjectSelect => {
+                self.push_page(Box::new(ProjectSelectPage {
+                    db: Rc::clone(&self.db),
+                }));
+                None
+            }
+            Action::NavigateToSprintList => {
+                self.push_page(Box::new(SprintPage {
+                    db: Rc::clone(&self.db),
+                }));
+                None
+            }
+            Action::NavigateToBacklog => {
+                self.push_page(Box::new(BacklogPage {
+                    db: Rc::clone(&self.db),
+                }));
+                None
+            }
+            Action::NavigateToSprintDetail { sprint_id } => {
+                self.push_page(Box::new(SprintDetail {
+                    sprint_id,
+                    db: Rc::clone(&self.db),
+                }));
+                None
+            }
+            Action::Search { query } => {
+                let query = if query.is_empty() {
+                    (self.prompts.search_query)()
+                } else {
+                    Some(query)
+                };
+
+                if let Some(query) = query {
+                    self.push_page(Box::new(SearchPage {
+                        query,
+                        db: Rc::clone(&self.db),
+                    }));
                 }
+                None
             }
-            Action::CreateEpic => {
-                let epic = (self.prompts.create_epic)();
+            Action::NavigateToReporterStories { reporter } => {
+                if let Some(reporter) = reporter.or_else(|| (self.prompts.reporter)()) {
+                    self.push_page(Box::new(ReporterPage {
+                        reporter,
+                        db: Rc::clone(&self.db),
+                    }));
+                }
+                None
+            }
+            Action::NavigateToReleases => {
+                self.push_page(Box::new(ReleasePage {
+                    db: Rc::clone(&self.db),
+                }));
+                None
+            }
+            Action::NavigateToReleaseDetail { release } => {
+                self.push_page(Box::new(ReleaseDetail {
+                    release,
+                    db: Rc::clone(&self.db),
+                }));
+                None
+            }
+            Action::NavigateToDashboard => {
+                self.push_page(Box::new(DashboardPage {
+                    db: Rc::clone(&self.db),
+                }));
+                None
+            }
+            Action::NavigateToImport => {
+                self.push_page(Box::new(ImportPage));
+                None
+            }
+            Action::NavigateToWatchList => {
+                self.push_page(Box::new(WatchListPage {
+                    watch_list: Rc::clone(&self.watched_stories),
+                    db: Rc::clone(&self.db),
+                }));
+                None
+            }
+            Action::ToggleWatch { story_id } => {
+                let watched = self
+                    .watched_stories
+                    .borrow_mut()
+                    .toggle(story_id)
+                    .with_context(|| anyhow!("failed to save the watch list!"))?;
+                if watched {
+                    Some(format!("Story #{} added to the watch list", story_id))
+                } else {
+                    Some(format!("Story #{} removed from the watch list", story_id))
+                }
+            }
+            Action::SortHomePage { key } => {
+                if let Some(key) = key.or_else(|| (self.prompts.sort_key)()) {
+                    self.update_home_page(|home_page| {
+                        home_page.sort_key = key;
+                        home_page.page = 0;
+                    })?;
+                }
+                None
+            }
+            Action::ReverseHomePageSort => {
+                self.update_home_page(|home_page| {
+                    home_page.sort_dir = home_page.sort_dir.reversed();
+                    home_page.page = 0;
+                })?;
+                None
+            }
+            Action::SortEpicStories { key } => {
+                if let Some(key) = key.or_else(|| (self.prompts.story_sort_key)()) {
+                    self.update_epic_detail(|epic_detail| {
+                        epic_detail.sort_key = key;
+                        epic_detail.page = 0;
+                    })?;
+                }
+                None
+            }
+            Action::ReverseEpicStoriesSort => {
+                self.update_epic_detail(|epic_detail| {
+                    epic_detail.sort_dir = epic_detail.sort_dir.reversed();
+                    epic_detail.page = 0;
+                })?;
+                None
+            }
+            Action::FilterHomePageByTag { tag } => {
+                if let Some(tag) = tag.or_else(|| (self.prompts.filter_tag)()) {
+                    self.update_home_page(|home_page| {
+                        home_page.active_tag = Some(tag);
+                        home_page.page = 0;
+                    })?;
+                }
+                None
+            }
+            Action::ClearHomePageTagFilter => {
+                self.update_home_page(|home_page| {
+                    home_page.active_tag = None;
+                    home_page.page = 0;
+                })?;
+                None
+            }
+            Action::NextPage if self.current_page_is::<EpicDetail>() => {
+                let db_state = self.db.read_db()?;
+                self.update_epic_detail(|epic_detail| {
+                    let story_count = db_state
+                        .epics
+                        .get(&epic_detail.epic_id)
+                        .map_or(0, |epic| epic.stories.len());
+                    if (epic_detail.page + 1) * epic_detail.page_size < story_count {
+                        epic_detail.page += 1;
+                    }
+                })?;
+                None
+            }
+            Action::PreviousListPage if self.current_page_is::<EpicDetail>() => {
+                self.update_epic_detail(|epic_detail| {
+                    epic_detail.page = epic_detail.page.saturating_sub(1);
+                })?;
+                None
+            }
+            Action::NextPage => {
+                let db_state = self.db.read_db()?;
+                self.update_home_page(|home_page| {
+                    let epic_count = home_page.epic_ids(&db_state.epics, &db_state.stories).len();
+                    if (home_page.page + 1) * home_page.page_size < epic_count {
+                        home_page.page += 1;
+                    }
+                })?;
+                None
+            }
+            Action::PreviousListPage => {
+                self.update_home_page(|home_page| {
+                    home_page.page = home_page.page.saturating_sub(1);
+                })?;
+                None
+            }
+            Action::CreateProject => match (self.prompts.create_project)() {
+                Some(name) => {
+                    self.db
+                        .use_project(&name)
+                        .with_context(|| anyhow!("failed to create project!"))?;
+                    self.pages.truncate(1);
+                    Some(format!("Switched to project \"{}\"", name))
+                }
+                None => None,
+            },
+            Action::SwitchProject { name } => {
                 self.db
+                    .use_project(&name)
+                    .with_context(|| anyhow!("failed to switch project!"))?;
+                self.pages.truncate(1);
+                Some(format!("Switched to project \"{}\"", name))
+            }
+            Action::CreateSprint => match (self.prompts.create_sprint)() {
+                Some(sprint) => {
+                    let sprint_id = self
+                        .db
+                        .create_sprint(sprint)
+                        .with_context(|| anyhow!("failed to create sprint!"))?;
+                    Some(format!("Sprint #{} created", sprint_id))
+                }
+                None => None,
+            },
+            Action::DeleteSprint { sprint_id } => {
+                if (self.prompts.delete_sprint)() {
+                    self.db
+                        .delete_sprint(sprint_id)
+                        .with_context(|| anyhow!("failed to delete sprint!"))?;
+
+                    self.pop_page();
+                    Some(format!("Sprint #{} deleted", sprint_id))
+                } else {
+                    None
+                }
+            }
+            Action::AssignStoryToSprint {
+                sprint_id,
+                story_id,
+            } => match story_id.or_else(|| (self.prompts.assign_sprint_story)()) {
+                Some(story_id) => {
+                    self.db
+                        .assign_story_to_sprint(sprint_id, story_id)
+                        .with_context(|| anyhow!("failed to update sprint!"))?;
+                    Some(format!(
+                        "Story #{} added to sprint #{}",
+                        story_id, sprint_id
+                    ))
+                }
+                None => None,
+            },
+            Action::RemoveStoryFromSprint { sprint_id } => {
+                match (self.prompts.remove_sprint_story)() {
+                    Some(story_id) => {
+                        self.db
+                            .remove_story_from_sprint(sprint_id, story_id)
+                            .with_context(|| anyhow!("failed to update sprint!"))?;
+                        Some(format!(
+                            "Story #{} removed from sprint #{}",
+                            story_id, sprint_id
+                        ))
+                    }
+                    None => None,
+                }
+            }
+            Action::CreateEpic => {
+                let epic = (self.prompts.create_epic)(&self.db.audit_user());
+                let epic_id = self
+                    .db
                     .create_epic(epic)
                     .with_context(|| anyhow!("failed to create epic!"))?;
+                Some(format!("Epic #{} created", epic_id))
             }
             Action::UpdateEpicStatus { epic_id } => {
                 let status = (self.prompts.update_status)();
 
-                if let Some(status) = status {
-                    self.db
-                        .update_epic_status(epic_id, status)
-                        .with_context(|| anyhow!("failed to update epic!"))?;
+                match status {
+                    Some(status) => {
+                        let message = format!("Epic #{} is now {}", epic_id, status);
+                        self.db
+                            .update_epic_status(epic_id, status)
+                            .with_context(|| anyhow!("failed to update epic!"))?;
+                        Some(message)
+                    }
+                    None => None,
+                }
+            }
+            Action::UpdateEpicDueDate { epic_id, date } => {
+                let date = match date {
+                    Some(date) => Some(date),
+                    None => (self.prompts.update_due_date)(self.epic(epic_id)?.due_date),
+                };
+
+                match date {
+                    Some(date) => {
+                        self.db
+                            .update_epic_due_date(epic_id, Some(date))
+                            .with_context(|| anyhow!("failed to update epic!"))?;
+                        Some(format!("Epic #{} is due {}", epic_id, date))
+                    }
+                    None => None,
+                }
+            }
+            Action::UpdateEpicName { epic_id, name } => {
+                let name = match name {
+                    Some(name) => Some(name),
+                    None => (self.prompts.update_name)(&self.epic(epic_id)?.name),
+                };
+
+                match name {
+                    Some(name) => {
+                        self.db
+                            .update_epic_name(epic_id, name)
+                            .with_context(|| anyhow!("failed to update epic!"))?;
+                        Some(format!("Epic #{} renamed", epic_id))
+                    }
+                    None => None,
+                }
+            }
+            Action::UpdateEpicDescription {
+                epic_id,
+                description,
+            } => {
+                let description = match description {
+                    Some(description) => Some(description),
+                    None => (self.prompts.update_description)(&self.epic(epic_id)?.description),
+                };
+
+                match description {
+                    Some(description) => {
+                        self.db
+                            .update_epic_description(epic_id, description)
+                            .with_context(|| anyhow!("failed to update epic!"))?;
+                        Some(format!("Epic #{} description updated", epic_id))
+                    }
+                    None => None,
+                }
+            }
+            Action::UpdateEpicRelease { epic_id, release } => {
+                let release = match release {
+                    Some(release) => Some(release),
+                    None => (self.prompts.update_release)(
+                        self.epic(epic_id)?.release.as_deref().unwrap_or_default(),
+                    ),
+                };
+
+                match release {
+                    Some(release) => {
+                        self.db
+                            .update_epic_release(epic_id, Some(release.clone()))
+                            .with_context(|| anyhow!("failed to update epic!"))?;
+                        if release.is_empty() {
+                            Some(format!("Epic #{} removed from its release", epic_id))
+                        } else {
+                            Some(format!("Epic #{} is now in release {}", epic_id, release))
+                        }
+                    }
+                    None => None,
                 }
             }
             Action::DeleteEpic { epic_id } => {
@@ -69,24 +510,237 @@ impl Navigator {
                         .delete_epic(epic_id)
                         .with_context(|| anyhow!("failed to delete epic!"))?;
 
-                    if !self.pages.is_empty() {
-                        self.pages.pop();
-                    }
+                    self.pop_page();
+                    Some(format!("Epic #{} deleted", epic_id))
+                } else {
+                    None
                 }
             }
             Action::CreateStory { epic_id } => {
-                let story = (self.prompts.create_story)();
-                self.db
+                let story = (self.prompts.create_story)(&self.db.audit_user());
+                let story_id = self
+                    .db
                     .create_story(story, epic_id)
                     .with_context(|| anyhow!("failed to create story!"))?;
+                Some(format!("Story #{} created", story_id))
+            }
+            Action::UpdateStoryRelease { story_id, release } => {
+                let release = match release {
+                    Some(release) => Some(release),
+                    None => (self.prompts.update_release)(
+                        self.story(story_id)?.release.as_deref().unwrap_or_default(),
+                    ),
+                };
+
+                match release {
+                    Some(release) => {
+                        self.db
+                            .update_story_release(story_id, Some(release.clone()))
+                            .with_context(|| anyhow!("failed to update story!"))?;
+                        if release.is_empty() {
+                            Some(format!("Story #{} removed from its release", story_id))
+                        } else {
+                            Some(format!("Story #{} is now in release {}", story_id, release))
+                        }
+                    }
+                    None => None,
+                }
+            }
+            Action::CloneStory { story_id } => {
+                let clone_id = self
+                    .db
+                    .clone_story(story_id)
+                    .with_context(|| anyhow!("failed to clone story!"))?;
+                Some(format!("Story #{} cloned as #{}", story_id, clone_id))
+            }
+            Action::MoveStory {
+                story_id,
+                target_epic_id,
+            } => match target_epic_id.or_else(|| (self.prompts.move_story)()) {
+                Some(target_epic_id) => {
+                    self.db
+                        .move_story(story_id, target_epic_id)
+                        .with_context(|| anyhow!("failed to move story!"))?;
+
+                    // The open story page still points at the old epic
+                    let showing_story = self
+                        .get_current_page()
+                        .and_then(|page| page.as_any().downcast_ref::<StoryDetail>())
+                        .is_some_and(|page| page.story_id == story_id);
+                    if showing_story {
+                        self.pop_page();
+                        self.push_page(Box::new(StoryDetail {
+                            epic_id: target_epic_id,
+                            story_id,
+                            db: Rc::clone(&self.db),
+                        }));
+                    }
+                    Some(format!(
+                        "Story #{} moved to epic #{}",
+                        story_id, target_epic_id
+                    ))
+                }
+                None => None,
+            },
+            Action::NavigateToComments { story_id } => {
+                self.push_page(Box::new(CommentsPage {
+                    story_id,
+                    db: Rc::clone(&self.db),
+                }));
+                None
             }
+            Action::AddComment { story_id } => match (self.prompts.add_comment)() {
+                Some(text) => {
+                    let comment_id = self
+                        .db
+                        .add_comment(story_id, &self.db.audit_user(), &text)
+                        .with_context(|| anyhow!("failed to add comment!"))?;
+                    Some(format!(
+                        "Comment #{} added to story #{}",
+                        comment_id, story_id
+                    ))
+                }
+                None => None,
+            },
+            Action::DeleteComment { story_id } => match (self.prompts.delete_comment)() {
+                Some(comment_id) => {
+                    self.db
+                        .delete_comment(story_id, comment_id)
+                        .with_context(|| anyhow!("failed to delete comment!"))?;
+                    Some(format!(
+                        "Comment #{} deleted from story #{}",
+                        comment_id, story_id
+                    ))
+                }
+                None => None,
+            },
             Action::UpdateStoryStatus { story_id } => {
                 let status = (self.prompts.update_status)();
 
-                if let Some(status) = status {
+                match status {
+                    Some(status) => {
+                        let message = format!("Story #{} is now {}", story_id, status);
+                        self.db
+                            .update_story_status(story_id, status)
+                            .with_context(|| anyhow!("failed to update story!"))?;
+                        Some(message)
+                    }
+                    None => None,
+                }
+            }
+            Action::UpdateStoryEstimate { story_id } => {
+                match (self.prompts.update_estimate)(self.story(story_id)?.estimate) {
+                    Some(estimate) => {
+                        self.db
+                            .update_story_estimate(story_id, estimate)
+                            .with_context(|| anyhow!("failed to update story!"))?;
+                        Some(format!(
+                            "Story #{} estimated at {} points",
+                            story_id, estimate
+                        ))
+                    }
+                    None => None,
+                }
+            }
+            Action::UpdateStoryType {
+                story_id,
+                story_type,
+            } => match story_type.or_else(|| (self.prompts.update_story_type)()) {
+                Some(story_type) => {
                     self.db
-                        .update_story_status(story_id, status)
+                        .update_story_type(story_id, story_type)
                         .with_context(|| anyhow!("failed to update story!"))?;
+                    Some(format!("Story #{} is now a {}", story_id, story_type))
+                }
+                None => None,
+            },
+            Action::LinkStoryDependency { story_id } => {
+                match (self.prompts.link_story_dependency)() {
+                    Some(blocked_id) => {
+                        self.db
+                            .add_story_dependency(story_id, blocked_id)
+                            .with_context(|| anyhow!("failed to link stories!"))?;
+                        Some(format!("Story #{} now blocks #{}", story_id, blocked_id))
+                    }
+                    None => None,
+                }
+            }
+            Action::UnlinkStoryDependency { story_id } => {
+                match (self.prompts.unlink_story_dependency)() {
+                    Some(other_id) => {
+                        // The link can point either way, so find out which
+                        // story is the blocker before removing it.
+                        let blocks_other = self
+                            .db
+                            .read_db()?
+                            .stories
+                            .get(&story_id)
+                            .is_some_and(|story| story.blocks.contains(&other_id));
+                        let (blocker_id, blocked_id) = if blocks_other {
+                            (story_id, other_id)
+                        } else {
+                            (other_id, story_id)
+                        };
+                        self.db
+                            .remove_story_dependency(blocker_id, blocked_id)
+                            .with_context(|| anyhow!("failed to unlink stories!"))?;
+                        Some(format!(
+                            "Story #{} no longer blocks #{}",
+                            blocker_id, blocked_id
+                        ))
+                    }
+                    None => None,
+                }
+            }
+            Action::UpdateStoryDueDate { story_id, date } => {
+                let date = match date {
+                    Some(date) => Some(date),
+                    None => (self.prompts.update_due_date)(self.story(story_id)?.due_date),
+                };
+
+                match date {
+                    Some(date) => {
+                        self.db
+                            .update_story_due_date(story_id, Some(date))
+                            .with_context(|| anyhow!("failed to update story!"))?;
+                        Some(format!("Story #{} is due {}", story_id, date))
+                    }
+                    None => None,
+                }
+            }
+            Action::UpdateStoryName { story_id, name } => {
+                let name = match name {
+                    Some(name) => Some(name),
+                    None => (self.prompts.update_name)(&self.story(story_id)?.name),
+                };
+
+                match name {
+                    Some(name) => {
+                        self.db
+                            .update_story_name(story_id, name)
+                            .with_context(|| anyhow!("failed to update story!"))?;
+                        Some(format!("Story #{} renamed", story_id))
+                    }
+                    None => None,
+                }
+            }
+            Action::UpdateStoryDescription {
+                story_id,
+                description,
+            } => {
+                let description = match description {
+                    Some(description) => Some(description),
+                    None => (self.prompts.update_description)(&self.story(story_id)?.description),
+                };
+
+                match description {
+                    Some(description) => {
+                        self.db
+                            .update_story_description(story_id, description)
+                            .with_context(|| anyhow!("failed to update story!"))?;
+                        Some(format!("Story #{} description updated", story_id))
+                    }
+                    None => None,
                 }
             }
             Action::DeleteStory { epic_id, story_id } => {
@@ -95,17 +749,169 @@ impl Navigator {
                         .delete_story(epic_id, story_id)
                         .with_context(|| anyhow!("failed to delete story!"))?;
 
-                    if !self.pages.is_empty() {
-                        self.pages.pop();
+                    self.pop_page();
+                    Some(format!("Story #{} deleted", story_id))
+                } else {
+                    None
+                }
+            }
+            Action::BatchDeleteStories { story_ids } => {
+                if story_ids.is_empty() {
+                    Some("There are no stories to delete".to_owned())
+                } else if (self.prompts.delete_stories)(story_ids.len()) {
+                    let report = self
+                        .db
+                        .delete_stories(&story_ids)
+                        .with_context(|| anyhow!("failed to delete stories!"))?;
+
+                    // The list may now be shorter than the page being shown
+                    if self.current_page_is::<EpicDetail>() {
+                        self.update_epic_detail(|epic_detail| epic_detail.page = 0)?;
                     }
+                    Some(format!("{} stories deleted", report.deleted.len()))
+                } else {
+                    None
                 }
             }
-            Action::Exit => self.pages.clear(),
-        }
+            Action::ShowHelp { context } => {
+                self.push_page(Box::new(HelpPage { context }));
+                None
+            }
+            Action::ExportCurrentPage { dest } => {
+                let page = self
+                    .pages
+                    .last()
+                    .ok_or_else(|| anyhow!("there is no page to export!"))?;
+                let dest = match dest {
+                    Some(dest) => dest,
+                    None => (self.prompts.export_destination)(&page.suggested_export_filename()),
+                };
+
+                io_utils::write_to_file(&dest, &page.render_to_string()?)
+                    .with_context(|| anyhow!("failed to export page!"))?;
+                Some(format!("Page exported to {}", dest.display()))
+            }
+            Action::Refresh => {
+                // The main loop redraws the current page from the fresh state
+                self.db.invalidate_cache();
+                self.db
+                    .read_db()
+                    .with_context(|| anyhow!("failed to reload database!"))?;
+                Some("Reloaded from disk".to_owned())
+            }
+            Action::ExportCsv => {
+                let dest = self
+                    .db
+                    .database
+                    .file_path()
+                    .map(|file_path| format!("{}.csv", file_path))
+                    .ok_or_else(|| anyhow!("the database is not stored in a file!"))?;
+                let file =
+                    File::create(&dest).with_context(|| anyhow!("failed to create {}!", dest))?;
+                self.db
+                    .export_csv(BufWriter::new(file))
+                    .with_context(|| anyhow!("failed to export csv!"))?;
+                Some(format!("Exported to {}", dest))
+            }
+            Action::ExportMarkdown => {
+                let dest = self
+                    .db
+                    .database
+                    .file_path()
+                    .map(|file_path| format!("{}.md", file_path))
+                    .ok_or_else(|| anyhow!("the database is not stored in a file!"))?;
+                let file =
+                    File::create(&dest).with_context(|| anyhow!("failed to create {}!", dest))?;
+                export::export_markdown(&self.db, MarkdownFormat::Report, BufWriter::new(file))
+                    .with_context(|| anyhow!("failed to export markdown!"))?;
+                Some(format!("Exported to {}", dest))
+            }
+            Action::ImportCsv { path } => {
+                let file =
+                    File::open(&path).with_context(|| anyhow!("failed to open {}!", path))?;
+                let report = self
+                    .db
+                    .import_csv_lenient(BufReader::new(file))
+                    .with_context(|| anyhow!("failed to import csv!"))?;
+
+                // Back to the page the import was started from
+                self.pop_page();
+                Some(format!(
+                    "Imported {} epics, {} stories, {} skipped",
+                    report.created_epics,
+                    report.created_stories,
+                    report.errors.len()
+                ))
+            }
+            // Every change is written as it is made, so there is nothing
+            // left to save. Writing the cached state here would undo changes
+            // another instance made since it was read.
+            Action::Exit => {
+                self.pages.clear();
+                None
+            }
+        };
+
+        self.record_action(history_entry);
+        self.actions_this_session += 1;
+        self.last_message = message.clone();
+
+        Ok(message)
+    }
+
+    // Current values for the update prompts to start from
+    fn epic(&self, epic_id: u32) -> Result<Epic> {
+        self.db
+            .read_db()?
+            .epics
+            .remove(&epic_id)
+            .ok_or_else(|| anyhow!("could not find epic {} in database!", epic_id))
+    }
+
+    fn story(&self, story_id: u32) -> Result<Story> {
+        self.db
+            .read_db()?
+            .stories
+            .remove(&story_id)
+            .ok_or_else(|| anyhow!("could not find story {} in database!", story_id))
+    }
 
+    /// Pages are immutable once pushed, so list state such as sorting and
+    /// paging is changed by swapping in an updated copy of the home page.
+    fn update_home_page(&mut self, update: impl FnOnce(&mut HomePage)) -> Result<()> {
+        let mut home_page = self
+            .get_current_page()
+            .and_then(|page| page.as_any().downcast_ref::<HomePage>())
+            .cloned()
+            .ok_or_else(|| anyhow!("the home page is not being displayed!"))?;
+        update(&mut home_page);
+        self.replace_top_page(Box::new(home_page));
         Ok(())
     }
 
+    fn current_page_is<P: Page + 'static>(&self) -> bool {
+        self.get_current_page()
+            .is_some_and(|page| page.as_any().is::<P>())
+    }
+
+    fn update_epic_detail(&mut self, update: impl FnOnce(&mut EpicDetail)) -> Result<()> {
+        let mut epic_detail = self
+            .get_current_page()
+            .and_then(|page| page.as_any().downcast_ref::<EpicDetail>())
+            .cloned()
+            .ok_or_else(|| anyhow!("the epic detail page is not being displayed!"))?;
+        update(&mut epic_detail);
+        self.replace_top_page(Box::new(epic_detail));
+        Ok(())
+    }
+
+    fn record_action(&mut self, action: Action) {
+        if self.action_history.len() == Self::ACTION_HISTORY_LIMIT {
+            self.action_history.pop_front();
+        }
+        self.action_history.push_back(action);
+    }
+
     // Private functions used for testing
 
     fn get_page_count(&self) -> usize {
@@ -120,16 +926,18 @@ impl Navigator {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use chrono::NaiveDate;
+
     use crate::{
         db::test_utils::MockDB,
-        models::{Epic, Status, Story},
+        models::{
+            Epic, PageType, SortDir, SortKey, Sprint, Status, Story, StorySortKey, StoryType,
+        },
     };
 
     #[test]
     fn should_start_on_home_page() {
-        let db = Rc::new(JiraDatabase {
-            database: Box::new(MockDB::new()),
-        });
+        let db = Rc::new(JiraDatabase::with_database(Box::new(MockDB::new())));
         let nav = Navigator::new(db);
 
         assert_eq!(nav.get_page_count(), 1);
@@ -142,9 +950,7 @@ mod tests {
 
     #[test]
     fn handle_action_should_navigate_pages() {
-        let db = Rc::new(JiraDatabase {
-            database: Box::new(MockDB::new()),
-        });
+        let db = Rc::new(JiraDatabase::with_database(Box::new(MockDB::new())));
 
         let mut nav = Navigator::new(db);
 
@@ -189,174 +995,1686 @@ mod tests {
     }
 
     #[test]
-    fn handle_action_should_clear_pages_on_exit() {
-        let db = Rc::new(JiraDatabase {
-            database: Box::new(MockDB::new()),
-        });
-
+    fn breadcrumb_should_follow_the_page_stack() {
+        let db = Rc::new(JiraDatabase::with_database(Box::new(MockDB::new())));
         let mut nav = Navigator::new(db);
+        assert_eq!(nav.breadcrumb(), "Epics");
 
         nav.handle_action(Action::NavigateToEpicDetail { epic_id: 1 })
             .unwrap();
+        assert_eq!(nav.breadcrumb(), "Epics > Epic #1");
+
         nav.handle_action(Action::NavigateToStoryDetail {
             epic_id: 1,
-            story_id: 2,
+            story_id: 3,
         })
         .unwrap();
-        nav.handle_action(Action::Exit).unwrap();
+        assert_eq!(nav.breadcrumb(), "Epics > Epic #1 > Story #3");
 
-        assert_eq!(nav.get_page_count(), 0);
+        nav.handle_action(Action::NavigateToPreviousPage).unwrap();
+        assert_eq!(nav.breadcrumb(), "Epics > Epic #1");
+
+        nav.pop_page();
+        nav.pop_page();
+        assert_eq!(nav.breadcrumb(), "");
     }
 
     #[test]
-    fn handle_action_should_handle_create_epic() {
-        let db = Rc::new(JiraDatabase {
-            database: Box::new(MockDB::new()),
-        });
+    fn get_current_page_should_return_none_for_an_empty_stack() {
+        let db = Rc::new(JiraDatabase::with_database(Box::new(MockDB::new())));
+        let mut nav = Navigator::new(db);
 
-        let mut nav = Navigator::new(Rc::clone(&db));
+        assert!(nav.pop_page().is_some());
 
-        let mut prompts = Prompts::new();
-        prompts.create_epic = Box::new(|| Epic::new("name".to_owned(), "description".to_owned()));
+        assert!(nav.get_current_page().is_none());
+        assert!(nav.pop_page().is_none());
+    }
 
-        nav.set_prompts(prompts);
+    #[test]
+    fn push_page_should_make_the_page_current() {
+        let db = Rc::new(JiraDatabase::with_database(Box::new(MockDB::new())));
+        let mut nav = Navigator::new(Rc::clone(&db));
 
-        nav.handle_action(Action::CreateEpic).unwrap();
+        nav.push_page(Box::new(ProjectSelectPage { db }));
 
-        let db_state = db.read_db().unwrap();
-        assert_eq!(db_state.epics.len(), 1);
+        assert_eq!(nav.get_page_count(), 2);
+        assert!(
+            nav.get_current_page()
+                .unwrap()
+                .as_any()
+                .downcast_ref::<ProjectSelectPage>()
+                .is_some()
+        );
 
-        let epic = db_state.epics.into_iter().next().unwrap().1;
-        assert_eq!(epic.name, "name".to_owned());
-        assert_eq!(epic.description, "description".to_owned());
+        let popped = nav.pop_page().unwrap();
+        assert!(
+            popped
+                .as_any()
+                .downcast_ref::<ProjectSelectPage>()
+                .is_some()
+        );
+        assert_eq!(nav.get_page_count(), 1);
     }
 
     #[test]
-    fn handle_action_should_handle_update_epic() {
-        let db = Rc::new(JiraDatabase {
-            database: Box::new(MockDB::new()),
-        });
-        let epic_id = db
-            .create_epic(Epic::new("".to_owned(), "".to_owned()))
-            .unwrap();
-
+    fn replace_top_page_should_not_grow_the_history() {
+        let db = Rc::new(JiraDatabase::with_database(Box::new(MockDB::new())));
         let mut nav = Navigator::new(Rc::clone(&db));
+        nav.push_page(Box::new(BacklogPage { db: Rc::clone(&db) }));
 
-        let mut prompts = Prompts::new();
-        prompts.update_status = Box::new(|| Some(Status::InProgress));
-
-        nav.set_prompts(prompts);
+        nav.replace_top_page(Box::new(SprintPage { db }));
 
-        nav.handle_action(Action::UpdateEpicStatus { epic_id })
-            .unwrap();
+        assert_eq!(nav.history_len(), 2);
+        assert!(nav.get_current_page().unwrap().as_any().is::<SprintPage>());
 
-        let db_state = db.read_db().unwrap();
-        assert_eq!(
-            db_state.epics.get(&epic_id).unwrap().status,
-            Status::InProgress
-        );
+        nav.pop_page();
+        assert!(nav.get_current_page().unwrap().as_any().is::<HomePage>());
     }
 
     #[test]
-    fn handle_action_should_handle_delete_epic() {
-        let db = Rc::new(JiraDatabase {
-            database: Box::new(MockDB::new()),
-        });
-        let epic_id = db
-            .create_epic(Epic::new("".to_owned(), "".to_owned()))
-            .unwrap();
+    fn global_home_shortcut_should_return_to_home_page() {
+        let db = Rc::new(JiraDatabase::with_database(Box::new(MockDB::new())));
 
-        let mut nav = Navigator::new(Rc::clone(&db));
+        let mut nav = Navigator::new(db);
 
-        let mut prompts = Prompts::new();
-        prompts.delete_epic = Box::new(|| true);
+        nav.handle_action(Action::NavigateToEpicDetail { epic_id: 1 })
+            .unwrap();
+        nav.handle_action(Action::NavigateToStoryDetail {
+            epic_id: 1,
+            story_id: 2,
+        })
+        .unwrap();
 
-        nav.set_prompts(prompts);
+        let action = crate::ui::handle_global_input("h").unwrap();
+        nav.handle_action(action).unwrap();
 
-        nav.handle_action(Action::DeleteEpic { epic_id }).unwrap();
+        assert_eq!(nav.get_page_count(), 1);
 
-        let db_state = db.read_db().unwrap();
-        assert_eq!(db_state.epics.len(), 0);
+        let current_page = nav.get_current_page().unwrap();
+        let home_page = current_page.as_any().downcast_ref::<HomePage>();
+        assert!(home_page.is_some());
     }
 
     #[test]
-    fn handle_action_should_handle_create_story() {
-        let db = Rc::new(JiraDatabase {
-            database: Box::new(MockDB::new()),
-        });
+    fn global_search_shortcut_should_open_search_page_from_epic_detail() {
+        let db = Rc::new(JiraDatabase::with_database(Box::new(MockDB::new())));
         let epic_id = db
-            .create_epic(Epic::new("".to_owned(), "".to_owned()))
+            .create_epic(Epic::new("epic".to_owned(), "".to_owned()))
             .unwrap();
 
         let mut nav = Navigator::new(Rc::clone(&db));
+        nav.handle_action(Action::NavigateToEpicDetail { epic_id })
+            .unwrap();
 
-        let mut prompts = Prompts::new();
-        prompts.create_story = Box::new(|| Story::new("name".to_owned(), "description".to_owned()));
+        let action = crate::ui::handle_global_input("/needle").unwrap();
+        nav.handle_action(action).unwrap();
 
-        nav.set_prompts(prompts);
+        assert_eq!(nav.get_page_count(), 3);
+        let current_page = nav.get_current_page().unwrap();
+        let search_page = current_page.as_any().downcast_ref::<SearchPage>().unwrap();
+        assert_eq!(search_page.query, "needle");
+    }
 
-        nav.handle_action(Action::CreateStory { epic_id }).unwrap();
+    #[test]
+    fn handle_action_should_open_search_page() {
+        let db = Rc::new(JiraDatabase::with_database(Box::new(MockDB::new())));
 
-        let db_state = db.read_db().unwrap();
-        assert_eq!(db_state.stories.len(), 1);
+        let mut nav = Navigator::new(db);
+
+        let mut prompts = Prompts::new();
+        prompts.search_query = Box::new(|| Some("prompted".to_owned()));
+        nav.set_prompts(prompts);
+
+        let current_query = |nav: &Navigator| {
+            nav.get_current_page()
+                .unwrap()
+                .as_any()
+                .downcast_ref::<SearchPage>()
+                .unwrap()
+                .query
+                .clone()
+        };
+
+        nav.handle_action(Action::Search {
+            query: "typed".to_owned(),
+        })
+        .unwrap();
+        assert_eq!(nav.get_page_count(), 2);
+        assert_eq!(current_query(&nav), "typed");
+
+        nav.handle_action(Action::Search {
+            query: String::new(),
+        })
+        .unwrap();
+        assert_eq!(nav.get_page_count(), 3);
+        assert_eq!(current_query(&nav), "prompted");
+    }
+
+    #[test]
+    fn handle_action_should_toggle_watched_stories() {
+        let db = Rc::new(JiraDatabase::with_database(Box::new(MockDB::new())));
+        let epic_id = db
+            .create_epic(Epic::new("epic".to_owned(), "".to_owned()))
+            .unwrap();
+        let story_id = db
+            .create_story(Story::new("story".to_owned(), "".to_owned()), epic_id)
+            .unwrap();
+
+        let mut nav = Navigator::new(db);
+        let watched = |nav: &Navigator| {
+            nav.get_current_page()
+                .unwrap()
+                .as_any()
+                .downcast_ref::<WatchListPage>()
+                .unwrap()
+                .watch_list
+                .borrow()
+                .story_ids()
+        };
+
+        nav.handle_action(Action::ToggleWatch { story_id }).unwrap();
+        nav.handle_action(Action::NavigateToWatchList).unwrap();
+        assert_eq!(watched(&nav), vec![story_id]);
+
+        // The page shares the list, so it sees later changes too
+        nav.handle_action(Action::ToggleWatch { story_id }).unwrap();
+        assert!(watched(&nav).is_empty());
+    }
+
+    #[test]
+    fn handle_action_should_navigate_to_the_dashboard() {
+        let db = Rc::new(JiraDatabase::with_database(Box::new(MockDB::new())));
+
+        let mut nav = Navigator::new(db);
+
+        nav.handle_action(Action::NavigateToDashboard).unwrap();
+        assert_eq!(nav.get_page_count(), 2);
+
+        let current_page = nav.get_current_page().unwrap();
+        assert!(
+            current_page
+                .as_any()
+                .downcast_ref::<DashboardPage>()
+                .is_some()
+        );
+    }
+
+    #[test]
+    fn import_page_should_import_the_typed_csv_file_and_return_home() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("backlog.csv");
+        std::fs::write(
+            &path,
+            "id,name,description,status,priority\n\
+             1,Billing,,OPEN,HIGH\n\
+             id,epic_id,name,description,status,estimate\n\
+             2,1,Pay,,OPEN,3\n\
+             3,99,Orphan,,OPEN,\n",
+        )
+        .unwrap();
+        let db = Rc::new(JiraDatabase::with_database(Box::new(MockDB::new())));
+
+        let mut nav = Navigator::new(Rc::clone(&db));
+        nav.handle_action(Action::NavigateToImport).unwrap();
+        let action = nav
+            .get_current_page()
+            .unwrap()
+            .handle_input(path.to_str().unwrap())
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            action,
+            Action::ImportCsv {
+                path: path.to_str().unwrap().to_owned()
+            }
+        );
+
+        let message = nav.handle_action(action).unwrap();
+        assert_eq!(
+            message.as_deref(),
+            Some("Imported 1 epics, 1 stories, 1 skipped")
+        );
+        assert_eq!(nav.get_page_count(), 1);
+        assert!(
+            nav.get_current_page()
+                .unwrap()
+                .as_any()
+                .downcast_ref::<HomePage>()
+                .is_some()
+        );
+
+        let db_state = db.read_db().unwrap();
+        assert_eq!(db_state.epics[&1].name, "Billing");
+        assert_eq!(db_state.stories[&2].name, "Pay");
+    }
+
+    #[test]
+    fn import_csv_should_stay_on_the_import_page_when_the_file_is_missing() {
+        let db = Rc::new(JiraDatabase::with_database(Box::new(MockDB::new())));
+
+        let mut nav = Navigator::new(Rc::clone(&db));
+        nav.handle_action(Action::NavigateToImport).unwrap();
+
+        let result = nav.handle_action(Action::ImportCsv {
+            path: "no/such/file.csv".to_owned(),
+        });
+        assert!(result.is_err());
+        assert_eq!(nav.get_page_count(), 2);
+        assert!(db.read_db().unwrap().epics.is_empty());
+    }
+
+    #[test]
+    fn can_go_back_should_need_a_page_under_the_current_one() {
+        let db = Rc::new(JiraDatabase::with_database(Box::new(MockDB::new())));
+
+        let mut nav = Navigator::new(Rc::clone(&db));
+        assert_eq!(nav.history_len(), 1);
+        assert!(!nav.can_go_back());
+
+        nav.push_page(Box::new(BacklogPage { db: Rc::clone(&db) }));
+        nav.push_page(Box::new(SprintPage { db }));
+        assert_eq!(nav.history_len(), 3);
+        assert!(nav.can_go_back());
+
+        nav.pop_page();
+        nav.pop_page();
+        assert!(!nav.can_go_back());
+    }
+
+    #[test]
+    fn handle_action_should_show_help_for_the_page() {
+        let db = Rc::new(JiraDatabase::with_database(Box::new(MockDB::new())));
+
+        let mut nav = Navigator::new(db);
+
+        nav.handle_action(Action::ShowHelp {
+            context: PageType::Home,
+        })
+        .unwrap();
+
+        assert_eq!(nav.get_page_count(), 2);
+        let help_page = nav.get_current_page().unwrap();
+        let help_page = help_page.as_any().downcast_ref::<HelpPage>().unwrap();
+        assert_eq!(help_page.context, PageType::Home);
+    }
+
+    #[test]
+    fn handle_action_should_navigate_to_reporter_stories() {
+        let db = Rc::new(JiraDatabase::with_database(Box::new(MockDB::new())));
+
+        let mut nav = Navigator::new(db);
+
+        let mut prompts = Prompts::new();
+        prompts.reporter = Box::new(|| Some("prompted".to_owned()));
+        nav.set_prompts(prompts);
+
+        let current_reporter = |nav: &Navigator| {
+            nav.get_current_page()
+                .unwrap()
+                .as_any()
+                .downcast_ref::<ReporterPage>()
+                .unwrap()
+                .reporter
+                .clone()
+        };
+
+        nav.handle_action(Action::NavigateToReporterStories {
+            reporter: Some("typed".to_owned()),
+        })
+        .unwrap();
+        assert_eq!(nav.get_page_count(), 2);
+        assert_eq!(current_reporter(&nav), "typed");
+
+        nav.handle_action(Action::NavigateToReporterStories { reporter: None })
+            .unwrap();
+        assert_eq!(nav.get_page_count(), 3);
+        assert_eq!(current_reporter(&nav), "prompted");
+    }
+
+    #[test]
+    fn handle_action_should_update_story_release() {
+        let db = Rc::new(JiraDatabase::with_database(Box::new(MockDB::new())));
+        let epic_id = db
+            .create_epic(Epic::new("epic".to_owned(), "".to_owned()))
+            .unwrap();
+        let story_id = db
+            .create_story(Story::new("story".to_owned(), "".to_owned()), epic_id)
+            .unwrap();
+
+        let mut nav = Navigator::new(Rc::clone(&db));
+
+        let mut prompts = Prompts::new();
+        prompts.update_release = Box::new(|_| Some("1.0".to_owned()));
+        nav.set_prompts(prompts);
+
+        nav.handle_action(Action::UpdateStoryRelease {
+            story_id,
+            release: None,
+        })
+        .unwrap();
+        assert_eq!(
+            db.read_db().unwrap().stories[&story_id].release.as_deref(),
+            Some("1.0")
+        );
+
+        nav.handle_action(Action::NavigateToReleases).unwrap();
+        nav.handle_action(Action::NavigateToReleaseDetail {
+            release: "1.0".to_owned(),
+        })
+        .unwrap();
+        assert_eq!(nav.get_page_count(), 3);
+        let page = nav.get_current_page().unwrap();
+        let page = page.as_any().downcast_ref::<ReleaseDetail>().unwrap();
+        assert_eq!(page.release, "1.0");
+    }
+
+    #[test]
+    fn handle_action_should_clear_the_release_when_the_answer_is_blank() {
+        let db = Rc::new(JiraDatabase::with_database(Box::new(MockDB::new())));
+        let epic_id = db
+            .create_epic(Epic::new("epic".to_owned(), "".to_owned()))
+            .unwrap();
+        let story_id = db
+            .create_story(Story::new("story".to_owned(), "".to_owned()), epic_id)
+            .unwrap();
+        db.update_story_release(story_id, Some("1.0".to_owned()))
+            .unwrap();
+
+        let mut nav = Navigator::new(Rc::clone(&db));
+
+        let mut prompts = Prompts::new();
+        prompts.update_release = Box::new(|_| Some(String::new()));
+        nav.set_prompts(prompts);
+
+        let message = nav
+            .handle_action(Action::UpdateStoryRelease {
+                story_id,
+                release: None,
+            })
+            .unwrap();
+        assert_eq!(
+            message,
+            Some(format!("Story #{} removed from its release", story_id))
+        );
+        assert_eq!(db.read_db().unwrap().stories[&story_id].release, None);
+    }
+
+    #[test]
+    fn handle_action_should_record_the_reporter_of_new_stories() {
+        let db = Rc::new(JiraDatabase::with_database(Box::new(MockDB::new())));
+        let epic_id = db
+            .create_epic(Epic::new("epic".to_owned(), "".to_owned()))
+            .unwrap();
+        db.set_audit_user("alice");
+
+        let mut nav = Navigator::new(Rc::clone(&db));
+
+        let mut prompts = Prompts::new();
+        prompts.create_story = Box::new(|reporter| {
+            let mut story = Story::new("name".to_owned(), "".to_owned());
+            story.reporter = reporter.to_owned();
+            story
+        });
+        nav.set_prompts(prompts);
+
+        nav.handle_action(Action::CreateStory { epic_id }).unwrap();
+
+        let story = db.read_db().unwrap().stories.into_values().next().unwrap();
+        assert_eq!(story.reporter, "alice");
+    }
+
+    #[test]
+    fn handle_action_should_record_the_reporter_of_new_epics() {
+        let db = Rc::new(JiraDatabase::with_database(Box::new(MockDB::new())));
+        db.set_audit_user("bob");
+
+        let mut nav = Navigator::new_with_prompts(Rc::clone(&db), Prompts::default_mock());
+        nav.handle_action(Action::CreateEpic).unwrap();
+
+        let epics = db.get_epics_by_reporter("bob").unwrap();
+        assert_eq!(epics.len(), 1);
+        assert_eq!(epics[0].1.name, "Mock Epic");
+    }
+
+    #[test]
+    fn handle_action_should_sort_home_page() {
+        let db = Rc::new(JiraDatabase::with_database(Box::new(MockDB::new())));
+
+        let mut nav = Navigator::new(db);
+
+        let mut prompts = Prompts::new();
+        prompts.sort_key = Box::new(|| Some(SortKey::DueDate));
+        nav.set_prompts(prompts);
+
+        let sort = |nav: &Navigator| {
+            let page = nav.get_current_page().unwrap();
+            let home_page = page.as_any().downcast_ref::<HomePage>().unwrap();
+            (home_page.sort_key, home_page.sort_dir)
+        };
+
+        assert_eq!(sort(&nav), (SortKey::Id, SortDir::Ascending));
+
+        nav.handle_action(Action::SortHomePage {
+            key: Some(SortKey::Priority),
+        })
+        .unwrap();
+        assert_eq!(nav.get_page_count(), 1);
+        assert_eq!(sort(&nav), (SortKey::Priority, SortDir::Ascending));
+
+        nav.handle_action(Action::ReverseHomePageSort).unwrap();
+        assert_eq!(sort(&nav), (SortKey::Priority, SortDir::Descending));
+
+        nav.handle_action(Action::SortHomePage { key: None })
+            .unwrap();
+        assert_eq!(sort(&nav), (SortKey::DueDate, SortDir::Descending));
+
+        nav.handle_action(Action::NavigateToEpicDetail { epic_id: 1 })
+            .unwrap();
+        assert!(nav.handle_action(Action::ReverseHomePageSort).is_err());
+        assert_eq!(nav.get_page_count(), 2);
+    }
+
+    #[test]
+    fn handle_action_should_sort_epic_detail_stories() {
+        let db = Rc::new(JiraDatabase::with_database(Box::new(MockDB::new())));
+        let epic_id = db
+            .create_epic(Epic::new("epic".to_owned(), "".to_owned()))
+            .unwrap();
+
+        let mut nav = Navigator::new(db);
+
+        let mut prompts = Prompts::new();
+        prompts.story_sort_key = Box::new(|| Some(StorySortKey::Estimate));
+        nav.set_prompts(prompts);
+
+        nav.handle_action(Action::NavigateToEpicDetail { epic_id })
+            .unwrap();
+
+        let sort = |nav: &Navigator| {
+            let page = nav.get_current_page().unwrap();
+            let epic_detail = page.as_any().downcast_ref::<EpicDetail>().unwrap();
+            (epic_detail.sort_key, epic_detail.sort_dir)
+        };
+
+        assert_eq!(sort(&nav), (StorySortKey::Status, SortDir::Ascending));
+
+        nav.handle_action(Action::SortEpicStories { key: None })
+            .unwrap();
+        nav.handle_action(Action::ReverseEpicStoriesSort).unwrap();
+        assert_eq!(nav.get_page_count(), 2);
+        assert_eq!(sort(&nav), (StorySortKey::Estimate, SortDir::Descending));
+
+        // The preference belongs to the page, so a new one starts afresh
+        nav.handle_action(Action::NavigateToPreviousPage).unwrap();
+        nav.handle_action(Action::NavigateToEpicDetail { epic_id })
+            .unwrap();
+        assert_eq!(sort(&nav), (StorySortKey::Status, SortDir::Ascending));
+    }
+
+    #[test]
+    fn handle_action_should_filter_home_page_by_tag() {
+        let db = Rc::new(JiraDatabase::with_database(Box::new(MockDB::new())));
+        let mut nav = Navigator::new(db);
+
+        let mut prompts = Prompts::new();
+        prompts.filter_tag = Box::new(|| Some("prompted".to_owned()));
+        nav.set_prompts(prompts);
+
+        let active_tag = |nav: &Navigator| {
+            nav.get_current_page()
+                .unwrap()
+                .as_any()
+                .downcast_ref::<HomePage>()
+                .unwrap()
+                .active_tag
+                .clone()
+        };
+
+        nav.handle_action(Action::FilterHomePageByTag { tag: None })
+            .unwrap();
+        assert_eq!(active_tag(&nav), Some("prompted".to_owned()));
+
+        nav.handle_action(Action::FilterHomePageByTag {
+            tag: Some("backend".to_owned()),
+        })
+        .unwrap();
+        assert_eq!(active_tag(&nav), Some("backend".to_owned()));
+        assert_eq!(nav.get_page_count(), 1);
+
+        nav.handle_action(Action::ClearHomePageTagFilter).unwrap();
+        assert_eq!(active_tag(&nav), None);
+    }
+
+    #[test]
+    fn handle_action_should_page_through_epics_on_home_page() {
+        let db = Rc::new(JiraDatabase::with_database(Box::new(MockDB::new())));
+        for i in 0..25 {
+            db.create_epic(Epic::new(format!("epic {}", i), "".to_owned()))
+                .unwrap();
+        }
+
+        let mut nav = Navigator::new(db);
+
+        let page = |nav: &Navigator| {
+            nav.get_current_page()
+                .unwrap()
+                .as_any()
+                .downcast_ref::<HomePage>()
+                .unwrap()
+                .page
+        };
+
+        nav.handle_action(Action::PreviousListPage).unwrap();
+        assert_eq!(page(&nav), 0);
+
+        nav.handle_action(Action::NextPage).unwrap();
+        nav.handle_action(Action::NextPage).unwrap();
+        assert_eq!(page(&nav), 2);
+
+        // 25 epics at 10 per page only fill three pages.
+        nav.handle_action(Action::NextPage).unwrap();
+        assert_eq!(page(&nav), 2);
+
+        nav.handle_action(Action::PreviousListPage).unwrap();
+        assert_eq!(page(&nav), 1);
+
+        nav.handle_action(Action::ReverseHomePageSort).unwrap();
+        assert_eq!(page(&nav), 0);
+        assert_eq!(nav.get_page_count(), 1);
+    }
+
+    #[test]
+    fn handle_action_should_page_through_stories_on_epic_detail() {
+        let db = Rc::new(JiraDatabase::with_database(Box::new(MockDB::new())));
+        let epic_id = db
+            .create_epic(Epic::new("epic".to_owned(), "".to_owned()))
+            .unwrap();
+        let story_ids: Vec<u32> = (0..45)
+            .map(|i| {
+                db.create_story(Story::new(format!("story {}", i), "".to_owned()), epic_id)
+                    .unwrap()
+            })
+            .collect();
+
+        let mut nav = Navigator::new(db);
+        nav.handle_action(Action::NavigateToEpicDetail { epic_id })
+            .unwrap();
+
+        let page = |nav: &Navigator| {
+            nav.get_current_page()
+                .unwrap()
+                .as_any()
+                .downcast_ref::<EpicDetail>()
+                .unwrap()
+                .page
+        };
+
+        nav.handle_action(Action::NextPage).unwrap();
+        nav.handle_action(Action::NextPage).unwrap();
+        assert_eq!(page(&nav), 2);
+
+        // 45 stories at 20 per page only fill three pages.
+        nav.handle_action(Action::NextPage).unwrap();
+        assert_eq!(page(&nav), 2);
+
+        nav.handle_action(Action::PreviousListPage).unwrap();
+        assert_eq!(page(&nav), 1);
+        assert_eq!(nav.get_page_count(), 2);
+
+        // Opening a story scrolls the epic to it, ready for the way back
+        nav.handle_action(Action::NavigateToStoryDetail {
+            epic_id,
+            story_id: story_ids[2],
+        })
+        .unwrap();
+        nav.handle_action(Action::NavigateToPreviousPage).unwrap();
+        assert_eq!(page(&nav), 0);
+
+        nav.handle_action(Action::NavigateToStoryDetail {
+            epic_id,
+            story_id: story_ids[44],
+        })
+        .unwrap();
+        nav.handle_action(Action::NavigateToPreviousPage).unwrap();
+        assert_eq!(page(&nav), 2);
+    }
+
+    #[test]
+    fn handle_action_should_clear_pages_on_exit() {
+        let db = Rc::new(JiraDatabase::with_database(Box::new(MockDB::new())));
+
+        let mut nav = Navigator::new(db);
+
+        nav.handle_action(Action::NavigateToEpicDetail { epic_id: 1 })
+            .unwrap();
+        nav.handle_action(Action::NavigateToStoryDetail {
+            epic_id: 1,
+            story_id: 2,
+        })
+        .unwrap();
+        nav.handle_action(Action::Exit).unwrap();
+
+        assert_eq!(nav.get_page_count(), 0);
+    }
+
+    #[test]
+    fn handle_action_should_not_write_db_on_exit() {
+        let mock = MockDB::new();
+        let write_count = mock.write_count();
+        let db = Rc::new(JiraDatabase::with_database(Box::new(mock)));
+
+        let mut nav = Navigator::new(db);
+
+        nav.handle_action(Action::NavigateToEpicDetail { epic_id: 1 })
+            .unwrap();
+        nav.handle_action(Action::Exit).unwrap();
+
+        assert_eq!(write_count.get(), 0);
+        assert_eq!(nav.get_page_count(), 0);
+    }
+
+    #[test]
+    fn handle_action_should_handle_create_epic() {
+        let db = Rc::new(JiraDatabase::with_database(Box::new(MockDB::new())));
+
+        let mut nav = Navigator::new(Rc::clone(&db));
+
+        let mut prompts = Prompts::new();
+        prompts.create_epic = Box::new(|_| Epic::new("name".to_owned(), "description".to_owned()));
+
+        nav.set_prompts(prompts);
+
+        nav.handle_action(Action::CreateEpic).unwrap();
+
+        let db_state = db.read_db().unwrap();
+        assert_eq!(db_state.epics.len(), 1);
+
+        let epic = db_state.epics.into_iter().next().unwrap().1;
+        assert_eq!(
+            epic,
+            Epic {
+                created_at: epic.created_at,
+                updated_at: epic.updated_at,
+                ..Epic::new("name".to_owned(), "description".to_owned())
+            }
+        );
+    }
+
+    #[test]
+    fn handle_action_should_handle_update_epic() {
+        let db = Rc::new(JiraDatabase::with_database(Box::new(MockDB::new())));
+        let epic_id = db
+            .create_epic(Epic::new("epic".to_owned(), "".to_owned()))
+            .unwrap();
+
+        let mut nav = Navigator::new(Rc::clone(&db));
+
+        let mut prompts = Prompts::new();
+        prompts.update_status = Box::new(|| Some(Status::InProgress));
+
+        nav.set_prompts(prompts);
+
+        nav.handle_action(Action::UpdateEpicStatus { epic_id })
+            .unwrap();
+
+        let db_state = db.read_db().unwrap();
+        assert_eq!(
+            db_state.epics.get(&epic_id).unwrap().status,
+            Status::InProgress
+        );
+    }
+
+    #[test]
+    fn handle_action_should_handle_update_epic_name_and_description() {
+        let original = Epic::new("name".to_owned(), "description".to_owned());
+        let db = Rc::new(JiraDatabase::with_database(Box::new(MockDB::new())));
+        let epic_id = db.create_epic(original.clone()).unwrap();
+
+        let mut nav = Navigator::new(Rc::clone(&db));
+
+        nav.handle_action(Action::UpdateEpicName {
+            epic_id,
+            name: Some("new name".to_owned()),
+        })
+        .unwrap();
+
+        let epic = db.read_db().unwrap().epics.remove(&epic_id).unwrap();
+        assert_eq!(
+            epic,
+            Epic {
+                name: "new name".to_owned(),
+                updated_at: epic.updated_at,
+                ..original.clone()
+            }
+        );
+
+        nav.handle_action(Action::UpdateEpicDescription {
+            epic_id,
+            description: Some("new description".to_owned()),
+        })
+        .unwrap();
+
+        let epic = db.read_db().unwrap().epics.remove(&epic_id).unwrap();
+        assert_eq!(
+            epic,
+            Epic {
+                name: "new name".to_owned(),
+                description: "new description".to_owned(),
+                updated_at: epic.updated_at,
+                ..original.clone()
+            }
+        );
+    }
+
+    #[test]
+    fn handle_action_should_handle_delete_epic() {
+        let db = Rc::new(JiraDatabase::with_database(Box::new(MockDB::new())));
+        let epic_id = db
+            .create_epic(Epic::new("epic".to_owned(), "".to_owned()))
+            .unwrap();
+
+        let mut nav = Navigator::new(Rc::clone(&db));
+
+        let mut prompts = Prompts::new();
+        prompts.delete_epic = Box::new(|| true);
+
+        nav.set_prompts(prompts);
+
+        nav.handle_action(Action::DeleteEpic { epic_id }).unwrap();
+
+        let db_state = db.read_db().unwrap();
+        assert_eq!(db_state.epics.len(), 0);
+    }
+
+    #[test]
+    fn handle_action_should_clone_story() {
+        let db = Rc::new(JiraDatabase::with_database(Box::new(MockDB::new())));
+        let epic_id = db
+            .create_epic(Epic::new("epic".to_owned(), "".to_owned()))
+            .unwrap();
+        let story_id = db
+            .create_story(Story::new("story".to_owned(), "".to_owned()), epic_id)
+            .unwrap();
+
+        let mut nav = Navigator::new(Rc::clone(&db));
+
+        let message = nav.handle_action(Action::CloneStory { story_id }).unwrap();
+
+        assert_eq!(message, Some("Story #2 cloned as #3".to_owned()));
+        assert_eq!(db.read_db().unwrap().stories[&3].name, "[COPY] story");
+    }
+
+    #[test]
+    fn handle_action_should_move_story_and_follow_it() {
+        let db = Rc::new(JiraDatabase::with_database(Box::new(MockDB::new())));
+        let source_id = db
+            .create_epic(Epic::new("source".to_owned(), "".to_owned()))
+            .unwrap();
+        let story_id = db
+            .create_story(Story::new("story".to_owned(), "".to_owned()), source_id)
+            .unwrap();
+        let target_id = db
+            .create_epic(Epic::new("target".to_owned(), "".to_owned()))
+            .unwrap();
+
+        let mut nav = Navigator::new(Rc::clone(&db));
+
+        let mut prompts = Prompts::new();
+        prompts.move_story = Box::new(move || Some(target_id));
+        nav.set_prompts(prompts);
+
+        nav.handle_action(Action::NavigateToStoryDetail {
+            epic_id: source_id,
+            story_id,
+        })
+        .unwrap();
+        nav.handle_action(Action::MoveStory {
+            story_id,
+            target_epic_id: None,
+        })
+        .unwrap();
+
+        assert_eq!(
+            db.read_db().unwrap().epics[&target_id].stories,
+            vec![story_id]
+        );
+        assert_eq!(nav.get_page_count(), 2);
+        let story_page = nav.get_current_page().unwrap();
+        let story_page = story_page.as_any().downcast_ref::<StoryDetail>().unwrap();
+        assert_eq!(story_page.epic_id, target_id);
+    }
+
+    #[test]
+    fn handle_action_should_add_and_delete_comments() {
+        let db = Rc::new(JiraDatabase::with_database(Box::new(MockDB::new())));
+        let epic_id = db
+            .create_epic(Epic::new("epic".to_owned(), "".to_owned()))
+            .unwrap();
+        let story_id = db
+            .create_story(Story::new("story".to_owned(), "".to_owned()), epic_id)
+            .unwrap();
+        db.set_audit_user("alice");
+
+        let mut nav = Navigator::new(Rc::clone(&db));
+
+        let mut prompts = Prompts::new();
+        prompts.add_comment = Box::new(|| Some("looks good".to_owned()));
+        prompts.delete_comment = Box::new(|| Some(1));
+        nav.set_prompts(prompts);
+
+        nav.handle_action(Action::NavigateToComments { story_id })
+            .unwrap();
+        assert!(
+            nav.get_current_page()
+                .unwrap()
+                .as_any()
+                .downcast_ref::<CommentsPage>()
+                .is_some()
+        );
+
+        let message = nav.handle_action(Action::AddComment { story_id }).unwrap();
+        assert_eq!(
+            message,
+            Some(format!("Comment #1 added to story #{}", story_id))
+        );
+        let comments = db.read_db().unwrap().stories[&story_id].comments.clone();
+        assert_eq!(comments.len(), 1);
+        assert_eq!(comments[0].author, "alice");
+        assert_eq!(comments[0].text, "looks good");
+
+        nav.handle_action(Action::DeleteComment { story_id })
+            .unwrap();
+        assert!(db.read_db().unwrap().stories[&story_id].comments.is_empty());
+    }
+
+    #[test]
+    fn handle_action_should_handle_create_story() {
+        let db = Rc::new(JiraDatabase::with_database(Box::new(MockDB::new())));
+        let epic_id = db
+            .create_epic(Epic::new("epic".to_owned(), "".to_owned()))
+            .unwrap();
+
+        let mut nav = Navigator::new(Rc::clone(&db));
+
+        let mut prompts = Prompts::new();
+        prompts.create_story =
+            Box::new(|_| Story::new("name".to_owned(), "description".to_owned()));
+
+        nav.set_prompts(prompts);
+
+        nav.handle_action(Action::CreateStory { epic_id }).unwrap();
+
+        let db_state = db.read_db().unwrap();
+        assert_eq!(db_state.stories.len(), 1);
 
         let story = db_state.stories.into_iter().next().unwrap().1;
-        assert_eq!(story.name, "name".to_owned());
-        assert_eq!(story.description, "description".to_owned());
+        assert_eq!(
+            story,
+            Story {
+                created_at: story.created_at,
+                updated_at: story.updated_at,
+                ..Story::new("name".to_owned(), "description".to_owned())
+            }
+        );
+    }
+
+    #[test]
+    fn handle_action_should_handle_update_story() {
+        let db = Rc::new(JiraDatabase::with_database(Box::new(MockDB::new())));
+        let epic_id = db
+            .create_epic(Epic::new("epic".to_owned(), "".to_owned()))
+            .unwrap();
+        let story_id = db
+            .create_story(Story::new("story".to_owned(), "".to_owned()), epic_id)
+            .unwrap();
+
+        let mut nav = Navigator::new(Rc::clone(&db));
+
+        let mut prompts = Prompts::new();
+        prompts.update_status = Box::new(|| Some(Status::InProgress));
+
+        nav.set_prompts(prompts);
+
+        nav.handle_action(Action::UpdateStoryStatus { story_id })
+            .unwrap();
+
+        let db_state = db.read_db().unwrap();
+        assert_eq!(
+            db_state.stories.get(&story_id).unwrap().status,
+            Status::InProgress
+        );
+    }
+
+    #[test]
+    fn handle_action_should_handle_update_story_type() {
+        let db = Rc::new(JiraDatabase::with_database(Box::new(MockDB::new())));
+        let epic_id = db
+            .create_epic(Epic::new("epic".to_owned(), "".to_owned()))
+            .unwrap();
+        let story_id = db
+            .create_story(Story::new("story".to_owned(), "".to_owned()), epic_id)
+            .unwrap();
+
+        let mut nav = Navigator::new(Rc::clone(&db));
+
+        let mut prompts = Prompts::new();
+        prompts.update_story_type = Box::new(|| Some(StoryType::Chore));
+        nav.set_prompts(prompts);
+
+        let message = nav
+            .handle_action(Action::UpdateStoryType {
+                story_id,
+                story_type: None,
+            })
+            .unwrap();
+
+        assert_eq!(message, Some(format!("Story #{} is now a CHORE", story_id)));
+        assert_eq!(
+            db.read_db().unwrap().stories[&story_id].story_type,
+            StoryType::Chore
+        );
+    }
+
+    #[test]
+    fn handle_action_should_handle_update_story_estimate() {
+        let db = Rc::new(JiraDatabase::with_database(Box::new(MockDB::new())));
+        let epic_id = db
+            .create_epic(Epic::new("epic".to_owned(), "".to_owned()))
+            .unwrap();
+        let story_id = db
+            .create_story(Story::new("story".to_owned(), "".to_owned()), epic_id)
+            .unwrap();
+
+        let mut nav = Navigator::new(Rc::clone(&db));
+
+        let mut prompts = Prompts::new();
+        prompts.update_estimate = Box::new(|_| Some(8));
+
+        nav.set_prompts(prompts);
+
+        let message = nav
+            .handle_action(Action::UpdateStoryEstimate { story_id })
+            .unwrap();
+
+        assert_eq!(
+            message,
+            Some(format!("Story #{} estimated at 8 points", story_id))
+        );
+        let db_state = db.read_db().unwrap();
+        assert_eq!(db_state.stories.get(&story_id).unwrap().estimate, Some(8));
+    }
+
+    #[test]
+    fn handle_action_should_handle_update_due_dates() {
+        let db = Rc::new(JiraDatabase::with_database(Box::new(MockDB::new())));
+        let epic_id = db
+            .create_epic(Epic::new("epic".to_owned(), "".to_owned()))
+            .unwrap();
+        let story_id = db
+            .create_story(Story::new("story".to_owned(), "".to_owned()), epic_id)
+            .unwrap();
+        let prompted_date = NaiveDate::from_ymd_opt(2025, 6, 30).unwrap();
+        let given_date = NaiveDate::from_ymd_opt(2025, 7, 1).unwrap();
+
+        let mut nav = Navigator::new(Rc::clone(&db));
+
+        let mut prompts = Prompts::new();
+        prompts.update_due_date = Box::new(move |_| Some(prompted_date));
+
+        nav.set_prompts(prompts);
+
+        let message = nav
+            .handle_action(Action::UpdateEpicDueDate {
+                epic_id,
+                date: None,
+            })
+            .unwrap();
+        assert_eq!(
+            message,
+            Some(format!("Epic #{} is due 2025-06-30", epic_id))
+        );
+        nav.handle_action(Action::UpdateStoryDueDate {
+            story_id,
+            date: Some(given_date),
+        })
+        .unwrap();
+
+        let db_state = db.read_db().unwrap();
+        assert_eq!(db_state.epics[&epic_id].due_date, Some(prompted_date));
+        assert_eq!(db_state.stories[&story_id].due_date, Some(given_date));
+    }
+
+    #[test]
+    fn handle_action_should_link_and_unlink_story_dependencies() {
+        let db = Rc::new(JiraDatabase::with_database(Box::new(MockDB::new())));
+        let epic_id = db
+            .create_epic(Epic::new("epic".to_owned(), "".to_owned()))
+            .unwrap();
+        let blocker_id = db
+            .create_story(Story::new("blocker".to_owned(), "".to_owned()), epic_id)
+            .unwrap();
+        let blocked_id = db
+            .create_story(Story::new("blocked".to_owned(), "".to_owned()), epic_id)
+            .unwrap();
+
+        let mut nav = Navigator::new(Rc::clone(&db));
+
+        let mut prompts = Prompts::new();
+        prompts.link_story_dependency = Box::new(move || Some(blocked_id));
+        prompts.unlink_story_dependency = Box::new(move || Some(blocker_id));
+        nav.set_prompts(prompts);
+
+        nav.handle_action(Action::LinkStoryDependency {
+            story_id: blocker_id,
+        })
+        .unwrap();
+        assert_eq!(
+            db.read_db().unwrap().stories[&blocked_id].blocked_by,
+            vec![blocker_id]
+        );
+
+        // Unlinking from the blocked story's side removes the same link
+        nav.handle_action(Action::UnlinkStoryDependency {
+            story_id: blocked_id,
+        })
+        .unwrap();
+        let db_state = db.read_db().unwrap();
+        assert!(db_state.stories[&blocker_id].blocks.is_empty());
+        assert!(db_state.stories[&blocked_id].blocked_by.is_empty());
     }
 
     #[test]
-    fn handle_action_should_handle_update_story() {
-        let db = Rc::new(JiraDatabase {
-            database: Box::new(MockDB::new()),
-        });
+    fn handle_action_should_handle_update_story_name_and_description() {
+        let original = Story::new("name".to_owned(), "description".to_owned());
+        let db = Rc::new(JiraDatabase::with_database(Box::new(MockDB::new())));
+        let epic_id = db
+            .create_epic(Epic::new("epic".to_owned(), "".to_owned()))
+            .unwrap();
+        let story_id = db.create_story(original.clone(), epic_id).unwrap();
+
+        let mut nav = Navigator::new(Rc::clone(&db));
+
+        nav.handle_action(Action::UpdateStoryName {
+            story_id,
+            name: Some("new name".to_owned()),
+        })
+        .unwrap();
+
+        let story = db.read_db().unwrap().stories.remove(&story_id).unwrap();
+        assert_eq!(
+            story,
+            Story {
+                name: "new name".to_owned(),
+                updated_at: story.updated_at,
+                ..original.clone()
+            }
+        );
+
+        nav.handle_action(Action::UpdateStoryDescription {
+            story_id,
+            description: Some("new description".to_owned()),
+        })
+        .unwrap();
+
+        let story = db.read_db().unwrap().stories.remove(&story_id).unwrap();
+        assert_eq!(
+            story,
+            Story {
+                name: "new name".to_owned(),
+                description: "new description".to_owned(),
+                updated_at: story.updated_at,
+                ..original.clone()
+            }
+        );
+    }
+
+    #[test]
+    fn handle_action_should_prompt_for_a_story_name_and_description_left_out() {
+        let original = Story::new("name".to_owned(), "description".to_owned());
+        let db = Rc::new(JiraDatabase::with_database(Box::new(MockDB::new())));
+        let epic_id = db
+            .create_epic(Epic::new("epic".to_owned(), "".to_owned()))
+            .unwrap();
+        let story_id = db.create_story(original.clone(), epic_id).unwrap();
+
+        let mut nav = Navigator::new(Rc::clone(&db));
+
+        // The prompts start from the current text
+        let mut prompts = Prompts::new();
+        prompts.update_name = Box::new(|current| Some(format!("new {}", current)));
+        prompts.update_description = Box::new(|current| Some(format!("new {}", current)));
+        nav.set_prompts(prompts);
+
+        nav.handle_action(Action::UpdateStoryName {
+            story_id,
+            name: None,
+        })
+        .unwrap();
+
+        let story = db.read_db().unwrap().stories.remove(&story_id).unwrap();
+        assert_eq!(
+            story,
+            Story {
+                name: "new name".to_owned(),
+                updated_at: story.updated_at,
+                ..original.clone()
+            }
+        );
+
+        nav.handle_action(Action::UpdateStoryDescription {
+            story_id,
+            description: None,
+        })
+        .unwrap();
+
+        let story = db.read_db().unwrap().stories.remove(&story_id).unwrap();
+        assert_eq!(
+            story,
+            Story {
+                name: "new name".to_owned(),
+                description: "new description".to_owned(),
+                updated_at: story.updated_at,
+                ..original.clone()
+            }
+        );
+    }
+
+    #[test]
+    fn handle_action_should_handle_delete_story() {
+        let db = Rc::new(JiraDatabase::with_database(Box::new(MockDB::new())));
         let epic_id = db
-            .create_epic(Epic::new("".to_owned(), "".to_owned()))
+            .create_epic(Epic::new("epic".to_owned(), "".to_owned()))
             .unwrap();
         let story_id = db
-            .create_story(Story::new("".to_owned(), "".to_owned()), epic_id)
+            .create_story(Story::new("story".to_owned(), "".to_owned()), epic_id)
             .unwrap();
 
         let mut nav = Navigator::new(Rc::clone(&db));
 
         let mut prompts = Prompts::new();
-        prompts.update_status = Box::new(|| Some(Status::InProgress));
+        prompts.delete_story = Box::new(|| true);
 
         nav.set_prompts(prompts);
 
-        nav.handle_action(Action::UpdateStoryStatus { story_id })
+        nav.handle_action(Action::DeleteStory { epic_id, story_id })
+            .unwrap();
+
+        let db_state = db.read_db().unwrap();
+        assert_eq!(db_state.stories.len(), 0);
+    }
+
+    #[test]
+    fn handle_action_should_batch_delete_closed_stories_from_an_epic() {
+        let (db, mut nav) = mocked_navigator();
+        let epic_id = db
+            .create_epic(Epic::new("epic".to_owned(), "".to_owned()))
+            .unwrap();
+        let mut story_ids = vec![];
+        for _ in 0..3 {
+            story_ids.push(
+                db.create_story(Story::new("story".to_owned(), "".to_owned()), epic_id)
+                    .unwrap(),
+            );
+        }
+        db.update_story_status(story_ids[0], Status::Closed)
+            .unwrap();
+        db.update_story_status(story_ids[2], Status::Closed)
+            .unwrap();
+        nav.handle_action(Action::NavigateToEpicDetail { epic_id })
             .unwrap();
 
+        let action = nav.get_current_page().unwrap().handle_input("D").unwrap();
+        let message = nav.handle_action(action.unwrap()).unwrap();
+
+        assert_eq!(message.as_deref(), Some("2 stories deleted"));
+        assert!(nav.current_page_is::<EpicDetail>());
         let db_state = db.read_db().unwrap();
+        assert!(!db_state.stories.contains_key(&story_ids[0]));
+        assert!(!db_state.stories.contains_key(&story_ids[2]));
+        assert_eq!(db_state.epics[&epic_id].stories, vec![story_ids[1]]);
+
+        let declining = Prompts {
+            delete_stories: Box::new(|_| false),
+            ..Prompts::default_mock()
+        };
+        nav.set_prompts(declining);
+        let message = nav
+            .handle_action(Action::BatchDeleteStories {
+                story_ids: vec![story_ids[1]],
+            })
+            .unwrap();
+        assert_eq!(message, None);
+        assert!(db.read_db().unwrap().stories.contains_key(&story_ids[1]));
+
+        let message = nav
+            .handle_action(Action::BatchDeleteStories { story_ids: vec![] })
+            .unwrap();
+        assert_eq!(message.as_deref(), Some("There are no stories to delete"));
+    }
+
+    #[test]
+    fn handle_action_should_keep_a_bounded_action_history() {
+        let db = Rc::new(JiraDatabase::with_database(Box::new(MockDB::new())));
+        let mut nav = Navigator::new(db);
+
+        for epic_id in 0..5 {
+            nav.handle_action(Action::NavigateToEpicDetail { epic_id })
+                .unwrap();
+        }
+
+        assert_eq!(nav.action_history().len(), 5);
         assert_eq!(
-            db_state.stories.get(&story_id).unwrap().status,
-            Status::InProgress
+            nav.last_action(),
+            Some(&Action::NavigateToEpicDetail { epic_id: 4 })
+        );
+
+        for epic_id in 5..25 {
+            nav.handle_action(Action::NavigateToEpicDetail { epic_id })
+                .unwrap();
+            assert!(nav.action_history().len() <= 20);
+        }
+
+        assert_eq!(nav.action_history().len(), 20);
+        assert_eq!(
+            nav.action_history().front(),
+            Some(&Action::NavigateToEpicDetail { epic_id: 5 })
+        );
+        assert_eq!(
+            nav.last_action(),
+            Some(&Action::NavigateToEpicDetail { epic_id: 24 })
         );
     }
 
     #[test]
-    fn handle_action_should_handle_delete_story() {
-        let db = Rc::new(JiraDatabase {
-            database: Box::new(MockDB::new()),
+    fn handle_action_should_not_record_failed_actions() {
+        let db = Rc::new(JiraDatabase::with_database(Box::new(MockDB::new())));
+        let mut nav = Navigator::new(db);
+
+        let mut prompts = Prompts::new();
+        prompts.update_status = Box::new(|| Some(Status::Closed));
+        nav.set_prompts(prompts);
+
+        let result = nav.handle_action(Action::UpdateEpicStatus { epic_id: 999 });
+
+        assert!(result.is_err());
+        assert_eq!(nav.last_action(), None);
+    }
+
+    #[test]
+    fn handle_action_should_manage_sprints() {
+        let db = Rc::new(JiraDatabase::with_database(Box::new(MockDB::new())));
+        let epic_id = db
+            .create_epic(Epic::new("epic".to_owned(), "".to_owned()))
+            .unwrap();
+        let story_id = db
+            .create_story(Story::new("story".to_owned(), "".to_owned()), epic_id)
+            .unwrap();
+
+        let mut nav = Navigator::new(Rc::clone(&db));
+
+        let mut prompts = Prompts::new();
+        prompts.create_sprint = Box::new(|| {
+            Some(Sprint::new(
+                "sprint 1".to_owned(),
+                NaiveDate::from_ymd_opt(2025, 6, 2).unwrap(),
+                NaiveDate::from_ymd_opt(2025, 6, 13).unwrap(),
+            ))
         });
+        prompts.assign_sprint_story = Box::new(move || Some(story_id));
+        prompts.delete_sprint = Box::new(|| true);
+        nav.set_prompts(prompts);
+
+        nav.handle_action(Action::NavigateToSprintList).unwrap();
+        assert!(
+            nav.get_current_page()
+                .unwrap()
+                .as_any()
+                .downcast_ref::<SprintPage>()
+                .is_some()
+        );
+
+        nav.handle_action(Action::CreateSprint).unwrap();
+        let sprint_id = *db.read_db().unwrap().sprints.keys().next().unwrap();
+
+        nav.handle_action(Action::NavigateToSprintDetail { sprint_id })
+            .unwrap();
+        nav.handle_action(Action::AssignStoryToSprint {
+            sprint_id,
+            story_id: None,
+        })
+        .unwrap();
+        assert_eq!(db.get_sprint(sprint_id).unwrap().story_ids, vec![story_id]);
+
+        nav.handle_action(Action::DeleteSprint { sprint_id })
+            .unwrap();
+        assert!(db.read_db().unwrap().sprints.is_empty());
+        assert!(
+            nav.get_current_page()
+                .unwrap()
+                .as_any()
+                .downcast_ref::<SprintPage>()
+                .is_some()
+        );
+    }
+
+    #[test]
+    fn handle_action_should_assign_a_backlog_story_without_prompting() {
+        let db = Rc::new(JiraDatabase::with_database(Box::new(MockDB::new())));
         let epic_id = db
-            .create_epic(Epic::new("".to_owned(), "".to_owned()))
+            .create_epic(Epic::new("epic".to_owned(), "".to_owned()))
             .unwrap();
         let story_id = db
-            .create_story(Story::new("".to_owned(), "".to_owned()), epic_id)
+            .create_story(Story::new("story".to_owned(), "".to_owned()), epic_id)
+            .unwrap();
+        let sprint_id = db
+            .create_sprint(Sprint::new(
+                "sprint 1".to_owned(),
+                NaiveDate::from_ymd_opt(2025, 6, 2).unwrap(),
+                NaiveDate::from_ymd_opt(2025, 6, 13).unwrap(),
+            ))
             .unwrap();
 
         let mut nav = Navigator::new(Rc::clone(&db));
 
         let mut prompts = Prompts::new();
-        prompts.delete_story = Box::new(|| true);
+        prompts.assign_sprint_story = Box::new(|| panic!("should not prompt"));
+        nav.set_prompts(prompts);
+
+        nav.handle_action(Action::NavigateToBacklog).unwrap();
+        assert!(
+            nav.get_current_page()
+                .unwrap()
+                .as_any()
+                .downcast_ref::<BacklogPage>()
+                .is_some()
+        );
+
+        nav.handle_action(Action::AssignStoryToSprint {
+            sprint_id,
+            story_id: Some(story_id),
+        })
+        .unwrap();
+
+        assert!(db.backlog_stories().unwrap().is_empty());
+    }
 
+    #[test]
+    fn handle_action_should_switch_projects_and_return_home() {
+        let db = Rc::new(JiraDatabase::with_database(Box::new(MockDB::new())));
+        let mut nav = Navigator::new(Rc::clone(&db));
+
+        nav.handle_action(Action::NavigateToProjectSelect).unwrap();
+        assert_eq!(nav.get_page_count(), 2);
+        assert!(
+            nav.get_current_page()
+                .unwrap()
+                .as_any()
+                .downcast_ref::<ProjectSelectPage>()
+                .is_some()
+        );
+
+        let mut prompts = Prompts::new();
+        prompts.create_project = Box::new(|| Some("side project".to_owned()));
         nav.set_prompts(prompts);
 
-        nav.handle_action(Action::DeleteStory { epic_id, story_id })
+        nav.handle_action(Action::CreateProject).unwrap();
+        assert_eq!(nav.get_page_count(), 1);
+        assert_eq!(db.active_project().unwrap(), "side project");
+
+        nav.handle_action(Action::SwitchProject {
+            name: "default".to_owned(),
+        })
+        .unwrap();
+        assert_eq!(db.active_project().unwrap(), "default");
+    }
+
+    #[test]
+    fn handle_action_should_export_the_current_page() {
+        let db = Rc::new(JiraDatabase::with_database(Box::new(MockDB::new())));
+        db.create_epic(Epic::new("exported epic".to_owned(), "".to_owned()))
+            .unwrap();
+        let mut nav = Navigator::new(db);
+
+        let dir = tempfile::tempdir().unwrap();
+        let dest = dir.path().join("home.txt");
+
+        let prompt_dest = dest.clone();
+        let mut prompts = Prompts::new();
+        prompts.export_destination = Box::new(move |_| prompt_dest.clone());
+        nav.set_prompts(prompts);
+
+        nav.handle_action(Action::ExportCurrentPage { dest: None })
+            .unwrap();
+
+        let contents = std::fs::read_to_string(&dest).unwrap();
+        assert_eq!(
+            contents,
+            nav.get_current_page().unwrap().render_to_string().unwrap()
+        );
+        assert!(contents.contains("exported epic"));
+    }
+
+    #[test]
+    fn handle_action_should_refresh_with_changes_made_elsewhere() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("db.json");
+        std::fs::write(
+            &path,
+            r#"{ "last_item_id": 0, "epics": {}, "stories": {} }"#,
+        )
+        .unwrap();
+        let file_path = path.to_str().unwrap().to_owned();
+
+        let mut nav = Navigator::new(Rc::new(JiraDatabase::new(file_path.clone())));
+        let draw_home =
+            |nav: &Navigator| nav.get_current_page().unwrap().render_to_string().unwrap();
+        assert!(!draw_home(&nav).contains("outside epic"));
+
+        std::thread::spawn(move || {
+            JiraDatabase::new(file_path)
+                .create_epic(Epic::new("outside epic".to_owned(), "".to_owned()))
+                .unwrap();
+        })
+        .join()
+        .unwrap();
+        assert!(!draw_home(&nav).contains("outside epic"));
+
+        let action = crate::ui::handle_global_input("R").unwrap();
+        nav.handle_action(action).unwrap();
+
+        assert_eq!(nav.history_len(), 1);
+        assert!(draw_home(&nav).contains("outside epic"));
+    }
+
+    #[test]
+    fn handle_action_should_keep_changes_made_elsewhere_on_exit() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("db.json");
+        std::fs::write(
+            &path,
+            r#"{ "last_item_id": 0, "epics": {}, "stories": {} }"#,
+        )
+        .unwrap();
+        let file_path = path.to_str().unwrap().to_owned();
+
+        let db = Rc::new(JiraDatabase::new(file_path.clone()));
+        let mut nav = Navigator::new(Rc::clone(&db));
+        assert!(db.read_db().unwrap().epics.is_empty());
+
+        let other_file_path = file_path.clone();
+        std::thread::spawn(move || {
+            JiraDatabase::new(other_file_path)
+                .create_epic(Epic::new("outside epic".to_owned(), "".to_owned()))
+                .unwrap();
+        })
+        .join()
+        .unwrap();
+
+        nav.handle_action(Action::Exit).unwrap();
+
+        let state = JiraDatabase::new(file_path).read_db().unwrap();
+        assert_eq!(state.epics.len(), 1);
+    }
+
+    #[test]
+    fn handle_action_should_report_a_status_message() {
+        let db = Rc::new(JiraDatabase::with_database(Box::new(MockDB::new())));
+        let mut nav = Navigator::new(Rc::clone(&db));
+
+        let mut prompts = Prompts::new();
+        prompts.create_epic = Box::new(|_| Epic::new("epic".to_owned(), "".to_owned()));
+        nav.set_prompts(prompts);
+
+        let message = nav.handle_action(Action::CreateEpic).unwrap();
+        let epic_id = *db.read_db().unwrap().epics.keys().next().unwrap();
+
+        assert_eq!(message, Some(format!("Epic #{} created", epic_id)));
+        assert_eq!(nav.last_message(), message.as_deref());
+
+        let message = nav
+            .handle_action(Action::NavigateToEpicDetail { epic_id })
+            .unwrap();
+
+        assert_eq!(message, None);
+        assert_eq!(nav.last_message(), None);
+    }
+
+    fn mocked_navigator() -> (Rc<JiraDatabase>, Navigator) {
+        let db = Rc::new(JiraDatabase::with_database(Box::new(MockDB::new())));
+        let nav = Navigator::new_with_prompts(Rc::clone(&db), Prompts::default_mock());
+        (db, nav)
+    }
+
+    #[test]
+    fn mocked_prompts_should_create_and_delete_an_epic() {
+        let (db, mut nav) = mocked_navigator();
+
+        nav.handle_action(Action::CreateEpic).unwrap();
+        let (epic_id, epic) = db.read_db().unwrap().epics.into_iter().next().unwrap();
+        assert_eq!(epic.name, "Mock Epic");
+
+        nav.handle_action(Action::NavigateToEpicDetail { epic_id })
+            .unwrap();
+        nav.handle_action(Action::DeleteEpic { epic_id }).unwrap();
+
+        assert!(db.read_db().unwrap().epics.is_empty());
+        assert_eq!(nav.get_page_count(), 1);
+    }
+
+    #[test]
+    fn mocked_prompts_should_update_an_epic_through_its_prompts() {
+        let (db, mut nav) = mocked_navigator();
+        nav.handle_action(Action::CreateEpic).unwrap();
+        let epic_id = *db.read_db().unwrap().epics.keys().next().unwrap();
+
+        nav.handle_action(Action::UpdateEpicStatus { epic_id })
+            .unwrap();
+        nav.handle_action(Action::UpdateEpicName {
+            epic_id,
+            name: None,
+        })
+        .unwrap();
+        nav.handle_action(Action::UpdateEpicDescription {
+            epic_id,
+            description: None,
+        })
+        .unwrap();
+        nav.handle_action(Action::UpdateEpicDueDate {
+            epic_id,
+            date: None,
+        })
+        .unwrap();
+
+        let epic = &db.read_db().unwrap().epics[&epic_id];
+        assert_eq!(epic.status, Status::InProgress);
+        assert_eq!(epic.name, "Mock Name");
+        assert_eq!(epic.description, "Mock description");
+        assert_eq!(epic.due_date, NaiveDate::from_ymd_opt(2030, 1, 1));
+    }
+
+    #[test]
+    fn mocked_prompts_should_create_update_and_delete_a_story() {
+        let (db, mut nav) = mocked_navigator();
+        nav.handle_action(Action::CreateEpic).unwrap();
+        let epic_id = *db.read_db().unwrap().epics.keys().next().unwrap();
+
+        nav.handle_action(Action::CreateStory { epic_id }).unwrap();
+        let story_id = db.read_db().unwrap().epics[&epic_id].stories[0];
+
+        nav.handle_action(Action::UpdateStoryStatus { story_id })
             .unwrap();
+        nav.handle_action(Action::UpdateStoryEstimate { story_id })
+            .unwrap();
+        nav.handle_action(Action::UpdateStoryType {
+            story_id,
+            story_type: None,
+        })
+        .unwrap();
+
+        let story = db.read_db().unwrap().stories[&story_id].clone();
+        assert_eq!(story.name, "Mock Story");
+        assert_eq!(story.status, Status::InProgress);
+        assert_eq!(story.estimate, Some(3));
+        assert_eq!(story.story_type, StoryType::Bug);
 
+        nav.handle_action(Action::DeleteStory { epic_id, story_id })
+            .unwrap();
         let db_state = db.read_db().unwrap();
-        assert_eq!(db_state.stories.len(), 0);
+        assert!(db_state.stories.is_empty());
+        assert!(db_state.epics[&epic_id].stories.is_empty());
+    }
+
+    #[test]
+    fn mocked_prompts_should_comment_on_a_story() {
+        let (db, mut nav) = mocked_navigator();
+        nav.handle_action(Action::CreateEpic).unwrap();
+        let epic_id = *db.read_db().unwrap().epics.keys().next().unwrap();
+        nav.handle_action(Action::CreateStory { epic_id }).unwrap();
+        let story_id = db.read_db().unwrap().epics[&epic_id].stories[0];
+
+        nav.handle_action(Action::NavigateToComments { story_id })
+            .unwrap();
+        nav.handle_action(Action::AddComment { story_id }).unwrap();
+
+        let comments = &db.read_db().unwrap().stories[&story_id].comments;
+        assert_eq!(comments.len(), 1);
+        assert_eq!(comments[0].text, "Mock comment");
+
+        // Without an id to pick, nothing is deleted
+        nav.handle_action(Action::DeleteComment { story_id })
+            .unwrap();
+        assert_eq!(db.read_db().unwrap().stories[&story_id].comments.len(), 1);
+    }
+
+    #[test]
+    fn mocked_prompts_should_create_and_delete_a_sprint() {
+        let (db, mut nav) = mocked_navigator();
+
+        nav.handle_action(Action::NavigateToSprintList).unwrap();
+        nav.handle_action(Action::CreateSprint).unwrap();
+        let (sprint_id, sprint) = db.read_db().unwrap().sprints.into_iter().next().unwrap();
+        assert_eq!(sprint.name, "Mock Sprint");
+
+        nav.handle_action(Action::NavigateToSprintDetail { sprint_id })
+            .unwrap();
+        nav.handle_action(Action::DeleteSprint { sprint_id })
+            .unwrap();
+        assert!(db.read_db().unwrap().sprints.is_empty());
+    }
+
+    #[test]
+    fn navigator_should_track_the_session() {
+        let db = Rc::new(JiraDatabase::with_database(Box::new(MockDB::new())));
+        let mut nav = Navigator::new(db);
+
+        std::thread::sleep(Duration::from_millis(5));
+        assert!(nav.session_duration() >= Duration::from_millis(5));
+        assert_eq!(nav.actions_this_session(), 0);
+
+        nav.handle_action(Action::NavigateToEpicDetail { epic_id: 1 })
+            .unwrap();
+        nav.handle_action(Action::NavigateToPreviousPage).unwrap();
+
+        assert_eq!(nav.actions_this_session(), 2);
     }
 }
@@ -1,5 +1,7 @@
 mod pages;
 mod prompts;
+mod theme;
 
 pub use pages::*;
 pub use prompts::*;
+pub use theme::*;
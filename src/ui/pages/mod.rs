@@ -1,91 +1,310 @@
 use std::any::Any;
+use std::cell::{Cell, RefCell};
+use std::collections::HashMap;
 use std::rc::Rc;
 
 use anyhow::Result;
 use anyhow::anyhow;
+use chrono::{Local, Utc};
 use itertools::Itertools;
 
-use crate::db::JiraDatabase;
-use crate::models::Action;
+use crate::db::{JiraDatabase, SearchQuery};
+use crate::models::{Action, Epic, PageType, SortDir, SortKey, Status, Story, StorySortKey};
+use crate::ui::Theme;
+use crate::watch_list::WatchList;
 
-mod page_helpers;
+pub(crate) mod page_helpers;
 use page_helpers::*;
-use crate::constants::APP_NAME;
+use crate::constants::{APP_NAME, DASHBOARD_ACTIVITY_LIMIT, EPIC_DETAIL_PAGE_SIZE, HOME_PAGE_SIZE};
+use ironyy_derive::PageBoilerplate;
 
-pub trait Page {
-    fn draw_page(&self) -> Result<()>;
-    fn handle_input(&self, input: &str) -> Result<Option<Action>>;
+// Marks an epic or story that is past its due date
+const OVERDUE_BADGE: &str = "[OVERDUE]";
 
-    #[cfg(test)]
+/// Implemented with `#[derive(PageBoilerplate)]` from `ironyy_derive`.
+pub trait PageBoilerplate {
+    fn page_name(&self) -> &'static str;
     fn as_any(&self) -> &dyn Any;
 }
 
+/// Terminal settings that affect how a page is drawn but not what it shows.
+pub struct DrawContext {
+    pub theme: Theme,
+    pub width: usize,
+    /// Whether the navigator has a page under this one
+    pub can_go_back: bool,
+    /// How many lines the last command bar drawn with this context took
+    pub command_bar_rows: Cell<usize>,
+}
+
+impl DrawContext {
+    pub fn for_terminal() -> Self {
+        Self {
+            theme: Theme::from_env(),
+            width: terminal_width(),
+            can_go_back: true,
+            command_bar_rows: Cell::new(0),
+        }
+    }
+
+    pub fn plain() -> Self {
+        Self {
+            theme: Theme::plain(),
+            width: DEFAULT_TERMINAL_WIDTH,
+            can_go_back: true,
+            command_bar_rows: Cell::new(0),
+        }
+    }
+
+    /// A page's command bar, led by `[p] previous` when there is a page to
+    /// go back to, and wrapped onto as many lines as the width needs.
+    pub fn command_bar(&self, commands: &str) -> Vec<String> {
+        let commands = match (self.can_go_back, commands.is_empty()) {
+            (true, true) => "[p] previous".to_owned(),
+            (true, false) => format!("[p] previous | {}", commands),
+            (false, _) => commands.to_owned(),
+        };
+        self.wrap_command_bar(&commands)
+    }
+
+    /// Wraps `commands` like `command_bar`, but without `[p] previous`, for
+    /// pages where `p` means something else.
+    pub fn wrap_command_bar(&self, commands: &str) -> Vec<String> {
+        let lines = wrap_commands(commands, self.width);
+        self.command_bar_rows.set(lines.len());
+        lines
+    }
+}
+
+pub trait Page: PageBoilerplate {
+    fn title(&self) -> String {
+        self.page_name().to_owned()
+    }
+
+    /// How the page is named in the breadcrumb, e.g. "Epic #1"
+    fn page_title(&self) -> String;
+
+    fn draw_page(&self, ctx: &DrawContext) -> Result<Vec<String>>;
+    fn handle_input(&self, input: &str) -> Result<Option<Action>>;
+
+    fn render_to_string(&self) -> Result<String> {
+        // Rendered text ends up in files, so leave out color codes
+        Ok(self.draw_page(&DrawContext::plain())?.join("\n"))
+    }
+
+    fn suggested_export_filename(&self) -> String {
+        format!(
+            "{}_{}.txt",
+            self.title(),
+            Utc::now().format("%Y%m%d_%H%M%S")
+        )
+    }
+
+    fn global_shortcuts(&self) -> Vec<(&'static str, &'static str)> {
+        vec![
+            ("h", "home"),
+            ("x", "export page"),
+            ("R", "refresh"),
+            ("/", "search"),
+            ("?", "help"),
+        ]
+    }
+
+    // (id, name) pairs offered as completions while typing an id
+    fn input_suggestions(&self) -> Result<Vec<(String, String)>> {
+        Ok(vec![])
+    }
+}
+
+pub fn handle_global_input(input: &str) -> Option<Action> {
+    match input {
+        "H" | "h" => Some(Action::NavigateToHomePage),
+        "X" | "x" => Some(Action::ExportCurrentPage { dest: None }),
+        // Case-sensitive, since several pages use `r` for their own commands
+        "R" => Some(Action::Refresh),
+        input if input.starts_with('/') => Some(Action::Search {
+            query: input[1..].trim().to_owned(),
+        }),
+        _ => None,
+    }
+}
+
+#[derive(PageBoilerplate)]
 pub struct RegistrationPage;
 
 impl Page for RegistrationPage {
-    fn draw_page(&self) -> Result<()> {
-        println!("--------------------------- REGISTER ---------------------------");
-        println!(
+    fn title(&self) -> String {
+        "register".to_owned()
+    }
+
+    fn page_title(&self) -> String {
+        "Register".to_owned()
+    }
+
+    fn draw_page(&self, _ctx: &DrawContext) -> Result<Vec<String>> {
+        Ok(vec![
+            "--------------------------- REGISTER ---------------------------".to_owned(),
             "Please enter your desired username or press ENTER to login to an existing account:"
-        );
-        Ok(())
+                .to_owned(),
+        ])
     }
 
     fn handle_input(&self, input: &str) -> Result<Option<Action>> {
         todo!();
     }
-
-    #[cfg(test)]
-    fn as_any(&self) -> &dyn Any {
-        self
-    }
 }
 
+#[derive(PageBoilerplate)]
 pub struct LoginPage;
 
 impl Page for LoginPage {
-    fn draw_page(&self) -> Result<()> {
-        println!("----------------------------- LOGIN -----------------------------");
-        println!("Please enter your username or press ENTER to create a new account:");
-        Ok(())
+    fn title(&self) -> String {
+        "login".to_owned()
     }
 
-    fn handle_input(&self, input: &str) -> Result<Option<Action>> {
-        todo!();
+    fn page_title(&self) -> String {
+        "Login".to_owned()
     }
 
-    #[cfg(test)]
-    fn as_any(&self) -> &dyn Any {
-        self
+    fn draw_page(&self, _ctx: &DrawContext) -> Result<Vec<String>> {
+        Ok(vec![
+            "----------------------------- LOGIN -----------------------------".to_owned(),
+            "Please enter your username or press ENTER to create a new account:".to_owned(),
+        ])
+    }
+
+    fn handle_input(&self, input: &str) -> Result<Option<Action>> {
+        todo!();
     }
 }
 
+#[derive(PageBoilerplate, Clone)]
 pub struct HomePage {
     pub db: Rc<JiraDatabase>,
+    pub sort_key: SortKey,
+    pub sort_dir: SortDir,
+    pub active_tag: Option<String>,
+    pub page: usize,
+    pub page_size: usize,
+}
+
+impl HomePage {
+    pub fn new(db: Rc<JiraDatabase>) -> Self {
+        Self {
+            db,
+            sort_key: SortKey::default(),
+            sort_dir: SortDir::default(),
+            active_tag: None,
+            page: 0,
+            page_size: HOME_PAGE_SIZE,
+        }
+    }
+
+    /// Ids of the epics to list across all pages, after the tag filter and
+    /// in display order.
+    pub fn epic_ids(&self, epics: &HashMap<u32, Epic>, stories: &HashMap<u32, Story>) -> Vec<u32> {
+        let ids = epics
+            .keys()
+            .copied()
+            .filter(|id| {
+                self.active_tag
+                    .as_ref()
+                    .is_none_or(|tag| epics[id].has_tag(tag))
+            })
+            .sorted();
+
+        // The sort is stable, so epics with equal keys stay in id order
+        ids.sorted_by(|a_id, b_id| {
+            let (a, b) = (&epics[a_id], &epics[b_id]);
+            let ordering = match self.sort_key {
+                SortKey::Id => a_id.cmp(b_id),
+                SortKey::Name => a.name.to_lowercase().cmp(&b.name.to_lowercase()),
+                SortKey::Status => a.status.cmp(&b.status),
+                SortKey::Priority => a.priority.cmp(&b.priority),
+                SortKey::DueDate => {
+                    return cmp_missing_last(a.due_date, b.due_date, self.sort_dir);
+                }
+                SortKey::CompletionPct => a
+                    .completion_percentage(stories)
+                    .total_cmp(&b.completion_percentage(stories)),
+            };
+            self.sort_dir.apply(ordering)
+        })
+        .collect()
+    }
 }
+
 impl Page for HomePage {
-    fn draw_page(&self) -> Result<()> {
-        println!("Welcome to {}!", APP_NAME);
-        println!();
-        println!("----------------------------- EPICS -----------------------------");
-        println!("     id     |               name               |      status      ");
+    fn title(&self) -> String {
+        "home".to_owned()
+    }
 
-        let epics = self.db.read_db()?.epics;
+    fn page_title(&self) -> String {
+        "Epics".to_owned()
+    }
+
+    fn draw_page(&self, ctx: &DrawContext) -> Result<Vec<String>> {
+        let widths = column_widths(ctx.width, &[11, 32, 16, 11, 8]);
+        let mut lines = vec![
+            format!("Welcome to {}!", APP_NAME),
+            format!("Project: {}", self.db.active_project()?),
+        ];
+        if let Some(tag) = &self.active_tag {
+            lines.push(format!("Tag: {}", tag));
+        }
+        lines.extend([
+            String::new(),
+            banner("EPICS", ctx.width),
+            header_row(&["id", "name", "status", "priority", "progress"], &widths),
+        ]);
+
+        let db_state = self.db.read_db()?;
+        let epics = db_state.epics;
+        let ids = self.epic_ids(&epics, &db_state.stories);
+
+        let today = Local::now().date_naive();
 
-        for id in epics.keys().sorted() {
+        for id in ids
+            .iter()
+            .skip(self.page * self.page_size)
+            .take(self.page_size)
+        {
             let epic = &epics[id];
-            let id_col = get_column_string(&id.to_string(), 11);
-            let name_col = get_column_string(&epic.name, 32);
-            let status_col = get_column_string(&epic.status.to_string(), 17);
-            println!("{} | {} | {}", id_col, name_col, status_col);
+            let columns = [
+                ColumnSpec::default_id(widths[0]).format(&id.to_string()),
+                ColumnSpec::default_name(widths[1])
+                    .format_with_badge(&epic.name, epic.is_overdue(today).then_some(OVERDUE_BADGE)),
+                ctx.theme.status_column(&epic.status, widths[2]),
+                ColumnSpec::left(widths[3]).format(&epic.priority.to_string()),
+                ColumnSpec::right(widths[4]).format(&format!(
+                    "{:.0}%",
+                    epic.completion_percentage(&db_state.stories)
+                )),
+            ];
+            lines.push(join_columns(&columns));
         }
 
-        println!();
-        println!();
+        lines.push(format!("Sorted by {} ({})", self.sort_key, self.sort_dir));
 
-        println!("[q] quit | [c] create epic | [:id:] navigate to epic");
+        if ids.len() > self.page_size {
+            let page_count = ids.len().div_ceil(self.page_size);
+            lines.push(format!("Page {} of {}", self.page + 1, page_count));
+            lines.push("[n] next page | [b] prev page".to_owned());
+        } else {
+            lines.push(String::new());
+        }
+
+        let tag_commands = if self.active_tag.is_some() {
+            "[t] filter by tag | [T] clear tag filter"
+        } else {
+            "[t] filter by tag"
+        };
+        lines.extend(ctx.wrap_command_bar(&format!(
+            "[q] quit | [c] create epic | [p] switch project | [r] sprints | [l] backlog | [v] releases | [W] watch list | [d] dashboard | [s] sort | [S] reverse sort | {} | [f] stories by reporter | [e] export csv | [m] export markdown | [I] import | [:id:] navigate to epic",
+            tag_commands
+        )));
 
-        Ok(())
+        Ok(lines)
     }
 
     fn handle_input(&self, input: &str) -> Result<Option<Action>> {
@@ -94,6 +313,33 @@ impl Page for HomePage {
         match input {
             "Q" | "q" => Ok(Some(Action::Exit)),
             "C" | "c" => Ok(Some(Action::CreateEpic)),
+            "P" | "p" => Ok(Some(Action::NavigateToProjectSelect)),
+            "?" => Ok(Some(Action::ShowHelp {
+                context: PageType::Home,
+            })),
+            "r" => Ok(Some(Action::NavigateToSprintList)),
+            "L" | "l" => Ok(Some(Action::NavigateToBacklog)),
+            "V" | "v" => Ok(Some(Action::NavigateToReleases)),
+            "W" | "w" => Ok(Some(Action::NavigateToWatchList)),
+            "D" | "d" => Ok(Some(Action::NavigateToDashboard)),
+            // Sorting is case-sensitive so that [S] can reverse it
+            "s" => Ok(Some(Action::SortHomePage { key: None })),
+            "S" => Ok(Some(Action::ReverseHomePageSort)),
+            "N" | "n" => Ok(Some(Action::NextPage)),
+            "B" | "b" => Ok(Some(Action::PreviousListPage)),
+            "E" | "e" => Ok(Some(Action::ExportCsv)),
+            "M" | "m" => Ok(Some(Action::ExportMarkdown)),
+            "I" | "i" => Ok(Some(Action::NavigateToImport)),
+            // Tag filtering is case-sensitive so that [T] can clear it
+            "t" => Ok(Some(Action::FilterHomePageByTag { tag: None })),
+            "T" => Ok(Some(Action::ClearHomePageTagFilter)),
+            input if input.starts_with("t ") => Ok(Some(Action::FilterHomePageByTag {
+                tag: Some(input[2..].trim().to_owned()).filter(|tag| !tag.is_empty()),
+            })),
+            "F" | "f" => Ok(Some(Action::NavigateToReporterStories { reporter: None })),
+            input if input.starts_with("f ") => Ok(Some(Action::NavigateToReporterStories {
+                reporter: Some(input[2..].trim().to_owned()).filter(|name| !name.is_empty()),
+            })),
             input => {
                 if let Ok(epic_id) = input.parse::<u32>() {
                     if epics.contains_key(&epic_id) {
@@ -105,57 +351,237 @@ impl Page for HomePage {
         }
     }
 
-    #[cfg(test)]
-    fn as_any(&self) -> &dyn Any {
-        self
+    fn input_suggestions(&self) -> Result<Vec<(String, String)>> {
+        let epics = self.db.read_db()?.epics;
+
+        Ok(epics
+            .keys()
+            .sorted()
+            .map(|id| (id.to_string(), epics[id].name.clone()))
+            .collect())
+    }
+}
+
+#[derive(PageBoilerplate)]
+pub struct ProjectSelectPage {
+    pub db: Rc<JiraDatabase>,
+}
+
+impl Page for ProjectSelectPage {
+    fn title(&self) -> String {
+        "projects".to_owned()
+    }
+
+    fn page_title(&self) -> String {
+        "Projects".to_owned()
+    }
+
+    fn draw_page(&self, ctx: &DrawContext) -> Result<Vec<String>> {
+        let active_project = self.db.active_project()?;
+        let projects = self.db.list_projects()?;
+
+        let mut lines = vec![
+            "---------------------------- PROJECTS ----------------------------".to_owned(),
+            "     #     |                        name                        ".to_owned(),
+        ];
+
+        for (index, name) in projects.iter().enumerate() {
+            let marker = if *name == active_project { "*" } else { " " };
+            let number_col = get_column_string(&format!("{}{}", marker, index + 1), 10);
+            let name_col = get_column_string(name, 52);
+            lines.push(format!("{} | {}", number_col, name_col));
+        }
+
+        lines.push(String::new());
+        lines.push(String::new());
+
+        lines.extend(ctx.command_bar("[n] new project | [:#:] switch to project"));
+
+        Ok(lines)
+    }
+
+    fn handle_input(&self, input: &str) -> Result<Option<Action>> {
+        let projects = self.db.list_projects()?;
+
+        match input {
+            "P" | "p" => Ok(Some(Action::NavigateToPreviousPage)),
+            "?" => Ok(Some(Action::ShowHelp {
+                context: PageType::ProjectSelect,
+            })),
+            "N" | "n" => Ok(Some(Action::CreateProject)),
+            input => Ok(input
+                .parse::<usize>()
+                .ok()
+                .and_then(|number| number.checked_sub(1))
+                .and_then(|index| projects.get(index))
+                .map(|name| Action::SwitchProject { name: name.clone() })),
+        }
     }
 }
 
+#[derive(PageBoilerplate, Clone)]
 pub struct EpicDetail {
     pub epic_id: u32,
     pub db: Rc<JiraDatabase>,
+    pub sort_key: StorySortKey,
+    pub sort_dir: SortDir,
+    pub page: usize,
+    pub page_size: usize,
+}
+
+impl EpicDetail {
+    pub fn new(epic_id: u32, db: Rc<JiraDatabase>) -> Self {
+        Self {
+            epic_id,
+            db,
+            sort_key: StorySortKey::default(),
+            sort_dir: SortDir::default(),
+            page: 0,
+            page_size: EPIC_DETAIL_PAGE_SIZE,
+        }
+    }
+
+    /// The page that lists `story_id`, if the epic has it.
+    pub fn page_of(
+        &self,
+        story_id: u32,
+        epic: &Epic,
+        stories: &HashMap<u32, Story>,
+    ) -> Option<usize> {
+        self.story_ids(epic, stories)
+            .iter()
+            .position(|id| *id == story_id)
+            .map(|index| index / self.page_size)
+    }
+
+    /// Ids of the epic's stories in display order. Missing stories sort
+    /// first so the warning lines are easy to spot.
+    pub fn story_ids(&self, epic: &Epic, stories: &HashMap<u32, Story>) -> Vec<u32> {
+        // The sort is stable, so stories with equal keys stay in id order
+        epic.stories
+            .iter()
+            .copied()
+            .sorted()
+            .sorted_by(|a_id, b_id| {
+                let (Some(a), Some(b)) = (stories.get(a_id), stories.get(b_id)) else {
+                    return stories.contains_key(a_id).cmp(&stories.contains_key(b_id));
+                };
+                let ordering = match self.sort_key {
+                    StorySortKey::Id => a_id.cmp(b_id),
+                    StorySortKey::Name => a.name.to_lowercase().cmp(&b.name.to_lowercase()),
+                    StorySortKey::Status => a.status.cmp(&b.status),
+                    StorySortKey::Estimate => {
+                        return cmp_missing_last(a.estimate, b.estimate, self.sort_dir);
+                    }
+                    StorySortKey::StoryType => a.story_type.cmp(&b.story_type),
+                };
+                self.sort_dir.apply(ordering)
+            })
+            .collect()
+    }
 }
 
 impl Page for EpicDetail {
-    fn draw_page(&self) -> Result<()> {
+    fn title(&self) -> String {
+        format!("epic_{}", self.epic_id)
+    }
+
+    fn page_title(&self) -> String {
+        format!("Epic #{}", self.epic_id)
+    }
+
+    fn draw_page(&self, ctx: &DrawContext) -> Result<Vec<String>> {
+        let mut lines = vec![];
+
         let db_state = self.db.read_db()?;
         let epic = db_state
             .epics
             .get(&self.epic_id)
             .ok_or_else(|| anyhow!("could not find epic!"))?;
 
-        println!("------------------------------ EPIC ------------------------------");
-        println!("  id  |     name     |         description         |    status    ");
+        let widths = column_widths(ctx.width, &[5, 12, 27, 12, 11]);
+        lines.push(banner("EPIC", ctx.width));
+        lines.push(header_row(
+            &["id", "name", "description", "status", "priority"],
+            &widths,
+        ));
+
+        let columns = [
+            ColumnSpec::default_id(widths[0]).format(&self.epic_id.to_string()),
+            ColumnSpec::default_name(widths[1]).format(&epic.name),
+            ColumnSpec::default_description(widths[2]).format(&epic.description),
+            ctx.theme.status_column(&epic.status, widths[3]),
+            ColumnSpec::left(widths[4]).format(&epic.priority.to_string()),
+        ];
+        lines.push(join_columns(&columns));
 
-        let id_col = get_column_string(&self.epic_id.to_string(), 5);
-        let name_col = get_column_string(&epic.name, 12);
-        let desc_col = get_column_string(&epic.description, 27);
-        let status_col = get_column_string(&epic.status.to_string(), 13);
-        println!("{} | {} | {} | {}", id_col, name_col, desc_col, status_col);
+        // The name column is too narrow here for the badge
+        let today = Local::now().date_naive();
+        if let Some(due_date) = epic.due_date {
+            if epic.is_overdue(today) {
+                lines.push(format!("Due: {} {}", due_date, OVERDUE_BADGE));
+            } else {
+                lines.push(format!("Due: {}", due_date));
+            }
+        }
+        if let Some(release) = &epic.release {
+            lines.push(format!("Release: {}", release));
+        }
+        lines.push(format!("Reporter: {}", epic.reporter));
 
-        println!();
+        lines.push(String::new());
 
-        println!("---------------------------- STORIES ----------------------------");
-        println!("     id     |               name               |      status      ");
+        let widths = column_widths(ctx.width, &[11, 32, 9, 16, 11]);
+        lines.push(banner("STORIES", ctx.width));
+        lines.push(header_row(
+            &["id", "name", "type", "status", "priority"],
+            &widths,
+        ));
 
         let stories = &db_state.stories;
 
-        for id in epic.stories.iter().sorted() {
-            let story = &stories[id];
-            let id_col = get_column_string(&id.to_string(), 11);
-            let name_col = get_column_string(&story.name, 32);
-            let status_col = get_column_string(&story.status.to_string(), 17);
-            println!("{} | {} | {}", id_col, name_col, status_col);
+        let ids = self.story_ids(epic, stories);
+
+        for id in ids
+            .iter()
+            .copied()
+            .skip(self.page * self.page_size)
+            .take(self.page_size)
+        {
+            let Some(story) = stories.get(&id) else {
+                lines.push(format!(
+                    "[!] Story #{} referenced but not found (run repair)",
+                    id
+                ));
+                continue;
+            };
+            let columns = [
+                ColumnSpec::default_id(widths[0]).format(&id.to_string()),
+                ColumnSpec::default_name(widths[1]).format_with_badge(
+                    &story.name,
+                    story.is_overdue(today).then_some(OVERDUE_BADGE),
+                ),
+                ColumnSpec::left(widths[2]).format(&story.story_type.to_string()),
+                ctx.theme.status_column(&story.status, widths[3]),
+                ColumnSpec::left(widths[4]).format(&story.priority.to_string()),
+            ];
+            lines.push(join_columns(&columns));
         }
 
-        println!();
-        println!();
+        lines.push(String::new());
+        lines.push(format!("Sorted by {} ({})", self.sort_key, self.sort_dir));
+
+        if ids.len() > self.page_size {
+            let page_count = ids.len().div_ceil(self.page_size);
+            lines.push(format!("Page {} of {}", self.page + 1, page_count));
+            lines.push("[>] next page | [<] prev page".to_owned());
+        }
 
-        println!(
-            "[p] previous | [u] update epic | [d] delete epic | [c] create story | [:id:] navigate to story"
+        lines.extend(
+            ctx.command_bar("[u] update epic | [n] rename | [e] edit description | [t] set due date | [r] set release | [d] delete epic | [D] delete closed stories | [c] create story | [s] sort | [S] reverse sort | [:id:] navigate to story"),
         );
 
-        Ok(())
+        Ok(lines)
     }
 
     fn handle_input(&self, input: &str) -> Result<Option<Action>> {
@@ -164,15 +590,59 @@ impl Page for EpicDetail {
 
         match input {
             "P" | "p" => Ok(Some(Action::NavigateToPreviousPage)),
+            "?" => Ok(Some(Action::ShowHelp {
+                context: PageType::EpicDetail,
+            })),
             "U" | "u" => Ok(Some(Action::UpdateEpicStatus {
                 epic_id: self.epic_id,
             })),
-            "D" | "d" => Ok(Some(Action::DeleteEpic {
+            "N" | "n" => Ok(Some(Action::UpdateEpicName {
+                epic_id: self.epic_id,
+                name: None,
+            })),
+            "E" | "e" => Ok(Some(Action::UpdateEpicDescription {
+                epic_id: self.epic_id,
+                description: None,
+            })),
+            "T" | "t" => Ok(Some(Action::UpdateEpicDueDate {
+                epic_id: self.epic_id,
+                date: None,
+            })),
+            "r" => Ok(Some(Action::UpdateEpicRelease {
+                epic_id: self.epic_id,
+                release: None,
+            })),
+            // Case-sensitive so that [D] can delete the closed stories
+            "d" => Ok(Some(Action::DeleteEpic {
                 epic_id: self.epic_id,
             })),
+            "D" => {
+                let story_ids = db_state
+                    .epics
+                    .get(&self.epic_id)
+                    .map(|epic| {
+                        epic.stories
+                            .iter()
+                            .copied()
+                            .filter(|id| {
+                                stories
+                                    .get(id)
+                                    .is_some_and(|story| story.status == Status::Closed)
+                            })
+                            .collect()
+                    })
+                    .unwrap_or_default();
+                Ok(Some(Action::BatchDeleteStories { story_ids }))
+            }
             "C" | "c" => Ok(Some(Action::CreateStory {
                 epic_id: self.epic_id,
             })),
+            // Sorting is case-sensitive so that [S] can reverse it
+            "s" => Ok(Some(Action::SortEpicStories { key: None })),
+            "S" => Ok(Some(Action::ReverseEpicStoriesSort)),
+            // [n] already renames the epic
+            ">" => Ok(Some(Action::NextPage)),
+            "<" => Ok(Some(Action::PreviousListPage)),
             input => {
                 if let Ok(story_id) = input.parse::<u32>() {
                     if stories.contains_key(&story_id) {
@@ -186,13 +656,9 @@ impl Page for EpicDetail {
             }
         }
     }
-
-    #[cfg(test)]
-    fn as_any(&self) -> &dyn Any {
-        self
-    }
 }
 
+#[derive(PageBoilerplate)]
 pub struct StoryDetail {
     pub epic_id: u32,
     pub story_id: u32,
@@ -200,35 +666,150 @@ pub struct StoryDetail {
 }
 
 impl Page for StoryDetail {
-    fn draw_page(&self) -> Result<()> {
+    fn title(&self) -> String {
+        format!("story_{}", self.story_id)
+    }
+
+    fn page_title(&self) -> String {
+        format!("Story #{}", self.story_id)
+    }
+
+    fn draw_page(&self, ctx: &DrawContext) -> Result<Vec<String>> {
+        let mut lines = vec![];
+
         let db_state = self.db.read_db()?;
         let story = db_state
             .stories
             .get(&self.story_id)
             .ok_or_else(|| anyhow!("could not find story!"))?;
 
-        println!("------------------------------ STORY ------------------------------");
-        println!("  id  |     name     |         description         |    status    ");
-        let id_col = get_column_string(&self.story_id.to_string(), 5);
-        let name_col = get_column_string(&story.name, 12);
-        let desc_col = get_column_string(&story.description, 27);
-        let status_col = get_column_string(&story.status.to_string(), 13);
-        println!("{} | {} | {} | {}", id_col, name_col, desc_col, status_col);
+        let widths = column_widths(ctx.width, &[5, 12, 27, 12, 11, 8]);
+        lines.push(banner("STORY", ctx.width));
+        lines.push(header_row(
+            &[
+                "id",
+                "name",
+                "description",
+                "status",
+                "priority",
+                "estimate",
+            ],
+            &widths,
+        ));
+        let estimate = story
+            .estimate
+            .map_or_else(|| "-".to_owned(), |estimate| estimate.to_string());
+        let columns = [
+            ColumnSpec::default_id(widths[0]).format(&self.story_id.to_string()),
+            ColumnSpec::default_name(widths[1]).format(&story.name),
+            ColumnSpec::default_description(widths[2]).format(&story.description),
+            ctx.theme.status_column(&story.status, widths[3]),
+            ColumnSpec::left(widths[4]).format(&story.priority.to_string()),
+            ColumnSpec::right(widths[5]).format(&estimate),
+        ];
+        lines.push(join_columns(&columns));
+
+        lines.push(format!("Type: {}", story.story_type));
+        lines.push(format!("Reporter: {}", story.reporter));
+        lines.push(format!("Comments: {}", story.comments.len()));
+        let now = Utc::now();
+        lines.push(format!("Created: {}", days_ago(story.created_at, now)));
+        lines.push(format!("Updated: {}", days_ago(story.updated_at, now)));
+        lines.push(format!(
+            "Description: {} words",
+            story.word_count_description()
+        ));
+
+        if let Some(due_date) = story.due_date {
+            lines.push(format!("Due: {}", due_date));
+        }
+        if let Some(release) = &story.release {
+            lines.push(format!("Release: {}", release));
+        }
+
+        for (heading, ids) in [("BLOCKS", &story.blocks), ("BLOCKED BY", &story.blocked_by)] {
+            lines.push(String::new());
+            lines.push(heading.to_owned());
+            if ids.is_empty() {
+                lines.push("  (none)".to_owned());
+            }
+            for id in ids.iter().sorted() {
+                let name = db_state
+                    .stories
+                    .get(id)
+                    .map_or("(missing)", |story| story.name.as_str());
+                let id_col = ColumnSpec::default_id(5).format(&id.to_string());
+                let name_col = ColumnSpec::default_name(32).format(name);
+                lines.push(format!("  {} | {}", id_col, name_col));
+            }
+        }
 
-        println!();
-        println!();
+        lines.push(String::new());
+        lines.push(String::new());
 
-        println!("[p] previous | [u] update story | [d] delete story");
+        lines.extend(
+            ctx.command_bar("[u] update story | [n] rename | [m] edit description | [e] set estimate | [y] set type | [t] set due date | [r] set release | [k] link dependency | [K] unlink dependency | [c] clone story | [v] move to epic | [o] comments | [w] watch / unwatch | [d] delete story"),
+        );
 
-        Ok(())
+        Ok(lines)
     }
 
     fn handle_input(&self, input: &str) -> Result<Option<Action>> {
         match input {
             "P" | "p" => Ok(Some(Action::NavigateToPreviousPage)),
+            "?" => Ok(Some(Action::ShowHelp {
+                context: PageType::StoryDetail,
+            })),
             "U" | "u" => Ok(Some(Action::UpdateStoryStatus {
                 story_id: self.story_id,
             })),
+            "N" | "n" => Ok(Some(Action::UpdateStoryName {
+                story_id: self.story_id,
+                name: None,
+            })),
+            // [e] already sets the estimate here
+            "M" | "m" => Ok(Some(Action::UpdateStoryDescription {
+                story_id: self.story_id,
+                description: None,
+            })),
+            "E" | "e" => Ok(Some(Action::UpdateStoryEstimate {
+                story_id: self.story_id,
+            })),
+            "T" | "t" => Ok(Some(Action::UpdateStoryDueDate {
+                story_id: self.story_id,
+                date: None,
+            })),
+            // Case matters here: k links and K unlinks
+            "k" => Ok(Some(Action::LinkStoryDependency {
+                story_id: self.story_id,
+            })),
+            "K" => Ok(Some(Action::UnlinkStoryDependency {
+                story_id: self.story_id,
+            })),
+            // [t] already sets the due date here
+            "Y" | "y" => Ok(Some(Action::UpdateStoryType {
+                story_id: self.story_id,
+                story_type: None,
+            })),
+            "r" => Ok(Some(Action::UpdateStoryRelease {
+                story_id: self.story_id,
+                release: None,
+            })),
+            "C" | "c" => Ok(Some(Action::CloneStory {
+                story_id: self.story_id,
+            })),
+            "W" | "w" => Ok(Some(Action::ToggleWatch {
+                story_id: self.story_id,
+            })),
+            // [m] already edits the description here
+            "V" | "v" => Ok(Some(Action::MoveStory {
+                story_id: self.story_id,
+                target_epic_id: None,
+            })),
+            // [c] already clones the story here
+            "O" | "o" => Ok(Some(Action::NavigateToComments {
+                story_id: self.story_id,
+            })),
             "D" | "d" => Ok(Some(Action::DeleteStory {
                 epic_id: self.epic_id,
                 story_id: self.story_id,
@@ -236,295 +817,3285 @@ impl Page for StoryDetail {
             _ => Ok(None),
         }
     }
+}
 
-    #[cfg(test)]
-    fn as_any(&self) -> &dyn Any {
-        self
-    }
+#[derive(PageBoilerplate)]
+pub struct CommentsPage {
+    pub story_id: u32,
+    pub db: Rc<JiraDatabase>,
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use crate::db::test_utils::MockDB;
-    use crate::models::{Epic, Story};
+impl Page for CommentsPage {
+    fn title(&self) -> String {
+        format!("story_{}_comments", self.story_id)
+    }
 
-    mod home_page {
-        use super::*;
+    fn page_title(&self) -> String {
+        "Comments".to_owned()
+    }
 
-        #[test]
-        fn draw_page_should_not_throw_error() {
-            let db = Rc::new(JiraDatabase {
-                database: Box::new(MockDB::new()),
-            });
+    fn draw_page(&self, ctx: &DrawContext) -> Result<Vec<String>> {
+        let db_state = self.db.read_db()?;
+        let story = db_state
+            .stories
+            .get(&self.story_id)
+            .ok_or_else(|| anyhow!("could not find story!"))?;
 
-            let page = HomePage { db };
-            assert_eq!(page.draw_page().is_ok(), true);
-        }
+        let widths = column_widths(ctx.width, &[5, 14, 16, 40]);
+        let mut lines = vec![
+            banner("COMMENTS", ctx.width),
+            format!("Story #{}: {}", self.story_id, story.name),
+            String::new(),
+            header_row(&["id", "author", "posted", "comment"], &widths),
+        ];
 
-        #[test]
-        fn handle_input_should_not_throw_error() {
-            let db = Rc::new(JiraDatabase {
-                database: Box::new(MockDB::new()),
-            });
+        for comment in &story.comments {
+            let posted = comment.created_at.format("%Y-%m-%d %H:%M").to_string();
+            let columns = [
+                get_column_string(&comment.id.to_string(), widths[0]),
+                get_column_string(&comment.author, widths[1]),
+                get_column_string(&posted, widths[2]),
+                get_column_string(&comment.text, widths[3]),
+            ];
+            lines.push(join_columns(&columns));
+        }
 
-            let page = HomePage { db };
-            assert_eq!(page.handle_input("").is_ok(), true);
+        if story.comments.is_empty() {
+            lines.push(String::new());
+            lines.push("No comments".to_owned());
         }
 
-        #[test]
-        fn handle_input_should_return_the_correct_actions() {
-            let db = Rc::new(JiraDatabase {
-                database: Box::new(MockDB::new()),
-            });
+        lines.push(String::new());
+        lines.push(String::new());
 
-            let epic = Epic::new("".to_owned(), "".to_owned());
+        lines.extend(ctx.command_bar("[a] add comment | [d] delete comment"));
 
-            let epic_id = db.create_epic(epic).unwrap();
+        Ok(lines)
+    }
 
-            let page = HomePage { db };
+    fn handle_input(&self, input: &str) -> Result<Option<Action>> {
+        match input {
+            "P" | "p" => Ok(Some(Action::NavigateToPreviousPage)),
+            "?" => Ok(Some(Action::ShowHelp {
+                context: PageType::Comments,
+            })),
+            "A" | "a" => Ok(Some(Action::AddComment {
+                story_id: self.story_id,
+            })),
+            "D" | "d" => Ok(Some(Action::DeleteComment {
+                story_id: self.story_id,
+            })),
+            _ => Ok(None),
+        }
+    }
+}
 
-            let q = "q";
-            let c = "c";
-            let valid_epic_id = epic_id.to_string();
-            let invalid_epic_id = "999";
-            let junk_input = "j983f2j";
-            let junk_input_with_valid_prefix = "q983f2j";
-            let input_with_trailing_white_spaces = "q\n";
+#[derive(PageBoilerplate)]
+pub struct SprintPage {
+    pub db: Rc<JiraDatabase>,
+}
 
-            assert_eq!(page.handle_input(q).unwrap(), Some(Action::Exit));
-            assert_eq!(page.handle_input(c).unwrap(), Some(Action::CreateEpic));
-            assert_eq!(
-                page.handle_input(&valid_epic_id).unwrap(),
-                Some(Action::NavigateToEpicDetail { epic_id: 1 })
-            );
-            assert_eq!(page.handle_input(invalid_epic_id).unwrap(), None);
-            assert_eq!(page.handle_input(junk_input).unwrap(), None);
-            assert_eq!(
-                page.handle_input(junk_input_with_valid_prefix).unwrap(),
-                None
-            );
-            assert_eq!(
-                page.handle_input(input_with_trailing_white_spaces).unwrap(),
-                None
-            );
-        }
+impl Page for SprintPage {
+    fn title(&self) -> String {
+        "sprints".to_owned()
     }
 
-    mod epic_detail_page {
-        use super::*;
+    fn page_title(&self) -> String {
+        "Sprints".to_owned()
+    }
 
-        #[test]
-        fn draw_page_should_not_throw_error() {
-            let db = Rc::new(JiraDatabase {
-                database: Box::new(MockDB::new()),
-            });
-            let epic_id = db
-                .create_epic(Epic::new("".to_owned(), "".to_owned()))
-                .unwrap();
+    fn draw_page(&self, ctx: &DrawContext) -> Result<Vec<String>> {
+        let db_state = self.db.read_db()?;
+        let today = Local::now().date_naive();
 
-            let page = EpicDetail { epic_id, db };
-            assert_eq!(page.draw_page().is_ok(), true);
+        let widths = column_widths(ctx.width, &[5, 20, 13, 13, 8, 7, 7]);
+        let mut lines = vec![
+            banner("SPRINTS", ctx.width),
+            header_row(
+                &["id", "name", "start", "end", "stories", "points", "active"],
+                &widths,
+            ),
+        ];
+
+        // Newest first, so the current and upcoming sprints are at the top
+        for sprint in db_state
+            .sprints
+            .values()
+            .sorted_by_key(|sprint| std::cmp::Reverse((sprint.start_date, sprint.id)))
+        {
+            let points: u32 = sprint
+                .story_ids
+                .iter()
+                .filter_map(|id| db_state.stories.get(id))
+                .map(|story| u32::from(story.estimate.unwrap_or(0)))
+                .sum();
+            let active = if sprint.is_active(today) { "*" } else { "" };
+            let columns = [
+                ColumnSpec::default_id(widths[0]).format(&sprint.id.to_string()),
+                ColumnSpec::default_name(widths[1]).format(&sprint.name),
+                ColumnSpec::left(widths[2]).format(&sprint.start_date.to_string()),
+                ColumnSpec::left(widths[3]).format(&sprint.end_date.to_string()),
+                ColumnSpec::right(widths[4]).format(&sprint.story_ids.len().to_string()),
+                ColumnSpec::right(widths[5]).format(&points.to_string()),
+                ColumnSpec::left(widths[6]).format(active),
+            ];
+            lines.push(join_columns(&columns));
         }
 
-        #[test]
-        fn handle_input_should_not_throw_error() {
-            let db = Rc::new(JiraDatabase {
-                database: Box::new(MockDB::new()),
-            });
-            let epic_id = db
-                .create_epic(Epic::new("".to_owned(), "".to_owned()))
-                .unwrap();
+        lines.push(String::new());
+        lines.push(String::new());
 
-            let page = EpicDetail { epic_id, db };
-            assert_eq!(page.handle_input("").is_ok(), true);
-        }
+        lines.extend(ctx.command_bar("[c] create sprint | [:id:] navigate to sprint"));
 
-        #[test]
-        fn draw_page_should_throw_error_for_invalid_epic_id() {
-            let db = Rc::new(JiraDatabase {
-                database: Box::new(MockDB::new()),
-            });
+        Ok(lines)
+    }
 
-            let page = EpicDetail { epic_id: 999, db };
-            assert_eq!(page.draw_page().is_err(), true);
-        }
+    fn handle_input(&self, input: &str) -> Result<Option<Action>> {
+        let sprints = self.db.read_db()?.sprints;
+
+        match input {
+            "P" | "p" => Ok(Some(Action::NavigateToPreviousPage)),
+            "?" => Ok(Some(Action::ShowHelp {
+                context: PageType::SprintList,
+            })),
+            "C" | "c" => Ok(Some(Action::CreateSprint)),
+            input => Ok(input
+                .parse::<u32>()
+                .ok()
+                .filter(|sprint_id| sprints.contains_key(sprint_id))
+                .map(|sprint_id| Action::NavigateToSprintDetail { sprint_id })),
+        }
+    }
+}
+
+#[derive(PageBoilerplate)]
+pub struct SprintDetail {
+    pub sprint_id: u32,
+    pub db: Rc<JiraDatabase>,
+}
+
+impl Page for SprintDetail {
+    fn title(&self) -> String {
+        format!("sprint_{}", self.sprint_id)
+    }
+
+    fn page_title(&self) -> String {
+        format!("Sprint #{}", self.sprint_id)
+    }
+
+    fn draw_page(&self, ctx: &DrawContext) -> Result<Vec<String>> {
+        let db_state = self.db.read_db()?;
+        let sprint = db_state
+            .sprints
+            .get(&self.sprint_id)
+            .ok_or_else(|| anyhow!("could not find sprint!"))?;
+
+        let mut lines = vec![
+            "---------------------------------- SPRINT ----------------------------------"
+                .to_owned(),
+            format!(
+                "{} ({} - {})",
+                sprint.name, sprint.start_date, sprint.end_date
+            ),
+            String::new(),
+            "---------------------------------- STORIES ---------------------------------"
+                .to_owned(),
+            "     id     |               name               |      status      | estimate "
+                .to_owned(),
+        ];
+
+        let mut total_points = 0u32;
+
+        for id in sprint.story_ids.iter().sorted() {
+            let Some(story) = db_state.stories.get(id) else {
+                continue;
+            };
+            let id_col = get_column_string(&id.to_string(), 11);
+            let name_col = get_column_string(&story.name, 32);
+            let status_col = ctx.theme.status_column(&story.status, 16);
+            let estimate = story
+                .estimate
+                .map_or("-".to_owned(), |estimate| estimate.to_string());
+            total_points += u32::from(story.estimate.unwrap_or(0));
+            lines.push(format!(
+                "{} | {} | {} | {}",
+                id_col, name_col, status_col, estimate
+            ));
+        }
+
+        lines.push(String::new());
+        lines.push(format!("Total: {} points", total_points));
+        lines.push(String::new());
+
+        lines.extend(ctx.command_bar(
+            "[a] add story | [r] remove story | [d] delete sprint | [:id:] navigate to story",
+        ));
+
+        Ok(lines)
+    }
+
+    fn handle_input(&self, input: &str) -> Result<Option<Action>> {
+        let db_state = self.db.read_db()?;
+
+        match input {
+            "P" | "p" => Ok(Some(Action::NavigateToPreviousPage)),
+            "?" => Ok(Some(Action::ShowHelp {
+                context: PageType::SprintDetail,
+            })),
+            "A" | "a" => Ok(Some(Action::AssignStoryToSprint {
+                sprint_id: self.sprint_id,
+                story_id: None,
+            })),
+            "r" => Ok(Some(Action::RemoveStoryFromSprint {
+                sprint_id: self.sprint_id,
+            })),
+            "D" | "d" => Ok(Some(Action::DeleteSprint {
+                sprint_id: self.sprint_id,
+            })),
+            input => {
+                let in_sprint = |story_id: &u32| {
+                    db_state
+                        .sprints
+                        .get(&self.sprint_id)
+                        .is_some_and(|sprint| sprint.story_ids.contains(story_id))
+                };
+                // Story pages need the owning epic, so look it up here
+                Ok(input
+                    .parse::<u32>()
+                    .ok()
+                    .filter(in_sprint)
+                    .and_then(|story_id| {
+                        db_state
+                            .epics
+                            .iter()
+                            .find(|(_, epic)| epic.stories.contains(&story_id))
+                            .map(|(epic_id, _)| Action::NavigateToStoryDetail {
+                                epic_id: *epic_id,
+                                story_id,
+                            })
+                    }))
+            }
+        }
+    }
+}
+
+#[derive(PageBoilerplate)]
+pub struct BacklogPage {
+    pub db: Rc<JiraDatabase>,
+}
+
+impl Page for BacklogPage {
+    fn title(&self) -> String {
+        "backlog".to_owned()
+    }
+
+    fn page_title(&self) -> String {
+        "Backlog".to_owned()
+    }
+
+    fn draw_page(&self, ctx: &DrawContext) -> Result<Vec<String>> {
+        let db_state = self.db.read_db()?;
+        let backlog = self.db.backlog_stories()?;
+
+        let widths = column_widths(ctx.width, &[20, 8, 32, 11]);
+        let mut lines = vec![
+            banner("BACKLOG", ctx.width),
+            header_row(&["epic", "id", "name", "priority"], &widths),
+        ];
+
+        // The top story is the one `[a :sprint_id:]` assigns
+        for (index, (epic_id, story_id, story)) in backlog.iter().enumerate() {
+            let marker = if index == 0 { ">" } else { " " };
+            let epic_name = db_state
+                .epics
+                .get(epic_id)
+                .map_or("", |epic| epic.name.as_str());
+            let columns = [
+                get_column_string(epic_name, widths[0]),
+                get_column_string(&format!("{}{}", marker, story_id), widths[1]),
+                get_column_string(&story.name, widths[2]),
+                get_column_string(&story.priority.to_string(), widths[3]),
+            ];
+            lines.push(join_columns(&columns));
+        }
+
+        if backlog.is_empty() {
+            lines.push(String::new());
+            lines.push("Every story is in a sprint".to_owned());
+        }
+
+        lines.push(String::new());
+        lines.push(String::new());
+
+        lines.extend(
+            ctx.command_bar("[a :sprint_id:] add > story to sprint | [:id:] navigate to story"),
+        );
+
+        Ok(lines)
+    }
+
+    fn handle_input(&self, input: &str) -> Result<Option<Action>> {
+        let backlog = self.db.backlog_stories()?;
+
+        match input {
+            "P" | "p" => Ok(Some(Action::NavigateToPreviousPage)),
+            "?" => Ok(Some(Action::ShowHelp {
+                context: PageType::Backlog,
+            })),
+            input if input.starts_with(['A', 'a']) => {
+                let Ok(sprint_id) = input[1..].trim().parse::<u32>() else {
+                    return Ok(None);
+                };
+                if !self.db.read_db()?.sprints.contains_key(&sprint_id) {
+                    return Ok(None);
+                }
+                Ok(backlog
+                    .first()
+                    .map(|(_, story_id, _)| Action::AssignStoryToSprint {
+                        sprint_id,
+                        story_id: Some(*story_id),
+                    }))
+            }
+            input => Ok(input.parse::<u32>().ok().and_then(|id| {
+                backlog.iter().find(|(_, story_id, _)| *story_id == id).map(
+                    |(epic_id, story_id, _)| Action::NavigateToStoryDetail {
+                        epic_id: *epic_id,
+                        story_id: *story_id,
+                    },
+                )
+            })),
+        }
+    }
+
+    fn input_suggestions(&self) -> Result<Vec<(String, String)>> {
+        Ok(self
+            .db
+            .backlog_stories()?
+            .into_iter()
+            .map(|(_, story_id, story)| (story_id.to_string(), story.name))
+            .collect())
+    }
+}
+
+#[derive(PageBoilerplate)]
+pub struct SearchPage {
+    pub query: String,
+    pub db: Rc<JiraDatabase>,
+}
+
+impl SearchPage {
+    fn search_query(&self) -> SearchQuery {
+        SearchQuery::builder().with_text(self.query.clone()).build()
+    }
+}
+
+impl Page for SearchPage {
+    fn title(&self) -> String {
+        "search".to_owned()
+    }
+
+    fn page_title(&self) -> String {
+        "Search".to_owned()
+    }
+
+    fn draw_page(&self, ctx: &DrawContext) -> Result<Vec<String>> {
+        let epics = self.db.search_epics(&self.search_query())?;
+        let stories = self.db.search_stories(&self.search_query())?;
+
+        let mut lines = vec![
+            format!("Results for \"{}\"", self.query),
+            String::new(),
+            "------------------------------- EPICS -------------------------------".to_owned(),
+            "     id     |               name               |      status      ".to_owned(),
+        ];
+
+        for (id, epic) in &epics {
+            let id_col = get_column_string(&id.to_string(), 11);
+            let name_col = get_column_string(&epic.name, 32);
+            let status_col = ctx.theme.status_column(&epic.status, 17);
+            lines.push(format!("{} | {} | {}", id_col, name_col, status_col));
+        }
+
+        lines.push(String::new());
+        lines.push(
+            "------------------------------ STORIES ------------------------------".to_owned(),
+        );
+        lines.push("     id     |               name               |      status      ".to_owned());
+
+        for (id, story) in &stories {
+            let id_col = get_column_string(&id.to_string(), 11);
+            let name_col = get_column_string(&story.name, 32);
+            let status_col = ctx.theme.status_column(&story.status, 17);
+            lines.push(format!("{} | {} | {}", id_col, name_col, status_col));
+        }
+
+        if epics.is_empty() && stories.is_empty() {
+            lines.push(String::new());
+            lines.push("No matches".to_owned());
+        }
+
+        lines.push(String::new());
+        lines.push(String::new());
+
+        lines.extend(ctx.command_bar("[:id:] navigate to epic or story"));
+
+        Ok(lines)
+    }
+
+    fn handle_input(&self, input: &str) -> Result<Option<Action>> {
+        match input {
+            "P" | "p" => Ok(Some(Action::NavigateToPreviousPage)),
+            "?" => Ok(Some(Action::ShowHelp {
+                context: PageType::Search,
+            })),
+            input => {
+                let Ok(id) = input.parse::<u32>() else {
+                    return Ok(None);
+                };
+
+                if self
+                    .db
+                    .search_epics(&self.search_query())?
+                    .iter()
+                    .any(|(epic_id, _)| *epic_id == id)
+                {
+                    return Ok(Some(Action::NavigateToEpicDetail { epic_id: id }));
+                }
+
+                if self
+                    .db
+                    .search_stories(&self.search_query())?
+                    .iter()
+                    .any(|(story_id, _)| *story_id == id)
+                {
+                    let epic_id = self.db.get_story_with_epic(id)?.epic_id;
+                    return Ok(Some(Action::NavigateToStoryDetail {
+                        epic_id,
+                        story_id: id,
+                    }));
+                }
+
+                Ok(None)
+            }
+        }
+    }
+
+    fn input_suggestions(&self) -> Result<Vec<(String, String)>> {
+        let epics = self.db.search_epics(&self.search_query())?;
+        let stories = self.db.search_stories(&self.search_query())?;
+
+        Ok(epics
+            .into_iter()
+            .map(|(id, epic)| (id.to_string(), epic.name))
+            .chain(
+                stories
+                    .into_iter()
+                    .map(|(id, story)| (id.to_string(), story.name)),
+            )
+            .collect())
+    }
+}
+
+#[derive(PageBoilerplate)]
+pub struct ReporterPage {
+    pub reporter: String,
+    pub db: Rc<JiraDatabase>,
+}
+
+impl Page for ReporterPage {
+    fn title(&self) -> String {
+        "reporter".to_owned()
+    }
+
+    fn page_title(&self) -> String {
+        format!("Reporter {}", self.reporter)
+    }
+
+    fn draw_page(&self, ctx: &DrawContext) -> Result<Vec<String>> {
+        let stories = self.db.get_stories_by_reporter(&self.reporter)?;
+
+        let widths = column_widths(ctx.width, &[11, 32, 17]);
+        let mut lines = vec![
+            format!("Stories reported by {}", self.reporter),
+            String::new(),
+            banner("STORIES", ctx.width),
+            header_row(&["id", "name", "status"], &widths),
+        ];
+
+        for (id, story) in &stories {
+            let columns = [
+                ColumnSpec::default_id(widths[0]).format(&id.to_string()),
+                ColumnSpec::default_name(widths[1]).format(&story.name),
+                ctx.theme.status_column(&story.status, widths[2]),
+            ];
+            lines.push(join_columns(&columns));
+        }
+
+        if stories.is_empty() {
+            lines.push(String::new());
+            lines.push("No stories".to_owned());
+        }
+
+        lines.push(String::new());
+        lines.push(String::new());
+
+        lines.extend(ctx.command_bar("[:id:] navigate to story"));
+
+        Ok(lines)
+    }
+
+    fn handle_input(&self, input: &str) -> Result<Option<Action>> {
+        match input {
+            "P" | "p" => Ok(Some(Action::NavigateToPreviousPage)),
+            "?" => Ok(Some(Action::ShowHelp {
+                context: PageType::Reporter,
+            })),
+            input => {
+                let Ok(id) = input.parse::<u32>() else {
+                    return Ok(None);
+                };
+
+                if !self
+                    .db
+                    .get_stories_by_reporter(&self.reporter)?
+                    .iter()
+                    .any(|(story_id, _)| *story_id == id)
+                {
+                    return Ok(None);
+                }
+
+                let epic_id = self.db.get_story_with_epic(id)?.epic_id;
+                Ok(Some(Action::NavigateToStoryDetail {
+                    epic_id,
+                    story_id: id,
+                }))
+            }
+        }
+    }
+
+    fn input_suggestions(&self) -> Result<Vec<(String, String)>> {
+        Ok(self
+            .db
+            .get_stories_by_reporter(&self.reporter)?
+            .into_iter()
+            .map(|(id, story)| (id.to_string(), story.name))
+            .collect())
+    }
+}
+
+#[derive(PageBoilerplate)]
+pub struct ReleasePage {
+    pub db: Rc<JiraDatabase>,
+}
+
+impl Page for ReleasePage {
+    fn title(&self) -> String {
+        "releases".to_owned()
+    }
+
+    fn page_title(&self) -> String {
+        "Releases".to_owned()
+    }
+
+    fn draw_page(&self, ctx: &DrawContext) -> Result<Vec<String>> {
+        let releases = self.db.releases()?;
+
+        let widths = column_widths(ctx.width, &[6, 40, 9, 9]);
+        let mut lines = vec![
+            banner("RELEASES", ctx.width),
+            header_row(&["#", "release", "epics", "stories"], &widths),
+        ];
+
+        for (index, release) in releases.iter().enumerate() {
+            let columns = [
+                ColumnSpec::default_id(widths[0]).format(&(index + 1).to_string()),
+                ColumnSpec::default_name(widths[1]).format(&release.name),
+                ColumnSpec::right(widths[2]).format(&release.epic_count.to_string()),
+                ColumnSpec::right(widths[3]).format(&release.story_count.to_string()),
+            ];
+            lines.push(join_columns(&columns));
+        }
+
+        if releases.is_empty() {
+            lines.push(String::new());
+            lines.push("No releases".to_owned());
+        }
+
+        lines.push(String::new());
+        lines.push(String::new());
+
+        lines.extend(ctx.command_bar("[:#:] show release"));
+
+        Ok(lines)
+    }
+
+    fn handle_input(&self, input: &str) -> Result<Option<Action>> {
+        let releases = self.db.releases()?;
+
+        match input {
+            "P" | "p" => Ok(Some(Action::NavigateToPreviousPage)),
+            "?" => Ok(Some(Action::ShowHelp {
+                context: PageType::Releases,
+            })),
+            input => Ok(input
+                .parse::<usize>()
+                .ok()
+                .and_then(|number| number.checked_sub(1))
+                .and_then(|index| releases.get(index))
+                .map(|release| Action::NavigateToReleaseDetail {
+                    release: release.name.clone(),
+                })),
+        }
+    }
+}
+
+#[derive(PageBoilerplate)]
+pub struct ReleaseDetail {
+    pub release: String,
+    pub db: Rc<JiraDatabase>,
+}
+
+impl Page for ReleaseDetail {
+    fn title(&self) -> String {
+        format!("release_{}", self.release)
+    }
+
+    fn page_title(&self) -> String {
+        format!("Release {}", self.release)
+    }
+
+    fn draw_page(&self, ctx: &DrawContext) -> Result<Vec<String>> {
+        let epics = self.db.get_epics_for_release(&self.release)?;
+        let stories = self.db.get_stories_for_release(&self.release)?;
+
+        let widths = column_widths(ctx.width, &[11, 32, 17]);
+        let mut lines = vec![
+            format!("Release {}", self.release),
+            String::new(),
+            banner("EPICS", ctx.width),
+            header_row(&["id", "name", "status"], &widths),
+        ];
+
+        for (id, epic) in &epics {
+            let columns = [
+                ColumnSpec::default_id(widths[0]).format(&id.to_string()),
+                ColumnSpec::default_name(widths[1]).format(&epic.name),
+                ctx.theme.status_column(&epic.status, widths[2]),
+            ];
+            lines.push(join_columns(&columns));
+        }
+
+        lines.push(String::new());
+        lines.push(banner("STORIES", ctx.width));
+        lines.push(header_row(&["id", "name", "status"], &widths));
+
+        for (id, story) in &stories {
+            let columns = [
+                ColumnSpec::default_id(widths[0]).format(&id.to_string()),
+                ColumnSpec::default_name(widths[1]).format(&story.name),
+                ctx.theme.status_column(&story.status, widths[2]),
+            ];
+            lines.push(join_columns(&columns));
+        }
+
+        lines.push(String::new());
+        lines.push(String::new());
+
+        lines.extend(ctx.command_bar("[:id:] navigate to epic or story"));
+
+        Ok(lines)
+    }
+
+    fn handle_input(&self, input: &str) -> Result<Option<Action>> {
+        match input {
+            "P" | "p" => Ok(Some(Action::NavigateToPreviousPage)),
+            "?" => Ok(Some(Action::ShowHelp {
+                context: PageType::ReleaseDetail,
+            })),
+            input => {
+                let Ok(id) = input.parse::<u32>() else {
+                    return Ok(None);
+                };
+
+                if self
+                    .db
+                    .get_epics_for_release(&self.release)?
+                    .iter()
+                    .any(|(epic_id, _)| *epic_id == id)
+                {
+                    return Ok(Some(Action::NavigateToEpicDetail { epic_id: id }));
+                }
+
+                if !self
+                    .db
+                    .get_stories_for_release(&self.release)?
+                    .iter()
+                    .any(|(story_id, _)| *story_id == id)
+                {
+                    return Ok(None);
+                }
+
+                let epic_id = self.db.get_story_with_epic(id)?.epic_id;
+                Ok(Some(Action::NavigateToStoryDetail {
+                    epic_id,
+                    story_id: id,
+                }))
+            }
+        }
+    }
+
+    fn input_suggestions(&self) -> Result<Vec<(String, String)>> {
+        let epics = self.db.get_epics_for_release(&self.release)?;
+        let stories = self.db.get_stories_for_release(&self.release)?;
+
+        Ok(epics
+            .into_iter()
+            .map(|(id, epic)| (id.to_string(), epic.name))
+            .chain(
+                stories
+                    .into_iter()
+                    .map(|(id, story)| (id.to_string(), story.name)),
+            )
+            .collect())
+    }
+}
+
+// The order statuses are counted in, following the workflow
+const DASHBOARD_STATUSES: [Status; 5] = [
+    Status::Open,
+    Status::InProgress,
+    Status::Blocked,
+    Status::Resolved,
+    Status::Closed,
+];
+
+/// Project-wide totals: how many epics and stories are in each status,
+/// story points done, overdue work and the latest changes.
+#[derive(PageBoilerplate)]
+pub struct DashboardPage {
+    pub db: Rc<JiraDatabase>,
+}
+
+impl DashboardPage {
+    fn status_chart<'a>(statuses: impl Iterator<Item = &'a Status>, width: usize) -> Vec<String> {
+        let statuses: Vec<&Status> = statuses.collect();
+        let counts =
+            DASHBOARD_STATUSES.map(|status| statuses.iter().filter(|s| ***s == status).count());
+        let max = counts.iter().copied().max().unwrap_or(0);
+
+        DASHBOARD_STATUSES
+            .iter()
+            .zip(counts)
+            .map(|(status, count)| {
+                format!(
+                    "{:<12} {:>4} {}",
+                    status.to_string(),
+                    count,
+                    bar(count, max, width)
+                )
+            })
+            .collect()
+    }
+}
+
+impl Page for DashboardPage {
+    fn title(&self) -> String {
+        "dashboard".to_owned()
+    }
+
+    fn page_title(&self) -> String {
+        "Dashboard".to_owned()
+    }
+
+    fn draw_page(&self, ctx: &DrawContext) -> Result<Vec<String>> {
+        let db_state = self.db.read_db()?;
+        let (epics, stories) = (&db_state.epics, &db_state.stories);
+        // Room for the status name and count in front of each bar
+        let bar_width = ctx.width.saturating_sub(18).clamp(1, 40);
+
+        let mut lines = vec![
+            banner("DASHBOARD", ctx.width),
+            format!("Project: {}", self.db.active_project()?),
+            String::new(),
+            format!("Epics ({})", epics.len()),
+        ];
+        lines.extend(Self::status_chart(
+            epics.values().map(|epic| &epic.status),
+            bar_width,
+        ));
+
+        lines.push(String::new());
+        lines.push(format!("Stories ({})", stories.len()));
+        lines.extend(Self::status_chart(
+            stories.values().map(|story| &story.status),
+            bar_width,
+        ));
+
+        let estimated: u32 = stories
+            .values()
+            .filter_map(|story| story.estimate)
+            .map(u32::from)
+            .sum();
+        let completed: u32 = stories
+            .values()
+            .filter(|story| matches!(story.status, Status::Resolved | Status::Closed))
+            .filter_map(|story| story.estimate)
+            .map(u32::from)
+            .sum();
+
+        let today = Local::now().date_naive();
+        let overdue = epics.values().filter(|epic| epic.is_overdue(today)).count()
+            + stories
+                .values()
+                .filter(|story| story.is_overdue(today))
+                .count();
+
+        lines.push(String::new());
+        lines.push(format!(
+            "Story points: {} of {} completed",
+            completed, estimated
+        ));
+        lines.push(format!("Overdue items: {}", overdue));
+
+        lines.push(String::new());
+        lines.push(banner("RECENT ACTIVITY", ctx.width));
+
+        let now = Utc::now();
+        let activity = epics
+            .iter()
+            .map(|(id, epic)| (epic.updated_at, format!("epic #{} {}", id, epic.name)))
+            .chain(
+                stories
+                    .iter()
+                    .map(|(id, story)| (story.updated_at, format!("story #{} {}", id, story.name))),
+            )
+            .sorted_by(|a, b| b.0.cmp(&a.0).then_with(|| a.1.cmp(&b.1)))
+            .take(DASHBOARD_ACTIVITY_LIMIT);
+        for (updated_at, item) in activity {
+            lines.push(format!("{} ({})", item, days_ago(updated_at, now)));
+        }
+        if epics.is_empty() && stories.is_empty() {
+            lines.push("No activity yet".to_owned());
+        }
+
+        lines.push(String::new());
+        lines.push(String::new());
+
+        lines.extend(ctx.command_bar(""));
+
+        Ok(lines)
+    }
+
+    fn handle_input(&self, input: &str) -> Result<Option<Action>> {
+        match input {
+            "P" | "p" => Ok(Some(Action::NavigateToPreviousPage)),
+            "?" => Ok(Some(Action::ShowHelp {
+                context: PageType::Dashboard,
+            })),
+            _ => Ok(None),
+        }
+    }
+}
+
+#[derive(PageBoilerplate)]
+pub struct WatchListPage {
+    pub watch_list: Rc<RefCell<WatchList>>,
+    pub db: Rc<JiraDatabase>,
+}
+
+impl WatchListPage {
+    // Watched stories that still exist, with their epic's id and name
+    fn watched_stories(&self) -> Result<Vec<(u32, Story, u32, String)>> {
+        let db_state = self.db.read_db()?;
+
+        Ok(self
+            .watch_list
+            .borrow()
+            .story_ids()
+            .into_iter()
+            .filter_map(|story_id| {
+                let story = db_state.stories.get(&story_id)?;
+                let (epic_id, epic) = db_state
+                    .epics
+                    .iter()
+                    .find(|(_, epic)| epic.stories.contains(&story_id))?;
+                Some((story_id, story.clone(), *epic_id, epic.name.clone()))
+            })
+            .collect())
+    }
+}
+
+impl Page for WatchListPage {
+    fn title(&self) -> String {
+        "watch_list".to_owned()
+    }
+
+    fn page_title(&self) -> String {
+        "Watch list".to_owned()
+    }
+
+    fn draw_page(&self, ctx: &DrawContext) -> Result<Vec<String>> {
+        let stories = self.watched_stories()?;
+
+        let widths = column_widths(ctx.width, &[11, 32, 24, 17]);
+        let mut lines = vec![
+            banner("WATCH LIST", ctx.width),
+            header_row(&["id", "name", "epic", "status"], &widths),
+        ];
+
+        for (story_id, story, _, epic_name) in &stories {
+            let columns = [
+                ColumnSpec::default_id(widths[0]).format(&story_id.to_string()),
+                ColumnSpec::default_name(widths[1]).format(&story.name),
+                ColumnSpec::default_name(widths[2]).format(epic_name),
+                ctx.theme.status_column(&story.status, widths[3]),
+            ];
+            lines.push(join_columns(&columns));
+        }
+
+        if stories.is_empty() {
+            lines.push(String::new());
+            lines.push("No watched stories".to_owned());
+        }
+
+        lines.push(String::new());
+        lines.push(String::new());
+
+        lines.extend(ctx.command_bar("[:id:] navigate to story"));
+
+        Ok(lines)
+    }
+
+    fn handle_input(&self, input: &str) -> Result<Option<Action>> {
+        match input {
+            "P" | "p" => Ok(Some(Action::NavigateToPreviousPage)),
+            "?" => Ok(Some(Action::ShowHelp {
+                context: PageType::WatchList,
+            })),
+            input => {
+                let Ok(id) = input.parse::<u32>() else {
+                    return Ok(None);
+                };
+
+                Ok(self
+                    .watched_stories()?
+                    .into_iter()
+                    .find(|(story_id, ..)| *story_id == id)
+                    .map(|(story_id, _, epic_id, _)| Action::NavigateToStoryDetail {
+                        epic_id,
+                        story_id,
+                    }))
+            }
+        }
+    }
+
+    fn input_suggestions(&self) -> Result<Vec<(String, String)>> {
+        Ok(self
+            .watched_stories()?
+            .into_iter()
+            .map(|(story_id, story, ..)| (story_id.to_string(), story.name))
+            .collect())
+    }
+}
+
+#[derive(PageBoilerplate)]
+pub struct ImportPage;
+
+impl Page for ImportPage {
+    fn title(&self) -> String {
+        "import".to_owned()
+    }
+
+    fn page_title(&self) -> String {
+        "Import".to_owned()
+    }
+
+    fn draw_page(&self, ctx: &DrawContext) -> Result<Vec<String>> {
+        let mut lines = vec![
+            banner("IMPORT", ctx.width),
+            "Type the path of a CSV file written by [e] export csv.".to_owned(),
+            "Its epics and stories are added with new ids, so nothing is overwritten.".to_owned(),
+            "Stories whose epic cannot be found are skipped.".to_owned(),
+        ];
+
+        lines.push(String::new());
+        lines.push(String::new());
+
+        lines.extend(ctx.command_bar("[:path:] import csv file"));
+
+        Ok(lines)
+    }
+
+    fn handle_input(&self, input: &str) -> Result<Option<Action>> {
+        match input.trim() {
+            "P" | "p" => Ok(Some(Action::NavigateToPreviousPage)),
+            "" => Ok(None),
+            path => Ok(Some(Action::ImportCsv {
+                path: path.to_owned(),
+            })),
+        }
+    }
+}
+
+// Keep these in step with the footer each page draws
+fn keybindings(context: PageType) -> Vec<(&'static str, &'static str)> {
+    match context {
+        PageType::Home => vec![
+            ("q", "quit"),
+            ("c", "create epic"),
+            ("p", "switch project"),
+            ("r", "sprints"),
+            ("l", "backlog"),
+            ("v", "releases"),
+            ("W", "watch list"),
+            ("d", "dashboard"),
+            ("s", "sort"),
+            ("S", "reverse sort"),
+            ("t", "filter by tag"),
+            ("T", "clear tag filter"),
+            ("f", "stories by reporter"),
+            ("n", "next page"),
+            ("b", "prev page"),
+            ("e", "export csv"),
+            ("m", "export markdown"),
+            ("I", "import"),
+            (":id:", "navigate to epic"),
+        ],
+        PageType::ProjectSelect => vec![
+            ("p", "previous"),
+            ("n", "new project"),
+            (":#:", "switch to project"),
+        ],
+        PageType::EpicDetail => vec![
+            ("p", "previous"),
+            ("u", "update epic"),
+            ("n", "rename"),
+            ("e", "edit description"),
+            ("t", "set due date"),
+            ("r", "set release"),
+            ("d", "delete epic"),
+            ("D", "delete closed stories"),
+            ("c", "create story"),
+            ("s", "sort"),
+            ("S", "reverse sort"),
+            (">", "next page"),
+            ("<", "prev page"),
+            (":id:", "navigate to story"),
+        ],
+        PageType::StoryDetail => vec![
+            ("p", "previous"),
+            ("u", "update story"),
+            ("n", "rename"),
+            ("m", "edit description"),
+            ("e", "set estimate"),
+            ("y", "set type"),
+            ("t", "set due date"),
+            ("r", "set release"),
+            ("k", "link dependency"),
+            ("K", "unlink dependency"),
+            ("c", "clone story"),
+            ("v", "move to epic"),
+            ("o", "comments"),
+            ("w", "watch / unwatch"),
+            ("d", "delete story"),
+        ],
+        PageType::Comments => vec![
+            ("p", "previous"),
+            ("a", "add comment"),
+            ("d", "delete comment"),
+        ],
+        PageType::SprintList => vec![
+            ("p", "previous"),
+            ("c", "create sprint"),
+            (":id:", "navigate to sprint"),
+        ],
+        PageType::SprintDetail => vec![
+            ("p", "previous"),
+            ("a", "add story"),
+            ("r", "remove story"),
+            ("d", "delete sprint"),
+            (":id:", "navigate to story"),
+        ],
+        PageType::Backlog => vec![
+            ("p", "previous"),
+            ("a :sprint_id:", "add > story to sprint"),
+            (":id:", "navigate to story"),
+        ],
+        PageType::Search => vec![("p", "previous"), (":id:", "navigate to epic or story")],
+        PageType::Reporter => vec![("p", "previous"), (":id:", "navigate to story")],
+        PageType::Releases => vec![("p", "previous"), (":#:", "show release")],
+        PageType::ReleaseDetail => vec![("p", "previous"), (":id:", "navigate to epic or story")],
+        PageType::WatchList => vec![("p", "previous"), (":id:", "navigate to story")],
+        PageType::Dashboard => vec![("p", "previous")],
+    }
+}
+
+#[derive(PageBoilerplate)]
+pub struct HelpPage {
+    pub context: PageType,
+}
+
+impl Page for HelpPage {
+    fn title(&self) -> String {
+        format!("help_{}", self.context)
+    }
+
+    fn page_title(&self) -> String {
+        "Help".to_owned()
+    }
+
+    fn draw_page(&self, ctx: &DrawContext) -> Result<Vec<String>> {
+        let widths = column_widths(ctx.width, &[16, 48]);
+        let mut lines = vec![
+            banner("HELP", ctx.width),
+            format!("Commands on the {} page", self.context),
+            String::new(),
+            header_row(&["key", "description"], &widths),
+        ];
+
+        // Global shortcuts work everywhere, so list them after the page's own
+        for (key, description) in keybindings(self.context)
+            .into_iter()
+            .chain(self.global_shortcuts())
+        {
+            let columns = [
+                get_column_string(&format!("[{}]", key), widths[0]),
+                get_column_string(description, widths[1]),
+            ];
+            lines.push(join_columns(&columns));
+        }
+
+        lines.push(String::new());
+        lines.push(String::new());
+
+        lines.extend(ctx.command_bar(""));
+
+        Ok(lines)
+    }
+
+    fn handle_input(&self, input: &str) -> Result<Option<Action>> {
+        match input {
+            "P" | "p" => Ok(Some(Action::NavigateToPreviousPage)),
+            _ => Ok(None),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::constants::UNKNOWN_USER;
+    use crate::db::test_utils::MockDB;
+    use crate::models::{Epic, Priority, Sprint, Status, Story, StoryType};
+    use unicode_width::UnicodeWidthStr;
+
+    // A database with one epic named `epic_name` holding `stories`, along with
+    // the epic's id and the stories' ids in order.
+    fn db_with_epic(epic_name: &str, stories: Vec<Story>) -> (Rc<JiraDatabase>, u32, Vec<u32>) {
+        let db = Rc::new(JiraDatabase::with_database(Box::new(MockDB::new())));
+        let epic_id = db
+            .create_epic(Epic::new(epic_name.to_owned(), "".to_owned()))
+            .unwrap();
+        let story_ids = stories
+            .into_iter()
+            .map(|story| db.create_story(story, epic_id).unwrap())
+            .collect();
+        (db, epic_id, story_ids)
+    }
+
+    #[test]
+    fn handle_global_input_should_return_the_correct_actions() {
+        assert_eq!(handle_global_input("h"), Some(Action::NavigateToHomePage));
+        assert_eq!(handle_global_input("H"), Some(Action::NavigateToHomePage));
+        assert_eq!(
+            handle_global_input("x"),
+            Some(Action::ExportCurrentPage { dest: None })
+        );
+        assert_eq!(handle_global_input("R"), Some(Action::Refresh));
+        assert_eq!(
+            handle_global_input("/ needle "),
+            Some(Action::Search {
+                query: "needle".to_owned()
+            })
+        );
+        assert_eq!(handle_global_input("r"), None);
+        assert_eq!(handle_global_input("p"), None);
+        assert_eq!(handle_global_input("h983f2j"), None);
+        assert_eq!(handle_global_input(""), None);
+    }
+
+    #[test]
+    fn suggested_export_filename_should_use_title_and_timestamp() {
+        let db = Rc::new(JiraDatabase::with_database(Box::new(MockDB::new())));
+        let page = EpicDetail::new(7, db);
+
+        let filename = page.suggested_export_filename();
+
+        let timestamp = filename
+            .strip_prefix("epic_7_")
+            .and_then(|rest| rest.strip_suffix(".txt"))
+            .expect("filename should be <title>_<timestamp>.txt");
+        let (date, time) = timestamp.split_once('_').unwrap();
+        assert_eq!(date.len(), 8);
+        assert_eq!(time.len(), 6);
+        assert!(date.chars().chain(time.chars()).all(|c| c.is_ascii_digit()));
+    }
+
+    #[test]
+    fn tables_should_fill_the_terminal_width() {
+        let db = Rc::new(JiraDatabase::with_database(Box::new(MockDB::new())));
+        let epic_id = db
+            .create_epic(Epic::new(
+                "an epic with a rather long name that will not fit".to_owned(),
+                "".to_owned(),
+            ))
+            .unwrap();
+        let story_id = db
+            .create_story(Story::new("story".to_owned(), "".to_owned()), epic_id)
+            .unwrap();
+        // Overdue items carry a badge that has to fit as well
+        let yesterday = Local::now().date_naive().pred_opt();
+        db.update_epic_due_date(epic_id, yesterday).unwrap();
+        db.update_story_due_date(story_id, yesterday).unwrap();
+
+        let ctx = DrawContext {
+            width: 100,
+            ..DrawContext::plain()
+        };
+        let pages: Vec<Box<dyn Page>> = vec![
+            Box::new(HomePage::new(Rc::clone(&db))),
+            Box::new(EpicDetail::new(epic_id, Rc::clone(&db))),
+            Box::new(StoryDetail {
+                epic_id,
+                story_id,
+                db: Rc::clone(&db),
+            }),
+            Box::new(ReporterPage {
+                reporter: UNKNOWN_USER.to_owned(),
+                db,
+            }),
+        ];
+
+        for page in pages {
+            let lines = page.draw_page(&ctx).unwrap();
+            for line in &lines {
+                assert!(line.width() <= 100, "{:?}", line);
+            }
+
+            // Banners, headers and rows span the whole width
+            let table_lines: Vec<&String> = lines
+                .iter()
+                .filter(|line| line.starts_with('-') || line.contains(" | "))
+                .filter(|line| !line.starts_with('['))
+                .collect();
+            assert!(table_lines.len() >= 3);
+            for line in table_lines {
+                assert_eq!(line.width(), 100, "{:?}", line);
+            }
+        }
+    }
+
+    #[test]
+    fn draw_page_should_give_the_same_lines_every_time() {
+        let db = Rc::new(JiraDatabase::with_database(Box::new(MockDB::new())));
+        let epic_id = db
+            .create_epic(Epic::new("epic".to_owned(), "".to_owned()))
+            .unwrap();
+        let story_id = db
+            .create_story(Story::new("story".to_owned(), "".to_owned()), epic_id)
+            .unwrap();
+
+        let pages: Vec<Box<dyn Page>> = vec![
+            Box::new(HomePage::new(Rc::clone(&db))),
+            Box::new(EpicDetail::new(epic_id, Rc::clone(&db))),
+            Box::new(StoryDetail {
+                epic_id,
+                story_id,
+                db: Rc::clone(&db),
+            }),
+            Box::new(BacklogPage { db: Rc::clone(&db) }),
+            Box::new(SearchPage {
+                query: "story".to_owned(),
+                db: Rc::clone(&db),
+            }),
+            Box::new(DashboardPage { db }),
+        ];
+
+        for page in pages {
+            let first = page.draw_page(&DrawContext::plain()).unwrap();
+            let second = page.draw_page(&DrawContext::plain()).unwrap();
+
+            assert_eq!(first, second, "{}", page.title());
+        }
+    }
+
+    mod home_page {
+        use super::*;
+        use chrono::NaiveDate;
+
+        #[test]
+        fn draw_page_should_not_throw_error() {
+            let db = Rc::new(JiraDatabase::with_database(Box::new(MockDB::new())));
+
+            let page = HomePage::new(db);
+            assert_eq!(page.draw_page(&DrawContext::plain()).is_ok(), true);
+        }
+
+        #[test]
+        fn draw_page_should_sort_epics_by_the_chosen_key() {
+            let db = Rc::new(JiraDatabase::with_database(Box::new(MockDB::new())));
+            db.create_epic(Epic {
+                priority: Priority::Low,
+                ..Epic::new("low".to_owned(), "".to_owned())
+            })
+            .unwrap();
+            db.create_epic(Epic {
+                priority: Priority::Critical,
+                ..Epic::new("critical".to_owned(), "".to_owned())
+            })
+            .unwrap();
+
+            let epic_names = |sort_key: SortKey| -> Vec<&'static str> {
+                let page = HomePage {
+                    sort_key,
+                    ..HomePage::new(Rc::clone(&db))
+                };
+                page.draw_page(&DrawContext::plain())
+                    .unwrap()
+                    .iter()
+                    .filter_map(|line| {
+                        ["low", "critical"]
+                            .into_iter()
+                            .find(|name| line.contains(&format!("| {} ", name)))
+                    })
+                    .collect()
+            };
+
+            assert_eq!(epic_names(SortKey::Id), vec!["low", "critical"]);
+            assert_eq!(epic_names(SortKey::Priority), vec!["critical", "low"]);
+            assert_eq!(epic_names(SortKey::Name), vec!["critical", "low"]);
+        }
+
+        #[test]
+        fn epic_ids_should_keep_epics_with_equal_keys_in_id_order() {
+            let mut epics = HashMap::new();
+            for (id, name) in [(1, "Beta"), (2, "alpha"), (3, "beta"), (4, "Alpha")] {
+                epics.insert(id, Epic::new(name.to_owned(), "".to_owned()));
+            }
+            epics.get_mut(&3).unwrap().due_date = NaiveDate::from_ymd_opt(2024, 5, 1);
+            let stories = HashMap::new();
+
+            let db = Rc::new(JiraDatabase::with_database(Box::new(MockDB::new())));
+            let epic_ids = |sort_key: SortKey, sort_dir: SortDir| {
+                HomePage {
+                    sort_key,
+                    sort_dir,
+                    ..HomePage::new(Rc::clone(&db))
+                }
+                .epic_ids(&epics, &stories)
+            };
+
+            // Every epic has the same priority and progress
+            for sort_dir in [SortDir::Ascending, SortDir::Descending] {
+                assert_eq!(epic_ids(SortKey::Priority, sort_dir), vec![1, 2, 3, 4]);
+                assert_eq!(epic_ids(SortKey::CompletionPct, sort_dir), vec![1, 2, 3, 4]);
+            }
+            assert_eq!(
+                epic_ids(SortKey::Name, SortDir::Ascending),
+                vec![2, 4, 1, 3]
+            );
+            assert_eq!(
+                epic_ids(SortKey::Name, SortDir::Descending),
+                vec![1, 3, 2, 4]
+            );
+            assert_eq!(epic_ids(SortKey::Id, SortDir::Descending), vec![4, 3, 2, 1]);
+            // Epics without a due date stay last in either direction
+            assert_eq!(
+                epic_ids(SortKey::DueDate, SortDir::Descending),
+                vec![3, 1, 2, 4]
+            );
+        }
+
+        #[test]
+        fn draw_page_should_only_list_epics_with_the_active_tag() {
+            let db = Rc::new(JiraDatabase::with_database(Box::new(MockDB::new())));
+            db.create_epic(Epic {
+                tags: vec!["backend".to_owned()],
+                ..Epic::new("tagged".to_owned(), "".to_owned())
+            })
+            .unwrap();
+            db.create_epic(Epic::new("untagged".to_owned(), "".to_owned()))
+                .unwrap();
+
+            let page = HomePage {
+                active_tag: Some("backend".to_owned()),
+                ..HomePage::new(db)
+            };
+            let lines = page.draw_page(&DrawContext::plain()).unwrap();
+
+            assert!(lines.contains(&"Tag: backend".to_owned()));
+            assert!(lines.iter().any(|line| line.contains("| tagged ")));
+            assert!(!lines.iter().any(|line| line.contains("untagged")));
+            assert!(
+                lines
+                    .iter()
+                    .any(|line| line.contains("[T] clear tag filter"))
+            );
+        }
+
+        #[test]
+        fn draw_page_should_only_render_the_current_page_of_epics() {
+            let db = Rc::new(JiraDatabase::with_database(Box::new(MockDB::new())));
+            for i in 1..=30 {
+                db.create_epic(Epic::new(format!("epic {}", i), "".to_owned()))
+                    .unwrap();
+            }
+
+            let page = HomePage {
+                page: 1,
+                page_size: 10,
+                ..HomePage::new(db)
+            };
+
+            let lines = page.draw_page(&DrawContext::plain()).unwrap();
+            let epic_ids: Vec<u32> = lines
+                .iter()
+                .filter_map(|line| line.split(" | ").next()?.trim().parse().ok())
+                .collect();
+
+            assert_eq!(epic_ids, (11..=20).collect::<Vec<u32>>());
+            assert!(lines.contains(&"Page 2 of 3".to_owned()));
+            assert!(lines.contains(&"[n] next page | [b] prev page".to_owned()));
+        }
+
+        #[test]
+        fn draw_page_should_color_statuses_only_when_the_theme_allows() {
+            let db = Rc::new(JiraDatabase::with_database(Box::new(MockDB::new())));
+            db.create_epic(Epic::new("epic".to_owned(), "".to_owned()))
+                .unwrap();
+            let page = HomePage::new(db);
+
+            let colored = DrawContext {
+                theme: Theme { use_color: true },
+                ..DrawContext::plain()
+            };
+            let colored_lines = page.draw_page(&colored).unwrap();
+            let plain_lines = page.draw_page(&DrawContext::plain()).unwrap();
+
+            assert!(
+                colored_lines
+                    .iter()
+                    .any(|line| line.contains("\x1b[37mOPEN"))
+            );
+            assert!(!plain_lines.iter().any(|line| line.contains('\x1b')));
+            // Stripping the color codes gives back the plain layout
+            let stripped: Vec<String> = colored_lines
+                .iter()
+                .map(|line| line.replace("\x1b[37m", "").replace("\x1b[0m", ""))
+                .collect();
+            assert_eq!(stripped, plain_lines);
+        }
+
+        #[test]
+        fn draw_page_should_hide_paging_hints_when_everything_fits() {
+            let db = Rc::new(JiraDatabase::with_database(Box::new(MockDB::new())));
+            db.create_epic(Epic::new("epic".to_owned(), "".to_owned()))
+                .unwrap();
+
+            let page = HomePage::new(db);
+
+            let lines = page.draw_page(&DrawContext::plain()).unwrap();
+            assert!(!lines.contains(&"[n] next page | [b] prev page".to_owned()));
+        }
+
+        #[test]
+        fn input_suggestions_should_list_epics_by_id() {
+            let db = Rc::new(JiraDatabase::with_database(Box::new(MockDB::new())));
+            db.create_epic(Epic::new("first".to_owned(), "".to_owned()))
+                .unwrap();
+            db.create_epic(Epic::new("second".to_owned(), "".to_owned()))
+                .unwrap();
+
+            let page = HomePage::new(db);
+
+            assert_eq!(
+                page.input_suggestions().unwrap(),
+                vec![
+                    ("1".to_owned(), "first".to_owned()),
+                    ("2".to_owned(), "second".to_owned())
+                ]
+            );
+        }
+
+        #[test]
+        fn handle_input_should_not_throw_error() {
+            let db = Rc::new(JiraDatabase::with_database(Box::new(MockDB::new())));
+
+            let page = HomePage::new(db);
+            assert_eq!(page.handle_input("").is_ok(), true);
+        }
+
+        #[test]
+        fn handle_input_should_return_the_correct_actions() {
+            let db = Rc::new(JiraDatabase::with_database(Box::new(MockDB::new())));
+
+            let epic = Epic::new("epic".to_owned(), "".to_owned());
+
+            let epic_id = db.create_epic(epic).unwrap();
+
+            let page = HomePage::new(db);
+
+            let q = "q";
+            let c = "c";
+            let p = "p";
+            let r = "r";
+            let l = "l";
+            let s = "s";
+            let n = "n";
+            let b = "b";
+            let e = "e";
+            let t = "t";
+            let t_with_tag = "t backend ";
+            let clear_tag = "T";
+            let f = "f";
+            let f_with_reporter = "f alice ";
+            let valid_epic_id = epic_id.to_string();
+            let invalid_epic_id = "999";
+            let junk_input = "j983f2j";
+            let junk_input_with_valid_prefix = "q983f2j";
+            let input_with_trailing_white_spaces = "q\n";
+
+            assert_eq!(page.handle_input(q).unwrap(), Some(Action::Exit));
+            assert_eq!(page.handle_input(c).unwrap(), Some(Action::CreateEpic));
+            assert_eq!(
+                page.handle_input(p).unwrap(),
+                Some(Action::NavigateToProjectSelect)
+            );
+            assert_eq!(
+                page.handle_input(r).unwrap(),
+                Some(Action::NavigateToSprintList)
+            );
+            assert_eq!(
+                page.handle_input(l).unwrap(),
+                Some(Action::NavigateToBacklog)
+            );
+            assert_eq!(
+                page.handle_input("v").unwrap(),
+                Some(Action::NavigateToReleases)
+            );
+            assert_eq!(
+                page.handle_input("W").unwrap(),
+                Some(Action::NavigateToWatchList)
+            );
+            assert_eq!(
+                page.handle_input("d").unwrap(),
+                Some(Action::NavigateToDashboard)
+            );
+            assert_eq!(
+                page.handle_input(s).unwrap(),
+                Some(Action::SortHomePage { key: None })
+            );
+            assert_eq!(
+                page.handle_input("S").unwrap(),
+                Some(Action::ReverseHomePageSort)
+            );
+            assert_eq!(page.handle_input(n).unwrap(), Some(Action::NextPage));
+            assert_eq!(
+                page.handle_input(b).unwrap(),
+                Some(Action::PreviousListPage)
+            );
+            assert_eq!(page.handle_input(e).unwrap(), Some(Action::ExportCsv));
+            assert_eq!(
+                page.handle_input("m").unwrap(),
+                Some(Action::ExportMarkdown)
+            );
+            assert_eq!(
+                page.handle_input("I").unwrap(),
+                Some(Action::NavigateToImport)
+            );
+            assert_eq!(
+                page.handle_input(t).unwrap(),
+                Some(Action::FilterHomePageByTag { tag: None })
+            );
+            assert_eq!(
+                page.handle_input(t_with_tag).unwrap(),
+                Some(Action::FilterHomePageByTag {
+                    tag: Some("backend".to_owned())
+                })
+            );
+            assert_eq!(
+                page.handle_input(clear_tag).unwrap(),
+                Some(Action::ClearHomePageTagFilter)
+            );
+            assert_eq!(
+                page.handle_input(f).unwrap(),
+                Some(Action::NavigateToReporterStories { reporter: None })
+            );
+            assert_eq!(
+                page.handle_input(f_with_reporter).unwrap(),
+                Some(Action::NavigateToReporterStories {
+                    reporter: Some("alice".to_owned())
+                })
+            );
+            assert_eq!(
+                page.handle_input(&valid_epic_id).unwrap(),
+                Some(Action::NavigateToEpicDetail { epic_id: 1 })
+            );
+            assert_eq!(page.handle_input(invalid_epic_id).unwrap(), None);
+            assert_eq!(
+                page.handle_input("?").unwrap(),
+                Some(Action::ShowHelp {
+                    context: PageType::Home
+                })
+            );
+            assert_eq!(page.handle_input(junk_input).unwrap(), None);
+            assert_eq!(
+                page.handle_input(junk_input_with_valid_prefix).unwrap(),
+                None
+            );
+            assert_eq!(
+                page.handle_input(input_with_trailing_white_spaces).unwrap(),
+                None
+            );
+        }
+    }
+
+    mod project_select_page {
+        use super::*;
+
+        #[test]
+        fn draw_page_should_mark_the_active_project() {
+            let db = Rc::new(JiraDatabase::with_database(Box::new(MockDB::new())));
+            db.use_project("side project").unwrap();
+
+            let page = ProjectSelectPage { db };
+            let lines = page.draw_page(&DrawContext::plain()).unwrap();
+
+            assert!(
+                lines
+                    .iter()
+                    .any(|line| line.starts_with(" 1 ") && line.contains("default"))
+            );
+            assert!(
+                lines
+                    .iter()
+                    .any(|line| line.starts_with("*2 ") && line.contains("side project"))
+            );
+        }
+
+        #[test]
+        fn handle_input_should_return_the_correct_actions() {
+            let db = Rc::new(JiraDatabase::with_database(Box::new(MockDB::new())));
+            db.use_project("side project").unwrap();
+
+            let page = ProjectSelectPage { db };
+
+            assert_eq!(
+                page.handle_input("p").unwrap(),
+                Some(Action::NavigateToPreviousPage)
+            );
+            assert_eq!(page.handle_input("n").unwrap(), Some(Action::CreateProject));
+            assert_eq!(
+                page.handle_input("1").unwrap(),
+                Some(Action::SwitchProject {
+                    name: "default".to_owned()
+                })
+            );
+            assert_eq!(page.handle_input("0").unwrap(), None);
+            assert_eq!(page.handle_input("3").unwrap(), None);
+            assert_eq!(page.handle_input("j983f2j").unwrap(), None);
+        }
+    }
+
+    mod epic_detail_page {
+        use super::*;
+
+        #[test]
+        fn draw_page_should_not_throw_error() {
+            let db = Rc::new(JiraDatabase::with_database(Box::new(MockDB::new())));
+            let epic_id = db
+                .create_epic(Epic::new("epic".to_owned(), "".to_owned()))
+                .unwrap();
+
+            let page = EpicDetail::new(epic_id, db);
+            assert_eq!(page.draw_page(&DrawContext::plain()).is_ok(), true);
+        }
+
+        #[test]
+        fn draw_page_should_show_how_old_the_story_is() {
+            let db = Rc::new(JiraDatabase::with_database(Box::new(MockDB::new())));
+
+            let epic_id = db
+                .create_epic(Epic::new("epic".to_owned(), "".to_owned()))
+                .unwrap();
+            let story_id = db
+                .create_story(
+                    Story {
+                        created_at: Utc::now() - chrono::TimeDelta::days(5),
+                        ..Story::new("story".to_owned(), "".to_owned())
+                    },
+                    epic_id,
+                )
+                .unwrap();
+
+            let page = StoryDetail {
+                epic_id,
+                story_id,
+                db,
+            };
+            let lines = page.draw_page(&DrawContext::plain()).unwrap();
+
+            assert!(lines.contains(&"Created: 5 days ago".to_owned()));
+            assert!(lines.contains(&"Updated: today".to_owned()));
+        }
+
+        #[test]
+        fn draw_page_should_show_the_updated_name_and_description() {
+            let db = Rc::new(JiraDatabase::with_database(Box::new(MockDB::new())));
+            let epic_id = db
+                .create_epic(Epic::new("old".to_owned(), "before".to_owned()))
+                .unwrap();
+            db.update_epic_name(epic_id, "renamed".to_owned()).unwrap();
+            db.update_epic_description(epic_id, "after".to_owned())
+                .unwrap();
+
+            let page = EpicDetail::new(epic_id, db);
+            let lines = page.draw_page(&DrawContext::plain()).unwrap();
+
+            assert!(lines.iter().any(|line| line.contains("| renamed ")));
+            assert!(lines.iter().any(|line| line.contains("| after ")));
+            assert!(!lines.iter().any(|line| line.contains("before")));
+        }
+
+        #[test]
+        fn draw_page_should_list_stories_in_workflow_order() {
+            let db = Rc::new(JiraDatabase::with_database(Box::new(MockDB::new())));
+            let epic_id = db
+                .create_epic(Epic::new("epic".to_owned(), "".to_owned()))
+                .unwrap();
+            let closed_story_id = db
+                .create_story(Story::new("closed".to_owned(), "".to_owned()), epic_id)
+                .unwrap();
+            db.create_story(Story::new("open".to_owned(), "".to_owned()), epic_id)
+                .unwrap();
+            db.update_story_status(closed_story_id, Status::Closed)
+                .unwrap();
+
+            let page = EpicDetail::new(epic_id, db);
+            let lines = page.draw_page(&DrawContext::plain()).unwrap();
+
+            let row_of = |name: &str| lines.iter().position(|line| line.contains(name)).unwrap();
+            assert!(row_of("open") < row_of("closed"));
+        }
+
+        #[test]
+        fn draw_page_should_warn_about_missing_stories() {
+            use crate::db::Database;
+
+            let mock = MockDB::new();
+            let epic = Epic {
+                stories: vec![42],
+                ..Epic::new("epic".to_owned(), "".to_owned())
+            };
+            let mut state = mock.read_db().unwrap();
+            state.last_item_id = 1;
+            state.epics.insert(1, epic);
+            mock.write_db(&state).unwrap();
+
+            let db = Rc::new(JiraDatabase::with_database(Box::new(mock)));
+            let page = EpicDetail::new(1, db);
+            let lines = page.draw_page(&DrawContext::plain()).unwrap();
+
+            assert!(
+                lines.contains(&"[!] Story #42 referenced but not found (run repair)".to_owned())
+            );
+        }
+
+        #[test]
+        fn draw_page_should_only_mark_epics_past_their_due_date_as_overdue() {
+            let db = Rc::new(JiraDatabase::with_database(Box::new(MockDB::new())));
+            let today = Local::now().date_naive();
+            let epic_id = db
+                .create_epic(Epic::new("epic".to_owned(), "".to_owned()))
+                .unwrap();
+            let page = EpicDetail::new(epic_id, Rc::clone(&db));
+            let home_page = HomePage::new(Rc::clone(&db));
+            let is_marked_overdue = |lines: Vec<String>| {
+                lines.iter().any(|line| {
+                    line.contains("| epic [OVERDUE] ")
+                        || (line.starts_with("Due: ") && line.ends_with(" [OVERDUE]"))
+                })
+            };
+
+            db.update_epic_due_date(epic_id, today.pred_opt()).unwrap();
+            assert!(is_marked_overdue(
+                page.draw_page(&DrawContext::plain()).unwrap()
+            ));
+            assert!(is_marked_overdue(
+                home_page.draw_page(&DrawContext::plain()).unwrap()
+            ));
+
+            db.update_epic_due_date(epic_id, today.succ_opt()).unwrap();
+            assert!(!is_marked_overdue(
+                page.draw_page(&DrawContext::plain()).unwrap()
+            ));
+            assert!(!is_marked_overdue(
+                home_page.draw_page(&DrawContext::plain()).unwrap()
+            ));
+        }
+
+        #[test]
+        fn draw_page_should_only_list_the_stories_on_the_current_page() {
+            let db = Rc::new(JiraDatabase::with_database(Box::new(MockDB::new())));
+            let epic_id = db
+                .create_epic(Epic::new("epic".to_owned(), "".to_owned()))
+                .unwrap();
+            for i in 0..25 {
+                db.create_story(Story::new(format!("story {}", i), "".to_owned()), epic_id)
+                    .unwrap();
+            }
+
+            let draw = |page: usize| {
+                EpicDetail {
+                    page,
+                    page_size: 10,
+                    ..EpicDetail::new(epic_id, Rc::clone(&db))
+                }
+                .draw_page(&DrawContext::plain())
+                .unwrap()
+            };
+            let story_rows = |lines: &[String]| {
+                lines
+                    .iter()
+                    .filter(|line| line.contains("| story "))
+                    .count()
+            };
+
+            let lines = draw(0);
+            assert_eq!(story_rows(&lines), 10);
+            assert!(lines.contains(&"Page 1 of 3".to_owned()));
+            assert!(lines.iter().any(|line| line.contains("| story 0 ")));
+
+            let lines = draw(1);
+            assert_eq!(story_rows(&lines), 10);
+            assert!(lines.contains(&"Page 2 of 3".to_owned()));
+            assert!(lines.iter().any(|line| line.contains("| story 10 ")));
+
+            let lines = draw(2);
+            assert_eq!(story_rows(&lines), 5);
+            assert!(lines.contains(&"Page 3 of 3".to_owned()));
+            assert!(lines.iter().any(|line| line.contains("| story 24 ")));
+
+            let lines = EpicDetail::new(epic_id, Rc::clone(&db))
+                .draw_page(&DrawContext::plain())
+                .unwrap();
+            assert_eq!(story_rows(&lines), 20);
+            assert!(lines.contains(&"Page 1 of 2".to_owned()));
+        }
+
+        #[test]
+        fn story_ids_should_keep_stories_with_equal_keys_in_id_order() {
+            let db = Rc::new(JiraDatabase::with_database(Box::new(MockDB::new())));
+            let epic_id = db
+                .create_epic(Epic::new("epic".to_owned(), "".to_owned()))
+                .unwrap();
+            for (name, estimate, story_type) in [
+                ("one", Some(3), StoryType::Task),
+                ("two", None, StoryType::Bug),
+                ("three", Some(3), StoryType::Task),
+                ("four", Some(1), StoryType::Bug),
+            ] {
+                let story = Story {
+                    estimate,
+                    story_type,
+                    ..Story::new(name.to_owned(), "".to_owned())
+                };
+                db.create_story(story, epic_id).unwrap();
+            }
+
+            let db_state = db.read_db().unwrap();
+            let epic = &db_state.epics[&epic_id];
+            let story_ids = |sort_key: StorySortKey, sort_dir: SortDir| {
+                EpicDetail {
+                    sort_key,
+                    sort_dir,
+                    ..EpicDetail::new(epic_id, Rc::clone(&db))
+                }
+                .story_ids(epic, &db_state.stories)
+            };
+
+            for sort_dir in [SortDir::Ascending, SortDir::Descending] {
+                assert_eq!(story_ids(StorySortKey::Status, sort_dir), vec![2, 3, 4, 5]);
+            }
+            assert_eq!(
+                story_ids(StorySortKey::Estimate, SortDir::Ascending),
+                vec![5, 2, 4, 3]
+            );
+            // Stories without an estimate stay last in either direction
+            assert_eq!(
+                story_ids(StorySortKey::Estimate, SortDir::Descending),
+                vec![2, 4, 5, 3]
+            );
+            assert_eq!(
+                story_ids(StorySortKey::StoryType, SortDir::Ascending),
+                vec![3, 5, 2, 4]
+            );
+            assert_eq!(
+                story_ids(StorySortKey::StoryType, SortDir::Descending),
+                vec![2, 4, 3, 5]
+            );
+            assert_eq!(
+                story_ids(StorySortKey::Id, SortDir::Descending),
+                vec![5, 4, 3, 2]
+            );
+        }
+
+        #[test]
+        fn handle_input_should_not_throw_error() {
+            let db = Rc::new(JiraDatabase::with_database(Box::new(MockDB::new())));
+            let epic_id = db
+                .create_epic(Epic::new("epic".to_owned(), "".to_owned()))
+                .unwrap();
+
+            let page = EpicDetail::new(epic_id, db);
+            assert_eq!(page.handle_input("").is_ok(), true);
+        }
+
+        #[test]
+        fn draw_page_should_throw_error_for_invalid_epic_id() {
+            let db = Rc::new(JiraDatabase::with_database(Box::new(MockDB::new())));
+
+            let page = EpicDetail::new(999, db);
+            assert_eq!(page.draw_page(&DrawContext::plain()).is_err(), true);
+        }
+
+        #[test]
+        fn handle_input_should_batch_delete_only_the_closed_stories() {
+            let db = Rc::new(JiraDatabase::with_database(Box::new(MockDB::new())));
+            let epic_id = db
+                .create_epic(Epic::new("epic".to_owned(), "".to_owned()))
+                .unwrap();
+            let mut closed_ids = vec![];
+            for status in [Status::Closed, Status::Open, Status::Closed] {
+                let story_id = db
+                    .create_story(Story::new("story".to_owned(), "".to_owned()), epic_id)
+                    .unwrap();
+                if status == Status::Closed {
+                    closed_ids.push(story_id);
+                }
+                db.update_story_status(story_id, status).unwrap();
+            }
+            let other_epic_id = db
+                .create_epic(Epic::new("other".to_owned(), "".to_owned()))
+                .unwrap();
+            let other_story_id = db
+                .create_story(Story::new("story".to_owned(), "".to_owned()), other_epic_id)
+                .unwrap();
+            db.update_story_status(other_story_id, Status::Closed)
+                .unwrap();
+
+            let page = EpicDetail::new(epic_id, db);
+
+            assert_eq!(
+                page.handle_input("D").unwrap(),
+                Some(Action::BatchDeleteStories {
+                    story_ids: closed_ids
+                })
+            );
+        }
+
+        #[test]
+        fn handle_input_should_return_the_correct_actions() {
+            let db = Rc::new(JiraDatabase::with_database(Box::new(MockDB::new())));
+
+            let epic_id = db
+                .create_epic(Epic::new("epic".to_owned(), "".to_owned()))
+                .unwrap();
+            let story_id = db
+                .create_story(Story::new("story".to_owned(), "".to_owned()), epic_id)
+                .unwrap();
+
+            let page = EpicDetail::new(epic_id, db);
+
+            let p = "p";
+            let u = "u";
+            let n = "n";
+            let e = "e";
+            let t = "t";
+            let d = "d";
+            let c = "c";
+            let invalid_story_id = "999";
+            let junk_input = "j983f2j";
+            let junk_input_with_valid_prefix = "p983f2j";
+            let input_with_trailing_white_spaces = "p\n";
+
+            assert_eq!(
+                page.handle_input(p).unwrap(),
+                Some(Action::NavigateToPreviousPage)
+            );
+            assert_eq!(
+                page.handle_input(u).unwrap(),
+                Some(Action::UpdateEpicStatus { epic_id: 1 })
+            );
+            assert_eq!(
+                page.handle_input(n).unwrap(),
+                Some(Action::UpdateEpicName {
+                    epic_id: 1,
+                    name: None
+                })
+            );
+            assert_eq!(
+                page.handle_input(e).unwrap(),
+                Some(Action::UpdateEpicDescription {
+                    epic_id: 1,
+                    description: None
+                })
+            );
+            assert_eq!(
+                page.handle_input(t).unwrap(),
+                Some(Action::UpdateEpicDueDate {
+                    epic_id: 1,
+                    date: None
+                })
+            );
+            assert_eq!(
+                page.handle_input("r").unwrap(),
+                Some(Action::UpdateEpicRelease {
+                    epic_id: 1,
+                    release: None
+                })
+            );
+            assert_eq!(
+                page.handle_input(d).unwrap(),
+                Some(Action::DeleteEpic { epic_id: 1 })
+            );
+            assert_eq!(
+                page.handle_input("D").unwrap(),
+                Some(Action::BatchDeleteStories { story_ids: vec![] })
+            );
+            assert_eq!(
+                page.handle_input("s").unwrap(),
+                Some(Action::SortEpicStories { key: None })
+            );
+            assert_eq!(
+                page.handle_input("S").unwrap(),
+                Some(Action::ReverseEpicStoriesSort)
+            );
+            assert_eq!(page.handle_input(">").unwrap(), Some(Action::NextPage));
+            assert_eq!(
+                page.handle_input("<").unwrap(),
+                Some(Action::PreviousListPage)
+            );
+            assert_eq!(
+                page.handle_input(c).unwrap(),
+                Some(Action::CreateStory { epic_id: 1 })
+            );
+            assert_eq!(
+                page.handle_input(&story_id.to_string()).unwrap(),
+                Some(Action::NavigateToStoryDetail {
+                    epic_id: 1,
+                    story_id: 2
+                })
+            );
+            assert_eq!(page.handle_input(invalid_story_id).unwrap(), None);
+            assert_eq!(
+                page.handle_input("?").unwrap(),
+                Some(Action::ShowHelp {
+                    context: PageType::EpicDetail
+                })
+            );
+            assert_eq!(page.handle_input(junk_input).unwrap(), None);
+            assert_eq!(
+                page.handle_input(junk_input_with_valid_prefix).unwrap(),
+                None
+            );
+            assert_eq!(
+                page.handle_input(input_with_trailing_white_spaces).unwrap(),
+                None
+            );
+        }
+    }
+
+    mod story_detail_page {
+        use super::*;
+
+        #[test]
+        fn draw_page_should_not_throw_error() {
+            let db = Rc::new(JiraDatabase::with_database(Box::new(MockDB::new())));
+
+            let epic_id = db
+                .create_epic(Epic::new("epic".to_owned(), "".to_owned()))
+                .unwrap();
+            let story_id = db
+                .create_story(Story::new("story".to_owned(), "".to_owned()), epic_id)
+                .unwrap();
+
+            let page = StoryDetail {
+                epic_id,
+                story_id,
+                db,
+            };
+            assert_eq!(page.draw_page(&DrawContext::plain()).is_ok(), true);
+        }
+
+        #[test]
+        fn draw_page_should_show_the_description_word_count() {
+            let db = Rc::new(JiraDatabase::with_database(Box::new(MockDB::new())));
+
+            let epic_id = db
+                .create_epic(Epic::new("epic".to_owned(), "".to_owned()))
+                .unwrap();
+            let story_id = db
+                .create_story(
+                    Story::new("story".to_owned(), " three  short words ".to_owned()),
+                    epic_id,
+                )
+                .unwrap();
+
+            let page = StoryDetail {
+                epic_id,
+                story_id,
+                db,
+            };
+            let lines = page.draw_page(&DrawContext::plain()).unwrap();
+
+            assert!(lines.contains(&"Description: 3 words".to_owned()));
+        }
+
+        #[test]
+        fn draw_page_should_show_the_updated_name_and_description() {
+            let db = Rc::new(JiraDatabase::with_database(Box::new(MockDB::new())));
+
+            let epic_id = db
+                .create_epic(Epic::new("epic".to_owned(), "".to_owned()))
+                .unwrap();
+            let story_id = db
+                .create_story(Story::new("old".to_owned(), "before".to_owned()), epic_id)
+                .unwrap();
+            db.update_story_name(story_id, "renamed".to_owned())
+                .unwrap();
+            db.update_story_description(story_id, "after".to_owned())
+                .unwrap();
+
+            let page = StoryDetail {
+                epic_id,
+                story_id,
+                db,
+            };
+            let lines = page.draw_page(&DrawContext::plain()).unwrap();
+
+            assert!(lines.iter().any(|line| line.contains("| renamed ")));
+            assert!(lines.iter().any(|line| line.contains("| after ")));
+            assert!(!lines.iter().any(|line| line.contains("before")));
+        }
+
+        #[test]
+        fn draw_page_should_list_blocking_and_blocked_stories() {
+            let db = Rc::new(JiraDatabase::with_database(Box::new(MockDB::new())));
+
+            let epic_id = db
+                .create_epic(Epic::new("epic".to_owned(), "".to_owned()))
+                .unwrap();
+            let story_id = db
+                .create_story(Story::new("story".to_owned(), "".to_owned()), epic_id)
+                .unwrap();
+            let blocked_id = db
+                .create_story(Story::new("later".to_owned(), "".to_owned()), epic_id)
+                .unwrap();
+            db.add_story_dependency(story_id, blocked_id).unwrap();
+
+            let page = StoryDetail {
+                epic_id,
+                story_id,
+                db,
+            };
+            let lines = page.draw_page(&DrawContext::plain()).unwrap();
+
+            let blocks_at = lines.iter().position(|line| line == "BLOCKS").unwrap();
+            assert!(lines[blocks_at + 1].contains("later"));
+            let blocked_by_at = lines.iter().position(|line| line == "BLOCKED BY").unwrap();
+            assert_eq!(lines[blocked_by_at + 1], "  (none)");
+        }
+
+        #[test]
+        fn handle_input_should_not_throw_error() {
+            let db = Rc::new(JiraDatabase::with_database(Box::new(MockDB::new())));
+
+            let epic_id = db
+                .create_epic(Epic::new("epic".to_owned(), "".to_owned()))
+                .unwrap();
+            let story_id = db
+                .create_story(Story::new("story".to_owned(), "".to_owned()), epic_id)
+                .unwrap();
+
+            let page = StoryDetail {
+                epic_id,
+                story_id,
+                db,
+            };
+            assert_eq!(page.handle_input("").is_ok(), true);
+        }
+
+        #[test]
+        fn draw_page_should_throw_error_for_invalid_story_id() {
+            let db = Rc::new(JiraDatabase::with_database(Box::new(MockDB::new())));
+
+            let epic_id = db
+                .create_epic(Epic::new("epic".to_owned(), "".to_owned()))
+                .unwrap();
+            let _ = db
+                .create_story(Story::new("story".to_owned(), "".to_owned()), epic_id)
+                .unwrap();
+
+            let page = StoryDetail {
+                epic_id,
+                story_id: 999,
+                db,
+            };
+            assert_eq!(page.draw_page(&DrawContext::plain()).is_err(), true);
+        }
+
+        #[test]
+        fn handle_input_should_return_the_correct_actions() {
+            let db = Rc::new(JiraDatabase::with_database(Box::new(MockDB::new())));
+
+            let epic_id = db
+                .create_epic(Epic::new("epic".to_owned(), "".to_owned()))
+                .unwrap();
+            let story_id = db
+                .create_story(Story::new("story".to_owned(), "".to_owned()), epic_id)
+                .unwrap();
+
+            let page = StoryDetail {
+                epic_id,
+                story_id,
+                db,
+            };
+
+            let p = "p";
+            let u = "u";
+            let n = "n";
+            let m = "m";
+            let e = "e";
+            let t = "t";
+            let k = "k";
+            let shift_k = "K";
+            let c = "c";
+            let v = "v";
+            let y = "y";
+            let o = "o";
+            let d = "d";
+            let some_number = "1";
+            let junk_input = "j983f2j";
+            let junk_input_with_valid_prefix = "p983f2j";
+            let input_with_trailing_white_spaces = "p\n";
+
+            assert_eq!(
+                page.handle_input(p).unwrap(),
+                Some(Action::NavigateToPreviousPage)
+            );
+            assert_eq!(
+                page.handle_input(u).unwrap(),
+                Some(Action::UpdateStoryStatus { story_id })
+            );
+            assert_eq!(
+                page.handle_input(n).unwrap(),
+                Some(Action::UpdateStoryName {
+                    story_id,
+                    name: None
+                })
+            );
+            assert_eq!(
+                page.handle_input(m).unwrap(),
+                Some(Action::UpdateStoryDescription {
+                    story_id,
+                    description: None
+                })
+            );
+            assert_eq!(
+                page.handle_input(e).unwrap(),
+                Some(Action::UpdateStoryEstimate { story_id })
+            );
+            assert_eq!(
+                page.handle_input(t).unwrap(),
+                Some(Action::UpdateStoryDueDate {
+                    story_id,
+                    date: None
+                })
+            );
+            assert_eq!(
+                page.handle_input(k).unwrap(),
+                Some(Action::LinkStoryDependency { story_id })
+            );
+            assert_eq!(
+                page.handle_input(shift_k).unwrap(),
+                Some(Action::UnlinkStoryDependency { story_id })
+            );
+            assert_eq!(
+                page.handle_input(c).unwrap(),
+                Some(Action::CloneStory { story_id })
+            );
+            assert_eq!(
+                page.handle_input(y).unwrap(),
+                Some(Action::UpdateStoryType {
+                    story_id,
+                    story_type: None
+                })
+            );
+            assert_eq!(
+                page.handle_input("r").unwrap(),
+                Some(Action::UpdateStoryRelease {
+                    story_id,
+                    release: None
+                })
+            );
+            assert_eq!(
+                page.handle_input("w").unwrap(),
+                Some(Action::ToggleWatch { story_id })
+            );
+            assert_eq!(
+                page.handle_input(v).unwrap(),
+                Some(Action::MoveStory {
+                    story_id,
+                    target_epic_id: None
+                })
+            );
+            assert_eq!(
+                page.handle_input(o).unwrap(),
+                Some(Action::NavigateToComments { story_id })
+            );
+            assert_eq!(
+                page.handle_input(d).unwrap(),
+                Some(Action::DeleteStory { epic_id, story_id })
+            );
+            assert_eq!(page.handle_input(some_number).unwrap(), None);
+            assert_eq!(
+                page.handle_input("?").unwrap(),
+                Some(Action::ShowHelp {
+                    context: PageType::StoryDetail
+                })
+            );
+            assert_eq!(page.handle_input(junk_input).unwrap(), None);
+            assert_eq!(
+                page.handle_input(junk_input_with_valid_prefix).unwrap(),
+                None
+            );
+            assert_eq!(
+                page.handle_input(input_with_trailing_white_spaces).unwrap(),
+                None
+            );
+        }
+    }
+
+    mod comments_page {
+        use super::*;
+
+        fn seeded_db() -> (Rc<JiraDatabase>, u32) {
+            let (db, _, story_ids) =
+                db_with_epic("epic", vec![Story::new("story".to_owned(), "".to_owned())]);
+            (db, story_ids[0])
+        }
+
+        #[test]
+        fn draw_page_should_list_comments() {
+            let (db, story_id) = seeded_db();
+            db.add_comment(story_id, "alice", "ship it").unwrap();
+            db.add_comment(story_id, "bob", "a".repeat(200).as_str())
+                .unwrap();
+
+            let page = CommentsPage { story_id, db };
+            let lines = page.draw_page(&DrawContext::plain()).unwrap();
+
+            assert!(
+                lines
+                    .iter()
+                    .any(|line| line.contains("alice") && line.contains("ship it"))
+            );
+            // Long comments are cut to fit the column
+            assert!(
+                lines
+                    .iter()
+                    .any(|line| line.contains("bob") && line.contains("..."))
+            );
+            assert!(!lines.iter().any(|line| line.contains(&"a".repeat(200))));
+            assert!(!lines.contains(&"No comments".to_owned()));
+        }
+
+        #[test]
+        fn draw_page_should_say_when_there_are_no_comments() {
+            let (db, story_id) = seeded_db();
+
+            let page = CommentsPage { story_id, db };
+
+            assert!(
+                page.draw_page(&DrawContext::plain())
+                    .unwrap()
+                    .contains(&"No comments".to_owned())
+            );
+        }
+
+        #[test]
+        fn handle_input_should_return_the_correct_actions() {
+            let (db, story_id) = seeded_db();
+
+            let page = CommentsPage { story_id, db };
+
+            assert_eq!(
+                page.handle_input("p").unwrap(),
+                Some(Action::NavigateToPreviousPage)
+            );
+            assert_eq!(
+                page.handle_input("a").unwrap(),
+                Some(Action::AddComment { story_id })
+            );
+            assert_eq!(
+                page.handle_input("d").unwrap(),
+                Some(Action::DeleteComment { story_id })
+            );
+            assert_eq!(page.handle_input("1").unwrap(), None);
+            assert_eq!(page.handle_input("j983f2j").unwrap(), None);
+        }
+    }
+
+    mod sprint_pages {
+        use super::*;
+        use chrono::NaiveDate;
+
+        // One epic holding two stories, with only the first in the sprint
+        fn seeded_db() -> (Rc<JiraDatabase>, u32, u32, u32) {
+            let (db, epic_id, story_ids) = db_with_epic(
+                "epic",
+                vec![
+                    Story::new("planned".to_owned(), "".to_owned()),
+                    Story::new("unplanned".to_owned(), "".to_owned()),
+                ],
+            );
+            let story_id = story_ids[0];
+            db.update_story_estimate(story_id, 5).unwrap();
+            let sprint_id = db
+                .create_sprint(Sprint::new(
+                    "sprint 1".to_owned(),
+                    NaiveDate::from_ymd_opt(2025, 6, 2).unwrap(),
+                    NaiveDate::from_ymd_opt(2025, 6, 13).unwrap(),
+                ))
+                .unwrap();
+            db.assign_story_to_sprint(sprint_id, story_id).unwrap();
+
+            (db, epic_id, story_id, sprint_id)
+        }
+
+        #[test]
+        fn sprint_page_should_list_sprints_newest_first_with_their_points() {
+            let (db, _, story_id, sprint_id) = seeded_db();
+            let today = Local::now().date_naive();
+            let active_sprint_id = db
+                .create_sprint(Sprint::new(
+                    "sprint 2".to_owned(),
+                    today.pred_opt().unwrap(),
+                    today.succ_opt().unwrap(),
+                ))
+                .unwrap();
+            let unplanned_id = story_id + 1;
+            db.update_story_estimate(unplanned_id, 3).unwrap();
+            db.assign_story_to_sprint(active_sprint_id, unplanned_id)
+                .unwrap();
+            let page = SprintPage { db };
+
+            let lines = page.draw_page(&DrawContext::plain()).unwrap();
+            let rows: Vec<Vec<&str>> = lines
+                .iter()
+                .filter(|line| line.contains("| sprint "))
+                .map(|line| line.split(" | ").map(str::trim).collect())
+                .collect();
+
+            assert_eq!(
+                rows,
+                vec![
+                    vec![
+                        &active_sprint_id.to_string(),
+                        "sprint 2",
+                        &today.pred_opt().unwrap().to_string(),
+                        &today.succ_opt().unwrap().to_string(),
+                        "1",
+                        "3",
+                        "*",
+                    ],
+                    vec![
+                        &sprint_id.to_string(),
+                        "sprint 1",
+                        "2025-06-02",
+                        "2025-06-13",
+                        "1",
+                        "5",
+                        ""
+                    ],
+                ]
+            );
+        }
+
+        #[test]
+        fn sprint_page_handle_input_should_return_the_correct_actions() {
+            let (db, _, _, sprint_id) = seeded_db();
+            let page = SprintPage { db };
+
+            assert_eq!(
+                page.handle_input("p").unwrap(),
+                Some(Action::NavigateToPreviousPage)
+            );
+            assert_eq!(page.handle_input("c").unwrap(), Some(Action::CreateSprint));
+            assert_eq!(
+                page.handle_input(&sprint_id.to_string()).unwrap(),
+                Some(Action::NavigateToSprintDetail { sprint_id })
+            );
+            assert_eq!(page.handle_input("999").unwrap(), None);
+        }
+
+        #[test]
+        fn sprint_detail_should_list_only_sprint_stories() {
+            let (db, _, _, sprint_id) = seeded_db();
+            let page = SprintDetail { sprint_id, db };
+
+            let lines = page.draw_page(&DrawContext::plain()).unwrap();
+
+            assert!(lines.iter().any(|line| line.contains("| planned ")));
+            assert!(!lines.iter().any(|line| line.contains("unplanned")));
+            assert!(lines.contains(&"Total: 5 points".to_owned()));
+        }
+
+        #[test]
+        fn sprint_detail_handle_input_should_return_the_correct_actions() {
+            let (db, epic_id, story_id, sprint_id) = seeded_db();
+            let page = SprintDetail { sprint_id, db };
+
+            assert_eq!(
+                page.handle_input("a").unwrap(),
+                Some(Action::AssignStoryToSprint {
+                    sprint_id,
+                    story_id: None
+                })
+            );
+            assert_eq!(
+                page.handle_input("r").unwrap(),
+                Some(Action::RemoveStoryFromSprint { sprint_id })
+            );
+            assert_eq!(
+                page.handle_input("d").unwrap(),
+                Some(Action::DeleteSprint { sprint_id })
+            );
+            assert_eq!(
+                page.handle_input(&story_id.to_string()).unwrap(),
+                Some(Action::NavigateToStoryDetail { epic_id, story_id })
+            );
+            // Stories outside the sprint are not reachable from here
+            assert_eq!(
+                page.handle_input(&(story_id + 1).to_string()).unwrap(),
+                None
+            );
+        }
+
+        #[test]
+        fn sprint_detail_should_throw_error_for_invalid_sprint_id() {
+            let (db, _, _, _) = seeded_db();
+            let page = SprintDetail { sprint_id: 999, db };
+
+            assert!(page.draw_page(&DrawContext::plain()).is_err());
+        }
+    }
+
+    mod backlog_page {
+        use super::*;
+        use chrono::NaiveDate;
+
+        // Three unplanned stories and an empty sprint
+        fn seeded_db() -> (Rc<JiraDatabase>, u32, Vec<u32>, u32) {
+            let (db, epic_id, story_ids) = db_with_epic(
+                "epic",
+                ["first", "second", "third"]
+                    .into_iter()
+                    .map(|name| Story::new(name.to_owned(), "".to_owned()))
+                    .collect(),
+            );
+            let sprint_id = db
+                .create_sprint(Sprint::new(
+                    "sprint 1".to_owned(),
+                    NaiveDate::from_ymd_opt(2025, 6, 2).unwrap(),
+                    NaiveDate::from_ymd_opt(2025, 6, 13).unwrap(),
+                ))
+                .unwrap();
+
+            (db, epic_id, story_ids, sprint_id)
+        }
+
+        fn listed_names(page: &BacklogPage) -> Vec<String> {
+            page.draw_page(&DrawContext::plain())
+                .unwrap()
+                .into_iter()
+                .filter_map(|line| {
+                    ["first", "second", "third"]
+                        .into_iter()
+                        .find(|name| line.contains(&format!("| {} ", name)))
+                        .map(str::to_owned)
+                })
+                .collect()
+        }
+
+        #[test]
+        fn draw_page_should_list_every_unassigned_story() {
+            let (db, _, _, _) = seeded_db();
+            let page = BacklogPage { db };
+
+            assert_eq!(listed_names(&page), vec!["first", "second", "third"]);
+        }
+
+        #[test]
+        fn assigned_stories_should_leave_the_backlog() {
+            let (db, _, story_ids, sprint_id) = seeded_db();
+            let page = BacklogPage { db: Rc::clone(&db) };
+
+            db.assign_story_to_sprint(sprint_id, story_ids[0]).unwrap();
+
+            assert_eq!(listed_names(&page), vec!["second", "third"]);
+        }
+
+        #[test]
+        fn handle_input_should_return_the_correct_actions() {
+            let (db, epic_id, story_ids, sprint_id) = seeded_db();
+            let page = BacklogPage { db };
+
+            assert_eq!(
+                page.handle_input("p").unwrap(),
+                Some(Action::NavigateToPreviousPage)
+            );
+            assert_eq!(
+                page.handle_input(&format!("a {}", sprint_id)).unwrap(),
+                Some(Action::AssignStoryToSprint {
+                    sprint_id,
+                    story_id: Some(story_ids[0])
+                })
+            );
+            assert_eq!(
+                page.handle_input(&story_ids[1].to_string()).unwrap(),
+                Some(Action::NavigateToStoryDetail {
+                    epic_id,
+                    story_id: story_ids[1]
+                })
+            );
+            assert_eq!(page.handle_input("a 999").unwrap(), None);
+            assert_eq!(page.handle_input("a").unwrap(), None);
+            assert_eq!(page.handle_input("999").unwrap(), None);
+        }
+    }
+
+    mod search_page {
+        use super::*;
+
+        fn seeded_db() -> Rc<JiraDatabase> {
+            let (db, _, _) = db_with_epic(
+                "Checkout",
+                vec![
+                    Story::new("Pay".to_owned(), "Stripe CHECKOUT flow".to_owned()),
+                    Story::new("Receipts".to_owned(), "".to_owned()),
+                ],
+            );
+            db
+        }
 
         #[test]
-        fn handle_input_should_return_the_correct_actions() {
-            let db = Rc::new(JiraDatabase {
-                database: Box::new(MockDB::new()),
-            });
+        fn draw_page_should_list_matching_epics_and_stories() {
+            let page = SearchPage {
+                query: "checkout".to_owned(),
+                db: seeded_db(),
+            };
 
-            let epic_id = db
-                .create_epic(Epic::new("".to_owned(), "".to_owned()))
-                .unwrap();
-            let story_id = db
-                .create_story(Story::new("".to_owned(), "".to_owned()), epic_id)
-                .unwrap();
+            let lines = page.draw_page(&DrawContext::plain()).unwrap();
 
-            let page = EpicDetail { epic_id, db };
+            assert!(lines.iter().any(|line| line.contains("| Checkout ")));
+            assert!(lines.iter().any(|line| line.contains("| Pay ")));
+            assert!(!lines.iter().any(|line| line.contains("Receipts")));
+            assert!(!lines.contains(&"No matches".to_owned()));
+        }
 
-            let p = "p";
-            let u = "u";
-            let d = "d";
-            let c = "c";
-            let invalid_story_id = "999";
-            let junk_input = "j983f2j";
-            let junk_input_with_valid_prefix = "p983f2j";
-            let input_with_trailing_white_spaces = "p\n";
+        #[test]
+        fn draw_page_should_say_when_nothing_matches() {
+            let page = SearchPage {
+                query: "refunds".to_owned(),
+                db: seeded_db(),
+            };
+
+            assert!(
+                page.draw_page(&DrawContext::plain())
+                    .unwrap()
+                    .contains(&"No matches".to_owned())
+            );
+        }
+
+        #[test]
+        fn handle_input_should_return_the_correct_actions() {
+            let page = SearchPage {
+                query: "checkout".to_owned(),
+                db: seeded_db(),
+            };
 
             assert_eq!(
-                page.handle_input(p).unwrap(),
+                page.handle_input("p").unwrap(),
                 Some(Action::NavigateToPreviousPage)
             );
             assert_eq!(
-                page.handle_input(u).unwrap(),
-                Some(Action::UpdateEpicStatus { epic_id: 1 })
+                page.handle_input("1").unwrap(),
+                Some(Action::NavigateToEpicDetail { epic_id: 1 })
             );
             assert_eq!(
-                page.handle_input(d).unwrap(),
-                Some(Action::DeleteEpic { epic_id: 1 })
+                page.handle_input("2").unwrap(),
+                Some(Action::NavigateToStoryDetail {
+                    epic_id: 1,
+                    story_id: 2
+                })
             );
+            // Story 3 exists but does not match the query
+            assert_eq!(page.handle_input("3").unwrap(), None);
+            assert_eq!(page.handle_input("j983f2j").unwrap(), None);
+        }
+    }
+
+    mod reporter_page {
+        use super::*;
+
+        fn seeded_db() -> Rc<JiraDatabase> {
+            let mut story = Story::new("Pay".to_owned(), "".to_owned());
+            story.reporter = "alice".to_owned();
+            let (db, _, _) = db_with_epic(
+                "epic",
+                vec![story, Story::new("Receipts".to_owned(), "".to_owned())],
+            );
+            db
+        }
+
+        #[test]
+        fn draw_page_should_only_list_the_reporters_stories() {
+            let page = ReporterPage {
+                reporter: "alice".to_owned(),
+                db: seeded_db(),
+            };
+
+            let lines = page.draw_page(&DrawContext::plain()).unwrap();
+
+            assert!(lines.iter().any(|line| line.contains("| Pay ")));
+            assert!(!lines.iter().any(|line| line.contains("Receipts")));
+            assert!(!lines.contains(&"No stories".to_owned()));
+        }
+
+        #[test]
+        fn handle_input_should_return_the_correct_actions() {
+            let page = ReporterPage {
+                reporter: "alice".to_owned(),
+                db: seeded_db(),
+            };
+
             assert_eq!(
-                page.handle_input(c).unwrap(),
-                Some(Action::CreateStory { epic_id: 1 })
+                page.handle_input("p").unwrap(),
+                Some(Action::NavigateToPreviousPage)
             );
             assert_eq!(
-                page.handle_input(&story_id.to_string()).unwrap(),
+                page.handle_input("2").unwrap(),
                 Some(Action::NavigateToStoryDetail {
                     epic_id: 1,
                     story_id: 2
                 })
             );
-            assert_eq!(page.handle_input(invalid_story_id).unwrap(), None);
-            assert_eq!(page.handle_input(junk_input).unwrap(), None);
+            // Story 3 was reported by someone else
+            assert_eq!(page.handle_input("3").unwrap(), None);
+            assert_eq!(page.handle_input("j983f2j").unwrap(), None);
+        }
+    }
+
+    mod release_pages {
+        use super::*;
+
+        fn seeded_db() -> Rc<JiraDatabase> {
+            let (db, epic_id, story_ids) = db_with_epic(
+                "Billing",
+                vec![
+                    Story::new("Pay".to_owned(), "".to_owned()),
+                    Story::new("Receipts".to_owned(), "".to_owned()),
+                ],
+            );
+            db.update_epic_release(epic_id, Some("1.0".to_owned()))
+                .unwrap();
+            db.update_story_release(story_ids[0], Some("1.0".to_owned()))
+                .unwrap();
+            db.update_story_release(story_ids[1], Some("2.0".to_owned()))
+                .unwrap();
+            db
+        }
+
+        #[test]
+        fn release_page_should_list_releases_with_counts() {
+            let page = ReleasePage { db: seeded_db() };
+
+            let lines = page.draw_page(&DrawContext::plain()).unwrap();
+
+            assert!(lines.iter().any(|line| line.contains("| 1.0 ")));
+            assert!(lines.iter().any(|line| line.contains("| 2.0 ")));
+            assert!(!lines.contains(&"No releases".to_owned()));
+
             assert_eq!(
-                page.handle_input(junk_input_with_valid_prefix).unwrap(),
-                None
+                page.handle_input("2").unwrap(),
+                Some(Action::NavigateToReleaseDetail {
+                    release: "2.0".to_owned()
+                })
             );
             assert_eq!(
-                page.handle_input(input_with_trailing_white_spaces).unwrap(),
-                None
+                page.handle_input("p").unwrap(),
+                Some(Action::NavigateToPreviousPage)
+            );
+            assert_eq!(page.handle_input("0").unwrap(), None);
+            assert_eq!(page.handle_input("3").unwrap(), None);
+        }
+
+        #[test]
+        fn release_page_should_say_when_there_are_no_releases() {
+            let db = Rc::new(JiraDatabase::with_database(Box::new(MockDB::new())));
+            let page = ReleasePage { db };
+
+            let lines = page.draw_page(&DrawContext::plain()).unwrap();
+
+            assert!(lines.contains(&"No releases".to_owned()));
+        }
+
+        #[test]
+        fn release_detail_should_only_list_items_in_the_release() {
+            let page = ReleaseDetail {
+                release: "1.0".to_owned(),
+                db: seeded_db(),
+            };
+
+            let lines = page.draw_page(&DrawContext::plain()).unwrap();
+
+            assert!(lines.iter().any(|line| line.contains("| Billing ")));
+            assert!(lines.iter().any(|line| line.contains("| Pay ")));
+            assert!(!lines.iter().any(|line| line.contains("Receipts")));
+        }
+
+        #[test]
+        fn release_detail_handle_input_should_return_the_correct_actions() {
+            let page = ReleaseDetail {
+                release: "1.0".to_owned(),
+                db: seeded_db(),
+            };
+
+            assert_eq!(
+                page.handle_input("p").unwrap(),
+                Some(Action::NavigateToPreviousPage)
             );
+            assert_eq!(
+                page.handle_input("1").unwrap(),
+                Some(Action::NavigateToEpicDetail { epic_id: 1 })
+            );
+            assert_eq!(
+                page.handle_input("2").unwrap(),
+                Some(Action::NavigateToStoryDetail {
+                    epic_id: 1,
+                    story_id: 2
+                })
+            );
+            // Story 3 is in another release
+            assert_eq!(page.handle_input("3").unwrap(), None);
+            assert_eq!(page.handle_input("j983f2j").unwrap(), None);
         }
     }
 
-    mod story_detail_page {
+    mod dashboard_page {
         use super::*;
 
         #[test]
-        fn draw_page_should_not_throw_error() {
-            let db = Rc::new(JiraDatabase {
-                database: Box::new(MockDB::new()),
-            });
+        fn draw_page_should_count_the_fixture_database() {
+            let db = Rc::new(JiraDatabase::with_database(Box::new(MockDB::new())));
+            let yesterday = Local::now().date_naive().pred_opt();
 
-            let epic_id = db
-                .create_epic(Epic::new("".to_owned(), "".to_owned()))
+            let billing = db
+                .create_epic(Epic::new("Billing".to_owned(), "".to_owned()))
                 .unwrap();
-            let story_id = db
-                .create_story(Story::new("".to_owned(), "".to_owned()), epic_id)
+            db.update_epic_due_date(billing, yesterday).unwrap();
+            let search = db
+                .create_epic(Epic::new("Search".to_owned(), "".to_owned()))
                 .unwrap();
+            db.update_epic_status(search, Status::Resolved).unwrap();
 
-            let page = StoryDetail {
-                epic_id,
-                story_id,
-                db,
+            for (name, estimate, status) in [
+                ("Pay", Some(3), Status::Resolved),
+                ("Refund", Some(5), Status::InProgress),
+                ("Receipts", None, Status::InProgress),
+            ] {
+                let story_id = db
+                    .create_story(
+                        Story {
+                            estimate,
+                            ..Story::new(name.to_owned(), "".to_owned())
+                        },
+                        billing,
+                    )
+                    .unwrap();
+                db.update_story_status(story_id, status).unwrap();
+            }
+            let late = db
+                .create_story(Story::new("Late".to_owned(), "".to_owned()), search)
+                .unwrap();
+            db.update_story_due_date(late, yesterday).unwrap();
+            // Items changed in the same instant would be listed by name, so
+            // only Late is changed now and everything else an hour ago
+            let mut state = db.read_db().unwrap();
+            let an_hour_ago = Utc::now() - chrono::TimeDelta::hours(1);
+            for epic in state.epics.values_mut() {
+                epic.updated_at = an_hour_ago;
+            }
+            for story in state.stories.values_mut() {
+                story.updated_at = an_hour_ago;
+            }
+            state.stories.get_mut(&late).unwrap().updated_at = Utc::now();
+            db.database.write_db(&state).unwrap();
+
+            let page = DashboardPage { db };
+            let lines = page.draw_page(&DrawContext::plain()).unwrap();
+
+            // The count column of a status row, read from below `heading`
+            let count = |heading: &str, status: &str| -> usize {
+                lines
+                    .iter()
+                    .skip_while(|line| *line != heading)
+                    .find(|line| line.starts_with(&format!("{:<12} ", status)))
+                    .unwrap()[13..17]
+                    .trim()
+                    .parse()
+                    .unwrap()
             };
-            assert_eq!(page.draw_page().is_ok(), true);
+
+            assert_eq!(count("Epics (2)", "OPEN"), 1);
+            assert_eq!(count("Epics (2)", "RESOLVED"), 1);
+            assert_eq!(count("Epics (2)", "IN PROGRESS"), 0);
+            assert_eq!(count("Stories (4)", "OPEN"), 1);
+            assert_eq!(count("Stories (4)", "IN PROGRESS"), 2);
+            assert_eq!(count("Stories (4)", "RESOLVED"), 1);
+            assert_eq!(count("Stories (4)", "CLOSED"), 0);
+            assert!(lines.contains(&"Story points: 3 of 8 completed".to_owned()));
+            assert!(lines.contains(&"Overdue items: 2".to_owned()));
+            assert!(lines.contains(&"story #6 Late (today)".to_owned()));
         }
 
         #[test]
-        fn handle_input_should_not_throw_error() {
-            let db = Rc::new(JiraDatabase {
-                database: Box::new(MockDB::new()),
-            });
+        fn handle_input_should_only_go_back_or_show_help() {
+            let db = Rc::new(JiraDatabase::with_database(Box::new(MockDB::new())));
+            let page = DashboardPage { db };
 
-            let epic_id = db
-                .create_epic(Epic::new("".to_owned(), "".to_owned()))
-                .unwrap();
-            let story_id = db
-                .create_story(Story::new("".to_owned(), "".to_owned()), epic_id)
-                .unwrap();
+            assert_eq!(
+                page.handle_input("p").unwrap(),
+                Some(Action::NavigateToPreviousPage)
+            );
+            assert_eq!(
+                page.handle_input("?").unwrap(),
+                Some(Action::ShowHelp {
+                    context: PageType::Dashboard
+                })
+            );
+            assert_eq!(page.handle_input("1").unwrap(), None);
+            assert_eq!(page.handle_input("d").unwrap(), None);
+        }
+    }
 
-            let page = StoryDetail {
-                epic_id,
-                story_id,
+    mod watch_list_page {
+        use super::*;
+
+        fn page_watching(story_ids: &[u32]) -> WatchListPage {
+            let (db, _, _) = db_with_epic(
+                "Billing",
+                vec![
+                    Story::new("Pay".to_owned(), "".to_owned()),
+                    Story::new("Receipts".to_owned(), "".to_owned()),
+                ],
+            );
+
+            let mut watch_list = WatchList::in_memory();
+            for story_id in story_ids {
+                watch_list.add(*story_id).unwrap();
+            }
+            WatchListPage {
+                watch_list: Rc::new(RefCell::new(watch_list)),
                 db,
-            };
-            assert_eq!(page.handle_input("").is_ok(), true);
+            }
         }
 
         #[test]
-        fn draw_page_should_throw_error_for_invalid_story_id() {
-            let db = Rc::new(JiraDatabase {
-                database: Box::new(MockDB::new()),
-            });
+        fn draw_page_should_only_list_watched_stories_with_their_epic() {
+            // Story 99 was deleted after it was watched
+            let page = page_watching(&[2, 99]);
 
-            let epic_id = db
-                .create_epic(Epic::new("".to_owned(), "".to_owned()))
-                .unwrap();
-            let _ = db
-                .create_story(Story::new("".to_owned(), "".to_owned()), epic_id)
-                .unwrap();
+            let lines = page.draw_page(&DrawContext::plain()).unwrap();
 
-            let page = StoryDetail {
-                epic_id,
-                story_id: 999,
-                db,
-            };
-            assert_eq!(page.draw_page().is_err(), true);
+            let row = lines.iter().find(|line| line.contains("| Pay ")).unwrap();
+            assert!(row.contains("| Billing "));
+            assert!(!lines.iter().any(|line| line.contains("Receipts")));
+            assert!(!lines.contains(&"No watched stories".to_owned()));
+
+            let lines = page_watching(&[]).draw_page(&DrawContext::plain()).unwrap();
+            assert!(lines.contains(&"No watched stories".to_owned()));
         }
 
         #[test]
         fn handle_input_should_return_the_correct_actions() {
-            let db = Rc::new(JiraDatabase {
-                database: Box::new(MockDB::new()),
-            });
+            let page = page_watching(&[2]);
+
+            assert_eq!(
+                page.handle_input("p").unwrap(),
+                Some(Action::NavigateToPreviousPage)
+            );
+            assert_eq!(
+                page.handle_input("2").unwrap(),
+                Some(Action::NavigateToStoryDetail {
+                    epic_id: 1,
+                    story_id: 2
+                })
+            );
+            // Story 3 is not watched
+            assert_eq!(page.handle_input("3").unwrap(), None);
+            assert_eq!(page.handle_input("j983f2j").unwrap(), None);
+        }
+    }
+
+    mod command_bar {
+        use super::*;
+
+        #[test]
+        fn command_bar_should_only_offer_previous_when_there_is_a_page_to_go_back_to() {
+            let ctx = DrawContext::plain();
+            assert_eq!(ctx.command_bar("[a] add"), vec!["[p] previous | [a] add"]);
+            assert_eq!(ctx.command_bar(""), vec!["[p] previous"]);
+
+            let ctx = DrawContext {
+                can_go_back: false,
+                ..DrawContext::plain()
+            };
+            assert_eq!(ctx.command_bar("[a] add"), vec!["[a] add"]);
+
+            let db = Rc::new(JiraDatabase::with_database(Box::new(MockDB::new())));
+            let lines = ReleasePage { db }.draw_page(&ctx).unwrap();
+            assert_eq!(lines.last().unwrap(), "[:#:] show release");
+        }
+
+        #[test]
+        fn command_bar_rows_should_count_the_lines_of_the_last_bar() {
+            let ctx = DrawContext {
+                width: 30,
+                ..DrawContext::plain()
+            };
+            let db = Rc::new(JiraDatabase::with_database(Box::new(MockDB::new())));
+
+            let lines = HomePage::new(db).draw_page(&ctx).unwrap();
+
+            let rows = ctx.command_bar_rows.get();
+            assert!(rows > 1);
+            assert!(lines[lines.len() - rows].starts_with("[q] quit"));
+        }
+    }
+
+    mod help_page {
+        use super::*;
+        use chrono::NaiveDate;
 
+        // Every `[key] description` shown at the foot of the page
+        fn offered_commands(page: &dyn Page) -> Vec<String> {
+            page.draw_page(&DrawContext::plain())
+                .unwrap()
+                .iter()
+                .filter(|line| line.starts_with('['))
+                .flat_map(|line| line.split(" | ").map(str::to_owned))
+                .sorted()
+                .collect()
+        }
+
+        fn listed_commands(context: PageType) -> Vec<String> {
+            keybindings(context)
+                .into_iter()
+                .map(|(key, description)| format!("[{}] {}", key, description))
+                .sorted()
+                .collect()
+        }
+
+        #[test]
+        fn keybindings_should_match_the_commands_each_page_offers() {
+            let db = Rc::new(JiraDatabase::with_database(Box::new(MockDB::new())));
             let epic_id = db
-                .create_epic(Epic::new("".to_owned(), "".to_owned()))
+                .create_epic(Epic {
+                    tags: vec!["backend".to_owned()],
+                    ..Epic::new("epic".to_owned(), "".to_owned())
+                })
                 .unwrap();
+            db.create_epic(Epic {
+                tags: vec!["backend".to_owned()],
+                ..Epic::new("other".to_owned(), "".to_owned())
+            })
+            .unwrap();
             let story_id = db
-                .create_story(Story::new("".to_owned(), "".to_owned()), epic_id)
+                .create_story(Story::new("story".to_owned(), "".to_owned()), epic_id)
+                .unwrap();
+            db.create_story(Story::new("other".to_owned(), "".to_owned()), epic_id)
+                .unwrap();
+            let sprint_id = db
+                .create_sprint(Sprint::new(
+                    "sprint 1".to_owned(),
+                    NaiveDate::from_ymd_opt(2025, 6, 2).unwrap(),
+                    NaiveDate::from_ymd_opt(2025, 6, 13).unwrap(),
+                ))
                 .unwrap();
 
-            let page = StoryDetail {
-                epic_id,
-                story_id,
-                db,
+            // Paged and filtered, so the conditional commands show up too
+            let pages: Vec<(PageType, Box<dyn Page>)> = vec![
+                (
+                    PageType::Home,
+                    Box::new(HomePage {
+                        active_tag: Some("backend".to_owned()),
+                        page_size: 1,
+                        ..HomePage::new(Rc::clone(&db))
+                    }),
+                ),
+                (
+                    PageType::ProjectSelect,
+                    Box::new(ProjectSelectPage { db: Rc::clone(&db) }),
+                ),
+                (
+                    PageType::EpicDetail,
+                    Box::new(EpicDetail {
+                        page_size: 1,
+                        ..EpicDetail::new(epic_id, Rc::clone(&db))
+                    }),
+                ),
+                (
+                    PageType::StoryDetail,
+                    Box::new(StoryDetail {
+                        epic_id,
+                        story_id,
+                        db: Rc::clone(&db),
+                    }),
+                ),
+                (
+                    PageType::Comments,
+                    Box::new(CommentsPage {
+                        story_id,
+                        db: Rc::clone(&db),
+                    }),
+                ),
+                (
+                    PageType::SprintList,
+                    Box::new(SprintPage { db: Rc::clone(&db) }),
+                ),
+                (
+                    PageType::SprintDetail,
+                    Box::new(SprintDetail {
+                        sprint_id,
+                        db: Rc::clone(&db),
+                    }),
+                ),
+                (
+                    PageType::Backlog,
+                    Box::new(BacklogPage { db: Rc::clone(&db) }),
+                ),
+                (
+                    PageType::Search,
+                    Box::new(SearchPage {
+                        query: "story".to_owned(),
+                        db: Rc::clone(&db),
+                    }),
+                ),
+                (
+                    PageType::Reporter,
+                    Box::new(ReporterPage {
+                        reporter: UNKNOWN_USER.to_owned(),
+                        db: Rc::clone(&db),
+                    }),
+                ),
+                (
+                    PageType::Releases,
+                    Box::new(ReleasePage { db: Rc::clone(&db) }),
+                ),
+                (
+                    PageType::ReleaseDetail,
+                    Box::new(ReleaseDetail {
+                        release: "v1".to_owned(),
+                        db: Rc::clone(&db),
+                    }),
+                ),
+                (
+                    PageType::WatchList,
+                    Box::new(WatchListPage {
+                        watch_list: Rc::new(RefCell::new(WatchList::in_memory())),
+                        db: Rc::clone(&db),
+                    }),
+                ),
+                (
+                    PageType::Dashboard,
+                    Box::new(DashboardPage { db: Rc::clone(&db) }),
+                ),
+            ];
+
+            for (context, page) in pages {
+                assert_eq!(
+                    offered_commands(page.as_ref()),
+                    listed_commands(context),
+                    "{} page",
+                    context
+                );
+            }
+        }
+
+        #[test]
+        fn draw_page_should_list_the_keys_for_its_context() {
+            let home_help = HelpPage {
+                context: PageType::Home,
+            };
+            let epic_help = HelpPage {
+                context: PageType::EpicDetail,
             };
 
-            let p = "p";
-            let u = "u";
-            let d = "d";
-            let some_number = "1";
-            let junk_input = "j983f2j";
-            let junk_input_with_valid_prefix = "p983f2j";
-            let input_with_trailing_white_spaces = "p\n";
+            let home_lines = home_help.draw_page(&DrawContext::plain()).unwrap();
+            let epic_lines = epic_help.draw_page(&DrawContext::plain()).unwrap();
 
-            assert_eq!(
-                page.handle_input(p).unwrap(),
-                Some(Action::NavigateToPreviousPage)
-            );
-            assert_eq!(
-                page.handle_input(u).unwrap(),
-                Some(Action::UpdateStoryStatus { story_id })
-            );
-            assert_eq!(
-                page.handle_input(d).unwrap(),
-                Some(Action::DeleteStory { epic_id, story_id })
+            assert!(home_lines.iter().any(|line| line.contains("create epic")));
+            assert!(!home_lines.iter().any(|line| line.contains("create story")));
+            assert!(epic_lines.iter().any(|line| line.contains("create story")));
+            assert!(!epic_lines.iter().any(|line| line.contains("create epic")));
+        }
+
+        #[test]
+        fn draw_page_should_put_keys_and_descriptions_in_columns() {
+            let page = HelpPage {
+                context: PageType::Comments,
+            };
+
+            let lines = page.draw_page(&DrawContext::plain()).unwrap();
+
+            assert!(
+                lines
+                    .iter()
+                    .any(|line| line.starts_with("[a] ") && line.contains(" | add comment"))
             );
-            assert_eq!(page.handle_input(some_number).unwrap(), None);
-            assert_eq!(page.handle_input(junk_input).unwrap(), None);
-            assert_eq!(
-                page.handle_input(junk_input_with_valid_prefix).unwrap(),
-                None
+            // Global shortcuts are listed on every help page
+            assert!(
+                lines
+                    .iter()
+                    .any(|line| line.starts_with("[x] ") && line.contains(" | export page"))
             );
+        }
+
+        #[test]
+        fn handle_input_should_return_the_correct_actions() {
+            let page = HelpPage {
+                context: PageType::Home,
+            };
+
             assert_eq!(
-                page.handle_input(input_with_trailing_white_spaces).unwrap(),
-                None
+                page.handle_input("p").unwrap(),
+                Some(Action::NavigateToPreviousPage)
             );
+            assert_eq!(page.handle_input("?").unwrap(), None);
+            assert_eq!(page.handle_input("j983f2j").unwrap(), None);
         }
     }
 }
@@ -1,40 +1,395 @@
-use ellipse::Ellipse;
+use std::cmp::Ordering;
 
-pub fn get_column_string(text: &str, width: usize) -> String {
-    let len = text.len();
+use chrono::{DateTime, Utc};
+use unicode_segmentation::UnicodeSegmentation;
+use unicode_width::UnicodeWidthStr;
+
+use crate::models::SortDir;
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+pub enum Alignment {
+    #[default]
+    Left,
+    Right,
+}
 
-    match len.cmp(&width) {
-        std::cmp::Ordering::Equal => text.to_owned(),
-        std::cmp::Ordering::Less => {
-            let left_over = width - len;
-            let mut column_string = text.to_owned();
+/// How a table cell is laid out: text that doesn't fit in `width` is cut
+/// short and ends in `truncate_suffix`, shorter text is padded according to
+/// `align`. Widths are terminal columns, so wide characters such as CJK
+/// and emoji count double.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct ColumnSpec {
+    pub width: usize,
+    pub truncate_suffix: &'static str,
+    pub align: Alignment,
+}
+
+impl ColumnSpec {
+    const ELLIPSIS: &'static str = "...";
 
-            for _ in 0..left_over {
-                column_string.push(' ');
+    pub fn left(width: usize) -> Self {
+        Self {
+            width,
+            truncate_suffix: Self::ELLIPSIS,
+            align: Alignment::Left,
+        }
+    }
+
+    pub fn right(width: usize) -> Self {
+        Self {
+            align: Alignment::Right,
+            ..Self::left(width)
+        }
+    }
+
+    /// Ids line up on their last digit.
+    pub fn default_id(width: usize) -> Self {
+        Self::right(width)
+    }
+
+    pub fn default_name(width: usize) -> Self {
+        Self::left(width)
+    }
+
+    pub fn default_status(width: usize) -> Self {
+        Self::left(width)
+    }
+
+    pub fn default_description(width: usize) -> Self {
+        Self::left(width)
+    }
+
+    pub fn format(&self, text: &str) -> String {
+        let width = self.width;
+        // A cell is a single line, so multi-line descriptions are flattened
+        let text = text.replace("\r\n", " ").replace('\n', " ");
+        let text = text.as_str();
+
+        let text = if text.width() > width {
+            let suffix_width = self.truncate_suffix.width();
+            // Too narrow for any text, so show as much of the suffix as fits
+            if width <= suffix_width {
+                return truncate_to_width(self.truncate_suffix, width).to_owned();
             }
+            format!(
+                "{}{}",
+                truncate_to_width(text, width - suffix_width),
+                self.truncate_suffix
+            )
+        } else {
+            text.to_owned()
+        };
+
+        // `format!` pads by char count, which is wrong for wide characters
+        let padding = " ".repeat(width.saturating_sub(text.width()));
+        match self.align {
+            Alignment::Left => text + &padding,
+            Alignment::Right => padding + &text,
+        }
+    }
+
+    /// Like [`format`](Self::format), but ends the text with `badge`, cutting
+    /// the text shorter to make room. A cell too narrow for the badge is
+    /// drawn without it.
+    pub fn format_with_badge(&self, text: &str, badge: Option<&str>) -> String {
+        let badge = match badge {
+            Some(badge) if badge.width() < self.width => format!(" {}", badge),
+            _ => return self.format(text),
+        };
+
+        let text = ColumnSpec {
+            width: self.width - badge.width(),
+            align: Alignment::Left,
+            ..*self
+        }
+        .format(text);
+        self.format(&format!("{}{}", text.trim_end(), badge))
+    }
+}
 
-            column_string
+/// The longest prefix of `text` that fits in `width` columns. It always
+/// ends on a grapheme boundary, so characters are never split.
+fn truncate_to_width(text: &str, width: usize) -> &str {
+    let mut used = 0;
+    for (index, grapheme) in text.grapheme_indices(true) {
+        used += grapheme.width();
+        if used > width {
+            return &text[..index];
         }
-        std::cmp::Ordering::Greater => {
-            if width == 0 {
-                return "".to_owned();
-            } else if width == 1 {
-                return ".".to_owned();
-            } else if width == 2 {
-                return "..".to_owned();
-            } else if width == 3 {
-                return "...".to_owned();
+    }
+    text
+}
+
+/// Shorthand for a left-aligned column of `width`.
+pub fn get_column_string(text: &str, width: usize) -> String {
+    ColumnSpec::left(width).format(text)
+}
+
+pub const DEFAULT_TERMINAL_WIDTH: usize = 80;
+
+const COLUMN_SEPARATOR: &str = " | ";
+
+/// Reads the width from `COLUMNS`, then asks the terminal, then falls back
+/// to 80 columns.
+pub fn terminal_width() -> usize {
+    std::env::var("COLUMNS")
+        .ok()
+        .and_then(|columns| columns.trim().parse::<usize>().ok())
+        .filter(|columns| *columns > 0)
+        .or_else(|| {
+            crossterm::terminal::size()
+                .ok()
+                .map(|(columns, _)| columns as usize)
+        })
+        .unwrap_or(DEFAULT_TERMINAL_WIDTH)
+}
+
+/// Splits `total_width` between columns in proportion to `weights`, after
+/// leaving room for the separators. Rounding leftovers go to the heaviest
+/// column so a row of these widths is exactly `total_width` wide.
+pub fn column_widths(total_width: usize, weights: &[usize]) -> Vec<usize> {
+    let separators = COLUMN_SEPARATOR.len() * weights.len().saturating_sub(1);
+    let available = total_width.saturating_sub(separators);
+    let total_weight: usize = weights.iter().sum();
+
+    if total_weight == 0 {
+        return vec![0; weights.len()];
+    }
+
+    let mut widths: Vec<usize> = weights
+        .iter()
+        .map(|weight| available * weight / total_weight)
+        .collect();
+
+    let leftover = available - widths.iter().sum::<usize>();
+    if let Some((heaviest, _)) = weights
+        .iter()
+        .enumerate()
+        .max_by_key(|(_, weight)| **weight)
+    {
+        widths[heaviest] += leftover;
+    }
+
+    widths
+}
+
+pub fn join_columns(columns: &[String]) -> String {
+    columns.join(COLUMN_SEPARATOR)
+}
+
+/// Packs the `" | "`-separated `commands` onto as few lines as fit in
+/// `width` columns. A command wider than `width` gets a line of its own.
+pub fn wrap_commands(commands: &str, width: usize) -> Vec<String> {
+    let mut lines: Vec<String> = vec![];
+    for command in commands.split(COLUMN_SEPARATOR) {
+        match lines.last_mut() {
+            Some(line) if line.width() + COLUMN_SEPARATOR.len() + command.width() <= width => {
+                line.push_str(COLUMN_SEPARATOR);
+                line.push_str(command);
             }
-            let result = text.truncate_ellipse(width - 3);
-            result.to_string()
+            _ => lines.push(command.to_owned()),
         }
     }
+    lines
+}
+
+pub fn header_row(titles: &[&str], widths: &[usize]) -> String {
+    let columns: Vec<String> = titles
+        .iter()
+        .zip(widths)
+        .map(|(title, width)| get_column_string(&format!("{:^width$}", title), *width))
+        .collect();
+
+    join_columns(&columns)
+}
+
+/// How long before `now` something happened, in whole days.
+pub fn days_ago(timestamp: DateTime<Utc>, now: DateTime<Utc>) -> String {
+    match now.signed_duration_since(timestamp).num_days() {
+        // Clocks can disagree a little, so the future counts as today
+        ..=0 => "today".to_owned(),
+        1 => "1 day ago".to_owned(),
+        days => format!("{} days ago", days),
+    }
+}
+
+/// Orders optional sort keys by `dir`, with missing values last either way.
+pub fn cmp_missing_last<T: Ord>(a: Option<T>, b: Option<T>, dir: SortDir) -> Ordering {
+    match (a, b) {
+        (Some(a), Some(b)) => dir.apply(a.cmp(&b)),
+        (Some(_), None) => Ordering::Less,
+        (None, Some(_)) => Ordering::Greater,
+        (None, None) => Ordering::Equal,
+    }
+}
+
+/// A bar of `#` for `count`, scaled so that `max` fills `width` columns.
+/// Any non-zero count gets at least one `#`.
+pub fn bar(count: usize, max: usize, width: usize) -> String {
+    if count == 0 || max == 0 {
+        return String::new();
+    }
+    "#".repeat((count * width / max).max(1))
+}
+
+/// A `---- TITLE ----` line spanning `width` columns.
+pub fn banner(title: &str, width: usize) -> String {
+    format!("{:-^width$}", format!(" {} ", title))
 }
 
 #[cfg(test)]
 mod tests {
+    use chrono::TimeDelta;
+
     use super::*;
 
+    #[test]
+    fn bar_should_scale_to_the_largest_count() {
+        assert_eq!(bar(4, 4, 8), "########");
+        assert_eq!(bar(2, 4, 8), "####");
+        assert_eq!(bar(1, 100, 8), "#");
+        assert_eq!(bar(0, 4, 8), "");
+    }
+
+    #[test]
+    fn column_widths_should_fill_the_total_width() {
+        let widths = column_widths(100, &[11, 32, 16, 11]);
+
+        assert_eq!(widths, vec![14, 43, 20, 14]);
+        assert_eq!(widths.iter().sum::<usize>() + 3 * 3, 100);
+    }
+
+    #[test]
+    fn column_widths_should_not_underflow_on_tiny_terminals() {
+        assert_eq!(column_widths(4, &[1, 1, 1]), vec![0, 0, 0]);
+    }
+
+    #[test]
+    fn header_row_should_center_titles_in_their_columns() {
+        assert_eq!(header_row(&["id", "name"], &[6, 8]), "  id   |   name  ");
+    }
+
+    #[test]
+    fn days_ago_should_count_whole_days() {
+        let now = Utc::now();
+
+        assert_eq!(days_ago(now, now), "today");
+        assert_eq!(days_ago(now - TimeDelta::days(1), now), "1 day ago");
+        assert_eq!(days_ago(now - TimeDelta::days(3), now), "3 days ago");
+        assert_eq!(days_ago(now + TimeDelta::days(1), now), "today");
+    }
+
+    #[test]
+    fn banner_should_span_the_width() {
+        assert_eq!(banner("EPICS", 15), "---- EPICS ----");
+    }
+
+    #[test]
+    fn column_spec_should_right_align_ids() {
+        let spec = ColumnSpec::default_id(5);
+
+        assert_eq!(spec.format("7"), "    7");
+        assert_eq!(spec.format("12345"), "12345");
+        assert_eq!(spec.format("123456"), "12...");
+    }
+
+    #[test]
+    fn column_spec_should_left_align_names() {
+        let spec = ColumnSpec::default_name(6);
+
+        assert_eq!(spec.format("test"), "test  ");
+        assert_eq!(spec.format("testmetest"), "tes...");
+    }
+
+    #[test]
+    fn column_spec_should_keep_multi_line_text_on_one_line() {
+        let spec = ColumnSpec::default_description(20);
+
+        assert_eq!(
+            spec.format("first\nsecond\r\nthird"),
+            "first second third  "
+        );
+    }
+
+    #[test]
+    fn column_spec_should_use_its_truncate_suffix() {
+        let spec = ColumnSpec {
+            truncate_suffix: "~",
+            ..ColumnSpec::default_description(6)
+        };
+
+        assert_eq!(spec.format("testmetest"), "testm~");
+        assert_eq!(ColumnSpec { width: 1, ..spec }.format("testmetest"), "~");
+    }
+
+    #[test]
+    fn column_spec_should_fit_a_badge_inside_the_cell() {
+        let spec = ColumnSpec::left(16);
+
+        assert_eq!(
+            spec.format_with_badge("epic", Some("[!]")),
+            "epic [!]        "
+        );
+        assert_eq!(
+            spec.format_with_badge("a long epic name", Some("[!]")),
+            "a long ep... [!]"
+        );
+        assert_eq!(spec.format_with_badge("epic", None), spec.format("epic"));
+        assert_eq!(
+            ColumnSpec::left(3).format_with_badge("epic", Some("[!]")),
+            "..."
+        );
+    }
+
+    #[test]
+    fn wrap_commands_should_keep_each_line_within_the_width() {
+        let commands = "[a] add | [b] bigger command | [c] c | [dddddddddddddddd] too wide";
+
+        assert_eq!(
+            wrap_commands(commands, 16),
+            vec![
+                "[a] add",
+                "[b] bigger command",
+                "[c] c",
+                "[dddddddddddddddd] too wide",
+            ]
+        );
+        assert_eq!(
+            wrap_commands(commands, 30),
+            vec![
+                "[a] add | [b] bigger command",
+                "[c] c",
+                "[dddddddddddddddd] too wide"
+            ]
+        );
+        assert_eq!(wrap_commands("[a] add", 80), vec!["[a] add"]);
+    }
+
+    #[test]
+    fn column_spec_should_never_split_or_overflow_wide_characters() {
+        let columns = |text: &str, width: usize| {
+            let formatted = get_column_string(text, width);
+            assert!(formatted.width() <= width, "{:?} is too wide", formatted);
+            formatted
+        };
+
+        // Each of these characters takes two columns
+        assert_eq!(columns("日本語テスト", 5), "日...");
+        assert_eq!(columns("日本語テスト", 6), "日... ");
+        assert_eq!(columns("日本語", 6), "日本語");
+        assert_eq!(columns("日本", 5), "日本 ");
+        assert_eq!(columns("🦀🦀🦀", 5), "🦀...");
+        assert_eq!(columns("🦀🦀🦀", 4), "... ");
+        assert_eq!(columns("🦀🦀🦀", 6), "🦀🦀🦀");
+
+        // An accent stays with its letter
+        assert_eq!(columns("cafe\u{301}s du monde", 7), "cafe\u{301}...");
+
+        for width in 0..12 {
+            columns("日本語テスト🦀", width);
+        }
+    }
+
     #[test]
     fn test_get_column_string() {
         let text1 = "";
@@ -1,14 +1,53 @@
+use std::path::PathBuf;
+
+use chrono::NaiveDate;
+
 use crate::{
-    io_utils::get_user_input,
-    models::{Epic, Status, Story},
+    constants::{DESCRIPTION_TERMINATOR, STORY_ESTIMATE_VALUES},
+    io_utils::{
+        get_user_input, get_user_input_with_default, read_multiline_input, read_password_confirmed,
+        wait_for_key_press,
+    },
+    models::{Epic, SortKey, Sprint, Status, Story, StorySortKey, StoryType},
 };
 
+// Get the current value, if any, and return None when it is left unchanged
+type EditPrompt<T> = Box<dyn Fn(Option<T>) -> Option<T>>;
+type TextEditPrompt = Box<dyn Fn(&str) -> Option<String>>;
+
 pub struct Prompts {
-    pub create_epic: Box<dyn Fn() -> Epic>,
-    pub create_story: Box<dyn Fn() -> Story>,
+    // Both are given the reporter to record on the new item
+    pub create_epic: Box<dyn Fn(&str) -> Epic>,
+    pub create_story: Box<dyn Fn(&str) -> Story>,
     pub delete_epic: Box<dyn Fn() -> bool>,
     pub delete_story: Box<dyn Fn() -> bool>,
+    // Given how many stories are about to be deleted
+    pub delete_stories: Box<dyn Fn(usize) -> bool>,
     pub update_status: Box<dyn Fn() -> Option<Status>>,
+    pub update_story_type: Box<dyn Fn() -> Option<StoryType>>,
+    pub update_estimate: EditPrompt<u8>,
+    pub update_due_date: EditPrompt<NaiveDate>,
+    pub update_name: TextEditPrompt,
+    pub update_description: TextEditPrompt,
+    pub update_release: TextEditPrompt,
+    pub link_story_dependency: Box<dyn Fn() -> Option<u32>>,
+    pub unlink_story_dependency: Box<dyn Fn() -> Option<u32>>,
+    pub move_story: Box<dyn Fn() -> Option<u32>>,
+    pub add_comment: Box<dyn Fn() -> Option<String>>,
+    pub delete_comment: Box<dyn Fn() -> Option<u32>>,
+    pub create_sprint: Box<dyn Fn() -> Option<Sprint>>,
+    pub delete_sprint: Box<dyn Fn() -> bool>,
+    pub assign_sprint_story: Box<dyn Fn() -> Option<u32>>,
+    pub remove_sprint_story: Box<dyn Fn() -> Option<u32>>,
+    pub create_project: Box<dyn Fn() -> Option<String>>,
+    // For registering and for changing a password
+    pub new_password: Box<dyn Fn() -> Option<String>>,
+    pub search_query: Box<dyn Fn() -> Option<String>>,
+    pub filter_tag: Box<dyn Fn() -> Option<String>>,
+    pub sort_key: Box<dyn Fn() -> Option<SortKey>>,
+    pub story_sort_key: Box<dyn Fn() -> Option<StorySortKey>>,
+    pub reporter: Box<dyn Fn() -> Option<String>>,
+    pub export_destination: Box<dyn Fn(&str) -> PathBuf>,
 }
 
 impl Prompts {
@@ -18,43 +57,125 @@ impl Prompts {
             create_story: Box::new(create_story_prompt),
             delete_epic: Box::new(delete_epic_prompt),
             delete_story: Box::new(delete_story_prompt),
+            delete_stories: Box::new(delete_stories_prompt),
             update_status: Box::new(update_status_prompt),
+            update_story_type: Box::new(update_story_type_prompt),
+            update_estimate: Box::new(update_estimate_prompt),
+            update_due_date: Box::new(update_due_date_prompt),
+            update_name: Box::new(update_name_prompt),
+            update_description: Box::new(update_description_prompt),
+            update_release: Box::new(update_release_prompt),
+            link_story_dependency: Box::new(link_story_dependency_prompt),
+            unlink_story_dependency: Box::new(unlink_story_dependency_prompt),
+            move_story: Box::new(move_story_prompt),
+            add_comment: Box::new(add_comment_prompt),
+            delete_comment: Box::new(delete_comment_prompt),
+            create_sprint: Box::new(create_sprint_prompt),
+            delete_sprint: Box::new(delete_sprint_prompt),
+            assign_sprint_story: Box::new(assign_sprint_story_prompt),
+            remove_sprint_story: Box::new(remove_sprint_story_prompt),
+            create_project: Box::new(create_project_prompt),
+            new_password: Box::new(new_password_prompt),
+            search_query: Box::new(search_query_prompt),
+            filter_tag: Box::new(filter_tag_prompt),
+            sort_key: Box::new(sort_key_prompt),
+            story_sort_key: Box::new(story_sort_key_prompt),
+            reporter: Box::new(reporter_prompt),
+            export_destination: Box::new(export_destination_prompt),
+        }
+    }
+
+    /// Prompts that answer straight away instead of reading stdin, so the
+    /// navigator can be driven from tests. New items are "Mock ..." ones,
+    /// edits get a fixed value, confirmations say yes, and prompts that
+    /// would ask for an id or a filter return None. Replace single fields
+    /// to answer differently.
+    pub fn default_mock() -> Self {
+        Self {
+            create_epic: Box::new(|reporter| Epic {
+                reporter: reporter.to_owned(),
+                ..Epic::new("Mock Epic".to_owned(), "".to_owned())
+            }),
+            create_story: Box::new(|reporter| Story {
+                reporter: reporter.to_owned(),
+                ..Story::new("Mock Story".to_owned(), "".to_owned())
+            }),
+            delete_epic: Box::new(|| true),
+            delete_story: Box::new(|| true),
+            delete_stories: Box::new(|_| true),
+            update_status: Box::new(|| Some(Status::InProgress)),
+            update_story_type: Box::new(|| Some(StoryType::Bug)),
+            update_estimate: Box::new(|_| Some(3)),
+            update_due_date: Box::new(|_| NaiveDate::from_ymd_opt(2030, 1, 1)),
+            update_name: Box::new(|_| Some("Mock Name".to_owned())),
+            update_description: Box::new(|_| Some("Mock description".to_owned())),
+            update_release: Box::new(|_| Some("1.0".to_owned())),
+            link_story_dependency: Box::new(|| None),
+            unlink_story_dependency: Box::new(|| None),
+            move_story: Box::new(|| None),
+            add_comment: Box::new(|| Some("Mock comment".to_owned())),
+            delete_comment: Box::new(|| None),
+            create_sprint: Box::new(|| {
+                Some(Sprint::new(
+                    "Mock Sprint".to_owned(),
+                    NaiveDate::from_ymd_opt(2030, 1, 1)?,
+                    NaiveDate::from_ymd_opt(2030, 1, 14)?,
+                ))
+            }),
+            delete_sprint: Box::new(|| true),
+            assign_sprint_story: Box::new(|| None),
+            remove_sprint_story: Box::new(|| None),
+            create_project: Box::new(|| Some("mock".to_owned())),
+            new_password: Box::new(|| Some("Mock-password-123".to_owned())),
+            search_query: Box::new(|| Some("Mock".to_owned())),
+            filter_tag: Box::new(|| None),
+            sort_key: Box::new(|| None),
+            story_sort_key: Box::new(|| None),
+            reporter: Box::new(|| None),
+            export_destination: Box::new(|filename| std::env::temp_dir().join(filename)),
         }
     }
 }
 
-fn create_epic_prompt() -> Epic {
+fn create_epic_prompt(reporter: &str) -> Epic {
     println!("----------------------------");
 
     println!("Epic Name: ");
 
     let epic_name = get_user_input();
 
-    println!("Epic Description: ");
+    let epic_desc = read_description("Epic");
 
-    let epic_desc = get_user_input();
-
-    let epic = Epic::new(epic_name.trim().to_owned(), epic_desc.trim().to_owned());
+    let mut epic = Epic::new(epic_name.trim().to_owned(), epic_desc.trim().to_owned());
+    epic.reporter = reporter.to_owned();
 
     epic
 }
 
-fn create_story_prompt() -> Story {
+fn create_story_prompt(reporter: &str) -> Story {
     println!("----------------------------");
 
     println!("Story Name: ");
 
     let story_name = get_user_input();
 
-    println!("Story Description: ");
-
-    let story_desc = get_user_input();
+    let story_desc = read_description("Story");
 
-    let story = Story::new(story_name.trim().to_owned(), story_desc.trim().to_owned());
+    let mut story = Story::new(story_name.trim().to_owned(), story_desc.trim().to_owned());
+    story.reporter = reporter.to_owned();
 
     story
 }
 
+fn read_description(kind: &str) -> String {
+    println!(
+        "{} Description (finish with \"{}\" on a line of its own): ",
+        kind, DESCRIPTION_TERMINATOR
+    );
+
+    read_multiline_input(DESCRIPTION_TERMINATOR).unwrap_or_default()
+}
+
 fn delete_epic_prompt() -> bool {
     println!("----------------------------");
 
@@ -85,10 +206,23 @@ fn delete_story_prompt() -> bool {
     false
 }
 
+fn delete_stories_prompt(count: usize) -> bool {
+    println!("----------------------------");
+
+    println!(
+        "Are you sure you want to delete these {} stories? [Y/n]: ",
+        count
+    );
+
+    let input = get_user_input();
+
+    input.trim().to_lowercase().eq("y")
+}
+
 fn update_status_prompt() -> Option<Status> {
     println!("----------------------------");
 
-    println!("New Status (1 - OPEN, 2 - IN-PROGRESS, 3 - RESOLVED, 4 - CLOSED): ");
+    println!("New Status (1 - OPEN, 2 - IN-PROGRESS, 3 - RESOLVED, 4 - CLOSED, 5 - BLOCKED): ");
 
     let status = get_user_input();
 
@@ -108,9 +242,364 @@ fn update_status_prompt() -> Option<Status> {
             4 => {
                 return Some(Status::Closed);
             }
+            5 => {
+                return Some(Status::Blocked);
+            }
             _ => return None,
         }
     }
 
     None
 }
+
+fn update_story_type_prompt() -> Option<StoryType> {
+    println!("----------------------------");
+
+    println!("New Type (1 - BUG, 2 - FEATURE, 3 - TASK, 4 - CHORE): ");
+
+    match get_user_input().trim().parse::<u8>() {
+        Ok(1) => Some(StoryType::Bug),
+        Ok(2) => Some(StoryType::Feature),
+        Ok(3) => Some(StoryType::Task),
+        Ok(4) => Some(StoryType::Chore),
+        _ => None,
+    }
+}
+
+fn update_estimate_prompt(current: Option<u8>) -> Option<u8> {
+    println!("----------------------------");
+
+    let values: Vec<String> = STORY_ESTIMATE_VALUES.iter().map(u8::to_string).collect();
+    println!("New Estimate ({}): ", values.join(", "));
+
+    let estimate = read_edit(
+        &current
+            .map(|estimate| estimate.to_string())
+            .unwrap_or_default(),
+    );
+    let estimate = estimate.trim();
+
+    if estimate.is_empty() {
+        return None;
+    }
+
+    match estimate.parse::<u8>() {
+        Ok(estimate) if Some(estimate) == current => None,
+        Ok(estimate) if STORY_ESTIMATE_VALUES.contains(&estimate) => Some(estimate),
+        _ => {
+            println!(
+                "\"{}\" is not a valid estimate. Estimates must be one of: {}",
+                estimate,
+                values.join(", ")
+            );
+            wait_for_key_press();
+            None
+        }
+    }
+}
+
+fn update_due_date_prompt(current: Option<NaiveDate>) -> Option<NaiveDate> {
+    println!("----------------------------");
+
+    println!("New Due Date (YYYY-MM-DD): ");
+
+    let date = parse_date(&read_edit(
+        &current.map(|date| date.to_string()).unwrap_or_default(),
+    ))?;
+
+    (Some(date) != current).then_some(date)
+}
+
+fn update_name_prompt(current: &str) -> Option<String> {
+    println!("----------------------------");
+
+    println!("New Name: ");
+
+    let name = read_edit(current);
+    let name = name.trim();
+
+    if name.is_empty() || name == current {
+        return None;
+    }
+
+    Some(name.to_owned())
+}
+
+fn update_description_prompt(current: &str) -> Option<String> {
+    println!("----------------------------");
+
+    println!("New Description: ");
+
+    let description = read_edit(current);
+    let description = description.trim();
+
+    if description.is_empty() || description == current {
+        return None;
+    }
+
+    Some(description.to_owned())
+}
+
+fn update_release_prompt(current: &str) -> Option<String> {
+    println!("----------------------------");
+
+    println!("Release (type a space to clear it): ");
+
+    let release = read_edit(current);
+    let release = release.trim();
+
+    // Unlike names, a blank release is a real answer: it leaves the release
+    if release == current {
+        return None;
+    }
+
+    Some(release.to_owned())
+}
+
+// Offers `current` as the default, unless there is nothing to offer
+fn read_edit(current: &str) -> String {
+    if current.is_empty() {
+        get_user_input()
+    } else {
+        get_user_input_with_default(current).unwrap_or_default()
+    }
+}
+
+fn read_date() -> Option<NaiveDate> {
+    parse_date(&get_user_input())
+}
+
+fn parse_date(date: &str) -> Option<NaiveDate> {
+    let date = date.trim();
+
+    if date.is_empty() {
+        return None;
+    }
+
+    match NaiveDate::parse_from_str(date, "%Y-%m-%d") {
+        Ok(date) => Some(date),
+        Err(_) => {
+            println!(
+                "\"{}\" is not a valid date. Dates must look like 2025-06-30",
+                date
+            );
+            wait_for_key_press();
+            None
+        }
+    }
+}
+
+fn link_story_dependency_prompt() -> Option<u32> {
+    println!("----------------------------");
+
+    println!("ID of the story this story blocks: ");
+
+    get_user_input().trim().parse::<u32>().ok()
+}
+
+fn unlink_story_dependency_prompt() -> Option<u32> {
+    println!("----------------------------");
+
+    println!("ID of the story to unlink: ");
+
+    get_user_input().trim().parse::<u32>().ok()
+}
+
+fn move_story_prompt() -> Option<u32> {
+    println!("----------------------------");
+
+    println!("ID of the epic to move this story to: ");
+
+    get_user_input().trim().parse::<u32>().ok()
+}
+
+fn add_comment_prompt() -> Option<String> {
+    println!("----------------------------");
+
+    println!("Comment: ");
+
+    let text = get_user_input();
+    let text = text.trim();
+
+    if text.is_empty() {
+        return None;
+    }
+
+    Some(text.to_owned())
+}
+
+fn delete_comment_prompt() -> Option<u32> {
+    println!("----------------------------");
+
+    println!("ID of the comment to delete: ");
+
+    get_user_input().trim().parse::<u32>().ok()
+}
+
+fn create_sprint_prompt() -> Option<Sprint> {
+    println!("----------------------------");
+
+    println!("Sprint Name: ");
+
+    let sprint_name = get_user_input();
+
+    println!("Start Date (YYYY-MM-DD): ");
+
+    let start_date = read_date()?;
+
+    println!("End Date (YYYY-MM-DD): ");
+
+    let end_date = read_date()?;
+
+    Some(Sprint::new(
+        sprint_name.trim().to_owned(),
+        start_date,
+        end_date,
+    ))
+}
+
+fn delete_sprint_prompt() -> bool {
+    println!("----------------------------");
+
+    println!("Are you sure you want to delete this sprint? [Y/n]: ");
+
+    let input = get_user_input();
+
+    input.trim().to_lowercase().eq("y")
+}
+
+fn assign_sprint_story_prompt() -> Option<u32> {
+    println!("----------------------------");
+
+    println!("ID of the story to add: ");
+
+    get_user_input().trim().parse::<u32>().ok()
+}
+
+fn remove_sprint_story_prompt() -> Option<u32> {
+    println!("----------------------------");
+
+    println!("ID of the story to remove: ");
+
+    get_user_input().trim().parse::<u32>().ok()
+}
+
+fn create_project_prompt() -> Option<String> {
+    println!("----------------------------");
+
+    println!("Project Name: ");
+
+    let name = get_user_input();
+    let name = name.trim();
+
+    if name.is_empty() {
+        return None;
+    }
+
+    Some(name.to_owned())
+}
+
+fn new_password_prompt() -> Option<String> {
+    println!("----------------------------");
+
+    match read_password_confirmed() {
+        Ok(password) => Some(password),
+        Err(error) => {
+            println!("{}", error);
+            None
+        }
+    }
+}
+
+fn search_query_prompt() -> Option<String> {
+    println!("----------------------------");
+
+    println!("Search for: ");
+
+    let query = get_user_input();
+    let query = query.trim();
+
+    if query.is_empty() {
+        return None;
+    }
+
+    Some(query.to_owned())
+}
+
+fn filter_tag_prompt() -> Option<String> {
+    println!("----------------------------");
+
+    println!("Show epics tagged: ");
+
+    let tag = get_user_input();
+    let tag = tag.trim();
+
+    if tag.is_empty() {
+        return None;
+    }
+
+    Some(tag.to_owned())
+}
+
+fn sort_key_prompt() -> Option<SortKey> {
+    println!("----------------------------");
+
+    println!(
+        "Sort epics by (1 - ID, 2 - NAME, 3 - STATUS, 4 - PRIORITY, 5 - DUE DATE, 6 - PROGRESS): "
+    );
+
+    match get_user_input().trim().parse::<u8>() {
+        Ok(1) => Some(SortKey::Id),
+        Ok(2) => Some(SortKey::Name),
+        Ok(3) => Some(SortKey::Status),
+        Ok(4) => Some(SortKey::Priority),
+        Ok(5) => Some(SortKey::DueDate),
+        Ok(6) => Some(SortKey::CompletionPct),
+        _ => None,
+    }
+}
+
+fn story_sort_key_prompt() -> Option<StorySortKey> {
+    println!("----------------------------");
+
+    println!("Sort stories by (1 - ID, 2 - NAME, 3 - STATUS, 4 - ESTIMATE, 5 - TYPE): ");
+
+    match get_user_input().trim().parse::<u8>() {
+        Ok(1) => Some(StorySortKey::Id),
+        Ok(2) => Some(StorySortKey::Name),
+        Ok(3) => Some(StorySortKey::Status),
+        Ok(4) => Some(StorySortKey::Estimate),
+        Ok(5) => Some(StorySortKey::StoryType),
+        _ => None,
+    }
+}
+
+fn reporter_prompt() -> Option<String> {
+    println!("----------------------------");
+
+    println!("Show stories reported by: ");
+
+    let reporter = get_user_input();
+    let reporter = reporter.trim();
+
+    if reporter.is_empty() {
+        return None;
+    }
+
+    Some(reporter.to_owned())
+}
+
+fn export_destination_prompt(default_filename: &str) -> PathBuf {
+    println!("----------------------------");
+
+    println!("Export to [{}]: ", default_filename);
+
+    let dest = get_user_input();
+    let dest = dest.trim();
+
+    if dest.is_empty() {
+        return PathBuf::from(default_filename);
+    }
+
+    PathBuf::from(dest)
+}
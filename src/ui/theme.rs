@@ -0,0 +1,98 @@
+use crate::{models::Status, ui::pages::page_helpers::ColumnSpec};
+
+const RESET: &str = "\x1b[0m";
+const GREEN: &str = "\x1b[32m";
+const YELLOW: &str = "\x1b[33m";
+const RED: &str = "\x1b[31m";
+const WHITE: &str = "\x1b[37m";
+
+pub fn colored_status(status: &Status) -> String {
+    paint(status, &status.to_string())
+}
+
+fn paint(status: &Status, text: &str) -> String {
+    let color = match status {
+        Status::Resolved | Status::Closed => GREEN,
+        Status::InProgress => YELLOW,
+        Status::Blocked => RED,
+        Status::Open => WHITE,
+    };
+
+    format!("{}{}{}", color, text, RESET)
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct Theme {
+    pub use_color: bool,
+}
+
+impl Theme {
+    pub fn plain() -> Self {
+        Self { use_color: false }
+    }
+
+    /// Colors are on unless `NO_COLOR` is set to a non-empty value, as
+    /// described at https://no-color.org.
+    pub fn from_env() -> Self {
+        Self {
+            use_color: std::env::var("NO_COLOR").map_or(true, |value| value.is_empty()),
+        }
+    }
+
+    pub fn status(&self, status: &Status) -> String {
+        if self.use_color {
+            colored_status(status)
+        } else {
+            status.to_string()
+        }
+    }
+
+    // Pads before coloring so the escape codes don't count towards the width
+    pub fn status_column(&self, status: &Status, width: usize) -> String {
+        let column = ColumnSpec::default_status(width).format(&status.to_string());
+
+        if self.use_color {
+            paint(status, &column)
+        } else {
+            column
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn colored_status_should_wrap_the_label_in_its_color() {
+        assert_eq!(colored_status(&Status::Closed), "\x1b[32mCLOSED\x1b[0m");
+        assert_eq!(colored_status(&Status::Resolved), "\x1b[32mRESOLVED\x1b[0m");
+        assert_eq!(
+            colored_status(&Status::InProgress),
+            "\x1b[33mIN PROGRESS\x1b[0m"
+        );
+        assert_eq!(colored_status(&Status::Blocked), "\x1b[31mBLOCKED\x1b[0m");
+        assert_eq!(colored_status(&Status::Open), "\x1b[37mOPEN\x1b[0m");
+    }
+
+    #[test]
+    fn status_should_only_be_colored_when_enabled() {
+        let colored = Theme { use_color: true };
+        let plain = Theme::plain();
+
+        assert_eq!(colored.status(&Status::Blocked), "\x1b[31mBLOCKED\x1b[0m");
+        assert_eq!(plain.status(&Status::Blocked), "BLOCKED");
+    }
+
+    #[test]
+    fn status_column_should_pad_inside_the_color_codes() {
+        let colored = Theme { use_color: true };
+        let plain = Theme::plain();
+
+        assert_eq!(
+            colored.status_column(&Status::Open, 6),
+            "\x1b[37mOPEN  \x1b[0m"
+        );
+        assert_eq!(plain.status_column(&Status::Open, 6), "OPEN  ");
+    }
+}
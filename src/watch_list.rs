@@ -0,0 +1,172 @@
+//! Stories the user wants to keep an eye on, saved as a JSON array of ids.
+//!
+//! The list belongs to the user rather than to a project, so it lives in
+//! `~/.ironyy/watchlist.json` instead of the database. Every change is
+//! saved straight away.
+
+use std::{
+    collections::HashSet,
+    fs,
+    io::ErrorKind,
+    path::{Path, PathBuf},
+};
+
+use anyhow::{Result, anyhow};
+use itertools::Itertools;
+
+const WATCH_LIST_DIR: &str = ".ironyy";
+const WATCH_LIST_FILE: &str = "watchlist.json";
+
+#[derive(Debug, Default)]
+pub struct WatchList {
+    stories: HashSet<u32>,
+    path: Option<PathBuf>,
+}
+
+impl WatchList {
+    /// A list that is never saved, for tests and throwaway sessions.
+    pub fn in_memory() -> Self {
+        Self::default()
+    }
+
+    /// `~/.ironyy/watchlist.json`, or `None` when there is no home directory.
+    pub fn default_path() -> Option<PathBuf> {
+        std::env::var_os("HOME")
+            .map(|home| Path::new(&home).join(WATCH_LIST_DIR).join(WATCH_LIST_FILE))
+    }
+
+    /// Reads the list saved at `path`, or starts empty if there is no file
+    /// there yet.
+    pub fn load_from_file(path: impl Into<PathBuf>) -> Result<Self> {
+        let path = path.into();
+
+        let stories = match fs::read(&path) {
+            Ok(bytes) => serde_json::from_slice(&bytes)
+                .map_err(|e| anyhow!("failed to parse {}: {}", path.display(), e))?,
+            Err(e) if e.kind() == ErrorKind::NotFound => HashSet::new(),
+            Err(e) => return Err(anyhow!("failed to read {}: {}", path.display(), e)),
+        };
+
+        Ok(Self {
+            stories,
+            path: Some(path),
+        })
+    }
+
+    pub fn save_to_file(&self) -> Result<()> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        fs::write(path, serde_json::to_vec(&self.story_ids())?)?;
+
+        Ok(())
+    }
+
+    pub fn contains(&self, story_id: u32) -> bool {
+        self.stories.contains(&story_id)
+    }
+
+    pub fn story_ids(&self) -> Vec<u32> {
+        self.stories.iter().copied().sorted().collect()
+    }
+
+    /// Returns whether the story was newly added.
+    pub fn add(&mut self, story_id: u32) -> Result<bool> {
+        let added = self.stories.insert(story_id);
+        if added {
+            self.save_or_undo(story_id)?;
+        }
+        Ok(added)
+    }
+
+    /// Returns whether the story was being watched.
+    pub fn remove(&mut self, story_id: u32) -> Result<bool> {
+        let removed = self.stories.remove(&story_id);
+        if removed {
+            self.save_or_undo(story_id)?;
+        }
+        Ok(removed)
+    }
+
+    /// Returns whether the story is watched afterwards.
+    pub fn toggle(&mut self, story_id: u32) -> Result<bool> {
+        if self.contains(story_id) {
+            self.remove(story_id)?;
+            Ok(false)
+        } else {
+            self.add(story_id)?;
+            Ok(true)
+        }
+    }
+
+    // A change that could not be saved would be lost on restart anyway
+    fn save_or_undo(&mut self, story_id: u32) -> Result<()> {
+        self.save_to_file().inspect_err(|_| {
+            if !self.stories.remove(&story_id) {
+                self.stories.insert(story_id);
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn add_and_remove_should_report_whether_anything_changed() {
+        let mut watch_list = WatchList::in_memory();
+
+        assert!(watch_list.add(3).unwrap());
+        assert!(!watch_list.add(3).unwrap());
+        assert!(watch_list.contains(3));
+
+        assert!(watch_list.remove(3).unwrap());
+        assert!(!watch_list.remove(3).unwrap());
+        assert!(!watch_list.contains(3));
+    }
+
+    #[test]
+    fn toggle_should_flip_whether_a_story_is_watched() {
+        let mut watch_list = WatchList::in_memory();
+
+        assert!(watch_list.toggle(5).unwrap());
+        assert!(watch_list.toggle(2).unwrap());
+        assert_eq!(watch_list.story_ids(), vec![2, 5]);
+
+        assert!(!watch_list.toggle(5).unwrap());
+        assert_eq!(watch_list.story_ids(), vec![2]);
+    }
+
+    #[test]
+    fn watch_list_should_survive_a_reload() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(WATCH_LIST_DIR).join(WATCH_LIST_FILE);
+
+        let mut watch_list = WatchList::load_from_file(&path).unwrap();
+        assert!(watch_list.story_ids().is_empty());
+        watch_list.add(7).unwrap();
+        watch_list.add(1).unwrap();
+        watch_list.toggle(7).unwrap();
+
+        let reloaded = WatchList::load_from_file(&path).unwrap();
+        assert_eq!(reloaded.story_ids(), vec![1]);
+    }
+
+    #[test]
+    fn failed_saves_should_leave_the_list_unchanged() {
+        let dir = tempfile::tempdir().unwrap();
+        // A directory cannot be written over like a file
+        let mut watch_list = WatchList {
+            stories: HashSet::new(),
+            path: Some(dir.path().to_owned()),
+        };
+
+        assert!(watch_list.add(4).is_err());
+        assert!(!watch_list.contains(4));
+    }
+}
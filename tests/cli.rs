@@ -0,0 +1,189 @@
+use std::{
+    fs,
+    io::Write,
+    path::{Path, PathBuf},
+    process::{Command, Output, Stdio},
+};
+
+use serde_json::Value;
+
+fn empty_db(dir: &Path) -> PathBuf {
+    let path = dir.join("db.json");
+    fs::write(
+        &path,
+        r#"{ "last_item_id": 0, "epics": {}, "stories": {} }"#,
+    )
+    .unwrap();
+    path
+}
+
+fn ironyy(db: &Path, args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_ironyy"))
+        .arg("--db")
+        .arg(db)
+        .args(args)
+        .output()
+        .expect("failed to run ironyy")
+}
+
+fn stdout_json(output: &Output) -> Value {
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    serde_json::from_slice(&output.stdout).expect("stdout should be json")
+}
+
+#[test]
+fn create_epic_should_show_up_in_list_epics() {
+    let dir = tempfile::tempdir().unwrap();
+    let db = empty_db(dir.path());
+
+    let created = stdout_json(&ironyy(
+        &db,
+        &[
+            "create-epic",
+            "--name",
+            "Billing",
+            "--desc",
+            "Take payments",
+        ],
+    ));
+    assert_eq!(created["id"], 1);
+
+    let epics = stdout_json(&ironyy(&db, &["list-epics"]));
+    let epics = epics.as_array().unwrap();
+    assert_eq!(epics.len(), 1);
+    assert_eq!(epics[0]["id"], 1);
+    assert_eq!(epics[0]["name"], "Billing");
+    assert_eq!(epics[0]["description"], "Take payments");
+}
+
+#[test]
+fn stories_should_be_created_updated_and_deleted() {
+    let dir = tempfile::tempdir().unwrap();
+    let db = empty_db(dir.path());
+
+    stdout_json(&ironyy(
+        &db,
+        &["create-epic", "--name", "Billing", "--desc", ""],
+    ));
+    let created = stdout_json(&ironyy(
+        &db,
+        &["create-story", "--epic", "1", "--name", "Pay", "--desc", ""],
+    ));
+    assert_eq!(created["id"], 2);
+
+    stdout_json(&ironyy(
+        &db,
+        &[
+            "update-status",
+            "--type",
+            "story",
+            "--id",
+            "2",
+            "--status",
+            "resolved",
+        ],
+    ));
+    let stories = stdout_json(&ironyy(&db, &["list-stories", "--epic", "1"]));
+    assert_eq!(stories[0]["name"], "Pay");
+    assert_eq!(stories[0]["status"], "Resolved");
+
+    stdout_json(&ironyy(&db, &["delete", "--type", "story", "--id", "2"]));
+    let stories = stdout_json(&ironyy(&db, &["list-stories", "--epic", "1"]));
+    assert_eq!(stories.as_array().unwrap().len(), 0);
+}
+
+#[test]
+fn created_items_should_be_credited_to_the_given_user() {
+    let dir = tempfile::tempdir().unwrap();
+    let db = empty_db(dir.path());
+
+    stdout_json(&ironyy(
+        &db,
+        &[
+            "--user",
+            "alice",
+            "create-epic",
+            "--name",
+            "Billing",
+            "--desc",
+            "",
+        ],
+    ));
+    stdout_json(&ironyy(
+        &db,
+        &[
+            "create-story",
+            "--epic",
+            "1",
+            "--name",
+            "Pay",
+            "--desc",
+            "",
+            "--user",
+            "bob",
+        ],
+    ));
+
+    let epics = stdout_json(&ironyy(&db, &["list-epics"]));
+    assert_eq!(epics[0]["reporter"], "alice");
+    let stories = stdout_json(&ironyy(&db, &["list-stories", "--epic", "1"]));
+    assert_eq!(stories[0]["reporter"], "bob");
+}
+
+#[test]
+fn errors_should_go_to_stderr_with_a_failing_exit_code() {
+    let dir = tempfile::tempdir().unwrap();
+    let db = empty_db(dir.path());
+
+    let output = ironyy(&db, &["delete", "--type", "epic", "--id", "999"]);
+
+    assert!(!output.status.success());
+    assert!(output.stdout.is_empty());
+    assert!(!output.stderr.is_empty());
+}
+
+#[test]
+fn tsv_list_epics_should_pipe_through_cut() {
+    let dir = tempfile::tempdir().unwrap();
+    let db = empty_db(dir.path());
+
+    stdout_json(&ironyy(
+        &db,
+        &["create-epic", "--name", "Billing", "--desc", "a\tb"],
+    ));
+    stdout_json(&ironyy(
+        &db,
+        &["create-epic", "--name", "Search", "--desc", ""],
+    ));
+
+    let output = ironyy(&db, &["list-epics", "--format", "tsv"]);
+    assert!(output.status.success());
+
+    let mut cut = Command::new("cut")
+        .arg("-f2")
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .expect("failed to run cut");
+    cut.stdin.take().unwrap().write_all(&output.stdout).unwrap();
+    let names = cut.wait_with_output().unwrap();
+
+    assert_eq!(
+        String::from_utf8(names.stdout).unwrap(),
+        "Billing\nSearch\n"
+    );
+}
+
+#[test]
+fn json_format_should_print_an_array() {
+    let dir = tempfile::tempdir().unwrap();
+    let db = empty_db(dir.path());
+
+    let epics = stdout_json(&ironyy(&db, &["list-epics", "--format", "json"]));
+
+    assert_eq!(epics.as_array().unwrap().len(), 0);
+}